@@ -0,0 +1,37 @@
+use braavos_accumulator::{BraavosAccumulator, CorpusOp, CorpusSpec};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+// One round of a fixed corpus over its initial members, so results from
+// different machines and versions time the same adds and deletions.
+fn churn_round(c: &mut Criterion) {
+    let spec = CorpusSpec {
+        initial_members: 200,
+        rounds: 1,
+        adds_per_round: 20,
+        deletes_per_round: 5,
+        ..CorpusSpec::default()
+    };
+    let corpus = spec.generate().expect("Failed to generate corpus");
+    let mut acc = BraavosAccumulator::new(128).expect("Failed to create accumulator");
+    for element in &corpus.initial {
+        acc.add(element).expect("Failed to add element");
+    }
+    let state = acc.to_state_bytes().expect("Failed to encode state");
+    c.bench_function("churn_round", |b| {
+        b.iter_batched(
+            || BraavosAccumulator::builder().restore(&state).unwrap(),
+            |mut acc| {
+                for op in corpus.operations() {
+                    match op {
+                        CorpusOp::Add(element) => acc.add(element).map(|_| ()).unwrap(),
+                        CorpusOp::Delete(element) => acc.delete(element).map(|_| ()).unwrap(),
+                    }
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, churn_round);
+criterion_main!(benches);
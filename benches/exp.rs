@@ -0,0 +1,59 @@
+use braavos_accumulator::{BraavosAccumulator, ElemPrime, ExpStrategy};
+use crypto_bigint::modular::{MontyForm, MontyParams};
+use crypto_bigint::{Odd, U256};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// Each add of an already-cached element is one full-width trapdoor
+// exponentiation, so this isolates the exponentiation strategy.
+fn trapdoor_exp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trapdoor_exp");
+    for strategy in [ExpStrategy::Native, ExpStrategy::Chunked] {
+        let mut acc = BraavosAccumulator::builder()
+            .prime_bits(128)
+            .exp_strategy(strategy)
+            .build()
+            .expect("Failed to create accumulator");
+        acc.add(b"element").expect("Failed to add element");
+        group.bench_function(BenchmarkId::from_parameter(format!("{:?}", strategy)), |b| {
+            b.iter(|| acc.add(b"element").unwrap())
+        });
+    }
+    group.finish();
+}
+
+// Issuance bursts with a fixed-base table for the current value, by budget.
+fn precomputed_exp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("precomputed_exp");
+    for budget in [64 << 10, 256 << 10, 1 << 20] {
+        let mut acc = BraavosAccumulator::builder()
+            .prime_bits(128)
+            .precompute(budget)
+            .build()
+            .expect("Failed to create accumulator");
+        acc.add(b"element").expect("Failed to add element");
+        group.bench_function(BenchmarkId::from_parameter(format!("{} KiB", budget >> 10)), |b| {
+            b.iter(|| acc.add(b"element").unwrap())
+        });
+    }
+    group.finish();
+}
+
+// Opening a witness: element primes are public, so pow_elem stops at the
+// exponent's top bit, against the constant-time pow over all 256 bits.
+fn public_exp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("public_exp");
+    let mut acc = BraavosAccumulator::new(128).expect("Failed to create accumulator");
+    let w = acc.add(b"element").unwrap().value;
+    let params = MontyParams::new(Odd::new(acc.params().n).unwrap());
+    let short = ElemPrime::from_be_bytes(&U256::from(65537u32).to_be_bytes());
+    for (bits, prime) in [(17, short), (256, acc.params().hash_to_prime(b"element"))] {
+        group.bench_function(BenchmarkId::new("adaptive", bits), |b| b.iter(|| w.pow_elem(&prime, &params)));
+        group.bench_function(BenchmarkId::new("constant_time", bits), |b| {
+            b.iter(|| MontyForm::new(w.as_uint(), params).pow(prime.as_uint()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, trapdoor_exp, precomputed_exp, public_exp);
+criterion_main!(benches);
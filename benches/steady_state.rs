@@ -0,0 +1,26 @@
+use braavos_accumulator::{BraavosAccumulator, DeviceVerifier, UpdateMessage, Witness};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+// The per-request paths a verifier or holder runs once the element cache is
+// warm: verify, device verification, a single-deletion witness update and
+// witness serialization.
+// None of them should allocate.
+fn steady_state(c: &mut Criterion) {
+    let mut acc = BraavosAccumulator::new(128).expect("Failed to create accumulator");
+    let w = acc.add(b"holder").expect("Failed to add holder");
+    acc.add(b"revoked").expect("Failed to add revoked");
+    let update = acc.delete(b"revoked").expect("Failed to delete revoked");
+    let prime = acc.element_prime(b"holder").unwrap();
+    let w = update.apply_to_witness(&prime, &w).unwrap();
+
+    c.bench_function("verify", |b| b.iter(|| assert!(acc.verify(b"holder", w))));
+    let device = DeviceVerifier::new(&acc.params()).unwrap();
+    let value = acc.value();
+    c.bench_function("device_verify", |b| b.iter(|| assert!(device.verify(b"holder", &w, &value))));
+    c.bench_function("apply_single_update", |b| b.iter(|| update.apply_to_witness(&prime, &w).unwrap()));
+    let mut buf = [0u8; Witness::ENCODED_LEN];
+    c.bench_function("witness_write_to", |b| b.iter(|| w.write_to(&mut buf).unwrap()));
+}
+
+criterion_group!(benches, steady_state);
+criterion_main!(benches);
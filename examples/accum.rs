@@ -0,0 +1,123 @@
+// Command-line manager whose store is kept encrypted at rest, with backups
+// checked before they are written and again before they are restored. The
+// passphrase comes from ACCUM_PASSPHRASE, never the command line:
+//
+//     export ACCUM_PASSPHRASE=...
+//     cargo run --release --example accum -- init acc.store
+//     cargo run --release --example accum -- add acc.store alice "issued 2024-05"
+//     cargo run --release --example accum -- backup acc.store acc.bak
+//     cargo run --release --example accum -- restore acc.bak --verify-only
+//     cargo run --release --example accum -- restore acc.bak restored.store
+use braavos_accumulator::{verify_backup, AllowAll, BraavosAccumulator, Manager};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "\
+usage: accum <command> ...
+  init <store> [prime_bits]          create a store with fresh keys
+  add <store> <x> [metadata]         add x
+  delete <store> <x>                 delete x
+  status <store>                     print the epoch, member count and value
+  backup <store> <archive>           write a verified, encrypted archive of the store
+  restore <archive> <store>          restore an archive into a new store
+  restore <archive> --verify-only    check an archive without restoring it";
+
+const OPERATOR: &str = "accum";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let Ok(passphrase) = env::var("ACCUM_PASSPHRASE") else {
+        eprintln!("ACCUM_PASSPHRASE is not set");
+        process::exit(2);
+    };
+    match run(&args, passphrase.as_bytes()) {
+        Ok(out) => println!("{out}"),
+        Err(USAGE) => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn run(args: &[&str], passphrase: &[u8]) -> Result<String, &'static str> {
+    match args {
+        ["init", store] | ["init", store, _] => {
+            let mut builder = BraavosAccumulator::builder();
+            if let Some(bits) = args.get(2) {
+                builder = builder.prime_bits(bits.parse().map_err(|_| "prime_bits must be a number")?);
+            }
+            let acc = builder.build()?;
+            create(store, &Manager::new(acc, AllowAll).backup(passphrase)?)?;
+            Ok(format!("created {store}"))
+        }
+        ["add", store, x] | ["add", store, x, _] => {
+            let mut manager = load(store, passphrase)?;
+            let metadata = args.get(3).map_or(&[][..], |m| m.as_bytes());
+            manager.add_with_metadata(OPERATOR, x.as_bytes(), metadata)?;
+            save(store, &manager.backup(passphrase)?)?;
+            Ok(format!("added {x}"))
+        }
+        ["delete", store, x] => {
+            let mut manager = load(store, passphrase)?;
+            let update = manager.delete(OPERATOR, x.as_bytes())?;
+            save(store, &manager.backup(passphrase)?)?;
+            Ok(format!("deleted {x}, now at epoch {}", update.to_epoch))
+        }
+        ["status", store] => {
+            let manager = load(store, passphrase)?;
+            let acc = manager.accumulator();
+            Ok(format!("epoch {}, {} members, value {}", acc.epoch(), acc.member_count(), acc.value().as_uint()))
+        }
+        ["backup", store, archive] => {
+            let bytes = load(store, passphrase)?.backup(passphrase)?;
+            let summary = verify_backup(&bytes, passphrase)?;
+            create(archive, &bytes)?;
+            Ok(format!("backed up epoch {} with {} members", summary.epoch, summary.members))
+        }
+        ["restore", archive, "--verify-only"] => {
+            let bytes = fs::read(archive).map_err(|_| "Failed to read archive")?;
+            let summary = verify_backup(&bytes, passphrase)?;
+            Ok(format!(
+                "archive reproduces value {} at epoch {} ({} members, {} audit records)",
+                summary.value.as_uint(),
+                summary.epoch,
+                summary.members,
+                summary.audit_records
+            ))
+        }
+        ["restore", archive, store] => {
+            let bytes = fs::read(archive).map_err(|_| "Failed to read archive")?;
+            let manager = Manager::restore_backup(&bytes, passphrase, &BraavosAccumulator::builder(), AllowAll)?;
+            create(store, &manager.backup(passphrase)?)?;
+            Ok(format!("restored epoch {} into {store}", manager.accumulator().epoch()))
+        }
+        _ => Err(USAGE),
+    }
+}
+
+fn load(store: &str, passphrase: &[u8]) -> Result<Manager, &'static str> {
+    let bytes = fs::read(store).map_err(|_| "Failed to read store")?;
+    Manager::restore_backup(&bytes, passphrase, &BraavosAccumulator::builder(), AllowAll)
+}
+
+// Never overwrites: a backup or restore must not clobber existing keys.
+fn create(path: &str, bytes: &[u8]) -> Result<(), &'static str> {
+    if Path::new(path).exists() {
+        return Err("Refusing to overwrite an existing file");
+    }
+    fs::write(path, bytes).map_err(|_| "Failed to write file")
+}
+
+// Replaces the store atomically so a crash never leaves a torn store behind.
+fn save(store: &str, bytes: &[u8]) -> Result<(), &'static str> {
+    let tmp = format!("{store}.tmp");
+    fs::write(&tmp, bytes).map_err(|_| "Failed to write store")?;
+    fs::rename(&tmp, store).map_err(|_| "Failed to replace store")
+}
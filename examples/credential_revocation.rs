@@ -0,0 +1,185 @@
+// Reference integration: revocable credentials with zero-knowledge
+// presentations. The issuer puts a random revocation handle for each
+// credential into the accumulator and certifies a Pedersen commitment to its
+// element prime inside the credential. To present, the holder proves the
+// committed handle is still a member, without revealing the handle or its
+// witness. Revoking deletes the handle; holders and verifiers follow the
+// issuer's update log.
+//
+//     cargo run --release --example credential_revocation
+//
+// The credential is a toy: an HMAC under a key shared with the verifier,
+// standing in for a signature scheme over committed attributes (CL, BBS+).
+// Every presentation of one credential shows the same commitment, so they
+// are linkable through it; unlinkable multi-show needs that real scheme to
+// re-commit per presentation.
+use braavos_accumulator::{
+    commit_element, verify_membership_of_commitment, AccValue, AllowAll, BraavosAccumulator, Manager,
+    MembershipProof, PublicParams, UpdateLog, WitnessHolder, BLINDING_BITS,
+};
+use crypto_bigint::{RandomBits, U1024, U512};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+
+const ISSUER: &str = "issuer";
+
+struct Credential {
+    attributes: String,
+    commitment: U512, // Commitment to the revocation handle's element prime
+    tag: [u8; 32],
+}
+
+impl Credential {
+    fn mac(key: &[u8], attributes: &str, commitment: &U512) -> [u8; 32] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes any key length");
+        mac.update(&(attributes.len() as u32).to_be_bytes());
+        mac.update(attributes.as_bytes());
+        mac.update(&commitment.to_be_bytes());
+        mac.finalize().into_bytes().into()
+    }
+}
+
+struct Issuer {
+    manager: Manager,
+    key: [u8; 32],
+}
+
+impl Issuer {
+    fn new() -> Self {
+        let acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        Self {
+            manager: Manager::new(acc, AllowAll),
+            key,
+        }
+    }
+
+    fn params(&self) -> PublicParams {
+        self.manager.accumulator().params()
+    }
+
+    // Returns the credential and what the holder keeps secret: the handle,
+    // the commitment's blinding and a witness for the handle.
+    fn issue(&mut self, attributes: &str) -> (Credential, Holder) {
+        let mut handle = [0u8; 16];
+        OsRng.fill_bytes(&mut handle);
+        let witness = self.manager.add(ISSUER, handle).expect("Failed to add handle");
+        let params = self.params();
+        let blinding = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+        let commitment = commit_element(&params, &params.hash_to_prime(handle), &blinding).expect("Failed to commit");
+        let credential = Credential {
+            attributes: attributes.to_string(),
+            commitment,
+            tag: Credential::mac(&self.key, attributes, &commitment),
+        };
+        let epoch = self.manager.accumulator().epoch();
+        let witness = WitnessHolder::new(&params, handle, witness, epoch).expect("Failed to create holder");
+        (credential, Holder { witness, blinding })
+    }
+
+    fn revoke(&mut self, holder: &Holder) {
+        self.manager.delete(ISSUER, holder.witness.element()).expect("Failed to revoke");
+    }
+
+    fn update_log(&self) -> &UpdateLog {
+        self.manager.update_log()
+    }
+}
+
+struct Holder {
+    witness: WitnessHolder,
+    blinding: U1024,
+}
+
+impl Holder {
+    fn sync(&mut self, log: &UpdateLog) -> Result<(), &'static str> {
+        self.witness.sync(log)
+    }
+
+    fn present(&self, params: &PublicParams, value: &AccValue) -> Result<MembershipProof, &'static str> {
+        let prime = params.hash_to_prime(self.witness.element());
+        MembershipProof::prove(params, value, &prime, &self.blinding, &self.witness.witness())
+    }
+}
+
+// Knows the public parameters, shares the credential key with the issuer,
+// and tracks the accumulator value from the update log.
+struct Verifier {
+    params: PublicParams,
+    key: [u8; 32],
+    value: AccValue,
+    epoch: u64,
+}
+
+impl Verifier {
+    fn sync(&mut self, log: &UpdateLog) -> Result<(), &'static str> {
+        if let Some(update) = log.since(self.epoch)? {
+            self.value = update.value;
+            self.epoch = update.to_epoch;
+        }
+        Ok(())
+    }
+
+    fn accept(&self, credential: &Credential, proof: &MembershipProof) -> Result<(), &'static str> {
+        if Credential::mac(&self.key, &credential.attributes, &credential.commitment) != credential.tag {
+            return Err("Credential was not issued by this issuer");
+        }
+        if !verify_membership_of_commitment(&self.params, &self.value, &credential.commitment, proof) {
+            return Err("Credential is revoked or the presentation is stale");
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    let mut issuer = Issuer::new();
+    let (alice_cred, mut alice) = issuer.issue("name=alice;role=engineer");
+    let (bob_cred, mut bob) = issuer.issue("name=bob;role=contractor");
+    let (_, carol) = issuer.issue("name=carol;role=auditor");
+    let mut verifier = Verifier {
+        params: issuer.params(),
+        key: issuer.key,
+        value: issuer.manager.accumulator().value(),
+        epoch: issuer.manager.accumulator().epoch(),
+    };
+    println!("Issued 3 credentials at epoch {}", verifier.epoch);
+
+    // Multi-show: each presentation is a fresh proof over the same credential
+    for show in 1..=3 {
+        let proof = alice.present(&verifier.params, &verifier.value).expect("Failed to present");
+        verifier.accept(&alice_cred, &proof).expect("Verifier rejected alice");
+        println!("alice presentation {show} accepted");
+    }
+    let bob_old = bob.present(&verifier.params, &verifier.value).expect("Failed to present");
+    verifier.accept(&bob_cred, &bob_old).expect("Verifier rejected bob");
+
+    // Revocation, then everyone follows the update log
+    issuer.revoke(&bob);
+    issuer.revoke(&carol);
+    verifier.sync(issuer.update_log()).expect("Verifier failed to sync");
+    println!("Revoked bob and carol; verifier now at epoch {}", verifier.epoch);
+
+    let stale = alice.present(&verifier.params, &verifier.value);
+    assert!(stale.is_err(), "Unsynced witness opened the new value");
+    alice.sync(issuer.update_log()).expect("Alice failed to sync");
+    let proof = alice.present(&verifier.params, &verifier.value).expect("Failed to present");
+    verifier.accept(&alice_cred, &proof).expect("Verifier rejected alice after syncing");
+    println!("alice synced to epoch {} and was accepted", alice.witness.epoch());
+
+    // Bob can neither sync past his own revocation nor replay an old proof
+    assert!(bob.sync(issuer.update_log()).is_err(), "Revoked witness synced");
+    assert!(bob.present(&verifier.params, &verifier.value).is_err(), "Revoked holder produced a proof");
+    assert_eq!(verifier.accept(&bob_cred, &bob_old), Err("Credential is revoked or the presentation is stale"));
+    println!("bob's credential is refused after revocation");
+
+    // A credential with a swapped attribute no longer matches its tag
+    let forged = Credential {
+        attributes: "name=alice;role=admin".to_string(),
+        ..alice_cred
+    };
+    assert_eq!(verifier.accept(&forged, &proof), Err("Credential was not issued by this issuer"));
+    println!("Tampered credential refused");
+}
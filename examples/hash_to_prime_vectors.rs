@@ -0,0 +1,16 @@
+// Prints the hash-to-prime test vectors as JSON, for implementations in other
+// languages; tests/fixtures/h2p_vectors_sha256.json is this output for SHA-256.
+//
+//     cargo run --release --example hash_to_prime_vectors > h2p_vectors_sha256.json
+use braavos_accumulator::{HashAlgorithm, HashToPrimeVectors};
+use std::process;
+
+fn main() {
+    match HashToPrimeVectors::standard(HashAlgorithm::Sha256) {
+        Ok(vectors) => print!("{}", vectors.to_json()),
+        Err(e) => {
+            eprintln!("failed to compute vectors: {e}");
+            process::exit(1);
+        }
+    }
+}
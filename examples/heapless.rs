@@ -0,0 +1,73 @@
+// Revocation check as a secure element would run it: the host provisions a
+// DeviceVerifier encoding once, and the device then checks a credential's
+// witness against each published value using only fixed-size buffers. A
+// counting allocator confirms the device side never touches the heap.
+//
+//     cargo run --release --example heapless
+use braavos_accumulator::{BraavosAccumulator, DeviceVerifier, Witness};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// What the device stores: provisioned once, then only ever read.
+struct SecureElement {
+    verifier: [u8; DeviceVerifier::ENCODED_LEN],
+    credential: &'static [u8],
+    witness: [u8; Witness::ENCODED_LEN],
+}
+
+impl SecureElement {
+    fn still_valid(&self, value: &[u8; 64]) -> bool {
+        let Ok(verifier) = DeviceVerifier::from_bytes(&self.verifier) else {
+            return false;
+        };
+        verifier.verify_encoded(self.credential, &self.witness, value)
+    }
+}
+
+fn main() {
+    // Host side: issue credentials and provision the device
+    let mut acc = BraavosAccumulator::new(128).expect("Failed to create accumulator");
+    acc.add(b"badge-0042").expect("Failed to add badge");
+    acc.add(b"badge-0099").expect("Failed to add badge");
+    let mut device = SecureElement {
+        verifier: [0; DeviceVerifier::ENCODED_LEN],
+        credential: b"badge-0042",
+        witness: [0; Witness::ENCODED_LEN],
+    };
+    let verifier = DeviceVerifier::new(&acc.params()).expect("Failed to derive verifier");
+    verifier.write_to(&mut device.verifier).expect("Verifier buffer too small");
+    let w = acc.recover_witness(device.credential).expect("Failed to recover witness");
+    w.write_to(&mut device.witness).expect("Witness buffer too small");
+
+    let value = acc.value().to_be_bytes();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let valid = device.still_valid(&value);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!("badge valid: {valid}, heap allocations on the device path: {allocations}");
+    assert!(valid && allocations == 0);
+
+    // The badge is revoked: its witness no longer opens the published value
+    acc.delete(device.credential).expect("Failed to revoke badge");
+    let value = acc.value().to_be_bytes();
+    let valid = device.still_valid(&value);
+    println!("after revocation, badge valid: {valid}");
+    assert!(!valid);
+}
@@ -0,0 +1,65 @@
+// Registry lifecycle walkthrough: bulk import, a series of revocation epochs
+// with new members joining in between, and holders keeping their witnesses
+// current from the manager's update log.
+//
+//     cargo run --release --example lifecycle [members] [epochs]
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Manager, WitnessHolder};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let members: u32 = args.next().map_or(10_000, |a| a.parse().expect("members must be a number"));
+    let epochs: u64 = args.next().map_or(100, |a| a.parse().expect("epochs must be a number"));
+    let mut rng = StdRng::seed_from_u64(7);
+
+    let acc = BraavosAccumulator::builder()
+        .prime_bits(64)
+        .build()
+        .expect("Failed to create accumulator");
+    let mut manager = Manager::new(acc, AllowAll);
+    let params = manager.accumulator().params();
+
+    let start = Instant::now();
+    let mut holders = Vec::new();
+    for i in 0..members {
+        let x = format!("member-{}", i).into_bytes();
+        let w = manager.add("importer", &x).expect("Failed to import member");
+        if i.is_multiple_of(100) {
+            holders.push(WitnessHolder::new(&params, &x, w, 0).unwrap());
+        }
+    }
+    println!("Imported {} members in {:?}", members, start.elapsed());
+
+    let start = Instant::now();
+    let mut next = members;
+    while manager.accumulator().epoch() < epochs {
+        for _ in 0..rng.gen_range(0..4) {
+            manager.add("issuer", format!("member-{}", next).as_bytes()).unwrap();
+            next += 1;
+        }
+        // Revoke a member that is not one of the tracked holders
+        let victim = loop {
+            let i = rng.gen_range(0..next);
+            let x = format!("member-{}", i).into_bytes();
+            if !i.is_multiple_of(100) && manager.accumulator().contains(&x) {
+                break x;
+            }
+        };
+        manager.delete("revoker", &victim).unwrap();
+    }
+    println!(
+        "Published {} epochs in {:?}; {} members remain",
+        epochs,
+        start.elapsed(),
+        manager.accumulator().member_count()
+    );
+
+    let start = Instant::now();
+    for holder in &mut holders {
+        holder.sync(manager.update_log()).expect("Holder failed to sync");
+        assert!(manager.verify(holder.element(), holder.witness()));
+    }
+    println!("Synced and verified {} holders in {:?}", holders.len(), start.elapsed());
+}
@@ -0,0 +1,127 @@
+// Interactive accumulator: add and delete elements, check and print their
+// witnesses, and save or load the state, one command per line.
+//
+//     cargo run --release --example repl [prime_bits]
+use braavos_accumulator::{AccumulatorBuilder, BraavosAccumulator, UpdateMessage, Witness};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+commands:
+  add <x>         add x and keep its witness
+  del <x>         delete x and update every kept witness
+  verify <x>      check the kept witness of x
+  witness <x>     print the kept witness of x, recovering it if none is kept
+  epoch           print the epoch, member count and value
+  save <path>     write the state (including the secret key) to path
+  load <path>     replace the accumulator with the state in path
+  help            show this list
+  quit            leave";
+
+// The accumulator and the witnesses handed out so far, as a holder would keep them.
+struct Session {
+    builder: AccumulatorBuilder,
+    acc: BraavosAccumulator,
+    witnesses: BTreeMap<Vec<u8>, Witness>,
+}
+
+impl Session {
+    fn run(&mut self, command: &str, arg: Option<&str>) -> Result<String, &'static str> {
+        match (command, arg) {
+            ("add", Some(x)) => {
+                let w = self.acc.add(x.as_bytes())?;
+                self.witnesses.insert(x.as_bytes().to_vec(), w);
+                Ok(format!("added {} at epoch {}", x, self.acc.epoch()))
+            }
+            ("del", Some(x)) => {
+                if !self.acc.contains(x.as_bytes()) {
+                    return Err("Element is not a member");
+                }
+                let update = self.acc.delete(x.as_bytes())?;
+                self.witnesses.remove(x.as_bytes());
+                for (y, w) in self.witnesses.iter_mut() {
+                    let prime = self.acc.element_prime(y).ok_or("Kept witness for an unknown element")?;
+                    *w = update.apply_to_witness(&prime, w)?;
+                }
+                Ok(format!("deleted {}, now at epoch {}", x, self.acc.epoch()))
+            }
+            ("verify", Some(x)) => {
+                let w = *self.witnesses.get(x.as_bytes()).ok_or("No witness kept for this element")?;
+                Ok(if self.acc.verify(x.as_bytes(), w) { "valid" } else { "invalid" }.to_string())
+            }
+            ("witness", Some(x)) => {
+                let w = match self.witnesses.get(x.as_bytes()) {
+                    Some(w) => *w,
+                    None => self.acc.recover_witness(x.as_bytes())?,
+                };
+                Ok(hex(&w.to_bytes()))
+            }
+            ("epoch", None) => Ok(format!(
+                "epoch {}, {} members, value {}",
+                self.acc.epoch(),
+                self.acc.member_count(),
+                self.acc.value().as_uint()
+            )),
+            ("save", Some(path)) => {
+                fs::write(path, self.acc.to_state_bytes()?).map_err(|_| "Failed to write state file")?;
+                Ok(format!("saved to {}", path))
+            }
+            ("load", Some(path)) => {
+                let state = fs::read(path).map_err(|_| "Failed to read state file")?;
+                self.acc = self.builder.restore(&state)?;
+                // Kept witnesses belonged to the previous accumulator
+                self.witnesses.clear();
+                Ok(format!("loaded epoch {} with {} members", self.acc.epoch(), self.acc.member_count()))
+            }
+            ("help", None) => Ok(HELP.to_string()),
+            _ => Err("Unknown command or wrong arguments; try help"),
+        }
+    }
+}
+
+fn main() {
+    let prime_bits = std::env::args().nth(1).map_or(64, |a| a.parse().expect("prime_bits must be a number"));
+    let builder = BraavosAccumulator::builder().prime_bits(prime_bits);
+    let estimate = builder.security_estimate();
+    if estimate.bits < 112 {
+        println!("warning: {estimate}; for experiments only");
+    }
+    let acc = builder.clone().build().expect("Failed to create accumulator");
+    let mut session = Session {
+        builder,
+        acc,
+        witnesses: BTreeMap::new(),
+    };
+    println!("{}-bit accumulator ready; type help for commands", prime_bits * 2);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("Failed to flush stdout");
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        if command == "quit" || command == "exit" {
+            break;
+        }
+        let arg = words.next();
+        if words.next().is_some() {
+            println!("error: too many arguments");
+            continue;
+        }
+        match session.run(command, arg) {
+            Ok(out) => println!("{}", out),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,44 @@
+// Runs a churn simulation and prints what it cost, for sizing a deployment.
+// Every field of the scenario can be set as key=value:
+//
+//     cargo run --release --example simulate -- rounds=20 adds=100 deletes=10 holders=1000000 interval=4
+use braavos_accumulator::Scenario;
+use std::env;
+use std::process;
+
+fn main() {
+    let mut scenario = Scenario::default();
+    for arg in env::args().skip(1) {
+        if let Err(e) = set(&mut scenario, &arg) {
+            eprintln!("{arg}: {e}");
+            eprintln!("keys: bits, rounds, members, adds, deletes, holders, tracked, interval, seed");
+            process::exit(2);
+        }
+    }
+    match scenario.run() {
+        Ok(report) => println!("{report}"),
+        Err(e) => {
+            eprintln!("simulation failed: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn set(scenario: &mut Scenario, arg: &str) -> Result<(), &'static str> {
+    let (key, value) = arg.split_once('=').ok_or("expected key=value")?;
+    let value: u64 = value.parse().map_err(|_| "value must be a number")?;
+    let small = || u32::try_from(value).map_err(|_| "value too large");
+    match key {
+        "bits" => scenario.prime_bits = small()?,
+        "rounds" => scenario.rounds = small()?,
+        "members" => scenario.initial_members = small()?,
+        "adds" => scenario.adds_per_round = small()?,
+        "deletes" => scenario.deletes_per_round = small()?,
+        "holders" => scenario.holders = value,
+        "tracked" => scenario.tracked_holders = small()?,
+        "interval" => scenario.sync_interval = small()?,
+        "seed" => scenario.seed = value,
+        _ => return Err("unknown key"),
+    }
+    Ok(())
+}
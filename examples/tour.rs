@@ -0,0 +1,85 @@
+// Short walkthrough of the public API: an issuer hands out witnesses, revokes
+// a member, holders catch up from the published updates and a verifier that
+// only knows the public parameters checks them. The tests cover each feature
+// in depth; see the repl example to try the basics interactively.
+//
+//     cargo run --release --example tour
+use braavos_accumulator::{
+    commit_element, AllowAll, BraavosAccumulator, Decision, DeviceVerifier, Manager, NonMembershipProof, Operation,
+    Quota, Service, WitnessHolder, BLINDING_BITS,
+};
+use crypto_bigint::{RandomBits, U1024};
+use rand::rngs::OsRng;
+
+fn main() {
+    // The issuer: an accumulator behind a manager whose policy only lets
+    // "admin" revoke. Every decision goes to the audit log. 64-bit primes keep
+    // the tour quick; deployments pick a Preset.
+    let acc = BraavosAccumulator::builder().prime_bits(64).build().expect("Failed to create accumulator");
+    let only_admin_deletes = |operator: &str, operation: Operation, _x: &[u8]| match operation {
+        Operation::Delete if operator != "admin" => Decision::Deny(format!("{} may not delete", operator)),
+        _ => Decision::Allow,
+    };
+    let mut manager = Manager::new(acc, only_admin_deletes);
+    let params = manager.accumulator().params();
+    println!("Modulus: {} bits, epoch {}", params.n.bits(), manager.accumulator().epoch());
+
+    // Each member gets a witness, which it keeps in a holder
+    let mut holders = Vec::new();
+    for x in ["alice", "bob", "carol"] {
+        let w = manager.add("clerk", x).expect("Failed to add member");
+        holders.push(WitnessHolder::new(&params, x, w, 0).expect("Failed to create holder"));
+        println!("Issued a witness to {}", x);
+    }
+
+    // Revoking bob publishes an epoch update
+    let denied = manager.delete("clerk", "bob");
+    println!("clerk deleting bob: {:?}", denied);
+    let update = manager.delete("admin", "bob").expect("Failed to delete bob");
+    println!("admin deleted bob: epoch {} -> {}", update.from_epoch, update.to_epoch);
+    for record in manager.audit_log().records() {
+        println!("  audit #{}: {} {:?} -> {:?}", record.seq, record.operator, record.operation, record.decision);
+    }
+
+    // Holders catch up from the update log; bob's witness cannot be updated
+    for holder in &mut holders {
+        let name = String::from_utf8_lossy(holder.element()).into_owned();
+        match holder.sync(manager.update_log()) {
+            Ok(()) => println!("{} is current at epoch {}", name, holder.epoch()),
+            Err(e) => println!("{} could not sync: {}", name, e),
+        }
+    }
+
+    // A verifier needs only the public parameters and the published value
+    let verifier = DeviceVerifier::new(&params).expect("Failed to create verifier");
+    let value = manager.accumulator().value();
+    for holder in &holders {
+        let name = String::from_utf8_lossy(holder.element()).into_owned();
+        println!("{} verifies: {}", name, verifier.verify(holder.element(), &holder.witness(), &value));
+    }
+
+    // Someone never added can prove so without revealing who they are
+    let prime = params.hash_to_prime("dave");
+    let blinding = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+    let witness = manager.accumulator().non_membership_witness("dave").expect("Failed to issue witness");
+    let proof = NonMembershipProof::prove(&params, &value, &prime, &blinding, &witness).expect("Failed to prove");
+    let commitment = commit_element(&params, &prime, &blinding).expect("Failed to commit");
+    let valid = proof.commitment == commitment && proof.verify(&params, &value);
+    println!("dave's committed non-membership proof verifies: {}", valid);
+
+    // The state, secret key included, can be saved and restored
+    let state = manager.accumulator().to_state_bytes().expect("Failed to encode state");
+    let restored = BraavosAccumulator::builder().restore(&state).expect("Failed to restore state");
+    println!("Restored {} members at epoch {} from {} bytes", restored.member_count(), restored.epoch(), state.len());
+
+    // Many issuers can share one service, each with its own keys and quota
+    let root = std::env::temp_dir().join(format!("braavos-tour-{}", std::process::id()));
+    let mut service = Service::new(&root).expect("Failed to start service");
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    service.open_tenant("issuer-a", b"token-a", Quota::default(), AllowAll, builder).expect("Failed to open tenant");
+    let mut session = service.session("issuer-a", b"token-a").expect("Failed to open session");
+    let w = session.add("clerk", "erin").expect("Failed to add erin");
+    println!("Tenant issuer-a verifies erin: {}", session.verify("erin", w).expect("Failed to verify"));
+    println!("Wrong token: {:?}", service.session("issuer-a", b"token-b").err());
+    std::fs::remove_dir_all(&root).expect("Failed to clean up service root");
+}
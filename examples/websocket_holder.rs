@@ -0,0 +1,36 @@
+// A holder following a manager over WebSocket: it subscribes from its epoch,
+// rides out a server-side disconnect by reconnecting and resyncing, and asks
+// the server to refresh a second witness it never updated itself.
+//
+//     cargo run --release --features websocket --example websocket_holder
+use braavos_accumulator::{BraavosAccumulator, UpdateFeed, UpdateLog, UpdateServer, WitnessHolder};
+
+fn main() {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let params = acc.params();
+    let w_alice = acc.add(b"alice").expect("Failed to add alice");
+    let w_bob = acc.add(b"bob").expect("Failed to add bob");
+    let witnesses: Vec<_> = (0..4u8).map(|i| acc.add([i]).expect("Failed to add element")).collect();
+    let server = UpdateServer::bind("127.0.0.1:0", params, UpdateLog::new()).expect("Failed to bind");
+    let mut feed = UpdateFeed::new(&server.local_addr().to_string(), 5);
+    let mut holder = WitnessHolder::new(&params, b"alice", w_alice, 0).expect("Failed to create holder");
+
+    server.publish(acc.delete([0]).unwrap()).expect("Failed to publish");
+    holder.sync_from(&mut feed, &mut ()).expect("Failed to sync");
+    println!("alice followed the live stream to epoch {}", holder.epoch());
+
+    // Two deletions while the connection is down: resubscribing from epoch 1
+    // delivers both, coalesced
+    server.disconnect_all();
+    server.publish(acc.delete([1]).unwrap()).expect("Failed to publish");
+    server.publish(acc.delete([2]).unwrap()).expect("Failed to publish");
+    holder.sync_from(&mut feed, &mut ()).expect("Failed to resync");
+    assert_eq!(holder.epoch(), 3);
+    assert!(acc.verify(b"alice", holder.witness()), "Resynced witness failed");
+    println!("alice reconnected and resynced to epoch {}", holder.epoch());
+
+    let (w_bob, epoch) = feed.refresh(b"bob", &w_bob, 0).expect("Failed to refresh");
+    assert!(epoch == 3 && acc.verify(b"bob", w_bob), "Refreshed witness failed");
+    println!("bob's witness refreshed by the server to epoch {}", epoch);
+    assert_eq!(feed.refresh([1], &witnesses[1], 0), Err("Element has been deleted"));
+}
@@ -0,0 +1,11 @@
+#!/bin/sh
+# Lints the minimal profile, builds and lints every pair of features, runs
+# the tests with each feature on its own, and prints what the minimal profile
+# links. Needs cargo-hack: cargo install cargo-hack
+set -eu
+cd "$(dirname "$0")/.."
+
+cargo clippy --no-default-features --features minimal --all-targets -- -D warnings
+cargo hack clippy --feature-powerset --depth 2 --all-targets -- -D warnings
+cargo hack test --each-feature
+cargo tree --no-default-features --features minimal --edges normal
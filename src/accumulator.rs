@@ -0,0 +1,1455 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    U256, U512, Odd, CheckedMul, Random,
+};
+#[cfg(feature = "zk")]
+use crypto_bigint::{NonZero, RandomBits, U1024};
+use crypto_primes::{generate_safe_prime, is_safe_prime, is_prime};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::blind::{BlindIssuance, BlindedPrime};
+use crate::builder::{AccumulatorBuilder, DuplicatePolicy};
+use crate::cache::VerificationCache;
+#[cfg(feature = "cardinality")]
+use crate::cardinality::{CardinalityProof, CountInclusionProof, CountTree};
+use crate::core;
+use crate::deadline::Deadline;
+#[cfg(feature = "zk")]
+use crate::delegation::DelegatedPresentation;
+use crate::element;
+use crate::bundle::{BundleEntry, StaticBundle};
+use crate::exp::{pow_adaptive, ExpStrategy, FixedBaseTable};
+use crate::expiry::ExpiryPolicy;
+use crate::failure::VerifyFailure;
+#[cfg(feature = "zk")]
+use crate::freshness::FreshnessProof;
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::hash::{self, HashAlgorithm};
+use crate::integrity::IntegrityError;
+use crate::migrate::{self, State};
+use crate::params::PublicParams;
+use crate::receipt::{self, IssuanceProof, IssuanceReceipt, IssuedBatch};
+use crate::registry::{ElementRegistry, RegistryEntry};
+use crate::replace::{ReplaceProof, Replacement};
+use crate::report::{StateReport, REPORTED_OPERATIONS};
+use crate::rotation::{self, KeyStatus, KeyUsage, RotationPolicy};
+use crate::security::SecurityEstimate;
+use crate::sharing::SecretKey;
+use crate::signature;
+#[cfg(feature = "threshold")]
+use crate::threshold::{self, Custodian, ThresholdAccumulator};
+use crate::update::{EpochUpdate, UpdateMessage};
+use crate::verification::CertifiedWitness;
+use crate::witness::{EncryptedWitness, NonMembershipWitness, Witness, WitnessEncryptor};
+#[cfg(feature = "zk")]
+use crate::zk::{CompositeProof, RerandomizationProof, BLINDING_BITS, RERANDOMIZATION_BITS};
+
+const BELOW_MIN_SECURITY: &str = "Configuration is below the minimum security level";
+#[cfg(feature = "cardinality")]
+const CARDINALITY_OFF: &str = "Cardinality tracking is not enabled";
+const KEYGEN_ATTEMPTS: usize = 8; // Prime pairs drawn before keygen gives up
+
+// A trapdoor result that failed its double check (see the builder's
+// double_check). Nothing was published or changed.
+pub const FAULT_DETECTED: &str = "Trapdoor result failed its double check";
+
+pub struct BraavosAccumulator {
+    sk: U256,        // p'q' = (p-1)/2 * (q-1)/2
+    n: Odd<U512>,    // RSA modulus as an Odd type
+    a: MontyForm<8>, // Current accumulator value in Montgomery form
+    base: AccValue,  // Accumulator value at setup
+    seed: Option<[u8; 32]>, // Public seed the base was derived from
+    genesis: Option<[u8; 32]>, // Digest of the GenesisRecord of the setup
+    prf_key: U256,   // Public key mixed into hash-to-prime
+    hash: HashAlgorithm, // Digest for hash-to-prime and signatures
+    element_cache: HashMap<Vec<u8>, ElemPrime>, // Cache for x -> prime mapping
+    members: BTreeSet<Vec<u8>>, // Elements currently accumulated
+    deleted: Vec<ElemPrime>,    // Primes of deleted elements, in deletion order
+    epoch: u64,                 // Number of deletions applied so far
+    params_digest: [u8; 32],    // PublicParams::digest(), cached for verify
+    checkpoints: VecDeque<(u64, AccValue)>, // Values of recent past epochs, oldest first
+    checkpoint_capacity: usize,
+    monty_params: MontyParams<8>, // Montgomery parameters for modular arithmetic
+    exp_strategy: ExpStrategy,    // Exponentiation used for trapdoor operations
+    strict: bool,                 // Re-derive cached primes on add, check state on load
+    double_check: bool,           // Re-verify every trapdoor result before using it
+    table_window: Option<u32>,    // Window of the fixed-base table, if one fits the budget
+    value_table: Option<FixedBaseTable>, // Precomputed powers of the current value
+    registry: Option<Arc<ElementRegistry>>, // Primes shared with other accumulators
+    verification_cache: Option<Arc<VerificationCache>>, // Recent verify results
+    #[cfg(feature = "cardinality")]
+    cardinality: Option<CountTree>,                     // Count-carrying tree of the members, if tracked
+    duplicates: DuplicatePolicy,                        // What adding a member again does
+    copies: BTreeMap<Vec<u8>, u64>,                     // Copies beyond the first, in multiset mode
+    key_operations: AtomicU64,                          // Trapdoor operations, see KeyUsage
+    key_created_at: Option<u64>,                        // Unix seconds at key generation, if known
+    rotation: RotationPolicy,                           // When key_status advises rotating
+    external: BTreeSet<Vec<u8>>,                        // Labels whose primes came from add_prime
+}
+
+impl BraavosAccumulator {
+    pub fn new(prime_bits: u32) -> Result<Self, &'static str> {
+        Self::builder().prime_bits(prime_bits).build()
+    }
+
+    pub fn builder() -> AccumulatorBuilder {
+        AccumulatorBuilder::new()
+    }
+
+    pub(crate) fn from_builder(builder: AccumulatorBuilder) -> Result<Self, &'static str> {
+        let prime_bits = builder.prime_bits;
+        if let Some(preset) = builder.preset {
+            preset.check_supported()?;
+        }
+        // sk = p'q' is held in 256 bits
+        if prime_bits > 128 {
+            return Err("Prime size must be at most 128 bits");
+        }
+        if builder.security_estimate().bits < builder.min_security_bits {
+            return Err(BELOW_MIN_SECURITY);
+        }
+
+        // Ceremony contributions, if any, fix the base seed
+        let contributed = builder.genesis.as_ref().and_then(|g| g.contribution_seed(builder.hash));
+        if builder.base_seed.zip(contributed).is_some_and(|(seed, contributed)| seed != contributed) {
+            return Err("Base seed conflicts with the genesis contributions");
+        }
+
+        // Generate safe primes using crypto-primes, or take them from the pool.
+        // A pool loaded from disk may hold anything, so candidates are checked
+        // and drawn again rather than trusted.
+        #[cfg(feature = "pool")]
+        if let Some(pool) = &builder.prime_pool {
+            if pool.bits() != prime_bits {
+                return Err("Prime pool generates primes of a different size");
+            }
+        }
+        let mut attempts = 0;
+        let (p, q) = loop {
+            if attempts == KEYGEN_ATTEMPTS {
+                return Err("Failed to generate distinct safe primes");
+            }
+            attempts += 1;
+            #[cfg(feature = "pool")]
+            let (p, q) = match &builder.prime_pool {
+                Some(pool) => (pool.take(), pool.take()),
+                None => (generate_safe_prime::<U256>(prime_bits), generate_safe_prime::<U256>(prime_bits)),
+            };
+            #[cfg(not(feature = "pool"))]
+            let (p, q) = (generate_safe_prime::<U256>(prime_bits), generate_safe_prime::<U256>(prime_bits));
+            if p != q && is_safe_prime(&p) && is_safe_prime(&q) {
+                break (p, q);
+            }
+        };
+
+        // Calculate p' and q' where p = 2p' + 1 and q = 2q' + 1
+        let p_prime = (p - U256::ONE).shr_vartime(1);
+        let q_prime = (q - U256::ONE).shr_vartime(1);
+
+        // Verify that p' and q' are also prime
+        if !is_prime(&p_prime) || !is_prime(&q_prime) {
+            return Err("Generated safe primes have non-prime p' and q'");
+        }
+
+        // Additional verification: ensure p' and q' are actually the Sophie Germain primes
+        // that correspond to p and q being safe primes
+        let p_check: Option<U256> = p_prime.checked_mul(&U256::from(2u32)).into();
+        let q_check: Option<U256> = q_prime.checked_mul(&U256::from(2u32)).into();
+        if p_check.map(|c| c + U256::ONE) != Some(p) || q_check.map(|c| c + U256::ONE) != Some(q) {
+            return Err("Generated primes are not proper safe primes");
+        }
+
+        // Convert U256 primes to U512 with proper padding
+        let p_512 = pad_u256_to_u512(p);
+        let q_512 = pad_u256_to_u512(q);
+
+        let n: Option<U512> = p_512.checked_mul(&q_512).into();
+        let n_odd: Option<Odd<U512>> = Odd::new(n.ok_or("RSA modulus overflows 512 bits")?).into();
+        let n_odd = n_odd.ok_or("RSA modulus must be odd")?;
+
+        let sk: Option<U256> = p_prime.checked_mul(&q_prime).into();
+        let sk = sk.ok_or("Secret key overflows 256 bits")?;
+
+        // Initialize Montgomery parameters
+        let monty_params = MontyParams::new(n_odd);
+
+        // Derive a = (a')^2 mod n with a' hashed from n and a public seed, so
+        // anyone can check the base was not chosen
+        let seed = builder.base_seed.or(contributed).unwrap_or_else(|| {
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            seed
+        });
+        let base = core::derive_base(builder.hash, &seed, &monty_params);
+        let a = MontyForm::new(base.as_uint(), monty_params);
+
+        // Generate random hash-to-prime key, or share the registry's
+        let prf_key = match &builder.element_registry {
+            Some(registry) if registry.hash() != builder.hash => return Err("Element registry uses a different hash"),
+            Some(registry) => registry.key(),
+            None => U256::random(&mut OsRng),
+        };
+
+        let mut acc = Self {
+            sk,
+            n: n_odd,
+            a,
+            base,
+            seed: Some(seed),
+            genesis: builder.genesis.as_ref().map(|g| g.digest(builder.hash)),
+            prf_key,
+            hash: builder.hash,
+            element_cache: HashMap::new(),
+            members: BTreeSet::new(),
+            deleted: Vec::new(),
+            epoch: 0,
+            params_digest: [0; 32],
+            checkpoints: VecDeque::new(),
+            checkpoint_capacity: builder.checkpoints,
+            monty_params,
+            exp_strategy: builder.exp_strategy,
+            strict: builder.strict,
+            double_check: builder.double_check,
+            table_window: FixedBaseTable::window_for_budget(builder.precompute_budget),
+            value_table: None,
+            registry: builder.element_registry,
+            verification_cache: builder.verification_cache,
+            #[cfg(feature = "cardinality")]
+            cardinality: builder.track_cardinality.then(|| CountTree::new(builder.hash)),
+            duplicates: builder.duplicates,
+            copies: BTreeMap::new(),
+            key_operations: AtomicU64::new(0),
+            key_created_at: rotation::unix_now(),
+            rotation: builder.rotation,
+            external: BTreeSet::new(),
+        };
+        acc.params_digest = acc.params().digest();
+        acc.refresh_table();
+        Ok(acc)
+    }
+
+    // Full state including the secret key, for persisting the accumulator, in
+    // the latest layout. Load it back with AccumulatorBuilder::restore.
+    pub fn to_state_bytes(&self) -> Result<Vec<u8>, &'static str> {
+        Ok(self.to_state()?.to_bytes())
+    }
+
+    pub(crate) fn to_state(&self) -> Result<State, &'static str> {
+        Ok(State {
+            params: self.params(),
+            secret_key: self.secret_key(),
+            value: self.value(),
+            epoch: self.epoch,
+            members: self.members.iter().map(|x| Ok((x.clone(), self.member_prime(x)?))).collect::<Result<_, _>>()?,
+            deleted: self.deleted.clone(),
+            copies: self.copies.iter().map(|(x, copies)| (x.clone(), *copies)).collect(),
+            key_usage: self.key_usage(),
+            external: self.external.iter().filter(|x| self.members.contains(*x)).cloned().collect(),
+        })
+    }
+
+    // Accepts every layout migrate::upgrade does. Runtime options
+    // (exponentiation, checkpoints) come from the builder; its key generation
+    // settings are ignored. Checkpoints are not persisted.
+    pub(crate) fn from_state_bytes(bytes: &[u8], builder: &AccumulatorBuilder) -> Result<Self, &'static str> {
+        let State {
+            params,
+            secret_key,
+            value,
+            epoch,
+            members: entries,
+            deleted,
+            copies,
+            key_usage,
+            external,
+        } = migrate::upgrade(bytes)?;
+        let sk = *secret_key.as_uint();
+        let value = *value.as_uint();
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let n = n.ok_or("Modulus must be odd")?;
+        let monty_params = MontyParams::new(n);
+
+        let mut members = BTreeSet::new();
+        let mut element_cache = HashMap::new();
+        for (x, prime) in entries {
+            element_cache.insert(x.clone(), prime);
+            if !members.insert(x) {
+                return Err("Duplicate member in state");
+            }
+        }
+        if !copies.is_empty() && builder.duplicates != DuplicatePolicy::Multiset {
+            return Err("State counts copies of members; restore it in multiset mode");
+        }
+        if copies.iter().any(|(x, copies)| *copies == 0 || !members.contains(x)) {
+            return Err("Invalid member copy count in state");
+        }
+        if external.iter().any(|x| !members.contains(x)) {
+            return Err("Supplied prime for a non-member in state");
+        }
+        if value >= params.n {
+            return Err("Accumulator value out of range");
+        }
+        if SecurityEstimate::for_params(&params).bits < builder.min_security_bits {
+            return Err(BELOW_MIN_SECURITY);
+        }
+        if let Some(preset) = builder.preset {
+            preset.check_params(&params)?;
+        }
+        if builder.element_registry.as_ref().is_some_and(|r| !r.matches(&params)) {
+            return Err("Element registry uses a different hash-to-prime key");
+        }
+
+        let mut acc = Self {
+            sk,
+            n,
+            a: MontyForm::new(&value, monty_params),
+            base: params.base,
+            seed: params.seed,
+            genesis: params.genesis,
+            prf_key: params.prf_key,
+            hash: params.hash,
+            element_cache,
+            members,
+            deleted,
+            epoch,
+            params_digest: params.digest(),
+            checkpoints: VecDeque::new(),
+            checkpoint_capacity: builder.checkpoints,
+            monty_params,
+            exp_strategy: builder.exp_strategy,
+            strict: builder.strict,
+            double_check: builder.double_check,
+            table_window: FixedBaseTable::window_for_budget(builder.precompute_budget),
+            value_table: None,
+            registry: builder.element_registry.clone(),
+            verification_cache: builder.verification_cache.clone(),
+            #[cfg(feature = "cardinality")]
+            cardinality: None,
+            duplicates: builder.duplicates,
+            copies: copies.into_iter().collect(),
+            key_operations: AtomicU64::new(key_usage.operations),
+            key_created_at: key_usage.created_at,
+            rotation: builder.rotation,
+            external: external.into_iter().collect(),
+        };
+        #[cfg(feature = "cardinality")]
+        if builder.track_cardinality {
+            let mut tree = CountTree::new(acc.hash);
+            for x in &acc.members {
+                tree.insert(x);
+            }
+            acc.cardinality = Some(tree);
+        }
+        if acc.strict {
+            acc.check_integrity().map_err(|_| "State failed the integrity check")?;
+        }
+        acc.refresh_table();
+        Ok(acc)
+    }
+
+    // Cross-checks the key, value, member set, element cache and deletion
+    // history against each other. Costs one hash-to-prime per member.
+    pub fn check_integrity(&self) -> Result<(), IntegrityError> {
+        let n = *self.n.as_ref();
+        let base = *self.base.as_uint();
+        if self.value().as_uint() == &U512::ZERO || base == U512::ZERO || base >= n {
+            return Err(IntegrityError::ValueOutOfRange);
+        }
+        // QR_n has order p'q', so both must vanish under the secret key
+        let sk = pad_u256_to_u512(self.sk);
+        let one = MontyForm::one(self.monty_params);
+        let base = MontyForm::new(&base, self.monty_params);
+        if self.mont_mod_exp(self.a, &sk) != one || self.mont_mod_exp(base, &sk) != one {
+            return Err(IntegrityError::KeyMismatch);
+        }
+        let params = self.params();
+        for x in &self.members {
+            let Some(cached) = self.element_cache.get(x) else {
+                return Err(IntegrityError::MissingPrime { element: x.clone() });
+            };
+            let expected = match self.external.contains(x) {
+                true => is_prime(cached.as_uint()),
+                false => *cached == params.hash_to_prime(x),
+            };
+            if !expected {
+                return Err(IntegrityError::PrimeMismatch { element: x.clone() });
+            }
+        }
+        if self.epoch != self.deleted.len() as u64 {
+            return Err(IntegrityError::EpochMismatch {
+                epoch: self.epoch,
+                deletions: self.deleted.len(),
+            });
+        }
+        Ok(())
+    }
+
+    // The invariant a delete must leave behind, for tests and audits of
+    // deployments: after `update` deleted x, x is not a member, the update
+    // removes x's prime, and none of `witnesses` (x's witnesses from before
+    // the deletion) opens the current value, as they are or brought forward
+    // through `update`. Only meaningful for the last copy of x, and while no
+    // later add has brought x back.
+    pub fn check_deleted(
+        &self,
+        x: impl AsRef<[u8]>,
+        update: &EpochUpdate,
+        witnesses: &[Witness],
+    ) -> Result<(), IntegrityError> {
+        let x = x.as_ref();
+        if self.members.contains(x) {
+            return Err(IntegrityError::StillMember { element: x.to_vec() });
+        }
+        let prime = self.lookup_prime(x).map_err(|_| IntegrityError::MissingPrime { element: x.to_vec() })?;
+        if !update.deleted.contains(&prime) {
+            return Err(IntegrityError::NotDeleted { element: x.to_vec() });
+        }
+        let value = self.value();
+        for w in witnesses {
+            let refreshed = update.apply_to_witness(&prime, w).ok();
+            let opens = |w: &Witness| core::opens(&w.value, &prime, &value, &self.monty_params);
+            if opens(w) || refreshed.as_ref().is_some_and(opens) {
+                return Err(IntegrityError::WitnessStillOpens { element: x.to_vec() });
+            }
+        }
+        Ok(())
+    }
+
+    // The value the deletion history leads to from the base: the base raised
+    // to the inverse of the product of deleted primes mod sk.
+    #[cfg(feature = "service")]
+    pub(crate) fn replay_value(&self) -> Result<AccValue, &'static str> {
+        let mod_sk = self.sk_params()?;
+        let mut d = MontyForm::one(mod_sk);
+        for y in &self.deleted {
+            d = d.mul(&MontyForm::new(y.as_uint(), mod_sk));
+        }
+        let d_inv: Option<MontyForm<4>> = d.inv().into();
+        let d_inv = d_inv.ok_or("Element not invertible modulo sk")?.retrieve();
+        let base = MontyForm::new(self.base.as_uint(), self.monty_params);
+        Ok(AccValue::new(self.mont_mod_exp(base, &pad_u256_to_u512(d_inv)).retrieve()))
+    }
+
+    pub fn value(&self) -> AccValue {
+        AccValue::new(self.a.retrieve())
+    }
+
+    pub fn modulus(&self) -> U512 {
+        *self.n.as_ref()
+    }
+
+    pub fn params(&self) -> PublicParams {
+        PublicParams {
+            n: *self.n.as_ref(),
+            base: self.base,
+            hash: self.hash,
+            prf_key: self.prf_key,
+            seed: self.seed,
+            genesis: self.genesis,
+            h2p_profile: hash::HASH_TO_PRIME_PROFILE,
+        }
+    }
+
+    // The trapdoor, for splitting among custodians at a key ceremony.
+    pub fn secret_key(&self) -> SecretKey {
+        SecretKey::new(self.sk)
+    }
+
+    // Hands the trapdoor to `custodians` as `tokens` one-time tokens, any
+    // `threshold` of whom can then add and delete together, and drops it from
+    // memory. Checkpoints and the deletion history are not carried over.
+    #[cfg(feature = "threshold")]
+    pub fn into_threshold(
+        self,
+        threshold: u32,
+        custodians: u32,
+        tokens: u64,
+    ) -> Result<(ThresholdAccumulator, Vec<Custodian>), &'static str> {
+        let dealt = threshold::deal(&self.secret_key(), threshold, custodians, tokens)?;
+        let members = self
+            .members
+            .iter()
+            .map(|x| Ok((x.clone(), self.member_prime(x)?)))
+            .collect::<Result<_, &'static str>>()?;
+        let acc = ThresholdAccumulator::new(self.params(), self.value(), self.epoch, members, threshold, tokens)?;
+        Ok((acc, dealt))
+    }
+
+    pub fn key_usage(&self) -> KeyUsage {
+        KeyUsage {
+            operations: self.key_operations.load(Ordering::Relaxed),
+            created_at: self.key_created_at,
+        }
+    }
+
+    // Whether the builder's rotation thresholds have been passed.
+    pub fn key_status(&self) -> KeyStatus {
+        self.key_status_at(SystemTime::now())
+    }
+
+    pub fn key_status_at(&self, now: SystemTime) -> KeyStatus {
+        self.rotation.status(&self.key_usage(), now)
+    }
+
+    // Counts `count` trapdoor operations towards KeyUsage.
+    fn use_trapdoor(&self, count: usize) {
+        self.key_operations.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    // Memory held by the fixed-base table, zero when precomputation is off.
+    pub fn precomputed_bytes(&self) -> usize {
+        self.value_table.as_ref().map_or(0, FixedBaseTable::bytes)
+    }
+
+    // Diagnostic snapshot for bug reports (see StateReport).
+    pub fn report(&self) -> StateReport {
+        let usage = self.key_usage();
+        let now = SystemTime::now();
+        StateReport {
+            modulus_bits: self.n.bits(),
+            hash: self.hash,
+            params_digest: self.params_digest,
+            epoch: self.epoch,
+            member_count: self.members.len(),
+            deleted_count: self.deleted.len(),
+            element_cache_entries: self.element_cache.len(),
+            precomputed_bytes: self.precomputed_bytes(),
+            verification_cache_entries: self.verification_cache.as_ref().map(|cache| cache.len()),
+            checkpoints_kept: self.checkpoints.len(),
+            key_operations: usage.operations,
+            key_age_secs: usage.age(now).map(|age| age.as_secs()),
+            key_status: self.rotation.status(&usage, now),
+            recent_deletions: self.deleted[self.deleted.len().saturating_sub(REPORTED_OPERATIONS)..].to_vec(),
+            recent_operations: Vec::new(),
+        }
+    }
+
+    // Rebuilds the fixed-base table after the value changes.
+    fn refresh_table(&mut self) {
+        if let Some(window) = self.table_window {
+            self.value_table = Some(FixedBaseTable::new(&self.a.retrieve(), window, &self.monty_params));
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn contains(&self, x: impl AsRef<[u8]>) -> bool {
+        self.members.contains(x.as_ref())
+    }
+
+    pub fn member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    // How many times x is accumulated: 0 or 1 unless in multiset mode.
+    pub fn multiplicity(&self, x: impl AsRef<[u8]>) -> u64 {
+        let x = x.as_ref();
+        match self.members.contains(x) {
+            true => 1 + self.copies.get(x).copied().unwrap_or(0),
+            false => 0,
+        }
+    }
+
+    pub fn element_prime(&self, x: impl AsRef<[u8]>) -> Option<ElemPrime> {
+        self.element_cache.get(x.as_ref()).copied()
+    }
+
+    fn element_within(&mut self, x: &[u8], deadline: &Deadline) -> Result<ElemPrime, &'static str> {
+        if let Some(&prime) = self.element_cache.get(x) {
+            return Ok(prime);
+        }
+        let prime = self.derive_prime(x, deadline)?;
+        self.element_cache.insert(x.to_vec(), prime);
+        Ok(prime)
+    }
+
+    // A member's prime, cached when it was added.
+    fn member_prime(&self, x: &[u8]) -> Result<ElemPrime, &'static str> {
+        self.element_prime(x).ok_or("Member has no cached prime")
+    }
+
+    // x's prime from the cache, the shared registry or a fresh search.
+    fn lookup_prime(&self, x: &[u8]) -> Result<ElemPrime, &'static str> {
+        match self.element_prime(x) {
+            Some(prime) => Ok(prime),
+            None => self.derive_prime(x, &Deadline::none()),
+        }
+    }
+
+    fn derive_prime(&self, x: &[u8], deadline: &Deadline) -> Result<ElemPrime, &'static str> {
+        match &self.registry {
+            Some(registry) => registry.prime_within(x, deadline),
+            None => self.params().hash_to_prime_within(x, deadline),
+        }
+    }
+
+    pub fn add(&mut self, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        self.add_within(x, &Deadline::none())
+    }
+
+    // add, failing with TIMEOUT or CANCELLED if the deadline passes first.
+    pub fn add_within(&mut self, x: impl AsRef<[u8]>, deadline: &Deadline) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.check_duplicate(x)?;
+        let elem = self.element_for_add(x, deadline)?;
+        deadline.check()?;
+        let w = self.root_of_value(&elem)?;
+        self.insert_member(x);
+        Ok(self.witness(w))
+    }
+
+    // Adds a member whose prime another system derived, e.g. from a CL
+    // credential, under `label`. The prime must be a 256-bit prime like
+    // those hash-to-prime gives, so it cannot divide the order of the group,
+    // and not another member's, which costs a scan of the members.
+    // Verifiers outside need the prime: hash-to-prime of the label differs.
+    pub fn add_prime(&mut self, prime: ElemPrime, label: &[u8]) -> Result<Witness, &'static str> {
+        element::check(label)?;
+        if prime.as_uint().bits() != hash::ELEMENT_PRIME_BITS || !is_prime(prime.as_uint()) {
+            return Err("Supplied element prime must be a 256-bit prime");
+        }
+        match self.element_cache.get(label) {
+            Some(cached) if self.members.contains(label) && *cached != prime => {
+                return Err("Label is a member with a different prime");
+            }
+            _ => {}
+        }
+        if self.members.iter().any(|x| x.as_slice() != label && self.element_cache.get(x) == Some(&prime)) {
+            return Err("Prime belongs to another member");
+        }
+        self.check_duplicate(label)?;
+        let w = self.root_of_value(&prime)?;
+        self.element_cache.insert(label.to_vec(), prime);
+        self.external.insert(label.to_vec());
+        self.insert_member(label);
+        Ok(self.witness(w))
+    }
+
+    // w with x's prime and its certificate, so verifiers need not search for
+    // the prime (see VerificationPolicy). Taken from the shared registry when
+    // it has one, else searched for; primes from add_prime have none.
+    pub fn certify(&self, x: impl AsRef<[u8]>, w: Witness) -> Result<CertifiedWitness, &'static str> {
+        let x = x.as_ref();
+        if self.external.contains(x) {
+            return Err("Element prime was supplied externally");
+        }
+        let entry = match self.registry.as_ref().and_then(|registry| registry.entry(x)) {
+            Some(entry) => entry,
+            None => RegistryEntry::search(self.hash, &self.prf_key, x, &Deadline::none())?,
+        };
+        Ok(CertifiedWitness { witness: w, entry })
+    }
+
+    // issue_witnesses, with a signed Merkle commitment to the batch and each
+    // element's proof of being in it (see IssuanceReceipt). The batch must
+    // not be empty.
+    pub fn issue_witnesses_with_receipt<T: AsRef<[u8]>>(&mut self, xs: &[T]) -> Result<IssuedBatch, &'static str> {
+        if xs.is_empty() {
+            return Err("Batch is empty");
+        }
+        let issued = self.issue_witnesses(xs)?;
+        let leaves = xs.iter().zip(&issued).map(|(x, (_, w))| receipt::leaf(self.hash, x.as_ref(), w)).collect();
+        let (root, paths) = receipt::commit(self.hash, leaves);
+        let mut receipt = IssuanceReceipt {
+            params_digest: self.params_digest,
+            epoch: self.epoch,
+            size: xs.len() as u64,
+            root,
+            signature: U512::ZERO,
+        };
+        receipt.signature = self.sign(&receipt.signed_bytes())?;
+        let issued = issued
+            .into_iter()
+            .zip(paths)
+            .enumerate()
+            .map(|(index, ((prime, w), siblings))| (prime, w, IssuanceProof { index: index as u64, siblings }))
+            .collect();
+        Ok(IssuedBatch { receipt, issued })
+    }
+
+    // Adds every element and returns its prime and witness, in input order.
+    // The inverses mod sk share one modular inversion, and every prime is
+    // derived before any element is added.
+    pub fn issue_witnesses<T: AsRef<[u8]>>(&mut self, xs: &[T]) -> Result<Vec<(ElemPrime, Witness)>, &'static str> {
+        let deadline = Deadline::none();
+        if self.duplicates == DuplicatePolicy::Reject {
+            let mut seen = BTreeSet::new();
+            for x in xs {
+                self.check_duplicate(x.as_ref())?;
+                if !seen.insert(x.as_ref()) {
+                    return Err("Element appears twice in the batch");
+                }
+            }
+        }
+        let primes = xs.iter().map(|x| self.element_for_add(x.as_ref(), &deadline)).collect::<Result<Vec<_>, _>>()?;
+        let inverses = core::batch_inverse(&primes, &self.sk).ok_or("Element not invertible modulo sk")?;
+        self.use_trapdoor(primes.len());
+        let value = self.value();
+        let mut roots = Vec::with_capacity(xs.len());
+        for (prime, inverse) in primes.iter().zip(inverses) {
+            let w = match &self.value_table {
+                Some(table) => table.pow(&inverse, &self.monty_params).retrieve(),
+                None => core::pow(self.exp_strategy, value.as_uint(), &pad_u256_to_u512(inverse), &self.monty_params),
+            };
+            let w = WitnessValue::new(w);
+            self.check_root(&w, prime, &value)?;
+            roots.push(w);
+        }
+        let mut issued = Vec::with_capacity(xs.len());
+        for ((x, prime), w) in xs.iter().zip(primes).zip(roots) {
+            self.insert_member(x.as_ref());
+            issued.push((prime, self.witness(w)));
+        }
+        Ok(issued)
+    }
+
+    // x's prime, re-derived in strict mode to catch a corrupted cache entry.
+    fn element_for_add(&mut self, x: &[u8], deadline: &Deadline) -> Result<ElemPrime, &'static str> {
+        element::check(x)?;
+        if self.strict && !self.external.contains(x) {
+            if let Some(&cached) = self.element_cache.get(x) {
+                if cached != self.params().hash_to_prime_within(x, deadline)? {
+                    return Err("Element is cached with a different prime");
+                }
+            }
+        }
+        self.element_within(x, deadline)
+    }
+
+    fn check_duplicate(&self, x: &[u8]) -> Result<(), &'static str> {
+        match self.duplicates == DuplicatePolicy::Reject && self.members.contains(x) {
+            true => Err("Element is already a member"),
+            false => Ok(()),
+        }
+    }
+
+    fn insert_member(&mut self, x: &[u8]) {
+        if self.members.insert(x.to_vec()) {
+            #[cfg(feature = "cardinality")]
+            if let Some(tree) = &mut self.cardinality {
+                tree.insert(x);
+            }
+        } else if self.duplicates == DuplicatePolicy::Multiset {
+            *self.copies.entry(x.to_vec()).or_insert(0) += 1;
+        }
+    }
+
+    // In multiset mode, whether deleting x only drops one of its copies.
+    fn has_copies(&self, x: &[u8]) -> bool {
+        self.copies.contains_key(x)
+    }
+
+    fn drop_copy(&mut self, x: &[u8]) {
+        if let Some(copies) = self.copies.get_mut(x) {
+            *copies -= 1;
+            if *copies == 0 {
+                self.copies.remove(x);
+            }
+        }
+    }
+
+    // The update for a delete that only dropped copies: no deletions, and
+    // the value and epoch stay as they are.
+    fn unchanged_update(&self) -> EpochUpdate {
+        EpochUpdate {
+            from_epoch: self.epoch,
+            to_epoch: self.epoch,
+            n: *self.n.as_ref(),
+            params_digest: self.params_digest,
+            deleted: Vec::new(),
+            value: self.value(),
+        }
+    }
+
+    pub fn delete(&mut self, x: impl AsRef<[u8]>) -> Result<EpochUpdate, &'static str> {
+        self.delete_within(x, &Deadline::none())
+    }
+
+    // delete, failing with TIMEOUT or CANCELLED if the deadline passes first.
+    pub fn delete_within(&mut self, x: impl AsRef<[u8]>, deadline: &Deadline) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        if self.has_copies(x) {
+            self.drop_copy(x);
+            return Ok(self.unchanged_update());
+        }
+        if !self.members.contains(x) {
+            return Err("Element is not a member");
+        }
+        // Step 1: Check that x is an odd prime (already done in element_within)
+        let elem = self.element_within(x, deadline)?;
+        deadline.check()?;
+
+        // Step 2: Let a = a^(x^-1 mod sk), which is exactly x's witness
+        let new_a = self.root_of_value(&elem)?.into_value_after_deletion();
+
+        // Step 3 & 4: Update accumulator and return the update for witness holders
+        let update = EpochUpdate {
+            from_epoch: self.epoch,
+            to_epoch: self.epoch + 1,
+            n: *self.n.as_ref(),
+            params_digest: self.params_digest,
+            deleted: vec![elem],
+            value: new_a,
+        };
+        self.apply_deletion(&[x], &update);
+        Ok(update)
+    }
+
+    // Deletes every element in one epoch, so holders and relays see a single
+    // update however many were revoked. Nothing changes if any step fails.
+    pub fn delete_batch<T: AsRef<[u8]>>(&mut self, xs: &[T]) -> Result<EpochUpdate, &'static str> {
+        self.delete_batch_within(xs, &Deadline::none())
+    }
+
+    pub fn delete_batch_within<T: AsRef<[u8]>>(
+        &mut self,
+        xs: &[T],
+        deadline: &Deadline,
+    ) -> Result<EpochUpdate, &'static str> {
+        if xs.is_empty() {
+            return Err("Batch is empty");
+        }
+        let mut deleted = Vec::with_capacity(xs.len());
+        let mut removed = Vec::with_capacity(xs.len());
+        let mut dropped = Vec::new();
+        let mut value = self.value();
+        for x in xs {
+            if self.has_copies(x.as_ref()) {
+                if dropped.contains(&x.as_ref()) {
+                    return Err("Element appears twice in the batch");
+                }
+                dropped.push(x.as_ref());
+                continue;
+            }
+            if !self.members.contains(x.as_ref()) {
+                return Err("Element is not a member");
+            }
+            let elem = self.element_within(x.as_ref(), deadline)?;
+            if deleted.contains(&elem) {
+                return Err("Element appears twice in the batch");
+            }
+            deadline.check()?;
+            self.use_trapdoor(1);
+            let root = core::root(&value, &elem, &self.sk, self.exp_strategy, &self.monty_params)
+                .ok_or("Element not invertible modulo sk")?;
+            self.check_root(&root, &elem, &value)?;
+            value = root.into_value_after_deletion();
+            deleted.push(elem);
+            removed.push(x.as_ref());
+        }
+        for x in dropped {
+            self.drop_copy(x);
+        }
+        if deleted.is_empty() {
+            return Ok(self.unchanged_update());
+        }
+        let update = EpochUpdate {
+            from_epoch: self.epoch,
+            to_epoch: self.epoch + 1,
+            n: *self.n.as_ref(),
+            params_digest: self.params_digest,
+            deleted,
+            value,
+        };
+        self.apply_deletion(&removed, &update);
+        Ok(update)
+    }
+
+    // Swaps x_old for x_new in one epoch: the single update deletes x_old,
+    // and x_new's witness opens the value it ends at, so holders update once
+    // and no epoch has both members or neither. The proof ties the two
+    // together (see ReplaceProof). x_old must be a member without other
+    // copies; nothing changes if any step fails.
+    pub fn replace(&mut self, x_old: impl AsRef<[u8]>, x_new: impl AsRef<[u8]>) -> Result<Replacement, &'static str> {
+        let (x_old, x_new) = (x_old.as_ref(), x_new.as_ref());
+        if x_old == x_new {
+            return Err("Element cannot replace itself");
+        }
+        if !self.members.contains(x_old) {
+            return Err("Replaced element is not a member");
+        }
+        if self.has_copies(x_old) {
+            return Err("Replaced element has other copies");
+        }
+        self.check_duplicate(x_new)?;
+        let removed = self.member_prime(x_old)?;
+        let added = self.element_for_add(x_new, &Deadline::none())?;
+        let value = self.value();
+        self.use_trapdoor(2);
+        let root = core::root(&value, &removed, &self.sk, self.exp_strategy, &self.monty_params)
+            .ok_or("Element not invertible modulo sk")?;
+        self.check_root(&root, &removed, &value)?;
+        let value = root.into_value_after_deletion();
+        let w = core::root(&value, &added, &self.sk, self.exp_strategy, &self.monty_params)
+            .ok_or("Element not invertible modulo sk")?;
+        self.check_root(&w, &added, &value)?;
+        let update = EpochUpdate {
+            from_epoch: self.epoch,
+            to_epoch: self.epoch + 1,
+            n: *self.n.as_ref(),
+            params_digest: self.params_digest,
+            deleted: vec![removed],
+            value,
+        };
+        let mut proof = ReplaceProof {
+            params_digest: self.params_digest,
+            epoch: update.to_epoch,
+            removed,
+            added,
+            value,
+            signature: U512::ZERO,
+        };
+        proof.signature = self.sign(&proof.signed_bytes())?;
+        self.apply_deletion(&[x_old], &update);
+        self.insert_member(x_new);
+        Ok(Replacement {
+            update,
+            witness: self.witness(w),
+            proof,
+        })
+    }
+
+    // Deletion without the trapdoor: the holder's witness becomes the new value.
+    pub fn delete_with_witness(&mut self, x: impl AsRef<[u8]>, w: Witness) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        if self.has_copies(x) {
+            return Err("Element has other copies; delete it with the trapdoor");
+        }
+        let update = EpochUpdate::from_witness_deletion(&self.params(), self.epoch, &self.value(), x, &w)?;
+        self.element_cache.insert(x.to_vec(), update.deleted[0]);
+        self.apply_deletion(&[x], &update);
+        Ok(update)
+    }
+
+    fn apply_deletion(&mut self, xs: &[&[u8]], update: &EpochUpdate) {
+        if self.checkpoint_capacity > 0 {
+            if self.checkpoints.len() == self.checkpoint_capacity {
+                self.checkpoints.pop_front();
+            }
+            self.checkpoints.push_back((self.epoch, self.value()));
+        }
+        self.a = MontyForm::new(update.value.as_uint(), self.monty_params);
+        for x in xs {
+            #[cfg(feature = "cardinality")]
+            if self.members.remove(*x) {
+                if let Some(tree) = &mut self.cardinality {
+                    tree.remove(x);
+                }
+            }
+            #[cfg(not(feature = "cardinality"))]
+            self.members.remove(*x);
+        }
+        self.deleted.extend_from_slice(&update.deleted);
+        self.epoch = update.to_epoch;
+        self.refresh_table();
+    }
+
+    pub fn verify(&mut self, x: impl AsRef<[u8]>, w: Witness) -> bool {
+        self.verify_detailed(x, w).is_ok()
+    }
+
+    // verify, saying why a witness is rejected. A witness is only known to be
+    // stale if it opens one of the kept checkpoints (see the builder's
+    // checkpoints) and x is still a member; otherwise a witness from an older
+    // epoch, or for a deleted element, is a Mismatch.
+    pub fn verify_detailed(&mut self, x: impl AsRef<[u8]>, w: Witness) -> Result<(), VerifyFailure> {
+        let x = x.as_ref();
+        if w.params_digest != self.params_digest {
+            return Err(VerifyFailure::WrongParams);
+        }
+        if !core::is_group_element(w.value.as_uint(), self.n.as_ref()) {
+            return Err(VerifyFailure::BadGroupElement);
+        }
+        if self.verify_within(x, w, &Deadline::none()).unwrap_or(false) {
+            return Ok(());
+        }
+        if !self.members.contains(x) {
+            return Err(VerifyFailure::Mismatch);
+        }
+        let elem = self.element_within(x, &Deadline::none()).map_err(|_| VerifyFailure::Mismatch)?;
+        let opens = |(_, value): &&(u64, AccValue)| core::opens(&w.value, &elem, value, &self.monty_params);
+        match self.checkpoints.iter().rev().find(opens) {
+            Some(&(epoch, _)) => Err(VerifyFailure::StaleEpoch {
+                epoch,
+                current: self.epoch,
+            }),
+            None => Err(VerifyFailure::Mismatch),
+        }
+    }
+
+    // verify_detailed under a verifier's freshness policy: a witness opening
+    // a kept past value is accepted if the policy allows its age.
+    pub fn verify_with_expiry(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        policy: &ExpiryPolicy,
+    ) -> Result<(), VerifyFailure> {
+        let epoch = match self.verify_detailed(x, w) {
+            Ok(()) => self.epoch,
+            Err(VerifyFailure::StaleEpoch { epoch, .. }) => epoch,
+            Err(failure) => return Err(failure),
+        };
+        policy.check(epoch, self.epoch)
+    }
+
+    // verify, failing with TIMEOUT or CANCELLED if the deadline passes first.
+    pub fn verify_within(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        deadline: &Deadline,
+    ) -> Result<bool, &'static str> {
+        let x = x.as_ref();
+        if w.params_digest != self.params_digest || !core::is_group_element(w.value.as_uint(), self.n.as_ref()) {
+            return Ok(false);
+        }
+        let Some(cache) = self.verification_cache.clone() else {
+            return self.verify_uncached(x, w, deadline);
+        };
+        let (digest, value) = (self.params_digest, self.value());
+        cache.verify_with(&digest, &value, x, &w, || self.verify_uncached(x, w, deadline))
+    }
+
+    fn verify_uncached(&mut self, x: &[u8], w: Witness, deadline: &Deadline) -> Result<bool, &'static str> {
+        let elem = self.element_within(x, deadline)?;
+        deadline.check()?;
+        Ok(core::opens(&w.value, &elem, &self.value(), &self.monty_params))
+    }
+
+    // The update delete_batch(xs) would return now, without deleting anything.
+    // Elements that would only drop a multiset copy leave the value as is.
+    #[cfg(feature = "service")]
+    pub(crate) fn preview_deletion<T: AsRef<[u8]>>(&self, xs: &[T]) -> Result<EpochUpdate, &'static str> {
+        let mut deleted = Vec::with_capacity(xs.len());
+        let mut value = self.value();
+        for x in xs.iter().filter(|x| !self.has_copies(x.as_ref())) {
+            let elem = self.lookup_prime(x.as_ref())?;
+            if deleted.contains(&elem) {
+                return Err("Element appears twice in the batch");
+            }
+            self.use_trapdoor(1);
+            let root = core::root(&value, &elem, &self.sk, self.exp_strategy, &self.monty_params)
+                .ok_or("Element not invertible modulo sk")?;
+            self.check_root(&root, &elem, &value)?;
+            value = root.into_value_after_deletion();
+            deleted.push(elem);
+        }
+        if deleted.is_empty() {
+            return Ok(self.unchanged_update());
+        }
+        Ok(EpochUpdate {
+            from_epoch: self.epoch,
+            to_epoch: self.epoch + 1,
+            n: *self.n.as_ref(),
+            params_digest: self.params_digest,
+            deleted,
+            value,
+        })
+    }
+
+    // A witness for x against `value`, one the accumulator is yet to hold
+    // (see Manager::stage_add). Checks x as add does, without adding it.
+    #[cfg(feature = "service")]
+    pub(crate) fn witness_against(&mut self, x: &[u8], value: &AccValue) -> Result<Witness, &'static str> {
+        self.check_duplicate(x)?;
+        let elem = self.element_for_add(x, &Deadline::none())?;
+        self.use_trapdoor(1);
+        let w = core::root(value, &elem, &self.sk, self.exp_strategy, &self.monty_params)
+            .ok_or("Element not invertible modulo sk")?;
+        self.check_root(&w, &elem, value)?;
+        Ok(self.witness(w))
+    }
+
+    // Whether w opens `value` for x, for values the accumulator does not hold.
+    #[cfg(feature = "service")]
+    pub(crate) fn opens(&self, x: &[u8], w: &Witness, value: &AccValue) -> bool {
+        if w.params_digest != self.params_digest || !core::is_group_element(w.value.as_uint(), self.n.as_ref()) {
+            return false;
+        }
+        self.lookup_prime(x).is_ok_and(|elem| core::opens(&w.value, &elem, value, &self.monty_params))
+    }
+
+    // Accepts a witness that was valid at a recent epoch, for verifiers with a
+    // grace window: anything older than the kept checkpoints is rejected.
+    pub fn verify_at_epoch(&mut self, x: impl AsRef<[u8]>, w: Witness, epoch: u64) -> bool {
+        let x = x.as_ref();
+        if epoch == self.epoch {
+            return self.verify(x, w);
+        }
+        let Some(&(_, value)) = self.checkpoints.iter().find(|(e, _)| *e == epoch) else {
+            return false;
+        };
+        if w.params_digest != self.params_digest || !core::is_group_element(w.value.as_uint(), self.n.as_ref()) {
+            return false;
+        }
+        let Ok(elem) = self.element_within(x, &Deadline::none()) else {
+            return false;
+        };
+        core::opens(&w.value, &elem, &value, &self.monty_params)
+    }
+
+    // Montgomery parameters modulo sk = p'q', a product of odd primes.
+    fn sk_params(&self) -> Result<MontyParams<4>, &'static str> {
+        let sk: Option<Odd<U256>> = Odd::new(self.sk).into();
+        Ok(MontyParams::new(sk.ok_or("Secret key must be odd")?))
+    }
+
+    fn mont_mod_exp(&self, base: MontyForm<8>, exponent: &U512) -> MontyForm<8> {
+        self.exp_strategy.pow(&base, exponent)
+    }
+
+    // The current value to an exponent reduced mod sk, from the table if built.
+    fn pow_value(&self, exponent: &U256) -> MontyForm<8> {
+        match &self.value_table {
+            Some(table) => table.pow(exponent, &self.monty_params),
+            None => self.mont_mod_exp(self.a, &pad_u256_to_u512(*exponent)),
+        }
+    }
+
+    pub fn update_witness_on_deletion(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        y: impl AsRef<[u8]>,
+    ) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        let y = y.as_ref();
+        if w.params_digest != self.params_digest {
+            return Err("Witness was issued under different parameters");
+        }
+        let elem_x = self.element_within(x, &Deadline::none())?;
+        let elem_y = self.element_within(y, &Deadline::none())?;
+        if elem_x == elem_y {
+            return Err("Cannot update the witness of the deleted element");
+        }
+        let n = *self.n.as_ref();
+        let p_prime_q_prime = self.sk; // This is p'q' = (p-1)/2 * (q-1)/2
+        self.use_trapdoor(1);
+        
+        // Convert to Montgomery form for calculations
+        let w_monty = MontyForm::new(w.value.as_uint(), self.monty_params);
+        
+        // Find y^(-1) mod p'q'
+        let y_inv: Option<U256> = elem_y.as_uint().inv_mod(&p_prime_q_prime).into();
+        let y_inv = y_inv.ok_or("y is not invertible modulo p'q'")?;
+        
+        // Calculate w^(1/y) mod n
+        // This is equivalent to w^(y^(-1) mod p'q') mod n
+        let y_inv_512 = pad_u256_to_u512(y_inv);
+        let result = self.mont_mod_exp(w_monty, &y_inv_512);
+        let result = WitnessValue::new(result.retrieve() % n);
+
+        if self.double_check {
+            // The witness given need not be in QR_n, so only up to sign
+            let opened = result.pow_elem(&elem_y, &self.monty_params);
+            if !core::same_class(opened.as_uint(), w.value.as_uint(), &n) {
+                return Err(FAULT_DETECTED);
+            }
+        }
+        Ok(self.witness(result))
+    }
+
+    fn witness(&self, value: WitnessValue) -> Witness {
+        Witness {
+            params_digest: self.params_digest,
+            value,
+        }
+    }
+
+    // Trapdoor operation: recomputes a current witness for a member from the
+    // secret key alone, for holders who lost theirs. Callers should gate and
+    // rate-limit this (see Manager::recover_witness).
+    pub fn recover_witness(&self, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        if !self.members.contains(x) {
+            return Err("Element is not a member");
+        }
+        let value = self.root_of_value(&self.member_prime(x)?)?;
+        Ok(self.witness(value))
+    }
+
+    // Trapdoor operation: the root for a blinded prime, unblinded by the
+    // holder into a witness for an element this accumulator never sees (see
+    // BlindedPrime). Nothing becomes a member; callers should gate and count
+    // requests (see Manager::issue_blind).
+    pub fn issue_blind(&self, blinded: &BlindedPrime) -> Result<BlindIssuance, &'static str> {
+        blinded.check()?;
+        self.use_trapdoor(1);
+        let value = core::root_wide(&self.value(), &blinded.0, &self.sk, self.exp_strategy, &self.monty_params)
+            .ok_or("Blinded prime not invertible modulo sk")?;
+        if self.double_check {
+            let opened = MontyForm::new(value.as_uint(), self.monty_params).pow(&blinded.0);
+            if opened.retrieve() != *self.value().as_uint() {
+                return Err(FAULT_DETECTED);
+            }
+        }
+        Ok(BlindIssuance {
+            params_digest: self.params_digest,
+            epoch: self.epoch,
+            value,
+        })
+    }
+
+    // Every member with its prime and a current witness, in element byte order,
+    // for reconciling against the operator's own records. Witnesses come from
+    // the trapdoor, so this carries the same caveats as recover_witness.
+    pub fn export_members(
+        &self,
+    ) -> impl Iterator<Item = Result<(&[u8], ElemPrime, Witness), &'static str>> + '_ {
+        self.members.iter().map(|x| {
+            let prime = self.member_prime(x)?;
+            let value = self.root_of_value(&prime)?;
+            Ok((x.as_slice(), prime, self.witness(value)))
+        })
+    }
+
+    // Recovers every member's witness and hands it to the encryptor, which seals
+    // it to that holder's public key.
+    pub fn export_witnesses_encrypted<E: WitnessEncryptor>(
+        &self,
+        encryptor: &E,
+    ) -> Result<Vec<EncryptedWitness>, &'static str> {
+        self.members
+            .iter()
+            .map(|x| {
+                let witness = self.recover_witness(x)?;
+                Ok(EncryptedWitness {
+                    element: x.clone(),
+                    epoch: self.epoch,
+                    ciphertext: encryptor.encrypt_for(x, &witness.to_bytes())?,
+                })
+            })
+            .collect()
+    }
+
+    // a^(prime^(-1) mod sk): the prime-th root of the current value.
+    fn root_of_value(&self, prime: &ElemPrime) -> Result<WitnessValue, &'static str> {
+        self.use_trapdoor(1);
+        let root = match &self.value_table {
+            Some(table) => core::root_precomputed(table, prime, &self.sk, &self.monty_params),
+            None => core::root(&self.value(), prime, &self.sk, self.exp_strategy, &self.monty_params),
+        }
+        .ok_or("Element not invertible modulo sk")?;
+        self.check_root(&root, prime, &self.value())?;
+        Ok(root)
+    }
+
+    // In double-check mode, that root^prime is value. A fault during the
+    // exponentiation (a glitch, a flipped bit) would otherwise publish a
+    // wrong value or hand out a wrong root, and a root wrong modulo one
+    // factor of n gives that factor away to anyone holding the right one.
+    fn check_root(&self, root: &WitnessValue, prime: &ElemPrime, value: &AccValue) -> Result<(), &'static str> {
+        if self.double_check && root.pow_elem(prime, &self.monty_params) != *value {
+            return Err(FAULT_DETECTED);
+        }
+        Ok(())
+    }
+
+    // Answers a verifier's nonce with proof of holding a current witness for
+    // x, without revealing it (see FreshnessProof).
+    #[cfg(feature = "zk")]
+    pub fn prove_membership_fresh(
+        &self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        nonce: &[u8],
+    ) -> Result<FreshnessProof, &'static str> {
+        FreshnessProof::prove(&self.params(), &self.value(), self.epoch, x, &w, nonce)
+    }
+
+    #[cfg(feature = "zk")]
+    pub fn verify_membership_fresh(&self, x: impl AsRef<[u8]>, proof: &FreshnessProof, nonce: &[u8]) -> bool {
+        proof.verify(&self.params(), &self.value(), self.epoch, x, nonce)
+    }
+
+    // Checks a device's presentation of a delegated membership (see
+    // Delegation): against the value at the delegation's epoch while it is
+    // kept (see the builder's checkpoints), and only while the element is
+    // still a member.
+    #[cfg(feature = "zk")]
+    pub fn verify_delegated(&self, presentation: &DelegatedPresentation, nonce: &[u8]) -> Result<(), &'static str> {
+        let delegation = &presentation.delegation;
+        if !self.members.contains(&delegation.element) {
+            return Err("Element is no longer a member");
+        }
+        let epoch = delegation.proof.epoch;
+        let value = match epoch == self.epoch {
+            true => self.value(),
+            false => {
+                let kept = self.checkpoints.iter().find(|(e, _)| *e == epoch);
+                kept.map(|&(_, v)| v).ok_or("Delegation epoch is not kept")?
+            }
+        };
+        presentation.verify(&self.params(), &value, self.epoch, nonce)
+    }
+
+    // Proves x is a member here and not deleted from `deny` in one proof,
+    // with a fresh commitment to x (see CompositeProof). Both accumulators
+    // must derive the same prime for x.
+    #[cfg(feature = "zk")]
+    pub fn prove_valid_composite(
+        &self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        deny: &BraavosAccumulator,
+    ) -> Result<CompositeProof, &'static str> {
+        let x = x.as_ref();
+        let prime = self.lookup_prime(x)?;
+        if deny.lookup_prime(x)? != prime {
+            return Err("Accumulators derive different primes for the element");
+        }
+        let non_witness = deny.non_membership_witness(x)?;
+        let blinding = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+        let (allow, deny_params) = (self.params(), deny.params());
+        CompositeProof::prove(&allow, &self.value(), &deny_params, &deny.value(), &prime, &blinding, (&w, &non_witness))
+    }
+
+    #[cfg(feature = "zk")]
+    pub fn verify_valid_composite(&self, deny: &BraavosAccumulator, proof: &CompositeProof) -> bool {
+        proof.verify(&self.params(), &self.value(), &deny.params(), &deny.value())
+    }
+
+    // The member count at this epoch, signed, for auditors; needs
+    // AccumulatorBuilder::track_cardinality.
+    #[cfg(feature = "cardinality")]
+    pub fn cardinality_proof(&self) -> Result<CardinalityProof, &'static str> {
+        let tree = self.cardinality.as_ref().ok_or(CARDINALITY_OFF)?;
+        let (root, count) = tree.root();
+        let mut proof = CardinalityProof {
+            params_digest: self.params_digest,
+            epoch: self.epoch,
+            value: self.value(),
+            root,
+            count,
+            signature: U512::ZERO,
+        };
+        proof.signature = self.sign(&proof.signed_bytes())?;
+        Ok(proof)
+    }
+
+    // Lets a member check it is counted in this epoch's cardinality_proof.
+    #[cfg(feature = "cardinality")]
+    pub fn count_inclusion(&self, x: impl AsRef<[u8]>) -> Result<CountInclusionProof, &'static str> {
+        let x = x.as_ref();
+        let tree = self.cardinality.as_ref().ok_or(CARDINALITY_OFF)?;
+        if !self.members.contains(x) {
+            return Err("Element is not a member");
+        }
+        Ok(tree.prove(x))
+    }
+
+    // Trapdoor operation: a witness that x is not among the deleted elements. With
+    // d the product of deleted primes, beta = d^(-1) mod x and the value raised to
+    // (1 - beta * d) / x, which only needs computing mod sk.
+    pub fn non_membership_witness(&self, x: impl AsRef<[u8]>) -> Result<NonMembershipWitness, &'static str> {
+        let x = x.as_ref();
+        let prime = self.lookup_prime(x)?;
+        if self.deleted.contains(&prime) {
+            return Err("Element has been deleted");
+        }
+        let mod_x: Option<Odd<U256>> = Odd::new(*prime.as_uint()).into();
+        let mod_x = MontyParams::new(mod_x.ok_or("Element primes must be odd")?);
+        let mod_sk = self.sk_params()?;
+        self.use_trapdoor(1);
+        let mut d_x = MontyForm::one(mod_x);
+        let mut d_sk = MontyForm::one(mod_sk);
+        for y in &self.deleted {
+            d_x = d_x.mul(&MontyForm::new(y.as_uint(), mod_x));
+            d_sk = d_sk.mul(&MontyForm::new(y.as_uint(), mod_sk));
+        }
+        let beta: Option<MontyForm<4>> = d_x.inv().into();
+        let beta = beta.ok_or("Element has been deleted")?.retrieve();
+        let x_inv: Option<MontyForm<4>> = MontyForm::new(prime.as_uint(), mod_sk).inv().into();
+        let x_inv = x_inv.ok_or("Element not invertible modulo sk")?;
+        let alpha = MontyForm::one(mod_sk).sub(&MontyForm::new(&beta, mod_sk).mul(&d_sk)).mul(&x_inv);
+        let d = self.pow_value(&alpha.retrieve());
+        Ok(NonMembershipWitness {
+            params_digest: self.params_digest,
+            epoch: self.epoch,
+            d: d.retrieve(),
+            beta,
+        })
+    }
+
+    // Raises the value and the base to a fresh random power r, unlinking the
+    // published value from the previous one for anyone without the proof. The
+    // parameters change with the base, so every outstanding witness (including
+    // non-membership ones) must be reissued; holders cannot follow this through
+    // update messages.
+    #[cfg(feature = "zk")]
+    pub fn rerandomize(&mut self) -> Result<RerandomizationProof, &'static str> {
+        let before = self.params();
+        let value_before = self.value();
+        let sk: Option<NonZero<U1024>> = NonZero::new(self.sk.resize()).into();
+        let sk = sk.ok_or("Secret key must be nonzero")?;
+        self.use_trapdoor(1);
+        let (r, r_mod_sk) = loop {
+            let r = U1024::random_bits(&mut OsRng, RERANDOMIZATION_BITS);
+            let r_mod_sk: U256 = r.rem(&sk).resize();
+            // r must be a unit mod sk or the value would leave the subgroup it generates
+            if bool::from(r_mod_sk.inv_mod(&self.sk).is_some()) {
+                break (r, pad_u256_to_u512(r_mod_sk));
+            }
+        };
+        self.a = self.mont_mod_exp(self.a, &r_mod_sk);
+        let base = MontyForm::new(self.base.as_uint(), self.monty_params);
+        self.base = AccValue::new(self.mont_mod_exp(base, &r_mod_sk).retrieve());
+        self.seed = None;
+        self.params_digest = self.params().digest();
+        self.checkpoints.clear();
+        self.refresh_table();
+        RerandomizationProof::prove(&before, &value_before, &self.params(), &self.value(), &r)
+    }
+
+    // Snapshot the current value and a witness for every member, signed with the
+    // trapdoor, so membership can be checked later without the live accumulator.
+    pub fn freeze(&self) -> Result<StaticBundle, &'static str> {
+        let mut members = BTreeMap::new();
+        for x in &self.members {
+            let prime = self.member_prime(x)?;
+            let witness = self.root_of_value(&prime)?;
+            members.insert(x.clone(), BundleEntry { prime, witness });
+        }
+
+        let mut bundle = StaticBundle {
+            params: self.params(),
+            value: self.value(),
+            members,
+            signature: U512::ZERO,
+        };
+        bundle.signature = self.sign(&bundle.signed_bytes())?;
+        Ok(bundle)
+    }
+
+    // RSA signature under the accumulator modulus: h^(e^(-1) mod p'q') where h is
+    // the message hashed into QR_n. Only the holder of sk can produce it.
+    pub(crate) fn sign(&self, msg: &[u8]) -> Result<U512, &'static str> {
+        let e = U256::from(signature::EXPONENT);
+        self.use_trapdoor(1);
+        let e_inv: Option<U256> = e.inv_mod(&self.sk).into();
+        let e_inv = e_inv.ok_or("Signature exponent not invertible modulo sk")?;
+        let h = signature::hash_to_qr(self.hash, &self.n, signature::SIGNATURE_DOMAIN, msg);
+        let h_monty = MontyForm::new(&h, self.monty_params);
+        let sig = self.mont_mod_exp(h_monty, &pad_u256_to_u512(e_inv));
+        // A faulty RSA signature is the classic way to leak the factors
+        if self.double_check && pow_adaptive(&sig, &e) != h_monty {
+            return Err(FAULT_DETECTED);
+        }
+        Ok(sig.retrieve() % *self.n.as_ref())
+    }
+}
+
+pub(crate) fn pad_u256_to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 64];
+    let value_bytes = value.to_be_bytes();
+    bytes[32..].copy_from_slice(&value_bytes);
+    U512::from_be_slice(&bytes)
+}
@@ -0,0 +1,87 @@
+use crate::chain::{verify_checkpoint_chain, Checkpoint};
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::params::PublicParams;
+
+const RECEIPT_TAG: &[u8] = b"braavos-anchor-receipt";
+
+// Writes checkpoint digests to a system outside the manager's control (a
+// transparency log, a blockchain, an RFC 3161 timestamping authority), so
+// the manager cannot later present a different chain without the anchor
+// disagreeing. Called by Manager::publish_checkpoint with each new
+// checkpoint before it joins the chain; an error leaves the chain as it was,
+// so the same checkpoint is anchored again on the next attempt.
+pub trait CheckpointAnchor: Send + Sync {
+    // Returns the external system's evidence of the write: an inclusion
+    // proof, a transaction id, a timestamp token. Opaque to this library.
+    fn anchor(&self, checkpoint: &Checkpoint, digest: &[u8; 32]) -> Result<Vec<u8>, &'static str>;
+}
+
+// Checks evidence from the same system on the verifier's side: that it
+// really records `digest`, under whatever trust the system gives (a log's
+// signed tree head, enough confirmations, the TSA's certificate).
+pub trait ReceiptVerifier {
+    fn verify(&self, digest: &[u8; 32], evidence: &[u8]) -> bool;
+}
+
+// A checkpoint's digest and where it was anchored, kept by the manager next
+// to the chain (see Manager::anchor_receipt).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnchorReceipt {
+    pub index: u64,        // Position of the checkpoint in the chain
+    pub to_epoch: u64,     // Epoch the checkpoint reaches
+    pub digest: [u8; 32],  // Checkpoint::digest
+    pub evidence: Vec<u8>, // From CheckpointAnchor::anchor
+}
+
+impl AnchorReceipt {
+    // Verifies the chain up to the receipt's checkpoint, that the receipt
+    // names that checkpoint, and the evidence with `verifier`. Checkpoints
+    // after it are not looked at.
+    pub fn check(
+        &self,
+        params: &PublicParams,
+        chain: &[Checkpoint],
+        verifier: &impl ReceiptVerifier,
+    ) -> Result<(), &'static str> {
+        let index = usize::try_from(self.index).map_err(|_| "Checkpoint is not in the chain")?;
+        let chain = chain.get(..=index).ok_or("Checkpoint is not in the chain")?;
+        verify_checkpoint_chain(params, chain)?;
+        let checkpoint = chain.last().ok_or("Checkpoint is not in the chain")?;
+        if checkpoint.to_epoch != self.to_epoch || checkpoint.digest(params) != self.digest {
+            return Err("Receipt is for a different checkpoint");
+        }
+        if !verifier.verify(&self.digest, &self.evidence) {
+            return Err("Anchor evidence does not record the checkpoint");
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(RECEIPT_TAG);
+        w.put_u64(self.index);
+        w.put_u64(self.to_epoch);
+        w.put_bytes(&self.digest);
+        w.put_bytes(&self.evidence);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, RECEIPT_TAG, policy)?;
+        let index = r.get_u64()?;
+        let to_epoch = r.get_u64()?;
+        let digest = r.get_bytes()?.try_into().map_err(|_| "Invalid checkpoint digest")?;
+        let evidence = r.get_bytes()?.to_vec();
+        r.finish()?;
+        Ok(Self {
+            index,
+            to_epoch,
+            digest,
+            evidence,
+        })
+    }
+}
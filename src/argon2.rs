@@ -0,0 +1,370 @@
+// Argon2id (RFC 9106, version 0x13) with its BLAKE2b, for ElementKdf. Lanes
+// are filled one after another: the result is the same as with one thread
+// per lane, only slower on many cores. Public so the RFC test vectors can be
+// checked against both.
+
+use crate::kdf::Argon2Params;
+
+const BLOCK_WORDS: usize = 128; // 1 KiB blocks
+const SYNC_POINTS: u32 = 4;
+const VERSION: u32 = 0x13;
+const ARGON2ID: u32 = 2;
+
+type Block = [u64; BLOCK_WORDS];
+
+// Tag of `out_len` bytes (at least 4) for the password and salt, optional
+// secret and associated data.
+pub fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    data: &[u8],
+    cost: &Argon2Params,
+    out_len: u32,
+) -> Result<Vec<u8>, &'static str> {
+    cost.check()?;
+    if out_len < 4 {
+        return Err("Argon2 tags are at least 4 bytes");
+    }
+    let Argon2Params {
+        memory_kib,
+        iterations,
+        lanes,
+    } = *cost;
+    let mut h = Blake2b::new(64);
+    for v in [lanes, out_len, memory_kib, iterations, VERSION, ARGON2ID] {
+        h.update(&v.to_le_bytes());
+    }
+    for part in [password, salt, secret, data] {
+        h.update(&(part.len() as u32).to_le_bytes());
+        h.update(part);
+    }
+    let h0 = h.finalize();
+
+    let segment_len = memory_kib / (SYNC_POINTS * lanes);
+    let lane_len = segment_len * SYNC_POINTS;
+    let mut memory = Memory {
+        blocks: vec![[0; BLOCK_WORDS]; (lane_len * lanes) as usize],
+        lanes,
+        lane_len,
+        segment_len,
+        iterations,
+    };
+    for lane in 0..lanes {
+        for i in 0..2u32 {
+            let bytes = hash_long(&[&h0, &i.to_le_bytes(), &lane.to_le_bytes()], 1024);
+            let index = memory.index(lane, i);
+            memory.blocks[index] = block_from_bytes(&bytes);
+        }
+    }
+    for pass in 0..iterations {
+        for slice in 0..SYNC_POINTS {
+            for lane in 0..lanes {
+                memory.fill_segment(pass, slice, lane);
+            }
+        }
+    }
+
+    let mut last = memory.blocks[memory.index(0, lane_len - 1)];
+    for lane in 1..lanes {
+        xor_into(&mut last, &memory.blocks[memory.index(lane, lane_len - 1)]);
+    }
+    Ok(hash_long(&[&block_to_bytes(&last)], out_len))
+}
+
+// Unkeyed BLAKE2b digest of `out_len` bytes, 1 to 64.
+pub fn blake2b(data: &[u8], out_len: usize) -> Result<Vec<u8>, &'static str> {
+    if !(1..=64).contains(&out_len) {
+        return Err("BLAKE2b digests are 1 to 64 bytes");
+    }
+    let mut h = Blake2b::new(out_len);
+    h.update(data);
+    Ok(h.finalize())
+}
+
+struct Memory {
+    blocks: Vec<Block>,
+    lanes: u32,
+    lane_len: u32,
+    segment_len: u32,
+    iterations: u32,
+}
+
+impl Memory {
+    fn index(&self, lane: u32, column: u32) -> usize {
+        (lane * self.lane_len + column) as usize
+    }
+
+    fn fill_segment(&mut self, pass: u32, slice: u32, lane: u32) {
+        // Argon2id: data-independent addressing for the first half of the first pass
+        let independent = pass == 0 && slice < SYNC_POINTS / 2;
+        let mut input = [0u64; BLOCK_WORDS];
+        let mut addresses = [0u64; BLOCK_WORDS];
+        if independent {
+            let total = (self.lane_len * self.lanes) as u64;
+            input[..6].copy_from_slice(&[pass as u64, lane as u64, slice as u64, total, self.iterations as u64, 2]);
+        }
+        let start = if pass == 0 && slice == 0 { 2 } else { 0 };
+        if independent && start != 0 {
+            next_addresses(&mut input, &mut addresses);
+        }
+        for i in start..self.segment_len {
+            let column = slice * self.segment_len + i;
+            let prev = if column == 0 { self.lane_len - 1 } else { column - 1 };
+            let prev = self.blocks[self.index(lane, prev)];
+            let pseudo_random = if independent {
+                if i % BLOCK_WORDS as u32 == 0 {
+                    next_addresses(&mut input, &mut addresses);
+                }
+                addresses[i as usize % BLOCK_WORDS]
+            } else {
+                prev[0]
+            };
+
+            let ref_lane = match pass == 0 && slice == 0 {
+                true => lane,
+                false => ((pseudo_random >> 32) % self.lanes as u64) as u32,
+            };
+            let ref_column = self.reference_column(pass, slice, i, ref_lane == lane, pseudo_random as u32);
+            let reference = self.blocks[self.index(ref_lane, ref_column)];
+            let mut next = compress(&prev, &reference);
+            let current = self.index(lane, column);
+            if pass > 0 {
+                xor_into(&mut next, &self.blocks[current]);
+            }
+            self.blocks[current] = next;
+        }
+    }
+
+    // RFC 9106 section 3.4.1.2: maps J1 into the blocks the current one may
+    // reference, biased towards the most recent.
+    fn reference_column(&self, pass: u32, slice: u32, index: u32, same_lane: bool, j1: u32) -> u32 {
+        let finished = match pass {
+            0 => slice * self.segment_len,
+            _ => self.lane_len - self.segment_len,
+        };
+        let area = match (same_lane, index == 0) {
+            (true, _) => finished + index - 1,
+            (false, true) => finished - 1,
+            (false, false) => finished,
+        };
+        let x = (j1 as u64 * j1 as u64) >> 32;
+        let relative = area as u64 - 1 - ((area as u64 * x) >> 32);
+        let start = match pass == 0 || slice == SYNC_POINTS - 1 {
+            true => 0,
+            false => (slice + 1) * self.segment_len,
+        };
+        ((start as u64 + relative) % self.lane_len as u64) as u32
+    }
+}
+
+fn next_addresses(input: &mut Block, addresses: &mut Block) {
+    input[6] += 1;
+    let zero = [0u64; BLOCK_WORDS];
+    *addresses = compress(&zero, &compress(&zero, input));
+}
+
+// G: the BLAKE2b round function over the 8x8 matrix of 16-byte registers,
+// row by row then column by column, XORed with its input.
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = *x;
+    xor_into(&mut r, y);
+    let mut z = r;
+    for row in 0..8 {
+        let mut idx = [0usize; 16];
+        for (k, i) in idx.iter_mut().enumerate() {
+            *i = 16 * row + k;
+        }
+        permute(&mut z, &idx);
+    }
+    for column in 0..8 {
+        let mut idx = [0usize; 16];
+        for (k, i) in idx.iter_mut().enumerate() {
+            *i = 16 * (k / 2) + 2 * column + k % 2;
+        }
+        permute(&mut z, &idx);
+    }
+    xor_into(&mut z, &r);
+    z
+}
+
+fn permute(v: &mut Block, idx: &[usize; 16]) {
+    let mut gb = |a: usize, b: usize, c: usize, d: usize| {
+        let (a, b, c, d) = (idx[a], idx[b], idx[c], idx[d]);
+        v[a] = fblamka(v[a], v[b]);
+        v[d] = (v[d] ^ v[a]).rotate_right(32);
+        v[c] = fblamka(v[c], v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(24);
+        v[a] = fblamka(v[a], v[b]);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = fblamka(v[c], v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(63);
+    };
+    gb(0, 4, 8, 12);
+    gb(1, 5, 9, 13);
+    gb(2, 6, 10, 14);
+    gb(3, 7, 11, 15);
+    gb(0, 5, 10, 15);
+    gb(1, 6, 11, 12);
+    gb(2, 7, 8, 13);
+    gb(3, 4, 9, 14);
+}
+
+fn fblamka(x: u64, y: u64) -> u64 {
+    let product = (x & 0xffff_ffff) * (y & 0xffff_ffff);
+    x.wrapping_add(y).wrapping_add(product.wrapping_mul(2))
+}
+
+fn xor_into(a: &mut Block, b: &Block) {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a ^= b;
+    }
+}
+
+fn block_from_bytes(bytes: &[u8]) -> Block {
+    let mut block = [0u64; BLOCK_WORDS];
+    for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+    }
+    block
+}
+
+fn block_to_bytes(block: &Block) -> Vec<u8> {
+    block.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+// H': BLAKE2b stretched to any output length by chaining 64-byte digests
+// and keeping the first half of each.
+fn hash_long(parts: &[&[u8]], out_len: u32) -> Vec<u8> {
+    let mut h = Blake2b::new(out_len.min(64) as usize);
+    h.update(&out_len.to_le_bytes());
+    for part in parts {
+        h.update(part);
+    }
+    let mut v = h.finalize();
+    if out_len <= 64 {
+        return v;
+    }
+    let mut out = Vec::with_capacity(out_len as usize);
+    while out_len as usize - out.len() > 64 {
+        out.extend_from_slice(&v[..32]);
+        let remaining = out_len as usize - out.len();
+        let mut h = Blake2b::new(remaining.min(64));
+        h.update(&v);
+        v = h.finalize();
+    }
+    out.extend_from_slice(&v);
+    out
+}
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+// Unkeyed BLAKE2b (RFC 7693) with 1 to 64 bytes of output.
+struct Blake2b {
+    h: [u64; 8],
+    buf: [u8; 128],
+    buf_len: usize,
+    counter: u128,
+    out_len: usize,
+}
+
+impl Blake2b {
+    fn new(out_len: usize) -> Self {
+        let mut h = IV;
+        h[0] ^= 0x0101_0000 ^ out_len as u64;
+        Self {
+            h,
+            buf: [0; 128],
+            buf_len: 0,
+            counter: 0,
+            out_len,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // The last block is compressed by finalize, so a full buffer waits for more data
+            if self.buf_len == 128 {
+                self.counter += 128;
+                let buf = self.buf;
+                self.compress(&buf, false);
+                self.buf_len = 0;
+            }
+            let n = data.len().min(128 - self.buf_len);
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&data[..n]);
+            self.buf_len += n;
+            data = &data[n..];
+        }
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        self.counter += self.buf_len as u128;
+        self.buf[self.buf_len..].fill(0);
+        let buf = self.buf;
+        self.compress(&buf, true);
+        let bytes: Vec<u8> = self.h.iter().flat_map(|word| word.to_le_bytes()).collect();
+        bytes[..self.out_len].to_vec()
+    }
+
+    fn compress(&mut self, block: &[u8; 128], last: bool) {
+        let mut m = [0u64; 16];
+        for (word, chunk) in m.iter_mut().zip(block.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+        }
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.h);
+        v[8..].copy_from_slice(&IV);
+        v[12] ^= self.counter as u64;
+        v[13] ^= (self.counter >> 64) as u64;
+        if last {
+            v[14] = !v[14];
+        }
+        for s in &SIGMA {
+            let mut g = |a: usize, b: usize, c: usize, d: usize, x: u64, y: u64| {
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(32);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(24);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right(16);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(63);
+            };
+            g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+            g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+        for (i, h) in self.h.iter_mut().enumerate() {
+            *h ^= v[i] ^ v[i + 8];
+        }
+    }
+}
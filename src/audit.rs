@@ -0,0 +1,169 @@
+use crate::encoding::{Reader, Writer};
+use crate::hash::HashAlgorithm;
+
+const BATCH_DOMAIN: &[u8] = b"braavos-audit-batch";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Add,
+    Delete,
+    RecoverWitness,
+    ExportWitnesses,
+    Rerandomize,
+    Bind,
+    Rebind,
+    IssueWitnesses, // A whole batch, logged once with its batch_digest
+    IssueBlind,     // Logged with the blinded prime, which names no element
+    ExportCustody,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny(String), // Reason given by the policy
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditRecord {
+    pub seq: u64,             // Position in the log
+    pub epoch: u64,           // Accumulator epoch when the request was made
+    pub operator: String,     // Who asked for the operation
+    pub operation: Operation,
+    pub element: Vec<u8>,
+    pub metadata: Vec<u8>, // Metadata supplied with the request, empty if none
+    pub decision: Decision,
+}
+
+// Append-only record of every operation requested from the manager.
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    records: Vec<AuditRecord>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn append(
+        &mut self,
+        epoch: u64,
+        operator: &str,
+        operation: Operation,
+        element: &[u8],
+        metadata: &[u8],
+        decision: Decision,
+    ) {
+        let seq = self.records.len() as u64;
+        self.records.push(AuditRecord {
+            seq,
+            epoch,
+            operator: operator.to_string(),
+            operation,
+            element: element.to_vec(),
+            metadata: metadata.to_vec(),
+            decision,
+        });
+    }
+
+    // Reloads records from a backup; they must be numbered from zero.
+    pub(crate) fn from_records(records: Vec<AuditRecord>) -> Result<Self, &'static str> {
+        if records.iter().enumerate().any(|(i, r)| r.seq != i as u64) {
+            return Err("Audit records out of sequence");
+        }
+        Ok(Self { records })
+    }
+
+    pub fn records(&self) -> &[AuditRecord] {
+        &self.records
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+// What a batch is audited under: a digest of its elements in order, so the
+// record can be matched against the submitted list.
+pub fn batch_digest<T: AsRef<[u8]>>(xs: &[T]) -> [u8; 32] {
+    let parts: Vec<&[u8]> = xs.iter().map(AsRef::as_ref).collect();
+    HashAlgorithm::Sha256.hash(BATCH_DOMAIN, &parts)
+}
+
+impl Operation {
+    fn id(&self) -> u32 {
+        match self {
+            Operation::Add => 1,
+            Operation::Delete => 2,
+            Operation::RecoverWitness => 3,
+            Operation::ExportWitnesses => 4,
+            Operation::Rerandomize => 5,
+            Operation::Bind => 6,
+            Operation::Rebind => 7,
+            Operation::IssueWitnesses => 8,
+            Operation::IssueBlind => 9,
+            Operation::ExportCustody => 10,
+        }
+    }
+
+    fn from_id(id: u32) -> Result<Self, &'static str> {
+        match id {
+            1 => Ok(Operation::Add),
+            2 => Ok(Operation::Delete),
+            3 => Ok(Operation::RecoverWitness),
+            4 => Ok(Operation::ExportWitnesses),
+            5 => Ok(Operation::Rerandomize),
+            6 => Ok(Operation::Bind),
+            7 => Ok(Operation::Rebind),
+            8 => Ok(Operation::IssueWitnesses),
+            9 => Ok(Operation::IssueBlind),
+            10 => Ok(Operation::ExportCustody),
+            _ => Err("Unknown audited operation"),
+        }
+    }
+}
+
+impl AuditRecord {
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        w.put_u64(self.seq);
+        w.put_u64(self.epoch);
+        w.put_bytes(self.operator.as_bytes());
+        w.put_u32(self.operation.id());
+        w.put_bytes(&self.element);
+        w.put_bytes(&self.metadata);
+        match &self.decision {
+            Decision::Allow => w.put_u32(0),
+            Decision::Deny(reason) => {
+                w.put_u32(1);
+                w.put_bytes(reason.as_bytes());
+            }
+        }
+    }
+
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, &'static str> {
+        let seq = r.get_u64()?;
+        let epoch = r.get_u64()?;
+        let operator = String::from_utf8(r.get_bytes()?.to_vec()).map_err(|_| "Operator is not UTF-8")?;
+        let operation = Operation::from_id(r.get_u32()?)?;
+        let element = r.get_bytes()?.to_vec();
+        let metadata = r.get_bytes()?.to_vec();
+        let decision = match r.get_u32()? {
+            0 => Decision::Allow,
+            1 => Decision::Deny(String::from_utf8(r.get_bytes()?.to_vec()).map_err(|_| "Reason is not UTF-8")?),
+            _ => return Err("Unknown audit decision"),
+        };
+        Ok(Self {
+            seq,
+            epoch,
+            operator,
+            operation,
+            element,
+            metadata,
+            decision,
+        })
+    }
+}
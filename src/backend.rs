@@ -0,0 +1,65 @@
+use crate::accumulator::BraavosAccumulator;
+use crate::group::AccValue;
+use crate::update::EpochUpdate;
+use crate::witness::{NonMembershipWitness, Witness};
+
+// Operations shared by the accumulator constructions, so applications can
+// choose one per workload. The RSA accumulator gives constant-size membership
+// witnesses that holders keep current through update messages; the sparse
+// Merkle tree gives logarithmic witnesses for membership and non-membership
+// alike, which suits workloads that mostly ask whether something is absent.
+//
+// Non-membership follows each construction: for the RSA accumulator it shows
+// the element was never deleted (revocation), for the tree that it is absent.
+pub trait AccumulatorBackend {
+    type Value;
+    type Witness;
+    type NonMembershipWitness;
+    type Update; // What a deletion publishes for witness holders
+
+    fn value(&self) -> Self::Value;
+    fn contains(&self, x: impl AsRef<[u8]>) -> bool;
+    fn add(&mut self, x: impl AsRef<[u8]>) -> Result<Self::Witness, &'static str>;
+    fn delete(&mut self, x: impl AsRef<[u8]>) -> Result<Self::Update, &'static str>;
+    fn verify(&mut self, x: impl AsRef<[u8]>, w: &Self::Witness) -> bool;
+    fn non_membership_witness(&self, x: impl AsRef<[u8]>) -> Result<Self::NonMembershipWitness, &'static str>;
+    fn verify_non_membership(&self, x: impl AsRef<[u8]>, w: &Self::NonMembershipWitness) -> bool;
+}
+
+impl AccumulatorBackend for BraavosAccumulator {
+    type Value = AccValue;
+    type Witness = Witness;
+    type NonMembershipWitness = NonMembershipWitness;
+    type Update = EpochUpdate;
+
+    fn value(&self) -> AccValue {
+        BraavosAccumulator::value(self)
+    }
+
+    fn contains(&self, x: impl AsRef<[u8]>) -> bool {
+        BraavosAccumulator::contains(self, x)
+    }
+
+    fn add(&mut self, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        BraavosAccumulator::add(self, x)
+    }
+
+    fn delete(&mut self, x: impl AsRef<[u8]>) -> Result<EpochUpdate, &'static str> {
+        BraavosAccumulator::delete(self, x)
+    }
+
+    fn verify(&mut self, x: impl AsRef<[u8]>, w: &Witness) -> bool {
+        BraavosAccumulator::verify(self, x, *w)
+    }
+
+    fn non_membership_witness(&self, x: impl AsRef<[u8]>) -> Result<NonMembershipWitness, &'static str> {
+        BraavosAccumulator::non_membership_witness(self, x)
+    }
+
+    fn verify_non_membership(&self, x: impl AsRef<[u8]>, w: &NonMembershipWitness) -> bool {
+        let x = x.as_ref();
+        let params = self.params();
+        let prime = self.element_prime(x).unwrap_or_else(|| params.hash_to_prime(x));
+        w.verify(&params, &prime, &BraavosAccumulator::value(self))
+    }
+}
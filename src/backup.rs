@@ -0,0 +1,252 @@
+use hmac::Mac;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::collections::BTreeMap;
+
+use crate::accumulator::BraavosAccumulator;
+use crate::audit::{AuditLog, AuditRecord};
+use crate::binding::{Binding, LinkRecord};
+use crate::builder::AccumulatorBuilder;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::hash::HashAlgorithm;
+use crate::mac::{apply_keystream, hmac, prf};
+use crate::manager::{Manager, Policy};
+
+const ARCHIVE_TAG: &[u8] = b"braavos-backup";
+const CONTENTS_TAG: &[u8] = b"braavos-backup-contents";
+const CHECKSUM_DOMAIN: &[u8] = b"braavos-backup-checksum";
+const ARCHIVE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const KDF_ROUNDS: u32 = 100_000;
+const MAX_KDF_ROUNDS: u32 = 10_000_000;
+
+// What an archive records in the clear, covered by its MAC. verify_backup
+// checks the decrypted state reproduces it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BackupSummary {
+    pub epoch: u64,
+    pub members: usize,
+    pub audit_records: usize,
+    pub value: AccValue,
+}
+
+// Everything a manager needs to resume: the accumulator state (secret key,
+// members, deletion history), member metadata, holder bindings and link
+// records, and the audit log. The update log and checkpoint chain restart at
+// the restored epoch.
+struct Contents {
+    state: Vec<u8>,
+    metadata: BTreeMap<Vec<u8>, Vec<u8>>,
+    bindings: BTreeMap<Vec<u8>, Binding>,
+    links: Vec<LinkRecord>,
+    audit: Vec<AuditRecord>,
+}
+
+// Archive layout: header (salt, KDF rounds, summary), ciphertext of the
+// contents, HMAC-SHA256 tag over both, then a SHA-256 checksum of everything
+// before it so corruption is told apart from a wrong passphrase. Keys come
+// from PBKDF2-HMAC-SHA256 over the passphrase; the keystream is HMAC-SHA256
+// in counter mode under a key that is fresh for every archive.
+impl<P: Policy> Manager<P> {
+    pub fn backup(&self, passphrase: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let acc = self.accumulator();
+        let contents = Contents {
+            state: acc.to_state_bytes()?,
+            metadata: self.metadata().clone(),
+            bindings: self.bindings().clone(),
+            links: self.links().to_vec(),
+            audit: self.audit_log().records().to_vec(),
+        };
+        let summary = BackupSummary {
+            epoch: acc.epoch(),
+            members: acc.member_count(),
+            audit_records: contents.audit.len(),
+            value: acc.value(),
+        };
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Ok(seal(passphrase, &salt, KDF_ROUNDS, &summary, &contents.encode()))
+    }
+
+    // Verifies the archive as verify_backup does, then resumes from it under
+    // the builder's runtime options.
+    pub fn restore_backup(
+        archive: &[u8],
+        passphrase: &[u8],
+        builder: &AccumulatorBuilder,
+        policy: P,
+    ) -> Result<Self, &'static str> {
+        let (summary, contents) = open(archive, passphrase)?;
+        let acc = builder.restore(&contents.state)?;
+        check(&acc, &summary, &contents)?;
+        let mut manager = Manager::new(acc, policy);
+        manager.restore_metadata(contents.metadata);
+        manager.restore_records(AuditLog::from_records(contents.audit)?, contents.bindings, contents.links);
+        Ok(manager)
+    }
+}
+
+// Decrypts the archive and checks the state passes the integrity check and
+// its deletion history reproduces the recorded value, without restoring
+// anything. Costs a hash-to-prime per member.
+pub fn verify_backup(archive: &[u8], passphrase: &[u8]) -> Result<BackupSummary, &'static str> {
+    let (summary, contents) = open(archive, passphrase)?;
+    let acc = AccumulatorBuilder::default().restore(&contents.state)?;
+    check(&acc, &summary, &contents)?;
+    AuditLog::from_records(contents.audit)?;
+    Ok(summary)
+}
+
+fn check(acc: &BraavosAccumulator, summary: &BackupSummary, contents: &Contents) -> Result<(), &'static str> {
+    acc.check_integrity().map_err(|_| "Backup state failed the integrity check")?;
+    let recorded = (summary.epoch, summary.members, summary.audit_records, summary.value);
+    if recorded != (acc.epoch(), acc.member_count(), contents.audit.len(), acc.value()) {
+        return Err("Backup contents do not match the recorded summary");
+    }
+    if acc.replay_value()? != summary.value {
+        return Err("Deletion history does not reproduce the recorded value");
+    }
+    let params = acc.params();
+    let bound = contents.bindings.iter().all(|(x, b)| acc.contains(x) && b.verify(&params));
+    if !bound || !contents.links.iter().all(|l| l.verify(&params)) {
+        return Err("Backup holds an invalid binding");
+    }
+    Ok(())
+}
+
+fn seal(passphrase: &[u8], salt: &[u8], rounds: u32, summary: &BackupSummary, plaintext: &[u8]) -> Vec<u8> {
+    let (enc_key, mac_key) = derive_keys(passphrase, salt, rounds);
+    let mut ciphertext = plaintext.to_vec();
+    apply_keystream(&enc_key, &mut ciphertext);
+    let mut w = Writer::new(ARCHIVE_TAG);
+    w.put_u32(ARCHIVE_VERSION);
+    w.put_bytes(salt);
+    w.put_u32(rounds);
+    w.put_u64(summary.epoch);
+    w.put_u64(summary.members as u64);
+    w.put_u64(summary.audit_records as u64);
+    w.put_u512(summary.value.as_uint());
+    w.put_bytes(&ciphertext);
+    let mut archive = w.finish();
+    archive.extend_from_slice(&prf(&mac_key, &[&archive]));
+    let checksum = HashAlgorithm::Sha256.hash(CHECKSUM_DOMAIN, &[&archive]);
+    archive.extend_from_slice(&checksum);
+    archive
+}
+
+fn open(archive: &[u8], passphrase: &[u8]) -> Result<(BackupSummary, Contents), &'static str> {
+    if archive.len() < 64 {
+        return Err("Truncated encoding");
+    }
+    let (sealed, checksum) = archive.split_at(archive.len() - 32);
+    if HashAlgorithm::Sha256.hash(CHECKSUM_DOMAIN, &[sealed]) != checksum {
+        return Err("Backup archive is corrupted");
+    }
+    let (body, tag) = sealed.split_at(sealed.len() - 32);
+    let mut r = Reader::with_policy(body, ARCHIVE_TAG, &archive_policy())?;
+    if r.get_u32()? != ARCHIVE_VERSION {
+        return Err("Unsupported backup version");
+    }
+    let salt = r.get_bytes()?;
+    let rounds = r.get_u32()?;
+    let summary = BackupSummary {
+        epoch: r.get_u64()?,
+        members: r.get_u64()? as usize,
+        audit_records: r.get_u64()? as usize,
+        value: AccValue::new(r.get_u512()?),
+    };
+    let ciphertext = r.get_bytes()?;
+    r.finish()?;
+    if rounds == 0 || rounds > MAX_KDF_ROUNDS {
+        return Err("Unsupported backup key derivation");
+    }
+
+    let (enc_key, mac_key) = derive_keys(passphrase, salt, rounds);
+    let mut mac = hmac(&mac_key);
+    mac.update(body);
+    mac.verify_slice(tag).map_err(|_| "Wrong passphrase or tampered backup archive")?;
+    let mut plaintext = ciphertext.to_vec();
+    apply_keystream(&enc_key, &mut plaintext);
+    Ok((summary, Contents::decode(&plaintext)?))
+}
+
+// Fields in an archive may be as long as the archive itself.
+fn archive_policy() -> DecodingPolicy {
+    let policy = DecodingPolicy::default();
+    DecodingPolicy {
+        max_field: policy.max_input,
+        ..policy
+    }
+}
+
+// PBKDF2-HMAC-SHA256 (RFC 8018), two blocks: encryption key, then MAC key.
+fn derive_keys(passphrase: &[u8], salt: &[u8], rounds: u32) -> ([u8; 32], [u8; 32]) {
+    let block = |i: u32| {
+        let mut u = prf(passphrase, &[salt, &i.to_be_bytes()]);
+        let mut t = u;
+        for _ in 1..rounds {
+            u = prf(passphrase, &[&u]);
+            t.iter_mut().zip(&u).for_each(|(t, u)| *t ^= u);
+        }
+        t
+    };
+    (block(1), block(2))
+}
+
+impl Contents {
+    fn encode(&self) -> Vec<u8> {
+        let mut w = Writer::new(CONTENTS_TAG);
+        w.put_bytes(&self.state);
+        w.put_u32(self.metadata.len() as u32);
+        for (x, metadata) in &self.metadata {
+            w.put_bytes(x);
+            w.put_bytes(metadata);
+        }
+        w.put_u32(self.bindings.len() as u32);
+        for binding in self.bindings.values() {
+            w.put_bytes(&binding.to_bytes());
+        }
+        w.put_u32(self.links.len() as u32);
+        for link in &self.links {
+            w.put_bytes(&link.to_bytes());
+        }
+        w.put_u32(self.audit.len() as u32);
+        for record in &self.audit {
+            record.encode(&mut w);
+        }
+        w.finish()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, CONTENTS_TAG, &archive_policy())?;
+        let state = r.get_bytes()?.to_vec();
+        let mut metadata = BTreeMap::new();
+        for _ in 0..r.get_count()? {
+            let x = r.get_bytes()?.to_vec();
+            metadata.insert(x, r.get_bytes()?.to_vec());
+        }
+        let mut bindings = BTreeMap::new();
+        for _ in 0..r.get_count()? {
+            let binding = Binding::from_bytes(r.get_bytes()?)?;
+            bindings.insert(binding.element.clone(), binding);
+        }
+        let mut links = Vec::new();
+        for _ in 0..r.get_count()? {
+            links.push(LinkRecord::from_bytes(r.get_bytes()?)?);
+        }
+        let mut audit = Vec::new();
+        for _ in 0..r.get_count()? {
+            audit.push(AuditRecord::decode(&mut r)?);
+        }
+        r.finish()?;
+        Ok(Self {
+            state,
+            metadata,
+            bindings,
+            links,
+            audit,
+        })
+    }
+}
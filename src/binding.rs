@@ -0,0 +1,135 @@
+use crypto_bigint::{Odd, U512};
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::params::PublicParams;
+use crate::signature;
+use crate::witness::Witness;
+
+const BINDING_TAG: &[u8] = b"braavos-holder-binding";
+const LINK_TAG: &[u8] = b"braavos-binding-link";
+
+// Manager-signed statement that an element's membership belongs to a holder
+// key (an opaque public key encoding, checked by the application). Signed
+// under the modulus alone, so bindings survive rerandomization.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binding {
+    pub element: Vec<u8>,
+    pub holder_key: Vec<u8>,
+    pub sequence: u32, // Rebindings of the element before this one
+    pub signature: U512,
+}
+
+// Published when a membership moves to a new key, so a verifier shown the old
+// binding can tell it has been superseded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkRecord {
+    pub element: Vec<u8>,
+    pub old_key: Vec<u8>,
+    pub new_key: Vec<u8>,
+    pub sequence: u32, // Sequence of the new binding
+    pub epoch: u64,    // Accumulator epoch of the rebinding
+    pub signature: U512,
+}
+
+// What a holder's new device receives from Manager::rebind_witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rebinding {
+    pub witness: Witness,
+    pub binding: Binding,
+    pub link: LinkRecord,
+}
+
+impl Binding {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(BINDING_TAG);
+        w.put_bytes(&self.element);
+        w.put_bytes(&self.holder_key);
+        w.put_u32(self.sequence);
+        w.finish()
+    }
+
+    pub fn verify(&self, params: &PublicParams) -> bool {
+        verify_signature(params, &self.signed_bytes(), &self.signature)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, BINDING_TAG, policy)?;
+        let element = r.get_bytes()?.to_vec();
+        let holder_key = r.get_bytes()?.to_vec();
+        let sequence = r.get_u32()?;
+        let signature = r.get_residue(None)?;
+        r.finish()?;
+        Ok(Self {
+            element,
+            holder_key,
+            sequence,
+            signature,
+        })
+    }
+}
+
+impl LinkRecord {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(LINK_TAG);
+        w.put_bytes(&self.element);
+        w.put_bytes(&self.old_key);
+        w.put_bytes(&self.new_key);
+        w.put_u32(self.sequence);
+        w.put_u64(self.epoch);
+        w.finish()
+    }
+
+    pub fn verify(&self, params: &PublicParams) -> bool {
+        verify_signature(params, &self.signed_bytes(), &self.signature)
+    }
+
+    // Whether this record moved the membership away from `binding`.
+    pub fn supersedes(&self, binding: &Binding) -> bool {
+        self.element == binding.element && self.old_key == binding.holder_key && self.sequence == binding.sequence + 1
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, LINK_TAG, policy)?;
+        let element = r.get_bytes()?.to_vec();
+        let old_key = r.get_bytes()?.to_vec();
+        let new_key = r.get_bytes()?.to_vec();
+        let sequence = r.get_u32()?;
+        let epoch = r.get_u64()?;
+        let signature = r.get_residue(None)?;
+        r.finish()?;
+        Ok(Self {
+            element,
+            old_key,
+            new_key,
+            sequence,
+            epoch,
+            signature,
+        })
+    }
+}
+
+pub(crate) fn verify_signature(params: &PublicParams, msg: &[u8], sig: &U512) -> bool {
+    let n: Option<Odd<U512>> = Odd::new(params.n).into();
+    n.is_some_and(|n| signature::verify(params.hash, &n, msg, sig))
+}
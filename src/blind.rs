@@ -0,0 +1,151 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, U256, U512,
+};
+use crypto_primes::generate_prime;
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::hash;
+use crate::holder::WitnessHolder;
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+const BLINDED_PRIME_TAG: &[u8] = b"braavos-blinded-prime";
+const BLIND_ISSUANCE_TAG: &[u8] = b"braavos-blind-issuance";
+
+// Blind issuance: the holder multiplies its element prime x by a random
+// 256-bit prime r and asks for the (x r)-th root of the value; raising that
+// to r gives the x-th root, its witness. The manager sees only x r.
+//
+// That hides x from a manager that cannot enumerate candidate elements: one
+// that can tests each guess by whether its prime divides x r, as with
+// PrivateEpochUpdate, and so can one that later sees x verified in the
+// clear. Blinded elements are not members: the manager cannot delete one
+// until it learns the element, and then adds it with add_prime and deletes
+// it. The root also opens for r and for any other factor of x r, so a
+// holder may pack two element primes into one request; deployments should
+// count requests rather than elements, and use blind issuance only where
+// elements are the holders' own secrets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindedPrime(pub U512);
+
+// The manager's answer: the (x r)-th root of the value at `epoch`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindIssuance {
+    pub params_digest: [u8; 32],
+    pub epoch: u64,
+    pub value: WitnessValue,
+}
+
+// What the holder keeps between request and answer.
+#[derive(Clone, Debug)]
+pub struct Unblinder {
+    element: Vec<u8>,
+    prime: ElemPrime,
+    blind: U256, // r
+}
+
+impl BlindedPrime {
+    // A blinded request for x, and what unblinds the answer.
+    pub fn new(params: &PublicParams, x: impl AsRef<[u8]>) -> (Self, Unblinder) {
+        let x = x.as_ref();
+        let prime = params.hash_to_prime(x);
+        let blind = loop {
+            let blind = generate_prime::<U256>(hash::ELEMENT_PRIME_BITS);
+            if blind != *prime.as_uint() {
+                break blind;
+            }
+        };
+        let (lo, hi) = prime.as_uint().split_mul(&blind);
+        let unblinder = Unblinder {
+            element: x.to_vec(),
+            prime,
+            blind,
+        };
+        (Self(lo.concat(&hi)), unblinder)
+    }
+
+    // The product of two 256-bit numbers is odd and 511 or 512 bits long;
+    // whether its factors are primes is past what the manager can check.
+    pub(crate) fn check(&self) -> Result<(), &'static str> {
+        if self.0.bits() < 2 * hash::ELEMENT_PRIME_BITS - 1 || !self.0.bit_vartime(0) {
+            return Err("Blinded prime is not a product of two element-sized primes");
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(BLINDED_PRIME_TAG);
+        w.put_u512(&self.0);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader::new(bytes, BLINDED_PRIME_TAG)?;
+        let blinded = Self(r.get_u512()?);
+        r.finish()?;
+        blinded.check()?;
+        Ok(blinded)
+    }
+}
+
+impl BlindIssuance {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(BLIND_ISSUANCE_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u64(self.epoch);
+        w.put_u512(self.value.as_uint());
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, BLIND_ISSUANCE_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let epoch = r.get_u64()?;
+        let value = WitnessValue::new(r.get_element(None)?);
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            epoch,
+            value,
+        })
+    }
+}
+
+impl Unblinder {
+    pub fn element(&self) -> &[u8] {
+        &self.element
+    }
+
+    // The holder for x from the manager's answer, checked against `value`,
+    // the accumulator value the holder knows for issuance.epoch.
+    pub fn unblind(
+        self,
+        params: &PublicParams,
+        issuance: &BlindIssuance,
+        value: &AccValue,
+    ) -> Result<WitnessHolder, &'static str> {
+        if issuance.params_digest != params.digest() {
+            return Err("Issuance was made under different parameters");
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let monty = MontyParams::new(n.ok_or("Modulus must be odd")?);
+        // Constant time: r is what keeps x from the manager
+        let root = MontyForm::new(issuance.value.as_uint(), monty).pow(&self.blind);
+        let witness = Witness {
+            params_digest: issuance.params_digest,
+            value: WitnessValue::new(root.retrieve()),
+        };
+        if !core::opens(&witness.value, &self.prime, value, &monty) {
+            return Err("Unblinded witness does not open the value");
+        }
+        WitnessHolder::new(params, &self.element, witness, issuance.epoch)
+    }
+}
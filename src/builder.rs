@@ -0,0 +1,239 @@
+use crate::accumulator::BraavosAccumulator;
+use crate::cache::VerificationCache;
+use crate::exp::ExpStrategy;
+use crate::genesis::GenesisRecord;
+use crate::hash::HashAlgorithm;
+#[cfg(feature = "pool")]
+use crate::pool::PrimePool;
+use crate::preset::Preset;
+use crate::registry::ElementRegistry;
+use crate::rotation::RotationPolicy;
+use crate::security::SecurityEstimate;
+use std::sync::Arc;
+use std::time::Duration;
+
+// What adding an element that is already a member does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    // Nothing changes and the element gets another witness
+    #[default]
+    Ignore,
+    // The add fails
+    Reject,
+    // The element's copies are counted, and it stays a member until deleted
+    // as many times as it was added. Only the last delete changes the value:
+    // taking x's root once per copy would invalidate the other holders'
+    // witnesses, which update messages cannot repair for x itself.
+    Multiset,
+}
+
+// Configuration for a new accumulator; build() runs key generation.
+#[derive(Clone, Debug)]
+pub struct AccumulatorBuilder {
+    pub(crate) prime_bits: u32,
+    pub(crate) hash: HashAlgorithm,
+    pub(crate) exp_strategy: ExpStrategy,
+    #[cfg(feature = "pool")]
+    pub(crate) prime_pool: Option<Arc<PrimePool<4>>>,
+    pub(crate) checkpoints: usize,
+    pub(crate) strict: bool,
+    pub(crate) double_check: bool,
+    pub(crate) precompute_budget: usize,
+    pub(crate) base_seed: Option<[u8; 32]>,
+    pub(crate) element_registry: Option<Arc<ElementRegistry>>,
+    pub(crate) verification_cache: Option<Arc<VerificationCache>>,
+    pub(crate) min_security_bits: u32,
+    #[cfg(feature = "cardinality")]
+    pub(crate) track_cardinality: bool,
+    pub(crate) genesis: Option<GenesisRecord>,
+    pub(crate) duplicates: DuplicatePolicy,
+    pub(crate) rotation: RotationPolicy,
+    pub(crate) preset: Option<Preset>,
+}
+
+impl Default for AccumulatorBuilder {
+    fn default() -> Self {
+        Self {
+            prime_bits: 128,
+            hash: HashAlgorithm::default(),
+            exp_strategy: ExpStrategy::default(),
+            #[cfg(feature = "pool")]
+            prime_pool: None,
+            checkpoints: 0,
+            strict: false,
+            double_check: false,
+            precompute_budget: 0,
+            base_seed: None,
+            element_registry: None,
+            verification_cache: None,
+            min_security_bits: 0,
+            #[cfg(feature = "cardinality")]
+            track_cardinality: false,
+            genesis: None,
+            duplicates: DuplicatePolicy::default(),
+            rotation: RotationPolicy::default(),
+            preset: None,
+        }
+    }
+}
+
+impl AccumulatorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Takes sizes, hash, strict mode and the minimum security level from a
+    // preset; build() fails if this build cannot generate keys for it.
+    // Options set afterwards still apply, except that min_security_bits
+    // keeps prime_bits from going below the preset's level.
+    pub fn preset(mut self, preset: Preset) -> Self {
+        self.prime_bits = preset.modulus_bits() / 2;
+        self.hash = preset.hash();
+        self.strict = preset.strict();
+        self.min_security_bits = preset.min_security_bits();
+        self.preset = Some(preset);
+        self
+    }
+
+    // Bit length of each safe prime factor of the modulus (at most 128).
+    pub fn prime_bits(mut self, prime_bits: u32) -> Self {
+        self.prime_bits = prime_bits;
+        self
+    }
+
+    pub fn hash(mut self, hash: HashAlgorithm) -> Self {
+        self.hash = hash;
+        self
+    }
+
+    pub fn exp_strategy(mut self, exp_strategy: ExpStrategy) -> Self {
+        self.exp_strategy = exp_strategy;
+        self
+    }
+
+    // Draw the modulus factors from a pre-generated pool instead of generating
+    // them on the spot. The pool must produce primes of prime_bits.
+    #[cfg(feature = "pool")]
+    pub fn prime_pool(mut self, pool: Arc<PrimePool<4>>) -> Self {
+        self.prime_pool = Some(pool);
+        self
+    }
+
+    // Keep the values of the last `count` epochs so verify_at_epoch accepts
+    // witnesses up to that many deletions stale. Off by default.
+    pub fn checkpoints(mut self, count: usize) -> Self {
+        self.checkpoints = count;
+        self
+    }
+
+    // Re-derive the cached prime of every added element and run
+    // check_integrity on restored state, trading speed for detecting a
+    // corrupted cache. Off by default.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    // Re-verify every trapdoor result before it is used or published:
+    // roots are raised back to their prime and signatures to the public
+    // exponent, and a mismatch fails the operation with FAULT_DETECTED
+    // instead of releasing a result that could leak the factors of n. Costs
+    // one public exponentiation per root. Off by default.
+    pub fn double_check(mut self, double_check: bool) -> Self {
+        self.double_check = double_check;
+        self
+    }
+
+    // Spend up to `bytes` on a fixed-base table for the current value, rebuilt
+    // after every deletion, so bursts of witness issuance in one epoch cost a
+    // fraction of a full exponentiation each. The widest window that fits is
+    // used (256 KiB gives 7-bit windows); below 16 KiB nothing is precomputed.
+    // Off by default.
+    pub fn precompute(mut self, bytes: usize) -> Self {
+        self.precompute_budget = bytes;
+        self
+    }
+
+    // Derive the base from this public seed (a published beacon value, say)
+    // instead of a fresh random one, so the choice of base can be audited
+    // against the seed's origin. The seed is published in PublicParams.
+    pub fn base_seed(mut self, seed: [u8; 32]) -> Self {
+        self.base_seed = Some(seed);
+        self
+    }
+
+    // Take element primes from a registry shared with other accumulators and
+    // verifiers, recording new ones there. The accumulator uses the
+    // registry's hash-to-prime key, and the builder's hash must match it.
+    pub fn element_registry(mut self, registry: Arc<ElementRegistry>) -> Self {
+        self.element_registry = Some(registry);
+        self
+    }
+
+    // Answer repeated verify calls for the same element and witness from a
+    // cache, possibly shared with other accumulators. Off by default.
+    pub fn verification_cache(mut self, cache: Arc<VerificationCache>) -> Self {
+        self.verification_cache = Some(cache);
+        self
+    }
+
+    // Refuse to build, or restore state, whose SecurityEstimate is below
+    // `bits`. Off by default, since small demo sizes are useful in tests.
+    pub fn min_security_bits(mut self, bits: u32) -> Self {
+        self.min_security_bits = bits;
+        self
+    }
+
+    // Keep a count-carrying Merkle tree of the members so cardinality_proof
+    // can sign the member count. Costs 256 hashes per add and delete, and a
+    // rebuild from the members on restore.
+    #[cfg(feature = "cardinality")]
+    pub fn track_cardinality(mut self, track: bool) -> Self {
+        self.track_cardinality = track;
+        self
+    }
+
+    // Document the setup: the record's digest goes into the parameters. With
+    // ceremony contributions the base seed is derived from them, and an
+    // explicit base_seed must agree with it.
+    pub fn genesis(mut self, record: GenesisRecord) -> Self {
+        self.genesis = Some(record);
+        self
+    }
+
+    // What adding a member again does; Ignore by default. Restoring state
+    // that counts copies needs Multiset.
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    // Advise rotating the key (see BraavosAccumulator::key_status) after
+    // more than `operations` trapdoor operations. Applies to restored state
+    // too, whose count is persisted.
+    pub fn rotate_after_operations(mut self, operations: u64) -> Self {
+        self.rotation.max_operations = Some(operations);
+        self
+    }
+
+    // Advise rotating the key once it is older than `age`.
+    pub fn rotate_after(mut self, age: Duration) -> Self {
+        self.rotation.max_age = Some(age);
+        self
+    }
+
+    // What the current prime size would give, for warning before building.
+    pub fn security_estimate(&self) -> SecurityEstimate {
+        SecurityEstimate::for_modulus_bits(2 * self.prime_bits)
+    }
+
+    pub fn build(self) -> Result<BraavosAccumulator, &'static str> {
+        BraavosAccumulator::from_builder(self)
+    }
+
+    // Loads state written by to_state_bytes instead of generating keys; only
+    // the runtime options of this builder apply.
+    pub fn restore(&self, state: &[u8]) -> Result<BraavosAccumulator, &'static str> {
+        BraavosAccumulator::from_state_bytes(state, self)
+    }
+}
@@ -0,0 +1,103 @@
+use crypto_bigint::{
+    modular::MontyParams,
+    Odd, U512,
+};
+use std::collections::BTreeMap;
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::params::PublicParams;
+use crate::signature;
+
+const BUNDLE_TAG: &[u8] = b"braavos-static-bundle-v2";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntry {
+    pub prime: ElemPrime,      // Element prime the witness was computed for
+    pub witness: WitnessValue, // witness^prime = value mod n
+}
+
+// Frozen accumulator state: everything needed to check membership against the
+// final value without access to the manager or its secret key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StaticBundle {
+    pub params: PublicParams,                     // Modulus and element derivation
+    pub value: AccValue,                          // Final accumulator value
+    pub members: BTreeMap<Vec<u8>, BundleEntry>,  // Element -> prime and witness
+    pub signature: U512,                          // Manager signature over signed_bytes()
+}
+
+impl StaticBundle {
+    // Canonical encoding of everything except the signature.
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(BUNDLE_TAG);
+        self.params.encode(&mut w);
+        w.put_u512(self.value.as_uint());
+        w.put_u32(self.members.len() as u32);
+        for (x, entry) in &self.members {
+            w.put_bytes(x);
+            w.put_u256(entry.prime.as_uint());
+            w.put_u512(entry.witness.as_uint());
+        }
+        w.finish()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, BUNDLE_TAG, policy)?;
+        let params = PublicParams::decode(&mut r)?;
+        let value = AccValue::new(r.get_element(Some(&params.n))?);
+        let count = r.get_count()?;
+        let mut members = BTreeMap::new();
+        for _ in 0..count {
+            let x = r.get_bytes()?.to_vec();
+            let prime = r.get_prime()?;
+            let witness = WitnessValue::new(r.get_element(Some(&params.n))?);
+            if members.insert(x, BundleEntry { prime, witness }).is_some() {
+                return Err("Duplicate member in bundle");
+            }
+        }
+        let signature = r.get_residue(Some(&params.n))?;
+        r.finish()?;
+        Ok(Self { params, value, members, signature })
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        let n: Option<Odd<U512>> = Odd::new(self.params.n).into();
+        match n {
+            Some(n) => signature::verify(self.params.hash, &n, &self.signed_bytes(), &self.signature),
+            None => false,
+        }
+    }
+
+    pub fn witness(&self, x: impl AsRef<[u8]>) -> Option<&BundleEntry> {
+        self.members.get(x.as_ref())
+    }
+
+    // Checks the bundle signature and that x's witness opens the frozen value.
+    pub fn verify_member(&self, x: impl AsRef<[u8]>) -> bool {
+        let x = x.as_ref();
+        if !self.verify_signature() {
+            return false;
+        }
+        let Some(entry) = self.members.get(x) else {
+            return false;
+        };
+        let n: Option<Odd<U512>> = Odd::new(self.params.n).into();
+        let Some(n) = n else {
+            return false;
+        };
+        core::opens(&entry.witness, &entry.prime, &self.value, &MontyParams::new(n))
+    }
+}
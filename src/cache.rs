@@ -0,0 +1,133 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::group::AccValue;
+use crate::hash::HashAlgorithm;
+use crate::sync::lock;
+use crate::witness::Witness;
+
+const CACHE_DOMAIN: &[u8] = b"braavos-verification-cache";
+
+// Recent verification results, for services presented the same witness many
+// times (one credential shown to many endpoints): a repeat within `ttl` costs
+// a hash instead of an exponentiation. Entries are keyed by a digest of the
+// parameters, the value, the element and the witness, so a deletion or
+// rerandomization never serves a stale answer; the TTL only bounds how long
+// an answer is kept. Share it between accumulators with Arc (see
+// AccumulatorBuilder::verification_cache).
+pub struct VerificationCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct Entries {
+    results: HashMap<[u8; 32], (bool, Instant)>,
+    order: VecDeque<([u8; 32], Instant)>, // Insertion order, for eviction
+}
+
+impl VerificationCache {
+    // Keeps at most `capacity` results, each for `ttl`; the oldest is evicted
+    // first when full.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    // The cached result for this verification, or `verify`'s, which is
+    // cached unless it fails (a timeout, say).
+    pub fn verify_with<F>(
+        &self,
+        params_digest: &[u8; 32],
+        value: &AccValue,
+        x: &[u8],
+        w: &Witness,
+        verify: F,
+    ) -> Result<bool, &'static str>
+    where
+        F: FnOnce() -> Result<bool, &'static str>,
+    {
+        let key = HashAlgorithm::Sha256.hash(
+            CACHE_DOMAIN,
+            &[params_digest, &value.to_be_bytes(), x, &w.params_digest, &w.value.to_be_bytes()],
+        );
+        if let Some(result) = self.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(result);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = verify()?;
+        self.insert(key, result);
+        Ok(result)
+    }
+
+    fn get(&self, key: &[u8; 32]) -> Option<bool> {
+        let entries = lock(&self.entries);
+        match entries.results.get(key) {
+            Some(&(result, at)) if at.elapsed() < self.ttl => Some(result),
+            _ => None,
+        }
+    }
+
+    fn insert(&self, key: [u8; 32], result: bool) {
+        if self.capacity == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let mut entries = lock(&self.entries);
+        entries.results.insert(key, (result, now));
+        entries.order.push_back((key, now));
+        // Queue entries whose key was since reinserted are skipped over
+        while entries.results.len() > self.capacity || entries.order.len() > 2 * self.capacity {
+            let Some((old, at)) = entries.order.pop_front() else {
+                break;
+            };
+            if entries.results.get(&old).is_some_and(|&(_, t)| t == at) {
+                entries.results.remove(&old);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.entries).results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn clear(&self) {
+        let mut entries = lock(&self.entries);
+        entries.results.clear();
+        entries.order.clear();
+    }
+}
+
+impl fmt::Debug for VerificationCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerificationCache")
+            .field("capacity", &self.capacity)
+            .field("ttl", &self.ttl)
+            .field("entries", &self.len())
+            .finish()
+    }
+}
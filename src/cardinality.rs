@@ -0,0 +1,284 @@
+use crypto_bigint::{Odd, U512};
+use std::collections::HashMap;
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::hash::HashAlgorithm;
+use crate::params::PublicParams;
+use crate::signature;
+use crate::smt::{bit, prefix};
+
+const KEY_DOMAIN: &[u8] = b"braavos-count-key";
+const LEAF_DOMAIN: &[u8] = b"braavos-count-leaf";
+const NODE_DOMAIN: &[u8] = b"braavos-count-node";
+const CARDINALITY_TAG: &[u8] = b"braavos-cardinality";
+const INCLUSION_TAG: &[u8] = b"braavos-count-inclusion";
+const DEPTH: usize = 256;
+
+// Sparse Merkle sum tree over the member digests: every node commits to its
+// children and to how many members lie beneath it, so the root fixes the
+// member count. Kept by the accumulator when the builder enables
+// track_cardinality; adds and deletes cost DEPTH hashes each.
+#[derive(Clone, Debug)]
+pub(crate) struct CountTree {
+    hash: HashAlgorithm,
+    nodes: HashMap<(usize, [u8; 32]), Node>, // (depth, key prefix) -> node, non-empty only
+    empty: Vec<[u8; 32]>,                    // Hash of an empty subtree at each depth
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Node {
+    hash: [u8; 32],
+    count: u64,
+}
+
+// Signed statement of the member count at one epoch, for auditors checking
+// registry size claims without seeing the members. The root is a sum tree
+// whose counts must add up to the count. A member left out of it fails its
+// CountInclusionProof, so omissions are caught as long as members check;
+// made-up leaves could only inflate the count, so "at most k" is the claim
+// an auditor can rely on most.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardinalityProof {
+    pub params_digest: [u8; 32],
+    pub epoch: u64,
+    pub value: AccValue,
+    pub root: [u8; 32],
+    pub count: u64,
+    pub signature: U512,
+}
+
+// Path from a member's leaf to the root of a CardinalityProof, listing only
+// the siblings that are not empty subtrees.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountInclusionProof {
+    pub present: [u8; 32],             // Bit d set when the sibling at depth d + 1 is listed
+    pub siblings: Vec<([u8; 32], u64)>, // Listed siblings with their counts, root side first
+}
+
+impl CountTree {
+    pub(crate) fn new(hash: HashAlgorithm) -> Self {
+        let mut empty = vec![[0; 32]; DEPTH + 1];
+        for d in (0..DEPTH).rev() {
+            let child = Node {
+                hash: empty[d + 1],
+                count: 0,
+            };
+            empty[d] = node_hash(hash, &child, &child);
+        }
+        Self {
+            hash,
+            nodes: HashMap::new(),
+            empty,
+        }
+    }
+
+    pub(crate) fn root(&self) -> ([u8; 32], u64) {
+        let root = self.node(0, &[0; 32]);
+        (root.hash, root.count)
+    }
+
+    pub(crate) fn insert(&mut self, x: &[u8]) {
+        let key = self.hash.hash(KEY_DOMAIN, &[x]);
+        let leaf = Node {
+            hash: self.hash.hash(LEAF_DOMAIN, &[&key]),
+            count: 1,
+        };
+        self.set(&key, leaf);
+    }
+
+    pub(crate) fn remove(&mut self, x: &[u8]) {
+        let key = self.hash.hash(KEY_DOMAIN, &[x]);
+        let empty = Node {
+            hash: self.empty[DEPTH],
+            count: 0,
+        };
+        self.set(&key, empty);
+    }
+
+    pub(crate) fn prove(&self, x: &[u8]) -> CountInclusionProof {
+        let key = self.hash.hash(KEY_DOMAIN, &[x]);
+        let mut proof = CountInclusionProof {
+            present: [0; 32],
+            siblings: Vec::new(),
+        };
+        for d in 0..DEPTH {
+            let mut sibling = prefix(&key, d + 1);
+            sibling[d / 8] ^= 0x80 >> (d % 8);
+            if let Some(node) = self.nodes.get(&(d + 1, sibling)) {
+                proof.present[d / 8] |= 0x80 >> (d % 8);
+                proof.siblings.push((node.hash, node.count));
+            }
+        }
+        proof
+    }
+
+    fn node(&self, depth: usize, id: &[u8; 32]) -> Node {
+        self.nodes.get(&(depth, *id)).copied().unwrap_or(Node {
+            hash: self.empty[depth],
+            count: 0,
+        })
+    }
+
+    fn set(&mut self, key: &[u8; 32], leaf: Node) {
+        self.store(DEPTH, *key, leaf);
+        let mut current = leaf;
+        for d in (0..DEPTH).rev() {
+            let mut sibling = prefix(key, d + 1);
+            sibling[d / 8] ^= 0x80 >> (d % 8);
+            let sibling = self.node(d + 1, &sibling);
+            let (left, right) = if bit(key, d) { (sibling, current) } else { (current, sibling) };
+            current = Node {
+                hash: node_hash(self.hash, &left, &right),
+                count: left.count + right.count,
+            };
+            self.store(d, prefix(key, d), current);
+        }
+    }
+
+    fn store(&mut self, depth: usize, id: [u8; 32], node: Node) {
+        if node.count == 0 {
+            self.nodes.remove(&(depth, id));
+        } else {
+            self.nodes.insert((depth, id), node);
+        }
+    }
+}
+
+impl CardinalityProof {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(CARDINALITY_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u64(self.epoch);
+        w.put_u512(self.value.as_uint());
+        w.put_bytes(&self.root);
+        w.put_u64(self.count);
+        w.finish()
+    }
+
+    // Checks the manager's signature and that the statement is about this
+    // value; the caller decides whether the value is current.
+    pub fn verify(&self, params: &PublicParams, value: &AccValue) -> bool {
+        if self.params_digest != params.digest() || self.value != *value {
+            return false;
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        n.is_some_and(|n| signature::verify(params.hash, &n, &self.signed_bytes(), &self.signature))
+    }
+
+    pub fn proves_exactly(&self, params: &PublicParams, value: &AccValue, k: u64) -> bool {
+        self.count == k && self.verify(params, value)
+    }
+
+    pub fn proves_at_most(&self, params: &PublicParams, value: &AccValue, k: u64) -> bool {
+        self.count <= k && self.verify(params, value)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, CARDINALITY_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let epoch = r.get_u64()?;
+        let value = AccValue::new(r.get_residue(None)?);
+        let root = r.get_bytes()?.try_into().map_err(|_| "Invalid cardinality root")?;
+        let count = r.get_u64()?;
+        let signature = r.get_residue(None)?;
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            epoch,
+            value,
+            root,
+            count,
+            signature,
+        })
+    }
+}
+
+impl CountInclusionProof {
+    // Checks that x is one of the members counted by the statement: the path
+    // must rebuild both its root and its count.
+    pub fn verify(&self, hash: HashAlgorithm, statement: &CardinalityProof, x: impl AsRef<[u8]>) -> bool {
+        let listed = self.present.iter().map(|b| b.count_ones() as usize).sum::<usize>();
+        if listed != self.siblings.len() {
+            return false;
+        }
+        let empty = CountTree::new(hash).empty;
+        let key = hash.hash(KEY_DOMAIN, &[x.as_ref()]);
+        let mut current = Node {
+            hash: hash.hash(LEAF_DOMAIN, &[&key]),
+            count: 1,
+        };
+        let mut siblings = self.siblings.iter().rev();
+        for d in (0..DEPTH).rev() {
+            let sibling = if bit(&self.present, d) {
+                let Some(&(hash, count)) = siblings.next() else {
+                    return false;
+                };
+                Node { hash, count }
+            } else {
+                Node {
+                    hash: empty[d + 1],
+                    count: 0,
+                }
+            };
+            let (left, right) = if bit(&key, d) { (sibling, current) } else { (current, sibling) };
+            let Some(count) = left.count.checked_add(right.count) else {
+                return false;
+            };
+            current = Node {
+                hash: node_hash(hash, &left, &right),
+                count,
+            };
+        }
+        current.hash == statement.root && current.count == statement.count
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(INCLUSION_TAG);
+        w.put_bytes(&self.present);
+        w.put_u32(self.siblings.len() as u32);
+        for (hash, count) in &self.siblings {
+            w.put_bytes(hash);
+            w.put_u64(*count);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, INCLUSION_TAG, policy)?;
+        let present = r.get_bytes()?.try_into().map_err(|_| "Invalid proof bitmap")?;
+        let count = r.get_count()?;
+        if count > DEPTH {
+            return Err("Too many siblings in proof");
+        }
+        let mut siblings = Vec::with_capacity(count);
+        for _ in 0..count {
+            let hash = r.get_bytes()?.try_into().map_err(|_| "Invalid sibling hash")?;
+            siblings.push((hash, r.get_u64()?));
+        }
+        r.finish()?;
+        Ok(Self { present, siblings })
+    }
+}
+
+fn node_hash(hash: HashAlgorithm, left: &Node, right: &Node) -> [u8; 32] {
+    hash.hash(
+        NODE_DOMAIN,
+        &[&left.hash, &left.count.to_be_bytes(), &right.hash, &right.count.to_be_bytes()],
+    )
+}
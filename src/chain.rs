@@ -0,0 +1,209 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    NonZero, Odd, U256, U512,
+};
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::exp::pow_adaptive;
+use crate::group::{AccValue, ElemPrime};
+use crate::hash;
+use crate::params::PublicParams;
+use crate::update::EpochUpdate;
+
+const CHECKPOINT_TAG: &[u8] = b"braavos-checkpoint";
+const CHALLENGE_TAG: &[u8] = b"braavos-checkpoint-challenge";
+
+// One link of a chain that lets a new verifier trust the current value
+// knowing only the genesis parameters. Adds leave the value unchanged, so a
+// transition is the deletions since the previous checkpoint: the new value
+// raised to their product gives the previous value, which the Wesolowski
+// proof of exponentiation shows without the verifier computing the product.
+// Anyone with the update log can produce checkpoints; forging one means
+// taking roots modulo n.
+//
+// The first checkpoint continues from the parameters' base at epoch 0. After
+// rerandomization the parameters change and a new chain is needed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    pub previous: [u8; 32],      // digest() of the previous checkpoint, or the parameter digest
+    pub deleted: Vec<ElemPrime>, // Primes deleted between the two checkpoints
+    pub value: AccValue,         // Accumulator value at to_epoch
+    pub proof: U512,             // value^(q) with q = floor(product of deleted / challenge)
+}
+
+impl Checkpoint {
+    // The checkpoint after `previous` (None for the first one), covering
+    // `update`: every update since, coalesced (see UpdateLog::since).
+    pub fn prove(
+        params: &PublicParams,
+        previous: Option<&Checkpoint>,
+        update: &EpochUpdate,
+    ) -> Result<Self, &'static str> {
+        let (from_epoch, prior, link) = start_of(params, previous);
+        if update.from_epoch != from_epoch {
+            return Err("Update does not start at the previous checkpoint");
+        }
+        if update.params_digest != params.digest() || update.n != params.n {
+            return Err("Update is for different parameters");
+        }
+        let monty_params = monty_params(params)?;
+        let l = challenge(params, &link, &prior, &update.value, &update.deleted);
+        let value = MontyForm::new(update.value.as_uint(), monty_params);
+        // Long division of the product by l, one prime at a time:
+        // value^(product so far) = proof^l * value^r
+        let mut proof = MontyForm::one(monty_params);
+        let mut r = U256::ONE;
+        for prime in &update.deleted {
+            let (q, rem) = divide_step(&r, prime, &l)?;
+            proof = pow_adaptive(&proof, prime.as_uint()).mul(&pow_adaptive(&value, &q));
+            r = rem;
+        }
+        Ok(Self {
+            from_epoch,
+            to_epoch: update.to_epoch,
+            previous: link,
+            deleted: update.deleted.clone(),
+            value: update.value,
+            proof: proof.retrieve(),
+        })
+    }
+
+    pub fn digest(&self, params: &PublicParams) -> [u8; 32] {
+        params.hash.hash(CHECKPOINT_TAG, &[&self.to_bytes()])
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(CHECKPOINT_TAG);
+        w.put_u64(self.from_epoch);
+        w.put_u64(self.to_epoch);
+        w.put_bytes(&self.previous);
+        w.put_u32(self.deleted.len() as u32);
+        for prime in &self.deleted {
+            w.put_u256(prime.as_uint());
+        }
+        w.put_u512(self.value.as_uint());
+        w.put_u512(&self.proof);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, CHECKPOINT_TAG, policy)?;
+        let from_epoch = r.get_u64()?;
+        let to_epoch = r.get_u64()?;
+        let previous = r.get_bytes()?.try_into().map_err(|_| "Invalid checkpoint digest")?;
+        let mut deleted = Vec::new();
+        for _ in 0..r.get_count()? {
+            deleted.push(r.get_prime()?);
+        }
+        let value = AccValue::new(r.get_element(None)?);
+        let proof = r.get_element(None)?;
+        r.finish()?;
+        Ok(Self {
+            from_epoch,
+            to_epoch,
+            previous,
+            deleted,
+            value,
+            proof,
+        })
+    }
+}
+
+// Checks every link from the genesis parameters and returns the epoch and
+// value the chain ends at. An empty chain ends at the base.
+pub fn verify_checkpoint_chain(
+    genesis: &PublicParams,
+    chain: &[Checkpoint],
+) -> Result<(u64, AccValue), &'static str> {
+    let monty_params = monty_params(genesis)?;
+    let mut previous = None;
+    for checkpoint in chain {
+        verify_link(genesis, previous, checkpoint, &monty_params)?;
+        previous = Some(checkpoint);
+    }
+    Ok(match previous {
+        Some(last) => (last.to_epoch, last.value),
+        None => (0, genesis.base),
+    })
+}
+
+// Checks one checkpoint against the one before it (None for the first).
+pub(crate) fn verify_link(
+    genesis: &PublicParams,
+    previous: Option<&Checkpoint>,
+    checkpoint: &Checkpoint,
+    monty_params: &MontyParams<8>,
+) -> Result<(), &'static str> {
+    let (from_epoch, prior, link) = start_of(genesis, previous);
+    if checkpoint.previous != link || checkpoint.from_epoch != from_epoch {
+        return Err("Checkpoint does not follow the previous one");
+    }
+    if checkpoint.to_epoch != from_epoch + checkpoint.deleted.len() as u64 {
+        return Err("Checkpoint epochs do not match its deletions");
+    }
+    let (value, proof) = (checkpoint.value.as_uint(), &checkpoint.proof);
+    if !core::is_group_element(value, &genesis.n) || !core::is_group_element(proof, &genesis.n) {
+        return Err("Checkpoint value is not a group element");
+    }
+    let l = challenge(genesis, &link, &prior, &checkpoint.value, &checkpoint.deleted);
+    let mut r = U256::ONE;
+    for prime in &checkpoint.deleted {
+        r = divide_step(&r, prime, &l)?.1;
+    }
+    let value = MontyForm::new(value, *monty_params);
+    let proof = MontyForm::new(proof, *monty_params);
+    let opened = pow_adaptive(&proof, &l).mul(&pow_adaptive(&value, &r));
+    if !core::same_class(&opened.retrieve(), prior.as_uint(), &genesis.n) {
+        return Err("Checkpoint transition proof failed");
+    }
+    Ok(())
+}
+
+// Epoch, value and link digest a checkpoint after `previous` starts from.
+fn start_of(params: &PublicParams, previous: Option<&Checkpoint>) -> (u64, AccValue, [u8; 32]) {
+    match previous {
+        Some(c) => (c.to_epoch, c.value, c.digest(params)),
+        None => (0, params.base, params.digest()),
+    }
+}
+
+// Fiat-Shamir challenge prime, bound to both ends of the transition.
+fn challenge(
+    params: &PublicParams,
+    link: &[u8; 32],
+    prior: &AccValue,
+    value: &AccValue,
+    deleted: &[ElemPrime],
+) -> U256 {
+    let mut w = Writer::new(CHALLENGE_TAG);
+    w.put_bytes(link);
+    w.put_u512(prior.as_uint());
+    w.put_u512(value.as_uint());
+    w.put_u32(deleted.len() as u32);
+    for prime in deleted {
+        w.put_u256(prime.as_uint());
+    }
+    hash::hash_to_prime(params.hash, &U256::ZERO, &w.finish())
+}
+
+// (r * prime) divided by l, as (quotient, remainder). r < l < 2^256, so both fit.
+fn divide_step(r: &U256, prime: &ElemPrime, l: &U256) -> Result<(U256, U256), &'static str> {
+    let product = r.resize::<8>().wrapping_mul(&prime.as_uint().resize::<8>());
+    let l: U512 = l.resize();
+    let l: Option<NonZero<U512>> = NonZero::new(l).into();
+    let (q, rem) = product.div_rem(&l.ok_or("Challenge prime must be nonzero")?);
+    Ok((q.resize(), rem.resize()))
+}
+
+fn monty_params(params: &PublicParams) -> Result<MontyParams<8>, &'static str> {
+    let n: Option<Odd<U512>> = Odd::new(params.n).into();
+    Ok(MontyParams::new(n.ok_or("Modulus must be odd")?))
+}
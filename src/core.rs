@@ -0,0 +1,182 @@
+// The soundness-critical arithmetic as plain functions: every input is an
+// argument, every result is the return value, and nothing reads state, caches
+// or draws randomness. The accumulator, update messages and witnesses all go
+// through these, so verifying them (hax, Kani, creusot) covers what the rest
+// of the crate relies on.
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    NonZero, Odd, U256, U512,
+};
+use subtle::{ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::accumulator::pad_u256_to_u512;
+use crate::exp::{ExpStrategy, FixedBaseTable};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::hash::{self, HashAlgorithm};
+use crate::math;
+
+// The element prime of x under the given digest and key.
+pub fn hash_to_prime(alg: HashAlgorithm, key: &U256, x: &[u8]) -> ElemPrime {
+    ElemPrime::new(hash::hash_to_prime(alg, key, x))
+}
+
+// The base a verifiable seed determines: (n, seed) hashed out to twice the
+// modulus size, reduced mod n and squared, so it is a quadratic residue whose
+// discrete logarithm nobody chose.
+pub fn derive_base(alg: HashAlgorithm, seed: &[u8; 32], params: &MontyParams<8>) -> AccValue {
+    let n = params.modulus().get();
+    let n_bytes = n.to_be_bytes();
+    let mut wide = [0u8; 128];
+    for (counter, block) in wide.chunks_mut(32).enumerate() {
+        block.copy_from_slice(&alg.hash(b"braavos-base", &[&n_bytes, seed, &[counter as u8]]));
+    }
+    let (high, low) = wide.split_at(64);
+    let wide = (U512::from_be_slice(low), U512::from_be_slice(high));
+    let reduced = U512::rem_wide_vartime(wide, params.modulus().as_nz_ref());
+    let root = MontyForm::new(&reduced, *params);
+    AccValue::new(root.square().retrieve())
+}
+
+// base^exponent mod n, computed with the given strategy.
+pub fn pow(strategy: ExpStrategy, base: &U512, exponent: &U512, params: &MontyParams<8>) -> U512 {
+    strategy.pow(&MontyForm::new(base, *params), exponent).retrieve()
+}
+
+// value^(x^-1 mod sk): the witness for x, and the value after deleting x.
+// None when x is not a unit mod sk.
+pub fn root(
+    value: &AccValue,
+    x: &ElemPrime,
+    sk: &U256,
+    strategy: ExpStrategy,
+    params: &MontyParams<8>,
+) -> Option<WitnessValue> {
+    let x_inv: Option<U256> = x.as_uint().inv_mod(sk).into();
+    let root = pow(strategy, value.as_uint(), &pad_u256_to_u512(x_inv?), params);
+    Some(WitnessValue::new(root))
+}
+
+// value^(e^-1 mod sk) for an exponent wider than an element prime, such as
+// a blinded one (see BlindedPrime). None when e is not a unit mod sk.
+pub fn root_wide(
+    value: &AccValue,
+    e: &U512,
+    sk: &U256,
+    strategy: ExpStrategy,
+    params: &MontyParams<8>,
+) -> Option<WitnessValue> {
+    let modulus: Option<NonZero<U512>> = NonZero::new(pad_u256_to_u512(*sk)).into();
+    let e: U256 = (*e % modulus?).resize();
+    let e_inv: Option<U256> = e.inv_mod(sk).into();
+    let root = pow(strategy, value.as_uint(), &pad_u256_to_u512(e_inv?), params);
+    Some(WitnessValue::new(root))
+}
+
+// x^-1 mod sk for every x with a single modular inversion (Montgomery's
+// trick): running products, one inverse of the total, then peeled apart from
+// the end. None when any x is not a unit mod sk.
+pub fn batch_inverse(xs: &[ElemPrime], sk: &U256) -> Option<Vec<U256>> {
+    let sk: Option<Odd<U256>> = Odd::new(*sk).into();
+    let params = MontyParams::new(sk?);
+    let mut prefix = Vec::with_capacity(xs.len());
+    let mut running = MontyForm::one(params);
+    for x in xs {
+        prefix.push(running);
+        running = running.mul(&MontyForm::new(x.as_uint(), params));
+    }
+    let inv: Option<MontyForm<4>> = running.inv().into();
+    let mut inv = inv?;
+    let mut out = vec![U256::ZERO; xs.len()];
+    for (i, x) in xs.iter().enumerate().rev() {
+        out[i] = inv.mul(&prefix[i]).retrieve();
+        inv = inv.mul(&MontyForm::new(x.as_uint(), params));
+    }
+    Some(out)
+}
+
+// root() against a table precomputed for the value.
+pub(crate) fn root_precomputed(
+    table: &FixedBaseTable,
+    x: &ElemPrime,
+    sk: &U256,
+    params: &MontyParams<8>,
+) -> Option<WitnessValue> {
+    let x_inv: Option<U256> = x.as_uint().inv_mod(sk).into();
+    Some(WitnessValue::new(table.pow(&x_inv?, params).retrieve()))
+}
+
+// Values live in QR_n, but without the factors a verifier can only check the
+// Jacobi symbol, which admits -QR_n as well. Verification therefore works in
+// the signed residues QR_n / {1, -1}: v and n - v are the same element, and
+// nothing with Jacobi symbol -1 (such as a square root of 1 other than +-1,
+// which would leak the factors) is accepted.
+
+// Whether x is in J_n = QR_n and -QR_n: nonzero, reduced and of Jacobi symbol 1.
+pub fn is_group_element(x: &U512, n: &U512) -> bool {
+    *x != U512::ZERO && x < n && math::jacobi(x, n) == 1
+}
+
+// Whether x is a group element other than +-1. With n a product of safe
+// primes those are the only elements of small order: every other one has
+// order p', q' or p'q'. A witness of order 1 or 2 opens +-1 for every element,
+// and blinding such a value with r^x hides nothing of it.
+pub fn is_nontrivial_element(x: &U512, n: &U512) -> bool {
+    is_group_element(x, n) && *x != U512::ONE && *x != n.wrapping_sub(&U512::ONE)
+}
+
+// Whether a and b are the same signed residue, in constant time.
+pub fn same_class(a: &U512, b: &U512, n: &U512) -> bool {
+    (a.ct_eq(b) | a.wrapping_add(b).ct_eq(n)).into()
+}
+
+// Whether w is an x-th root of value, up to sign.
+pub fn opens(w: &WitnessValue, x: &ElemPrime, value: &AccValue, params: &MontyParams<8>) -> bool {
+    canonical_root(w, x, value, params).is_some()
+}
+
+// The sign of w whose x-th power is exactly value, for values known to be
+// in QR_n: since x is odd, that is the one in QR_n. None if w opens neither.
+// The sign is chosen without branching on the witness.
+pub fn canonical_root(
+    w: &WitnessValue,
+    x: &ElemPrime,
+    value: &AccValue,
+    params: &MontyParams<8>,
+) -> Option<WitnessValue> {
+    let n = params.modulus().get();
+    let opened = w.pow_elem(x, params);
+    let exact = opened.ct_eq(value);
+    let negated = opened.as_uint().wrapping_add(value.as_uint()).ct_eq(&n);
+    let root = WitnessValue::conditional_select(&WitnessValue::new(n.wrapping_sub(w.as_uint())), w, exact);
+    CtOption::new(root, exact | negated).into()
+}
+
+// Carries x's witness across the deletion of y, given the value after it.
+// With alpha x - beta y = 1 the new witness is w^-beta value_after^alpha.
+// None when x = y or w is not a unit.
+pub fn update_on_deletion(
+    w: &WitnessValue,
+    x: &ElemPrime,
+    y: &ElemPrime,
+    value_after: &AccValue,
+    params: &MontyParams<8>,
+) -> Option<WitnessValue> {
+    let (alpha, beta) = math::bezout(x.as_uint(), y.as_uint())?;
+    let w_inv: Option<MontyForm<8>> = MontyForm::new(w.as_uint(), *params).inv().into();
+    let value_after = MontyForm::new(value_after.as_uint(), *params);
+    Some(WitnessValue::new(w_inv?.pow(&beta).mul(&value_after.pow(&alpha)).retrieve()))
+}
+
+// Whether (d, beta) shows x was never deleted: d^x base^beta = value, up to sign.
+pub fn opens_non_membership(
+    d: &U512,
+    beta: &U256,
+    x: &ElemPrime,
+    base: &AccValue,
+    value: &AccValue,
+    params: &MontyParams<8>,
+) -> bool {
+    let d = MontyForm::new(d, *params).pow(x.as_uint());
+    let base = MontyForm::new(base.as_uint(), *params).pow(beta);
+    same_class(&d.mul(&base).retrieve(), value.as_uint(), &params.modulus().get())
+}
@@ -0,0 +1,136 @@
+use crate::hash::HashAlgorithm;
+
+const ELEMENT_DOMAIN: &[u8] = b"braavos-corpus-element";
+const DELETE_DOMAIN: &[u8] = b"braavos-corpus-delete";
+const DIGEST_DOMAIN: &[u8] = b"braavos-corpus-digest";
+
+// Elements at least this long do not collide in any corpus that fits in memory.
+const MIN_ELEMENT_LEN: usize = 16;
+
+// A churn workload determined by its seed alone, so benchmarks, fuzzing and
+// the simulator run identical element sets and schedules on every machine
+// and version. Elements and choices come from SHA-256 in counter mode, not
+// from rand, whose generators may change between releases. Compare digest()
+// to check two runs used the same workload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CorpusSpec {
+    pub seed: u64,
+    pub initial_members: u32,
+    pub protected: u32, // Initial members never deleted, such as tracked holders
+    pub rounds: u32,
+    pub adds_per_round: u32,
+    pub deletes_per_round: u32, // Fewer once only protected members are left
+    pub element_len: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CorpusOp {
+    Add(Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Corpus {
+    pub spec: CorpusSpec,
+    pub initial: Vec<Vec<u8>>,     // Added before the first round
+    pub rounds: Vec<Vec<CorpusOp>>, // Each round's adds, then its deletes
+}
+
+impl Default for CorpusSpec {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            initial_members: 1000,
+            protected: 0,
+            rounds: 10,
+            adds_per_round: 100,
+            deletes_per_round: 10,
+            element_len: 32,
+        }
+    }
+}
+
+impl CorpusSpec {
+    pub fn generate(&self) -> Result<Corpus, &'static str> {
+        if self.protected > self.initial_members {
+            return Err("More protected members than initial members");
+        }
+        if self.element_len < MIN_ELEMENT_LEN {
+            return Err("Corpus elements must be at least 16 bytes");
+        }
+        let initial: Vec<_> = (0..self.initial_members as u64).map(|i| self.element(i)).collect();
+        let mut deletable = initial[self.protected as usize..].to_vec();
+        let mut next = self.initial_members as u64;
+        let mut rounds = Vec::with_capacity(self.rounds as usize);
+        for round in 0..self.rounds {
+            let mut ops = Vec::new();
+            for _ in 0..self.adds_per_round {
+                let element = self.element(next);
+                next += 1;
+                deletable.push(element.clone());
+                ops.push(CorpusOp::Add(element));
+            }
+            for i in 0..self.deletes_per_round {
+                if deletable.is_empty() {
+                    break;
+                }
+                let pick = self.pick(round, i, deletable.len());
+                ops.push(CorpusOp::Delete(deletable.swap_remove(pick)));
+            }
+            rounds.push(ops);
+        }
+        Ok(Corpus {
+            spec: *self,
+            initial,
+            rounds,
+        })
+    }
+
+    // Element `index`, expanded block by block to element_len bytes.
+    fn element(&self, index: u64) -> Vec<u8> {
+        let mut element = Vec::with_capacity(self.element_len + 32);
+        for block in 0u32.. {
+            if element.len() >= self.element_len {
+                break;
+            }
+            let parts: [&[u8]; 3] = [&self.seed.to_be_bytes(), &index.to_be_bytes(), &block.to_be_bytes()];
+            element.extend_from_slice(&HashAlgorithm::Sha256.hash(ELEMENT_DOMAIN, &parts));
+        }
+        element.truncate(self.element_len);
+        element
+    }
+
+    // Which of `len` deletable members the i-th deletion of a round takes.
+    fn pick(&self, round: u32, i: u32, len: usize) -> usize {
+        let parts: [&[u8]; 3] = [&self.seed.to_be_bytes(), &round.to_be_bytes(), &i.to_be_bytes()];
+        let digest = HashAlgorithm::Sha256.hash(DELETE_DOMAIN, &parts);
+        (digest[..8].iter().fold(0u64, |acc, &b| acc << 8 | b as u64) % len as u64) as usize
+    }
+}
+
+impl Corpus {
+    // The initial members that are never deleted, in order.
+    pub fn protected(&self) -> &[Vec<u8>] {
+        &self.initial[..self.spec.protected as usize]
+    }
+
+    // Every round's operations in order, after the initial members.
+    pub fn operations(&self) -> impl Iterator<Item = &CorpusOp> {
+        self.rounds.iter().flatten()
+    }
+
+    // Fingerprint of the whole workload, to compare across machines.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut parts: Vec<&[u8]> = Vec::new();
+        for element in &self.initial {
+            parts.extend_from_slice(&[b"i", element]);
+        }
+        for op in self.operations() {
+            match op {
+                CorpusOp::Add(element) => parts.extend_from_slice(&[b"a", element]),
+                CorpusOp::Delete(element) => parts.extend_from_slice(&[b"d", element]),
+            }
+        }
+        HashAlgorithm::Sha256.hash(DIGEST_DOMAIN, &parts)
+    }
+}
@@ -0,0 +1,181 @@
+use crypto_bigint::{U256, U512};
+#[cfg(feature = "zk")]
+use crypto_bigint::U2048;
+
+use crate::chain::Checkpoint;
+use crate::group::{AccValue, WitnessValue};
+use crate::params::PublicParams;
+#[cfg(feature = "smt")]
+use crate::smt::{SmtProof, DEPTH};
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+#[cfg(feature = "zk")]
+use crate::zk::{MembershipProof, NonMembershipProof};
+
+// What a verifier (or, for updates, a holder) receives and checks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CostedOperation {
+    Membership,                         // Witness against the value
+    NonMembership,                      // NonMembershipWitness: never deleted
+    WitnessUpdate { deletions: usize }, // One EpochUpdate applied by a holder
+    Checkpoint { deletions: usize },    // One link of the checkpoint chain
+    #[cfg(feature = "smt")]
+    SmtMembership { members: u64 }, // SmtProof from a tree of this many members
+    #[cfg(feature = "smt")]
+    SmtNonMembership { members: u64 },
+    // MembershipProof and NonMembershipProof about a committed element
+    #[cfg(feature = "zk")]
+    ZkMembership,
+    #[cfg(feature = "zk")]
+    ZkNonMembership,
+}
+
+// Expected cost of one operation. Exponentiations are constant-time, so each
+// costs about one modular squaring per bit of its exponent's type whatever
+// the exponent's value; exponent_bits sums those widths and is the number to
+// budget CPU by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CostEstimate {
+    pub proof_bytes: usize, // Encoded size of what is sent
+    pub exponentiations: u32,
+    pub exponent_bits: u32,
+    pub hashes_to_prime: u32, // Each a search of several hashes and primality tests
+    pub hashes: u32,          // Plain digests, Merkle nodes say
+}
+
+// Sizes come from the actual encodings under the given parameters, and
+// counts from the verification code, so they track both.
+pub struct CostModel;
+
+impl CostModel {
+    pub fn estimate(op: CostedOperation, params: &PublicParams) -> CostEstimate {
+        match op {
+            CostedOperation::Membership => CostEstimate {
+                proof_bytes: witness_bytes(params),
+                exponentiations: 1,
+                exponent_bits: U256::BITS,
+                hashes_to_prime: 1,
+                hashes: 0,
+            },
+            // No wire encoding of its own: the digest, epoch, d and beta
+            CostedOperation::NonMembership => CostEstimate {
+                proof_bytes: 32 + 8 + 64 + 32,
+                exponentiations: 2,
+                exponent_bits: 2 * U256::BITS,
+                hashes_to_prime: 1,
+                hashes: 0,
+            },
+            // Two exponentiations per deletion with the Bezout coefficients,
+            // plus walking the value back over all but the last deletion
+            CostedOperation::WitnessUpdate { deletions } => CostEstimate {
+                proof_bytes: update_bytes(params, deletions),
+                exponentiations: (3 * deletions).saturating_sub(1) as u32,
+                exponent_bits: (3 * deletions).saturating_sub(1) as u32 * U256::BITS,
+                hashes_to_prime: 0,
+                hashes: 0,
+            },
+            // The Wesolowski check: proof^l value^r, l hashed to a prime
+            CostedOperation::Checkpoint { deletions } => CostEstimate {
+                proof_bytes: checkpoint_bytes(params, deletions),
+                exponentiations: 2,
+                exponent_bits: 2 * U256::BITS,
+                hashes_to_prime: 1,
+                hashes: 1,
+            },
+            // The relations plus two powers of the challenge
+            #[cfg(feature = "zk")]
+            CostedOperation::ZkMembership => CostEstimate {
+                proof_bytes: membership_proof_bytes(),
+                exponentiations: 9,
+                exponent_bits: 9 * U2048::BITS,
+                hashes_to_prime: 0,
+                hashes: 1,
+            },
+            #[cfg(feature = "zk")]
+            CostedOperation::ZkNonMembership => CostEstimate {
+                proof_bytes: non_membership_proof_bytes(),
+                exponentiations: 10,
+                exponent_bits: 10 * U2048::BITS,
+                hashes_to_prime: 0,
+                hashes: 1,
+            },
+            // The key, the leaf and one node per level
+            #[cfg(feature = "smt")]
+            CostedOperation::SmtMembership { members } | CostedOperation::SmtNonMembership { members } => {
+                CostEstimate {
+                    proof_bytes: smt_proof_bytes(members),
+                    exponentiations: 0,
+                    exponent_bits: 0,
+                    hashes_to_prime: 0,
+                    hashes: DEPTH as u32 + 2,
+                }
+            }
+        }
+    }
+}
+
+fn witness_bytes(params: &PublicParams) -> usize {
+    let witness = Witness {
+        params_digest: params.digest(),
+        value: WitnessValue::new(U512::ONE),
+    };
+    witness.to_bytes().len()
+}
+
+// Deleted primes are fixed-width, so sizes are linear in the count.
+fn update_bytes(params: &PublicParams, deletions: usize) -> usize {
+    let update = EpochUpdate {
+        from_epoch: 0,
+        to_epoch: 1,
+        n: params.n,
+        params_digest: params.digest(),
+        deleted: Vec::new(),
+        value: AccValue::new(U512::ONE),
+    };
+    update.to_bytes().len() + deletions * U256::BYTES
+}
+
+fn checkpoint_bytes(params: &PublicParams, deletions: usize) -> usize {
+    let checkpoint = Checkpoint {
+        from_epoch: 0,
+        to_epoch: 1,
+        previous: params.digest(),
+        deleted: Vec::new(),
+        value: AccValue::new(U512::ONE),
+        proof: U512::ONE,
+    };
+    checkpoint.to_bytes().len() + deletions * U256::BYTES
+}
+
+#[cfg(feature = "zk")]
+fn membership_proof_bytes() -> usize {
+    let proof = MembershipProof {
+        blinded_witness: U512::ZERO,
+        t: [U512::ZERO; 3],
+        s: [U2048::ZERO; 5],
+    };
+    proof.to_bytes().len()
+}
+
+#[cfg(feature = "zk")]
+fn non_membership_proof_bytes() -> usize {
+    let proof = NonMembershipProof {
+        epoch: 0,
+        commitment: U512::ZERO,
+        blinded_witness: U512::ZERO,
+        t: [U512::ZERO; 3],
+        s: [U2048::ZERO; 6],
+    };
+    proof.to_bytes().len()
+}
+
+// With random keys a path lists about log2(members) non-empty siblings.
+#[cfg(feature = "smt")]
+fn smt_proof_bytes(members: u64) -> usize {
+    let siblings = (u64::BITS - members.leading_zeros()) as usize;
+    let proof = SmtProof {
+        present: [0; 32],
+        siblings: vec![[0; 32]; siblings],
+    };
+    proof.to_bytes().len()
+}
@@ -0,0 +1,167 @@
+use crypto_bigint::modular::{MontyForm, MontyParams};
+use crypto_bigint::{Odd, U512};
+
+use crate::audit::{AuditRecord, Decision, Operation};
+use crate::binding::verify_signature;
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::exp::pow_adaptive;
+use crate::group::{AccValue, WitnessValue};
+use crate::params::PublicParams;
+use crate::registry::RegistryEntry;
+use crate::response::{SignedState, SignedWitness};
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const CUSTODY_TAG: &[u8] = b"braavos-custody-record";
+
+// Chain of custody for one element's witness, for showing it was valid at a
+// given epoch: how the element's prime was derived, the audited add that
+// issued it, every update published since, and the current witness and
+// value, all under one manager signature (see Manager::custody_record).
+//
+// Epochs are the only time this library knows; mapping a date to an epoch
+// is up to the deployment (its epoch schedule, or a signed state it kept).
+// witness_at replays the updates backwards from the signed current value,
+// so the value it returns for an epoch should be compared with one
+// published at that epoch, such as a checkpoint: a record that left out a
+// deletion would disagree there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustodyRecord {
+    pub element: Vec<u8>,
+    pub derivation: RegistryEntry,  // The element's prime and its certificate
+    pub issuance: AuditRecord,      // The add the witness descends from
+    pub updates: Vec<EpochUpdate>,  // Every update from the issuance epoch on, as published
+    pub current: SignedWitness,     // Witness at the current epoch
+    pub state: SignedState,         // Value at the current epoch
+    pub signature: U512,            // Manager signature over all of the above
+}
+
+impl CustodyRecord {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(CUSTODY_TAG);
+        w.put_bytes(&self.element);
+        w.put_u256(self.derivation.prime.as_uint());
+        w.put_u32(self.derivation.counter);
+        w.put_bytes(&self.derivation.certificate);
+        self.issuance.encode(&mut w);
+        w.put_u32(self.updates.len() as u32);
+        for update in &self.updates {
+            w.put_bytes(&update.to_bytes());
+        }
+        w.put_bytes(&self.current.to_bytes());
+        w.put_bytes(&self.state.to_bytes());
+        w.finish()
+    }
+
+    // Checks the signatures, the derivation, and that the updates run
+    // unbroken from the issuance to the current epoch without deleting the
+    // element, ending at a value its current witness opens.
+    pub fn verify(&self, params: &PublicParams) -> Result<(), &'static str> {
+        if !verify_signature(params, &self.signed_bytes(), &self.signature) {
+            return Err("Custody record signature is invalid");
+        }
+        if !self.current.verify(params) || !self.state.verify(params) {
+            return Err("Current witness or state signature is invalid");
+        }
+        if self.current.element != self.element || self.issuance.element != self.element {
+            return Err("Custody record is for more than one element");
+        }
+        if self.issuance.operation != Operation::Add || self.issuance.decision != Decision::Allow {
+            return Err("Issuance record is not an authorized add");
+        }
+        if !self.derivation.check(params.hash, &params.prf_key, &self.element) {
+            return Err("Element prime certificate is invalid");
+        }
+        let mut epoch = self.issuance.epoch;
+        for update in &self.updates {
+            if update.from_epoch != epoch || update.params_digest != params.digest() {
+                return Err("Updates do not continue from the issuance");
+            }
+            if update.deleted.contains(&self.derivation.prime) {
+                return Err("Element was deleted after issuance");
+            }
+            epoch = update.to_epoch;
+        }
+        if epoch != self.current.epoch || self.state.epoch != epoch {
+            return Err("Updates do not reach the current epoch");
+        }
+        if self.updates.last().is_some_and(|u| u.value != self.state.value) {
+            return Err("Updates do not end at the current value");
+        }
+        if !core::opens(&self.current.witness.value, &self.derivation.prime, &self.state.value, &monty(params)?) {
+            return Err("Current witness does not open the current value");
+        }
+        Ok(())
+    }
+
+    // The witness and value at an epoch between issuance and now, by undoing
+    // the deletions since: both are raised to each prime deleted after it.
+    // Call verify first.
+    pub fn witness_at(&self, params: &PublicParams, epoch: u64) -> Result<(Witness, AccValue), &'static str> {
+        if epoch < self.issuance.epoch || epoch > self.current.epoch {
+            return Err("Epoch is outside the custody record");
+        }
+        if epoch != self.issuance.epoch && !self.updates.iter().any(|u| u.to_epoch == epoch) {
+            return Err("Epoch is not on an update boundary");
+        }
+        let monty = monty(params)?;
+        let mut witness = MontyForm::new(self.current.witness.value.as_uint(), monty);
+        let mut value = MontyForm::new(self.state.value.as_uint(), monty);
+        for update in self.updates.iter().rev().take_while(|u| u.to_epoch > epoch) {
+            for prime in &update.deleted {
+                witness = pow_adaptive(&witness, prime.as_uint());
+                value = pow_adaptive(&value, prime.as_uint());
+            }
+        }
+        let witness = Witness {
+            params_digest: self.current.witness.params_digest,
+            value: WitnessValue::new(witness.retrieve()),
+        };
+        Ok((witness, AccValue::new(value.retrieve())))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, CUSTODY_TAG, policy)?;
+        let element = r.get_bytes()?.to_vec();
+        let derivation = RegistryEntry {
+            prime: r.get_prime()?,
+            counter: r.get_u32()?,
+            certificate: r.get_bytes()?.to_vec(),
+        };
+        let issuance = AuditRecord::decode(&mut r)?;
+        let mut updates = Vec::new();
+        for _ in 0..r.get_count()? {
+            updates.push(EpochUpdate::from_bytes_with(r.get_bytes()?, policy)?);
+        }
+        let current = SignedWitness::from_bytes_with(r.get_bytes()?, policy)?;
+        let state = SignedState::from_bytes_with(r.get_bytes()?, policy)?;
+        let signature = r.get_residue(policy.modulus.as_ref())?;
+        r.finish()?;
+        Ok(Self {
+            element,
+            derivation,
+            issuance,
+            updates,
+            current,
+            state,
+            signature,
+        })
+    }
+}
+
+fn monty(params: &PublicParams) -> Result<MontyParams<8>, &'static str> {
+    let n: Option<Odd<U512>> = Odd::new(params.n).into();
+    Ok(MontyParams::new(n.ok_or("Modulus must be odd")?))
+}
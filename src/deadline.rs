@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Returned by the `_within` operations when their deadline passes or they are
+// cancelled before finishing. Nothing has changed when they are returned.
+pub const TIMEOUT: &str = "Operation timed out";
+pub const CANCELLED: &str = "Operation cancelled";
+
+// Time budget for one operation. Checked between hash-to-prime candidates,
+// whose number is unbounded, and before each exponentiation, so an operation
+// overruns by at most one primality test or exponentiation.
+#[derive(Clone, Debug, Default)]
+pub struct Deadline {
+    at: Option<Instant>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+// Cancels every operation running under the deadlines made from it.
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl Deadline {
+    // No time limit and no cancellation: what the plain operations use.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn at(at: Instant) -> Self {
+        Self {
+            at: Some(at),
+            cancel: None,
+        }
+    }
+
+    pub fn after(budget: Duration) -> Self {
+        Self::at(Instant::now() + budget)
+    }
+
+    pub fn cancelled_by(mut self, handle: &CancelHandle) -> Self {
+        self.cancel = Some(handle.0.clone());
+        self
+    }
+
+    pub fn remaining(&self) -> Option<Duration> {
+        self.at.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+            return Err(CANCELLED);
+        }
+        match self.at {
+            Some(at) if Instant::now() >= at => Err(TIMEOUT),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
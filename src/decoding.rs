@@ -0,0 +1,59 @@
+use crypto_bigint::U512;
+
+use crate::params::PublicParams;
+
+// Limits and canonicality rules for decoding untrusted bytes. Every decoder
+// enforces the size limits; `from_bytes` uses the default policy and
+// `from_bytes_with` takes one.
+//
+// Under a canonical policy, residues mod n must be reduced and nonzero and
+// element primes must have the shape hash-to-prime gives them, so each value
+// has exactly one accepted encoding. Otherwise residues are reduced mod n on
+// decode. Encodings that do not carry the modulus (witnesses, proofs) are
+// checked against `modulus` when it is set, and with `nontrivial_witnesses`
+// witness values of small order are rejected too (see
+// WitnessValue::parse_checked).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodingPolicy {
+    pub max_input: usize, // Longest encoding accepted at all
+    pub max_field: usize, // Longest length-prefixed field, such as an element
+    pub max_items: usize, // Longest list: members, deleted primes, tokens, ...
+    pub canonical: bool,  // Reject instead of normalizing
+    pub nontrivial_witnesses: bool,
+    pub modulus: Option<U512>,
+}
+
+impl Default for DecodingPolicy {
+    // Generous enough for full accumulator state with millions of members.
+    fn default() -> Self {
+        Self {
+            max_input: 1 << 30,
+            max_field: 1 << 20,
+            max_items: 1 << 24,
+            canonical: false,
+            nontrivial_witnesses: false,
+            modulus: None,
+        }
+    }
+}
+
+impl DecodingPolicy {
+    // For verifiers facing adversarial input: sized for single witnesses,
+    // proofs and update messages, and canonical.
+    pub fn strict() -> Self {
+        Self {
+            max_input: 1 << 20,
+            max_field: 1 << 12,
+            max_items: 1 << 12,
+            canonical: true,
+            nontrivial_witnesses: true,
+            modulus: None,
+        }
+    }
+
+    // Checks residues in encodings without a modulus against this one.
+    pub fn for_params(mut self, params: &PublicParams) -> Self {
+        self.modulus = Some(params.n);
+        self
+    }
+}
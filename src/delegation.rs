@@ -0,0 +1,262 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, RandomMod, U2048, U256, U512,
+};
+use rand::rngs::OsRng;
+use std::fmt;
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::freshness::FreshnessProof;
+use crate::group::AccValue;
+use crate::hash::HashAlgorithm;
+use crate::holder::WitnessHolder;
+use crate::params::PublicParams;
+use crate::sharing::{field_params, group_params, GROUP_MODULUS, GROUP_ORDER};
+
+const DELEGATION_TAG: &[u8] = b"braavos-delegation";
+const PRESENTATION_TAG: &[u8] = b"braavos-delegated-presentation";
+const SIGNATURE_DOMAIN: &[u8] = b"braavos-device-signature";
+
+// Witness delegation: a holder lets a sub-device prove membership until an
+// epoch without giving it the witness. The holder makes a Delegation, a
+// freshness proof of knowing the witness whose nonce commits to the element,
+// the last epoch and the device's key, and hands it to the device. The
+// device answers each verifier's nonce by signing it together with the
+// delegation, so a copied delegation is useless without the device key.
+//
+// The proof is of membership at the epoch it was made. A verifier holding
+// the accumulator also checks the element is still a member (see
+// BraavosAccumulator::verify_delegated); one with the public parameters only
+// learns of a deletion once the delegation runs out, so the last epoch bounds
+// how long a revoked holder's devices keep passing.
+
+// A device's Schnorr key in the RFC 3526 group 14 (as for share commitments).
+#[derive(Clone)]
+pub struct DeviceKey {
+    secret: U2048,
+    public: DevicePublicKey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DevicePublicKey(pub U2048);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceSignature {
+    pub r: U2048, // g^k
+    pub s: U2048, // k + e secret mod q
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delegation {
+    pub element: Vec<u8>,
+    pub until_epoch: u64, // Last epoch the device may prove membership at
+    pub device: DevicePublicKey,
+    pub proof: FreshnessProof, // The holder's, at proof.epoch, bound to the fields above
+}
+
+// What a device shows a verifier: the delegation and its signature over the
+// verifier's nonce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelegatedPresentation {
+    pub delegation: Delegation,
+    pub signature: DeviceSignature,
+}
+
+impl DeviceKey {
+    pub fn generate() -> Result<Self, &'static str> {
+        let order = field_params()?;
+        let secret = loop {
+            let secret = U2048::random_mod(&mut OsRng, order.modulus().as_nz_ref());
+            if secret != U2048::ZERO {
+                break secret;
+            }
+        };
+        Ok(Self {
+            secret,
+            public: DevicePublicKey(generator()?.pow(&secret).retrieve()),
+        })
+    }
+
+    pub fn public(&self) -> DevicePublicKey {
+        self.public
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Result<DeviceSignature, &'static str> {
+        let order = field_params()?;
+        let k = U2048::random_mod(&mut OsRng, order.modulus().as_nz_ref());
+        let r = generator()?.pow(&k).retrieve();
+        let e = challenge(&self.public, &r, message);
+        let es = MontyForm::new(&e, order).mul(&MontyForm::new(&self.secret, order));
+        Ok(DeviceSignature {
+            r,
+            s: MontyForm::new(&k, order).add(&es).retrieve(),
+        })
+    }
+
+    // Answers a verifier's nonce under a delegation to this device.
+    pub fn present(&self, delegation: &Delegation, nonce: &[u8]) -> Result<DelegatedPresentation, &'static str> {
+        if delegation.device != self.public {
+            return Err("Delegation is for another device");
+        }
+        Ok(DelegatedPresentation {
+            delegation: delegation.clone(),
+            signature: self.sign(&presentation_message(delegation, nonce))?,
+        })
+    }
+}
+
+impl fmt::Debug for DeviceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeviceKey").field("public", &self.public).finish_non_exhaustive()
+    }
+}
+
+impl DevicePublicKey {
+    // Whether s is this key's signature on the message. The key must be in
+    // the prime-order subgroup, so it cannot leak through small subgroups.
+    pub fn verify(&self, message: &[u8], signature: &DeviceSignature) -> bool {
+        let (Ok(group), Ok(g)) = (group_params(), generator()) else {
+            return false;
+        };
+        let in_group = |v: &U2048| *v > U2048::ONE && *v < GROUP_MODULUS;
+        if !in_group(&self.0) || !in_group(&signature.r) || signature.s >= GROUP_ORDER {
+            return false;
+        }
+        let public = MontyForm::new(&self.0, group);
+        if public.pow(&GROUP_ORDER) != MontyForm::one(group) {
+            return false;
+        }
+        let e = challenge(self, &signature.r, message);
+        g.pow(&signature.s) == MontyForm::new(&signature.r, group).mul(&public.pow(&e))
+    }
+}
+
+impl WitnessHolder {
+    // A delegation of this holder's membership to a device until the given
+    // epoch, proven against the value the witness opens now. The witness
+    // must be current: a verifier only accepts the proof against the value
+    // at this holder's epoch.
+    pub fn delegate(
+        &self,
+        params: &PublicParams,
+        until_epoch: u64,
+        device: &DevicePublicKey,
+    ) -> Result<Delegation, &'static str> {
+        if until_epoch < self.epoch() {
+            return Err("Delegation would already have expired");
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let monty_params = MontyParams::new(n.ok_or("Modulus must be odd")?);
+        let value = self.witness().value.pow_elem(&params.hash_to_prime(self.element()), &monty_params);
+        let nonce = delegation_nonce(self.element(), until_epoch, device);
+        Ok(Delegation {
+            element: self.element().to_vec(),
+            until_epoch,
+            device: *device,
+            proof: FreshnessProof::prove(params, &value, self.epoch(), self.element(), &self.witness(), &nonce)?,
+        })
+    }
+}
+
+impl Delegation {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(DELEGATION_TAG);
+        w.put_bytes(&self.element);
+        w.put_u64(self.until_epoch);
+        w.put_u2048(&self.device.0);
+        w.put_bytes(&self.proof.to_bytes());
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, DELEGATION_TAG, policy)?;
+        let element = r.get_bytes()?.to_vec();
+        let until_epoch = r.get_u64()?;
+        let device = DevicePublicKey(r.get_u2048()?);
+        let proof = FreshnessProof::from_bytes_with(r.get_bytes()?, policy)?;
+        r.finish()?;
+        Ok(Self {
+            element,
+            until_epoch,
+            device,
+            proof,
+        })
+    }
+}
+
+impl DelegatedPresentation {
+    // Checks the presentation against the verifier's nonce, the current
+    // epoch and `value`, the accumulator value at delegation.proof.epoch.
+    pub fn verify(
+        &self,
+        params: &PublicParams,
+        value: &AccValue,
+        current_epoch: u64,
+        nonce: &[u8],
+    ) -> Result<(), &'static str> {
+        let d = &self.delegation;
+        if current_epoch > d.until_epoch {
+            return Err("Delegation has expired");
+        }
+        let delegation_nonce = delegation_nonce(&d.element, d.until_epoch, &d.device);
+        if !d.proof.verify(params, value, d.proof.epoch, &d.element, &delegation_nonce) {
+            return Err("Delegation proof does not verify");
+        }
+        if nonce.is_empty() || !d.device.verify(&presentation_message(d, nonce), &self.signature) {
+            return Err("Device signature does not verify");
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PRESENTATION_TAG);
+        w.put_bytes(&self.delegation.to_bytes());
+        w.put_u2048(&self.signature.r);
+        w.put_u2048(&self.signature.s);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, PRESENTATION_TAG, policy)?;
+        let delegation = Delegation::from_bytes_with(r.get_bytes()?, policy)?;
+        let signature = DeviceSignature {
+            r: r.get_u2048()?,
+            s: r.get_u2048()?,
+        };
+        r.finish()?;
+        Ok(Self { delegation, signature })
+    }
+}
+
+fn generator() -> Result<MontyForm<32>, &'static str> {
+    Ok(MontyForm::new(&U2048::from(2u8), group_params()?))
+}
+
+fn challenge(public: &DevicePublicKey, r: &U2048, message: &[u8]) -> U2048 {
+    let digest = HashAlgorithm::Sha256.hash(SIGNATURE_DOMAIN, &[&public.0.to_be_bytes(), &r.to_be_bytes(), message]);
+    U256::from_be_slice(&digest).resize()
+}
+
+fn delegation_nonce(element: &[u8], until_epoch: u64, device: &DevicePublicKey) -> Vec<u8> {
+    let mut w = Writer::new(DELEGATION_TAG);
+    w.put_bytes(element);
+    w.put_u64(until_epoch);
+    w.put_u2048(&device.0);
+    w.finish()
+}
+
+fn presentation_message(delegation: &Delegation, nonce: &[u8]) -> Vec<u8> {
+    let mut w = Writer::new(PRESENTATION_TAG);
+    w.put_bytes(&delegation.to_bytes());
+    w.put_bytes(nonce);
+    w.finish()
+}
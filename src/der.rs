@@ -0,0 +1,292 @@
+use crypto_bigint::{U256, U512};
+
+use crate::core;
+use crate::group::{AccValue, WitnessValue};
+use crate::hash::{self, HashAlgorithm};
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+// DER profile for embedding accumulator artifacts in X.509 extensions and
+// CMS content. OIDs hang off a UUID arc (X.667), which needs no registration:
+//
+//   id-braavos ::= { joint-iso-itu-t uuid(25) 17768071121773413640551432227162810603 }
+//
+//   id-braavos-accumulator ::= { id-braavos 1 }     -- CL-RSA-B over a safe-prime modulus
+//   id-braavos-h2p         ::= { id-braavos 2 }     -- hash-to-prime, 256-bit primes, with
+//                                                     the HashAlgorithm id as last arc;
+//                                                     HASH_TO_PRIME_PROFILE 1
+//   id-braavos-params      ::= { id-braavos 3 }     -- extension / content type OIDs
+//   id-braavos-value       ::= { id-braavos 4 }
+//   id-braavos-witness     ::= { id-braavos 5 }
+//
+//   BraavosParameters ::= SEQUENCE {
+//       algorithm    OBJECT IDENTIFIER,  -- id-braavos-accumulator
+//       modulus      INTEGER,
+//       base         INTEGER,
+//       hashToPrime  SEQUENCE { profile OBJECT IDENTIFIER, key OCTET STRING (SIZE (32)) },
+//       baseSeed     OCTET STRING (SIZE (32)) OPTIONAL,
+//       genesis      [0] IMPLICIT OCTET STRING (SIZE (32)) OPTIONAL }  -- GenesisRecord digest
+//
+//   BraavosValue ::= SEQUENCE {
+//       paramsDigest OCTET STRING (SIZE (32)),
+//       epoch        INTEGER,
+//       value        INTEGER }
+//
+//   BraavosWitness ::= SEQUENCE {
+//       paramsDigest OCTET STRING (SIZE (32)),
+//       witness      INTEGER }
+//
+// Decoders accept exactly the DER encoding: minimal lengths and integers,
+// no negative integers and no trailing bytes.
+pub const OID_BRAAVOS: &str = "2.25.17768071121773413640551432227162810603";
+pub const OID_ACCUMULATOR: &str = "2.25.17768071121773413640551432227162810603.1";
+pub const OID_HASH_TO_PRIME: &str = "2.25.17768071121773413640551432227162810603.2";
+pub const OID_PARAMS: &str = "2.25.17768071121773413640551432227162810603.3";
+pub const OID_VALUE: &str = "2.25.17768071121773413640551432227162810603.4";
+pub const OID_WITNESS: &str = "2.25.17768071121773413640551432227162810603.5";
+
+// Content octets of OID_BRAAVOS; each arc below it is one more byte.
+const ARC: [u8; 19] = [
+    0x69, 0x9a, 0xde, 0x80, 0xe2, 0xd6, 0xcd, 0xd2, 0xac, 0xf7, 0xb7, 0xa7, 0xcf, 0xcc, 0xb9, 0x86, 0xf7, 0xd1, 0x6b,
+];
+const ARC_ACCUMULATOR: u8 = 1;
+const ARC_HASH_TO_PRIME: u8 = 2;
+
+const INTEGER: u8 = 0x02;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+const GENESIS: u8 = 0x80; // [0] IMPLICIT OCTET STRING
+
+impl PublicParams {
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut fields = vec![
+            oid(&[ARC_ACCUMULATOR]),
+            integer(&self.n.to_be_bytes()),
+            integer(&self.base.to_be_bytes()),
+            tlv(
+                SEQUENCE,
+                &[oid(&[ARC_HASH_TO_PRIME, self.hash.id()]), tlv(OCTET_STRING, &self.prf_key.to_be_bytes())].concat(),
+            ),
+        ];
+        if let Some(seed) = &self.seed {
+            fields.push(tlv(OCTET_STRING, seed));
+        }
+        if let Some(genesis) = &self.genesis {
+            fields.push(tlv(GENESIS, genesis));
+        }
+        tlv(SEQUENCE, &fields.concat())
+    }
+
+    pub fn from_der(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut outer = Der::new(bytes);
+        let mut r = outer.sequence()?;
+        outer.finish()?;
+        if r.oid()? != [&ARC[..], &[ARC_ACCUMULATOR]].concat() {
+            return Err("Unsupported accumulator scheme");
+        }
+        let n = U512::from_be_slice(&pad::<64>(r.unsigned(64)?));
+        if !n.bit_vartime(0) || n == U512::ONE {
+            return Err("Modulus must be odd");
+        }
+        let base = U512::from_be_slice(&pad::<64>(r.unsigned(64)?));
+        if !core::is_group_element(&base, &n) {
+            return Err("Value is not in the quadratic residue subgroup");
+        }
+        let mut h2p = r.sequence()?;
+        let profile = h2p.oid()?;
+        let hash = match profile.strip_prefix(&ARC[..]) {
+            Some([ARC_HASH_TO_PRIME, id]) => HashAlgorithm::from_id(*id)?,
+            _ => return Err("Unsupported hash-to-prime profile"),
+        };
+        let prf_key = U256::from_be_slice(h2p.octets_of(32)?);
+        h2p.finish()?;
+        let seed = match r.peek() {
+            Some(OCTET_STRING) => Some(r.octets_of(32)?.try_into().map_err(|_| "Invalid DER seed")?),
+            _ => None,
+        };
+        let genesis = match r.peek() {
+            Some(GENESIS) => Some(r.read(GENESIS)?.try_into().map_err(|_| "Invalid DER genesis digest")?),
+            _ => None,
+        };
+        r.finish()?;
+        Ok(Self {
+            n,
+            base: AccValue::new(base),
+            hash,
+            prf_key,
+            seed,
+            genesis,
+            h2p_profile: hash::FIRST_PROFILE,
+        })
+    }
+}
+
+impl AccValue {
+    // The value at `epoch` under `params`, which the encoding names by digest.
+    pub fn to_der(&self, params: &PublicParams, epoch: u64) -> Vec<u8> {
+        let fields = [
+            tlv(OCTET_STRING, &params.digest()),
+            integer(&epoch.to_be_bytes()),
+            integer(&self.to_be_bytes()),
+        ];
+        tlv(SEQUENCE, &fields.concat())
+    }
+
+    // The value and its epoch, if the encoding names these parameters.
+    pub fn from_der(bytes: &[u8], params: &PublicParams) -> Result<(Self, u64), &'static str> {
+        let mut outer = Der::new(bytes);
+        let mut r = outer.sequence()?;
+        outer.finish()?;
+        if r.octets_of(32)? != params.digest() {
+            return Err("Value was published under different parameters");
+        }
+        let epoch = u64::from_be_bytes(pad::<8>(r.unsigned(8)?));
+        let value = U512::from_be_slice(&pad::<64>(r.unsigned(64)?));
+        r.finish()?;
+        if !core::is_group_element(&value, &params.n) {
+            return Err("Value is not in the quadratic residue subgroup");
+        }
+        Ok((Self::new(value), epoch))
+    }
+}
+
+impl Witness {
+    pub fn to_der(&self) -> Vec<u8> {
+        let fields = [tlv(OCTET_STRING, &self.params_digest), integer(&self.value.to_be_bytes())];
+        tlv(SEQUENCE, &fields.concat())
+    }
+
+    // Like from_bytes, the value is only range-checked once the parameters
+    // are known (check_params, verification).
+    pub fn from_der(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut outer = Der::new(bytes);
+        let mut r = outer.sequence()?;
+        outer.finish()?;
+        let params_digest = r.octets_of(32)?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let value = U512::from_be_slice(&pad::<64>(r.unsigned(64)?));
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            value: WitnessValue::new(value),
+        })
+    }
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let len = content.len();
+    let mut out = vec![tag];
+    match len {
+        0..=0x7f => out.push(len as u8),
+        0x80..=0xff => out.extend_from_slice(&[0x81, len as u8]),
+        _ => {
+            out.push(0x82);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+// A non-negative INTEGER from big-endian bytes: leading zeros stripped, and
+// one put back if the top bit would read as a sign.
+fn integer(be: &[u8]) -> Vec<u8> {
+    let start = be.iter().position(|&b| b != 0).unwrap_or(be.len());
+    let magnitude = &be[start..];
+    match magnitude.first() {
+        None => tlv(INTEGER, &[0]),
+        Some(b) if b & 0x80 != 0 => tlv(INTEGER, &[&[0], magnitude].concat()),
+        Some(_) => tlv(INTEGER, magnitude),
+    }
+}
+
+fn oid(arcs: &[u8]) -> Vec<u8> {
+    tlv(OBJECT_IDENTIFIER, &[&ARC[..], arcs].concat())
+}
+
+fn pad<const N: usize>(magnitude: &[u8]) -> [u8; N] {
+    let mut out = [0u8; N];
+    out[N - magnitude.len()..].copy_from_slice(magnitude);
+    out
+}
+
+struct Der<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buf.first().copied()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        if self.buf.len() < n {
+            return Err("Truncated DER encoding");
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn read(&mut self, tag: u8) -> Result<&'a [u8], &'static str> {
+        if self.take(1)?[0] != tag {
+            return Err("Unexpected DER tag");
+        }
+        let len = match self.take(1)?[0] {
+            len @ 0..=0x7f => len as usize,
+            0x81 => match self.take(1)?[0] {
+                len @ 0x80..=0xff => len as usize,
+                _ => return Err("Non-minimal DER length"),
+            },
+            0x82 => match u16::from_be_bytes(self.take(2)?.try_into().map_err(|_| "Truncated DER encoding")?) {
+                len @ 0x100.. => len as usize,
+                _ => return Err("Non-minimal DER length"),
+            },
+            _ => return Err("Unsupported DER length"),
+        };
+        self.take(len)
+    }
+
+    fn sequence(&mut self) -> Result<Der<'a>, &'static str> {
+        self.read(SEQUENCE).map(Der::new)
+    }
+
+    fn oid(&mut self) -> Result<&'a [u8], &'static str> {
+        self.read(OBJECT_IDENTIFIER)
+    }
+
+    fn octets_of(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        let octets = self.read(OCTET_STRING)?;
+        if octets.len() != len {
+            return Err("Unexpected DER octet string length");
+        }
+        Ok(octets)
+    }
+
+    // Magnitude of a non-negative INTEGER of at most `max` bytes.
+    fn unsigned(&mut self, max: usize) -> Result<&'a [u8], &'static str> {
+        let content = self.read(INTEGER)?;
+        let magnitude = match content {
+            [] => return Err("Empty DER integer"),
+            [b, ..] if b & 0x80 != 0 => return Err("Negative DER integer"),
+            [0, b, ..] if b & 0x80 == 0 => return Err("Non-minimal DER integer"),
+            [0, rest @ ..] if !rest.is_empty() => rest,
+            _ => content,
+        };
+        if magnitude.len() > max {
+            return Err("DER integer too large");
+        }
+        Ok(magnitude)
+    }
+
+    fn finish(self) -> Result<(), &'static str> {
+        if !self.buf.is_empty() {
+            return Err("Trailing bytes after DER encoding");
+        }
+        Ok(())
+    }
+}
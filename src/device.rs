@@ -0,0 +1,96 @@
+use crypto_bigint::{modular::MontyParams, Odd, U256, U512};
+
+use crate::core;
+use crate::deadline::Deadline;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime};
+use crate::hash::{self, HashAlgorithm};
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+const DEVICE_TAG: &[u8] = b"braavos-device-verifier";
+
+// Membership checks for devices without a heap, such as secure elements
+// checking revocation status on-device. A host derives it from the public
+// parameters once (computing the digest allocates); after that loading it,
+// decoding witnesses and verifying only touch fixed-size stack values and the
+// caller's buffers, and never allocate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceVerifier {
+    params_digest: [u8; 32],
+    hash: HashAlgorithm,
+    prf_key: U256,
+    monty_params: MontyParams<8>,
+}
+
+impl DeviceVerifier {
+    // Size of the encoding written by write_to.
+    pub const ENCODED_LEN: usize = 4 + DEVICE_TAG.len() + 4 + 32 + 4 + 32 + 64;
+
+    pub fn new(params: &PublicParams) -> Result<Self, &'static str> {
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        Ok(Self {
+            params_digest: params.digest(),
+            hash: params.hash,
+            prf_key: params.prf_key,
+            monty_params: MontyParams::new(n.ok_or("Modulus must be odd")?),
+        })
+    }
+
+    pub fn params_digest(&self) -> [u8; 32] {
+        self.params_digest
+    }
+
+    pub fn write_to(&self, out: &mut [u8]) -> Result<usize, &'static str> {
+        let mut w = Writer::in_slice(out, DEVICE_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u32(self.hash.id() as u32);
+        w.put_u256(&self.prf_key);
+        w.put_u512(&self.monty_params.modulus().get());
+        w.finish()
+    }
+
+    // The digest is taken as written: it was computed by the host, and the
+    // device has no way to recompute it without the base.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader::new(bytes, DEVICE_TAG)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let hash = HashAlgorithm::from_id(u8::try_from(r.get_u32()?).map_err(|_| "Unsupported hash algorithm")?)?;
+        let prf_key = r.get_u256()?;
+        let n: Option<Odd<U512>> = Odd::new(r.get_u512()?).into();
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            hash,
+            prf_key,
+            monty_params: MontyParams::new(n.ok_or("Modulus must be odd")?),
+        })
+    }
+
+    // Whether x is a member under the published value: one hash-to-prime and
+    // one exponentiation. Values outside the subgroup are rejected.
+    pub fn verify(&self, x: impl AsRef<[u8]>, witness: &Witness, value: &AccValue) -> bool {
+        let x = x.as_ref();
+        let n = self.monty_params.modulus().get();
+        if witness.params_digest != self.params_digest || !core::is_group_element(value.as_uint(), &n) {
+            return false;
+        }
+        let prime = hash::hash_to_prime_within(self.hash, &self.prf_key, x, &Deadline::none());
+        prime.is_ok_and(|p| core::opens(&witness.value, &ElemPrime::new(p), value, &self.monty_params))
+    }
+
+    // As verify, straight from a Witness::write_to encoding and the value's
+    // big-endian bytes. The witness is decoded strictly, as from a prover.
+    pub fn verify_encoded(&self, x: impl AsRef<[u8]>, witness: &[u8], value: &[u8; 64]) -> bool {
+        let x = x.as_ref();
+        let policy = DecodingPolicy {
+            modulus: Some(self.monty_params.modulus().get()),
+            ..DecodingPolicy::strict()
+        };
+        let Ok(witness) = Witness::from_bytes_with(witness, &policy) else {
+            return false;
+        };
+        self.verify(x, &witness, &AccValue::from_be_bytes(value))
+    }
+}
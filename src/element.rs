@@ -0,0 +1,135 @@
+// Canonical byte encodings for identifier types, so every party turns the
+// same id into the same element. Anything AsRef<[u8]> (str, String, Vec<u8>,
+// [u8; 32] digests) can be passed to the accumulator as is; integers need a
+// fixed encoding first, which is big-endian here. A UUID is its 16 RFC 4122
+// bytes: pass uuid.as_bytes(), or uuid.as_u128().into_element(), which is
+// the same element.
+//
+// Such a digest is hashed again like any other element; ElementDigest maps
+// it to a prime directly.
+pub trait IntoElement {
+    type Bytes: AsRef<[u8]>;
+
+    fn into_element(self) -> Self::Bytes;
+}
+
+impl IntoElement for u64 {
+    type Bytes = [u8; 8];
+
+    fn into_element(self) -> [u8; 8] {
+        self.to_be_bytes()
+    }
+}
+
+impl IntoElement for u128 {
+    type Bytes = [u8; 16];
+
+    fn into_element(self) -> [u8; 16] {
+        self.to_be_bytes()
+    }
+}
+
+impl<'a> IntoElement for &'a str {
+    type Bytes = &'a [u8];
+
+    fn into_element(self) -> &'a [u8] {
+        self.as_bytes()
+    }
+}
+
+impl IntoElement for [u8; 32] {
+    type Bytes = [u8; 32];
+
+    fn into_element(self) -> [u8; 32] {
+        self
+    }
+}
+
+// Elements starting with this are digest elements. It cannot begin valid
+// UTF-8, so text identifiers never collide with it.
+const DIGEST_MARKER: &[u8] = b"\xffbraavos-digest";
+const MAX_DOMAIN_LEN: usize = 64;
+
+// A 32-byte digest of an identifier, for systems that only expose hashes of
+// personal data and never the identifier itself. Its prime is derived from
+// the digest directly rather than by hashing it again. The domain names how
+// the digest was made ("sha256:email", say); it is part of the element, so
+// the same digest from two domains gives two unrelated primes.
+//
+// The mapping is not injective on arbitrary inputs (digests one bit apart
+// share a prime), which is harmless for outputs of a collision-resistant hash
+// but means the digest must never be picked by the party it identifies.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ElementDigest {
+    bytes: Vec<u8>, // DIGEST_MARKER, domain length, domain, digest
+}
+
+impl ElementDigest {
+    // Domains are 1 to 64 printable ASCII characters.
+    pub fn new(domain: &str, digest: [u8; 32]) -> Result<Self, &'static str> {
+        if !valid_domain(domain.as_bytes()) {
+            return Err("Digest domain must be 1 to 64 printable ASCII characters");
+        }
+        let mut bytes = DIGEST_MARKER.to_vec();
+        bytes.push(domain.len() as u8);
+        bytes.extend_from_slice(domain.as_bytes());
+        bytes.extend_from_slice(&digest);
+        Ok(Self { bytes })
+    }
+
+    // The digest element encoded in `element`, e.g. one read back from
+    // export_members; None for any other element.
+    pub fn from_element(element: &[u8]) -> Option<Self> {
+        parse_digest(element).map(|_| Self {
+            bytes: element.to_vec(),
+        })
+    }
+
+    pub fn domain(&self) -> &str {
+        let domain = parse_digest(&self.bytes).map_or(&[][..], |(domain, _)| domain);
+        std::str::from_utf8(domain).unwrap_or_default()
+    }
+
+    pub fn digest(&self) -> [u8; 32] {
+        parse_digest(&self.bytes).map_or([0; 32], |(_, digest)| *digest)
+    }
+}
+
+impl AsRef<[u8]> for ElementDigest {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl IntoElement for ElementDigest {
+    type Bytes = Vec<u8>;
+
+    fn into_element(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+// (domain, digest) of a well-formed digest element.
+pub(crate) fn parse_digest(x: &[u8]) -> Option<(&[u8], &[u8; 32])> {
+    let rest = x.strip_prefix(DIGEST_MARKER)?;
+    let (&len, rest) = rest.split_first()?;
+    let len = len as usize;
+    if rest.len() != len + 32 || !valid_domain(&rest[..len]) {
+        return None;
+    }
+    let (domain, digest) = rest.split_at(len);
+    Some((domain, digest.try_into().ok()?))
+}
+
+// Rejects elements that claim to be digest elements but are malformed, so
+// nothing is accumulated whose prime other parties might derive differently.
+pub(crate) fn check(x: &[u8]) -> Result<(), &'static str> {
+    if x.starts_with(DIGEST_MARKER) && parse_digest(x).is_none() {
+        return Err("Malformed digest element");
+    }
+    Ok(())
+}
+
+fn valid_domain(domain: &[u8]) -> bool {
+    (1..=MAX_DOMAIN_LEN).contains(&domain.len()) && domain.iter().all(|b| b.is_ascii_graphic())
+}
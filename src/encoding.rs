@@ -0,0 +1,221 @@
+use crypto_bigint::{NonZero, U2048, U256, U512};
+
+use crate::decoding::DecodingPolicy;
+use crate::group::ElemPrime;
+use crate::hash::ELEMENT_PRIME_BITS;
+use crate::math;
+
+// Where a Writer puts its bytes: a growable Vec, or a caller's buffer on hot
+// paths that must not allocate.
+pub(crate) trait Sink {
+    fn extend(&mut self, bytes: &[u8]);
+}
+
+impl Sink for Vec<u8> {
+    fn extend(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+// Fixed buffer that records an overflow instead of panicking.
+pub(crate) struct SliceSink<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    overflow: bool,
+}
+
+impl Sink for SliceSink<'_> {
+    fn extend(&mut self, bytes: &[u8]) {
+        match self.buf.get_mut(self.len..self.len + bytes.len()) {
+            Some(dst) if !self.overflow => {
+                dst.copy_from_slice(bytes);
+                self.len += bytes.len();
+            }
+            _ => self.overflow = true,
+        }
+    }
+}
+
+// Minimal length-prefixed big-endian encoding shared by everything that gets
+// signed or written to disk, so the byte layout is canonical.
+pub(crate) struct Writer<S: Sink = Vec<u8>> {
+    buf: S,
+}
+
+impl Writer {
+    pub(crate) fn new(tag: &[u8]) -> Self {
+        let mut w = Self { buf: Vec::new() };
+        w.put_bytes(tag);
+        w
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+impl<'a> Writer<SliceSink<'a>> {
+    pub(crate) fn in_slice(buf: &'a mut [u8], tag: &[u8]) -> Self {
+        let mut w = Self {
+            buf: SliceSink { buf, len: 0, overflow: false },
+        };
+        w.put_bytes(tag);
+        w
+    }
+
+    // Number of bytes written.
+    pub(crate) fn finish(self) -> Result<usize, &'static str> {
+        if self.buf.overflow {
+            return Err("Output buffer too small");
+        }
+        Ok(self.buf.len)
+    }
+}
+
+impl<S: Sink> Writer<S> {
+    pub(crate) fn put_u32(&mut self, v: u32) {
+        self.buf.extend(&v.to_be_bytes());
+    }
+
+    pub(crate) fn put_u64(&mut self, v: u64) {
+        self.buf.extend(&v.to_be_bytes());
+    }
+
+    pub(crate) fn put_bytes(&mut self, v: &[u8]) {
+        self.put_u32(v.len() as u32);
+        self.buf.extend(v);
+    }
+
+    pub(crate) fn put_u256(&mut self, v: &U256) {
+        self.buf.extend(&v.to_be_bytes());
+    }
+
+    pub(crate) fn put_u512(&mut self, v: &U512) {
+        self.buf.extend(&v.to_be_bytes());
+    }
+
+    pub(crate) fn put_u2048(&mut self, v: &U2048) {
+        self.buf.extend(&v.to_be_bytes());
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    policy: DecodingPolicy,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8], tag: &[u8]) -> Result<Self, &'static str> {
+        Self::with_policy(buf, tag, &DecodingPolicy::default())
+    }
+
+    pub(crate) fn with_policy(buf: &'a [u8], tag: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        if buf.len() > policy.max_input {
+            return Err("Encoding exceeds the size limit");
+        }
+        let mut r = Self { buf, policy: *policy };
+        if r.get_bytes()? != tag {
+            return Err("Unexpected encoding tag");
+        }
+        Ok(r)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        if self.buf.len() < len {
+            return Err("Truncated encoding");
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], &'static str> {
+        self.take(N)?.try_into().map_err(|_| "Truncated encoding")
+    }
+
+    pub(crate) fn get_u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_be_bytes(self.take_array()?))
+    }
+
+    pub(crate) fn get_u64(&mut self) -> Result<u64, &'static str> {
+        Ok(u64::from_be_bytes(self.take_array()?))
+    }
+
+    pub(crate) fn get_bytes(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = self.get_u32()? as usize;
+        if len > self.policy.max_field {
+            return Err("Field exceeds the size limit");
+        }
+        self.take(len)
+    }
+
+    // Length of a list that follows.
+    pub(crate) fn get_count(&mut self) -> Result<usize, &'static str> {
+        let count = self.get_u32()? as usize;
+        if count > self.policy.max_items {
+            return Err("List exceeds the size limit");
+        }
+        Ok(count)
+    }
+
+    pub(crate) fn policy(&self) -> &DecodingPolicy {
+        &self.policy
+    }
+
+    pub(crate) fn get_u256(&mut self) -> Result<U256, &'static str> {
+        Ok(U256::from_be_slice(self.take(32)?))
+    }
+
+    pub(crate) fn get_u512(&mut self) -> Result<U512, &'static str> {
+        Ok(U512::from_be_slice(self.take(64)?))
+    }
+
+    // A residue mod n, or mod the policy's modulus when n is None. Rejected
+    // if not reduced (or zero) under a canonical policy, reduced otherwise.
+    pub(crate) fn get_residue(&mut self, n: Option<&U512>) -> Result<U512, &'static str> {
+        let v = self.get_u512()?;
+        let Some(n) = n.or(self.policy.modulus.as_ref()) else {
+            return Ok(v);
+        };
+        if v < *n && v != U512::ZERO {
+            return Ok(v);
+        }
+        if self.policy.canonical {
+            return Err("Value is not reduced modulo n");
+        }
+        let n: Option<NonZero<U512>> = NonZero::new(*n).into();
+        Ok(v.rem(&n.ok_or("Modulus must be nonzero")?))
+    }
+
+    // An accumulator value or witness: a residue whose Jacobi symbol is 1, as
+    // for every element of QR_n. Anything else is rejected under any policy
+    // once the modulus is known, since no honest encoding produces it.
+    pub(crate) fn get_element(&mut self, n: Option<&U512>) -> Result<U512, &'static str> {
+        let v = self.get_residue(n)?;
+        match n.or(self.policy.modulus.as_ref()) {
+            Some(n) if math::jacobi(&v, n) != 1 => Err("Value is not in the quadratic residue subgroup"),
+            _ => Ok(v),
+        }
+    }
+
+    // An element prime; under a canonical policy it must be odd and of full
+    // size, as hash-to-prime makes them.
+    pub(crate) fn get_prime(&mut self) -> Result<ElemPrime, &'static str> {
+        let p = self.get_u256()?;
+        if self.policy.canonical && (!p.bit_vartime(0) || p.bits() != ELEMENT_PRIME_BITS) {
+            return Err("Malformed element prime");
+        }
+        Ok(ElemPrime::new(p))
+    }
+
+    pub(crate) fn get_u2048(&mut self) -> Result<U2048, &'static str> {
+        Ok(U2048::from_be_slice(self.take(256)?))
+    }
+
+    pub(crate) fn finish(self) -> Result<(), &'static str> {
+        if !self.buf.is_empty() {
+            return Err("Trailing bytes after encoding");
+        }
+        Ok(())
+    }
+}
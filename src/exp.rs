@@ -0,0 +1,148 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    subtle::{ConditionallySelectable, ConstantTimeEq},
+    Uint, U256, U512,
+};
+
+// Exponents up to this length take plain square-and-multiply in
+// pow_adaptive; past it the window's table pays for itself.
+const SHORT_EXPONENT_BITS: u32 = 32;
+
+// How trapdoor exponentiations (full-width exponents mod p'q') are computed.
+// Native is crypto-bigint's windowed constant-time pow. benches/exp.rs measured
+// it at ~250us per add against ~285us for the hand-rolled Chunked loop (128-bit
+// primes), so Native is the default and Chunked is only kept for comparison.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpStrategy {
+    #[default]
+    Native,
+    Chunked,
+}
+
+impl ExpStrategy {
+    pub(crate) fn pow(&self, base: &MontyForm<8>, exponent: &U512) -> MontyForm<8> {
+        match self {
+            ExpStrategy::Native => base.pow(exponent),
+            ExpStrategy::Chunked => chunked_pow(base, exponent),
+        }
+    }
+}
+
+// base^exponent, with the method picked from the exponent's width. Secret
+// exponents in this crate are full width (trapdoor exponents are reduced mod
+// sk and padded to 512 bits, see ExpStrategy) and take crypto-bigint's
+// windowed constant-time pow. Narrower ones are public: element primes,
+// proof-of-exponentiation challenges and quotients, small factors. Those
+// stop at their top set bit: square-and-multiply when short, the same
+// window bounded to their length otherwise. benches/exp.rs measured a
+// 17-bit exponent at ~3.5us against ~55us constant-time (256-bit modulus). At
+// 256 bits nothing gains: the bounded window matches the constant-time one,
+// and a width-5 NAF was no faster, its inversion eating the saved multiplies.
+pub(crate) fn pow_adaptive<const L: usize>(base: &MontyForm<8>, exponent: &Uint<L>) -> MontyForm<8> {
+    if L >= U512::LIMBS {
+        return base.pow(exponent);
+    }
+    let bits = exponent.bits_vartime();
+    if bits > SHORT_EXPONENT_BITS {
+        return base.pow_bounded_exp(exponent, bits);
+    }
+    let mut result = MontyForm::one(*base.params());
+    for i in (0..bits).rev() {
+        result = result.square();
+        if exponent.bit_vartime(i) {
+            result = result.mul(base);
+        }
+    }
+    result
+}
+
+// Square-and-multiply over all 512 exponent bits, renormalising every 64 bits.
+fn chunked_pow(base: &MontyForm<8>, exponent: &U512) -> MontyForm<8> {
+    let params = *base.params();
+    let n = params.modulus().as_nz_ref();
+    let mut result = MontyForm::new(&U512::ONE, params);
+
+    // Process exponent in chunks of 64 bits
+    for i in (0..512).rev() {
+        // Square step
+        result = result.mul(&result);
+
+        // Multiply step (if bit is set)
+        if (exponent.as_words()[i / 64] >> (i % 64)) & 1 == 1 {
+            result = result.mul(base);
+        }
+
+        // Every 64 bits, reduce the intermediate result
+        if i % 64 == 0 {
+            let temp = result.retrieve() % n;
+            result = MontyForm::new(&temp, params);
+        }
+    }
+
+    result
+}
+
+// Fixed-base comb for exponents below 2^256 (everything reduced mod sk).
+// Row i holds base^(j * 2^(w*i)) for j = 1..2^w, so a pow is one multiply per
+// w-bit window and no squarings. Entries are kept in Montgomery form without
+// their parameters, at 64 bytes each. Lookups scan the whole row so the
+// secret exponent does not show in memory access patterns.
+#[derive(Clone, Debug)]
+pub(crate) struct FixedBaseTable {
+    window: u32,
+    rows: Vec<Vec<U512>>,
+}
+
+impl FixedBaseTable {
+    // Widest window whose table fits in `budget` bytes; None when not even
+    // the one-bit table (16 KiB) fits.
+    pub(crate) fn window_for_budget(budget: usize) -> Option<u32> {
+        (1..=8).rev().find(|&w| Self::size(w) <= budget)
+    }
+
+    fn size(window: u32) -> usize {
+        U256::BITS.div_ceil(window) as usize * ((1 << window) - 1) * size_of::<U512>()
+    }
+
+    pub(crate) fn new(base: &U512, window: u32, params: &MontyParams<8>) -> Self {
+        let mut rows = Vec::new();
+        let mut step = MontyForm::new(base, *params); // base^(2^(w*i))
+        for _ in 0..U256::BITS.div_ceil(window) {
+            let mut row = Vec::with_capacity((1 << window) - 1);
+            let mut entry = step;
+            for _ in 1..(1 << window) {
+                row.push(*entry.as_montgomery());
+                entry = entry.mul(&step);
+            }
+            step = entry; // base^(2^w * 2^(w*i))
+            rows.push(row);
+        }
+        Self { window, rows }
+    }
+
+    pub(crate) fn bytes(&self) -> usize {
+        Self::size(self.window)
+    }
+
+    pub(crate) fn pow(&self, exponent: &U256, params: &MontyParams<8>) -> MontyForm<8> {
+        let mask = (1u64 << self.window) - 1;
+        let mut result = MontyForm::one(*params);
+        for (i, row) in self.rows.iter().enumerate() {
+            let offset = i as u32 * self.window;
+            let word = offset / 64;
+            let mut digit = exponent.as_words()[word as usize] >> (offset % 64);
+            // Windows that do not divide 64 straddle two words
+            if offset % 64 + self.window > 64 && (word as usize) + 1 < U256::LIMBS {
+                digit |= exponent.as_words()[word as usize + 1] << (64 - offset % 64);
+            }
+            let digit = digit & mask;
+            // A zero digit multiplies by one
+            let mut entry = *MontyForm::one(*params).as_montgomery();
+            for (j, candidate) in row.iter().enumerate() {
+                entry.conditional_assign(candidate, (j as u64 + 1).ct_eq(&digit));
+            }
+            result = result.mul(&MontyForm::from_montgomery(entry, *params));
+        }
+        result
+    }
+}
@@ -0,0 +1,39 @@
+use crate::failure::VerifyFailure;
+
+// Freshness a verifier demands beyond a witness opening a value: how many
+// epochs behind the latest the value it opens may be, and the earliest epoch
+// it may be from, such as the epoch a revocation took effect. The default
+// accepts current witnesses only, as verify does.
+//
+// An accumulator knows a past epoch only while it keeps its value (see the
+// builder's checkpoints), so a window longer than the checkpoints kept
+// accepts no more than they allow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExpiryPolicy {
+    pub max_witness_age_epochs: u64,
+    pub min_epoch: Option<u64>,
+}
+
+impl ExpiryPolicy {
+    pub fn max_witness_age_epochs(mut self, epochs: u64) -> Self {
+        self.max_witness_age_epochs = epochs;
+        self
+    }
+
+    pub fn require_epoch(mut self, epoch: u64) -> Self {
+        self.min_epoch = Some(epoch);
+        self
+    }
+
+    // Whether a witness valid at `epoch` passes when the latest is `current`,
+    // for verifiers that learn the epoch otherwise, as from a SignedWitness.
+    pub fn check(&self, epoch: u64, current: u64) -> Result<(), VerifyFailure> {
+        if let Some(required) = self.min_epoch.filter(|&required| epoch < required) {
+            return Err(VerifyFailure::Superseded { epoch, required });
+        }
+        if current.saturating_sub(epoch) > self.max_witness_age_epochs {
+            return Err(VerifyFailure::Expired { epoch, current });
+        }
+        Ok(())
+    }
+}
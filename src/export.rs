@@ -0,0 +1,63 @@
+use std::io::{self, Write};
+
+use crate::accumulator::BraavosAccumulator;
+
+// Serialized forms of export_members() for reconciliation tooling. Both keep
+// the deterministic member order, so two exports of the same state are
+// byte-identical.
+impl BraavosAccumulator {
+    // One row per member: element, prime and encoded witness, all lowercase hex
+    // since elements are arbitrary bytes.
+    pub fn export_members_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "element,prime,witness")?;
+        for member in self.export_members() {
+            let (x, prime, witness) = member.map_err(io::Error::other)?;
+            writeln!(out, "{},{},{}", hex(x), hex(&prime.to_be_bytes()), hex(&witness.to_bytes()))?;
+        }
+        Ok(())
+    }
+
+    // A CBOR array with one [element, prime, witness] array of byte strings per
+    // member, all definite-length.
+    pub fn export_members_cbor<W: Write>(&self, mut out: W) -> io::Result<()> {
+        cbor_head(&mut out, CBOR_ARRAY, self.member_count() as u64)?;
+        for member in self.export_members() {
+            let (x, prime, witness) = member.map_err(io::Error::other)?;
+            cbor_head(&mut out, CBOR_ARRAY, 3)?;
+            for field in [x, &prime.to_be_bytes(), &witness.to_bytes()] {
+                cbor_head(&mut out, CBOR_BYTES, field.len() as u64)?;
+                out.write_all(field)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// CBOR major types used by the export.
+const CBOR_BYTES: u8 = 2;
+const CBOR_ARRAY: u8 = 4;
+
+// Initial byte plus the shortest big-endian argument encoding (RFC 8949 3.1).
+fn cbor_head<W: Write>(out: &mut W, major: u8, arg: u64) -> io::Result<()> {
+    let major = major << 5;
+    match arg {
+        0..=23 => out.write_all(&[major | arg as u8]),
+        24..=0xff => out.write_all(&[major | 24, arg as u8]),
+        0x100..=0xffff => {
+            out.write_all(&[major | 25])?;
+            out.write_all(&(arg as u16).to_be_bytes())
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.write_all(&[major | 26])?;
+            out.write_all(&(arg as u32).to_be_bytes())
+        }
+        _ => {
+            out.write_all(&[major | 27])?;
+            out.write_all(&arg.to_be_bytes())
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::error::Error;
+use std::fmt;
+
+// Why BraavosAccumulator::verify_detailed (or verify_with_expiry) rejected a witness, so the holder
+// knows whether to resync the parameters, refresh the witness or give up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyFailure {
+    WrongParams,                             // Issued under other parameters: resync them, then get a new witness
+    StaleEpoch { epoch: u64, current: u64 }, // Opens a kept past value: apply the updates since
+    BadGroupElement,                         // Witness value is not in the group: malformed, reject
+    Mismatch,                                // Opens no known value for the element: reject
+    Expired { epoch: u64, current: u64 },    // Older than the ExpiryPolicy allows: apply the updates since
+    Superseded { epoch: u64, required: u64 }, // Before the epoch the ExpiryPolicy requires: apply the updates since
+}
+
+impl fmt::Display for VerifyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyFailure::WrongParams => write!(f, "witness was issued under different parameters"),
+            VerifyFailure::StaleEpoch { epoch, current } => {
+                write!(f, "witness is for epoch {}, the accumulator is at epoch {}", epoch, current)
+            }
+            VerifyFailure::BadGroupElement => write!(f, "witness value is not a group element"),
+            VerifyFailure::Mismatch => write!(f, "witness does not open the accumulator value"),
+            VerifyFailure::Expired { epoch, current } => {
+                write!(f, "witness is for epoch {}, too far behind epoch {}", epoch, current)
+            }
+            VerifyFailure::Superseded { epoch, required } => {
+                write!(f, "witness is for epoch {}, epoch {} or later is required", epoch, required)
+            }
+        }
+    }
+}
+
+impl Error for VerifyFailure {}
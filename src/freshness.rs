@@ -0,0 +1,125 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, RandomMod, U128, U512,
+};
+use rand::rngs::OsRng;
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+const FRESHNESS_TAG: &[u8] = b"braavos-freshness-proof";
+
+const CHALLENGE_BITS: u32 = 128; // Below the element prime, as soundness needs
+
+// Challenge-response membership: the holder answers a verifier's nonce with
+// a proof of knowing the witness for x at the current epoch, without handing
+// the witness over. It is a Guillou-Quisquater proof that the prover knows w
+// with w^x = value,
+//
+//     t = r^x,    c = H(params, value, epoch, x, nonce, t),    s = r w^c
+//
+// checked as s^x = t value^c. The nonce and epoch are in the challenge, so a
+// recorded proof is useless against any other nonce or epoch: a verifier that
+// draws a fresh nonce per request gets liveness and replay protection without
+// keeping state per holder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FreshnessProof {
+    pub epoch: u64, // Epoch of the value the proof was made against
+    pub t: U512,    // r^x
+    pub s: U512,    // r w^c
+}
+
+impl FreshnessProof {
+    pub fn prove(
+        params: &PublicParams,
+        value: &AccValue,
+        epoch: u64,
+        x: impl AsRef<[u8]>,
+        witness: &Witness,
+        nonce: &[u8],
+    ) -> Result<Self, &'static str> {
+        let x = x.as_ref();
+        witness.check_params(params)?;
+        if nonce.is_empty() {
+            return Err("Verifier nonce must not be empty");
+        }
+        let monty_params = monty_params(params)?;
+        let prime = params.hash_to_prime(x);
+        // The check is exact, so use the sign of w whose x-th power is value
+        let Some(root) = core::canonical_root(&witness.value, &prime, value, &monty_params) else {
+            return Err("Witness does not open the accumulator value");
+        };
+
+        let r = U512::random_mod(&mut OsRng, monty_params.modulus().as_nz_ref());
+        let r = MontyForm::new(&r, monty_params).square();
+        let t = r.pow(prime.as_uint()).retrieve();
+        let c = challenge(params, value, epoch, x, nonce, &t);
+        let s = r.mul(&MontyForm::new(root.as_uint(), monty_params).pow(&c)).retrieve();
+        Ok(Self { epoch, t, s })
+    }
+
+    // Checks the proof against the verifier's nonce and the value it holds
+    // for `epoch`; a proof made at any other epoch is rejected as stale.
+    pub fn verify(
+        &self,
+        params: &PublicParams,
+        value: &AccValue,
+        epoch: u64,
+        x: impl AsRef<[u8]>,
+        nonce: &[u8],
+    ) -> bool {
+        let x = x.as_ref();
+        if self.epoch != epoch || nonce.is_empty() || self.t >= params.n || self.s >= params.n {
+            return false;
+        }
+        if !core::is_group_element(value.as_uint(), &params.n) {
+            return false;
+        }
+        let Ok(monty_params) = monty_params(params) else {
+            return false;
+        };
+        let prime = params.hash_to_prime(x);
+        let c = challenge(params, value, epoch, x, nonce, &self.t);
+        let t = MontyForm::new(&self.t, monty_params);
+        let lhs = MontyForm::new(&self.s, monty_params).pow(prime.as_uint());
+        self.t != U512::ZERO && lhs == t.mul(&MontyForm::new(value.as_uint(), monty_params).pow(&c))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(FRESHNESS_TAG);
+        w.put_u64(self.epoch);
+        w.put_u512(&self.t);
+        w.put_u512(&self.s);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, FRESHNESS_TAG, policy)?;
+        let epoch = r.get_u64()?;
+        let t = r.get_residue(None)?;
+        let s = r.get_residue(None)?;
+        r.finish()?;
+        Ok(Self { epoch, t, s })
+    }
+}
+
+fn monty_params(params: &PublicParams) -> Result<MontyParams<8>, &'static str> {
+    let n: Option<Odd<U512>> = Odd::new(params.n).into();
+    Ok(MontyParams::new(n.ok_or("Modulus must be odd")?))
+}
+
+fn challenge(params: &PublicParams, value: &AccValue, epoch: u64, x: &[u8], nonce: &[u8], t: &U512) -> U128 {
+    let digest = params.hash.hash(
+        FRESHNESS_TAG,
+        &[&params.digest(), &value.to_be_bytes(), &epoch.to_be_bytes(), x, nonce, &t.to_be_bytes()],
+    );
+    U128::from_be_slice(&digest[..(CHALLENGE_BITS / 8) as usize])
+}
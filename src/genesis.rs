@@ -0,0 +1,140 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::hash::HashAlgorithm;
+use crate::params::PublicParams;
+
+const GENESIS_TAG: &[u8] = b"braavos-genesis-record";
+const SEED_DOMAIN: &[u8] = b"braavos-genesis-seed";
+
+// How the trusted setup was performed: when, by which software, from which
+// entropy, and with whose contributions. Passed to the builder, its digest
+// goes into PublicParams and so into the params digest every witness
+// carries; the record itself is published alongside the parameters, and
+// anyone can check it against them with `matches`.
+//
+// With contributions and no explicit base seed, the builder derives the
+// base seed from them, so every participant can check their input shaped
+// the base. Keys are still generated locally; the record documents that
+// step rather than distributing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenesisRecord {
+    pub timestamp: u64,                   // Unix seconds at setup
+    pub generator: String,                // Software and version that ran the setup
+    pub entropy_sources: Vec<String>,     // Where the randomness came from, free-form
+    pub contributions: Vec<Contribution>, // Inputs of ceremony participants, in order
+}
+
+// One participant's input to the ceremony: typically the hash of randomness
+// they committed to before anyone else revealed theirs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    pub participant: String,
+    pub value: [u8; 32],
+}
+
+impl GenesisRecord {
+    // A record stamped with the current time, this crate as generator and the
+    // operating system RNG, which key generation draws from.
+    pub fn new() -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self {
+            timestamp,
+            generator: concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")).to_string(),
+            entropy_sources: vec!["OsRng".to_string()],
+            contributions: Vec::new(),
+        }
+    }
+
+    pub fn with_entropy_source(mut self, source: impl Into<String>) -> Self {
+        self.entropy_sources.push(source.into());
+        self
+    }
+
+    pub fn with_contribution(mut self, participant: impl Into<String>, value: [u8; 32]) -> Self {
+        self.contributions.push(Contribution {
+            participant: participant.into(),
+            value,
+        });
+        self
+    }
+
+    pub fn digest(&self, hash: HashAlgorithm) -> [u8; 32] {
+        hash.hash(GENESIS_TAG, &[&self.to_bytes()])
+    }
+
+    // The base seed the contributions determine, or None without any.
+    pub fn contribution_seed(&self, hash: HashAlgorithm) -> Option<[u8; 32]> {
+        if self.contributions.is_empty() {
+            return None;
+        }
+        let mut w = Writer::new(SEED_DOMAIN);
+        for c in &self.contributions {
+            w.put_bytes(c.participant.as_bytes());
+            w.put_bytes(&c.value);
+        }
+        Some(hash.hash(SEED_DOMAIN, &[&w.finish()]))
+    }
+
+    // Whether these are the parameters the record describes: its digest is
+    // the one they carry and, with contributions, the base came from them.
+    pub fn matches(&self, params: &PublicParams) -> bool {
+        if params.genesis != Some(self.digest(params.hash)) {
+            return false;
+        }
+        match self.contribution_seed(params.hash) {
+            Some(seed) => params.seed == Some(seed) && params.verify_base(),
+            None => true,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(GENESIS_TAG);
+        w.put_u64(self.timestamp);
+        w.put_bytes(self.generator.as_bytes());
+        w.put_u32(self.entropy_sources.len() as u32);
+        for source in &self.entropy_sources {
+            w.put_bytes(source.as_bytes());
+        }
+        w.put_u32(self.contributions.len() as u32);
+        for c in &self.contributions {
+            w.put_bytes(c.participant.as_bytes());
+            w.put_bytes(&c.value);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, GENESIS_TAG, policy)?;
+        let timestamp = r.get_u64()?;
+        let generator = String::from_utf8(r.get_bytes()?.to_vec()).map_err(|_| "Generator is not UTF-8")?;
+        let mut entropy_sources = Vec::new();
+        for _ in 0..r.get_count()? {
+            entropy_sources.push(String::from_utf8(r.get_bytes()?.to_vec()).map_err(|_| "Source is not UTF-8")?);
+        }
+        let mut contributions = Vec::new();
+        for _ in 0..r.get_count()? {
+            let participant = String::from_utf8(r.get_bytes()?.to_vec()).map_err(|_| "Participant is not UTF-8")?;
+            let value = r.get_bytes()?.try_into().map_err(|_| "Invalid contribution")?;
+            contributions.push(Contribution { participant, value });
+        }
+        r.finish()?;
+        Ok(Self {
+            timestamp,
+            generator,
+            entropy_sources,
+            contributions,
+        })
+    }
+}
+
+impl Default for GenesisRecord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,363 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Encoding, U256, U512,
+};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::core;
+use crate::exp;
+use crate::hash::ELEMENT_PRIME_BITS;
+use crate::params::PublicParams;
+
+// Distinct types for the three kinds of number the scheme moves around, so an
+// accumulator value cannot be passed where a witness is expected (or a prime
+// where either is). Conversions are explicit and only exist where the algebra
+// allows them.
+//
+// Witnesses and accumulator values compare in constant time, `==` included,
+// and all three select without branching, so code handling witnesses on
+// behalf of holders can keep secret-dependent choices off the timing channel.
+// Element primes are public but implement the same traits for uniformity.
+//
+// Each also converts to and from little-endian bytes, and to and from any
+// fixed length in either byte order, for protocols that pad to their own
+// width. Lengths are checked strictly: encoding fails if the value needs more
+// bytes than asked for, and decoding if the input is not exactly `len` bytes
+// or holds more than the type does. Those decoders check nothing else: a
+// value decoded from untrusted bytes goes through the `_checked` variants,
+// which apply the same rules as a canonical DecodingPolicy and parse_checked.
+
+// Exponent contributed by one element, as derived by hash-to-prime or
+// supplied to add_prime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ElemPrime(U256);
+
+impl ElemPrime {
+    pub(crate) fn new(prime: U256) -> Self {
+        Self(prime)
+    }
+
+    pub fn as_uint(&self) -> &U256 {
+        &self.0
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        self.0.to_be_bytes()
+    }
+
+    // Not checked to be prime; add_prime checks what it is given.
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Self {
+        Self(U256::from_be_bytes(*bytes))
+    }
+
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        self.0.to_le_bytes()
+    }
+
+    // Unchecked, as from_be_bytes.
+    pub fn from_le_bytes(bytes: &[u8; 32]) -> Self {
+        Self(U256::from_le_bytes(*bytes))
+    }
+
+    pub fn to_be_bytes_padded(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        pad_be(&self.to_be_bytes(), len)
+    }
+
+    pub fn to_le_bytes_padded(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        pad_le(&self.to_be_bytes(), len)
+    }
+
+    pub fn from_be_bytes_padded(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_be_bytes(&unpad_be(bytes, len)?))
+    }
+
+    pub fn from_le_bytes_padded(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_be_bytes(&unpad_le(bytes, len)?))
+    }
+
+    // As the padded decoders, but rejecting primes not of the form
+    // hash-to-prime gives (odd and of full size), as a canonical policy does.
+    // Primality is still left to add_prime.
+    pub fn from_be_bytes_padded_checked(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Self::from_be_bytes_padded(bytes, len)?.checked()
+    }
+
+    pub fn from_le_bytes_padded_checked(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Self::from_le_bytes_padded(bytes, len)?.checked()
+    }
+
+    fn checked(self) -> Result<Self, &'static str> {
+        if !self.0.bit_vartime(0) || self.0.bits() != ELEMENT_PRIME_BITS {
+            return Err("Malformed element prime");
+        }
+        Ok(self)
+    }
+}
+
+// Accumulator value: an element of QR_n.
+#[derive(Clone, Copy, Debug)]
+pub struct AccValue(U512);
+
+impl AccValue {
+    pub fn new(value: U512) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uint(&self) -> &U512 {
+        &self.0
+    }
+
+    // Fixed-size big-endian forms; neither allocates.
+    pub fn to_be_bytes(&self) -> [u8; 64] {
+        self.0.to_be_bytes()
+    }
+
+    pub fn from_be_bytes(bytes: &[u8; 64]) -> Self {
+        Self(U512::from_be_bytes(*bytes))
+    }
+
+    pub fn to_le_bytes(&self) -> [u8; 64] {
+        self.0.to_le_bytes()
+    }
+
+    // Unchecked, as from_be_bytes.
+    pub fn from_le_bytes(bytes: &[u8; 64]) -> Self {
+        Self(U512::from_le_bytes(*bytes))
+    }
+
+    pub fn to_be_bytes_padded(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        pad_be(&self.to_be_bytes(), len)
+    }
+
+    pub fn to_le_bytes_padded(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        pad_le(&self.to_be_bytes(), len)
+    }
+
+    pub fn from_be_bytes_padded(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_be_bytes(&unpad_be(bytes, len)?))
+    }
+
+    pub fn from_le_bytes_padded(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_be_bytes(&unpad_le(bytes, len)?))
+    }
+
+    // As the padded decoders, but rejecting values that are not reduced mod
+    // n, not in QR_n or of small order, as WitnessValue::parse_checked does.
+    pub fn from_be_bytes_padded_checked(
+        bytes: &[u8],
+        len: usize,
+        params: &PublicParams,
+    ) -> Result<Self, &'static str> {
+        let value = Self::from_be_bytes_padded(bytes, len)?;
+        check_element(&value.0, &params.n)?;
+        Ok(value)
+    }
+
+    pub fn from_le_bytes_padded_checked(
+        bytes: &[u8],
+        len: usize,
+        params: &PublicParams,
+    ) -> Result<Self, &'static str> {
+        let value = Self::from_le_bytes_padded(bytes, len)?;
+        check_element(&value.0, &params.n)?;
+        Ok(value)
+    }
+}
+
+// Membership witness: an element prime-th root of an accumulator value.
+#[derive(Clone, Copy, Debug)]
+pub struct WitnessValue(U512);
+
+impl WitnessValue {
+    pub fn new(value: U512) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uint(&self) -> &U512 {
+        &self.0
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 64] {
+        self.0.to_be_bytes()
+    }
+
+    pub fn from_be_bytes(bytes: &[u8; 64]) -> Self {
+        Self(U512::from_be_bytes(*bytes))
+    }
+
+    pub fn to_le_bytes(&self) -> [u8; 64] {
+        self.0.to_le_bytes()
+    }
+
+    // Unchecked, as from_be_bytes; see parse_checked.
+    pub fn from_le_bytes(bytes: &[u8; 64]) -> Self {
+        Self(U512::from_le_bytes(*bytes))
+    }
+
+    pub fn to_be_bytes_padded(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        pad_be(&self.to_be_bytes(), len)
+    }
+
+    pub fn to_le_bytes_padded(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        pad_le(&self.to_be_bytes(), len)
+    }
+
+    pub fn from_be_bytes_padded(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_be_bytes(&unpad_be(bytes, len)?))
+    }
+
+    pub fn from_le_bytes_padded(bytes: &[u8], len: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_be_bytes(&unpad_le(bytes, len)?))
+    }
+
+    // parse_checked for the padded forms.
+    pub fn from_be_bytes_padded_checked(
+        bytes: &[u8],
+        len: usize,
+        params: &PublicParams,
+    ) -> Result<Self, &'static str> {
+        let value = Self::from_be_bytes_padded(bytes, len)?;
+        Self::check(&value.0, &params.n)?;
+        Ok(value)
+    }
+
+    pub fn from_le_bytes_padded_checked(
+        bytes: &[u8],
+        len: usize,
+        params: &PublicParams,
+    ) -> Result<Self, &'static str> {
+        let value = Self::from_le_bytes_padded(bytes, len)?;
+        Self::check(&value.0, &params.n)?;
+        Ok(value)
+    }
+
+    // A witness value from an untrusted prover, as 64 big-endian bytes:
+    // rejected unless reduced mod n, in the subgroup and not of small order
+    // (see core::is_nontrivial_element), never normalized.
+    pub fn parse_checked(bytes: &[u8], params: &PublicParams) -> Result<Self, &'static str> {
+        let bytes: &[u8; 64] = bytes.try_into().map_err(|_| "Witness value must be 64 bytes")?;
+        let value = U512::from_be_bytes(*bytes);
+        Self::check(&value, &params.n)?;
+        Ok(Self(value))
+    }
+
+    pub(crate) fn check(value: &U512, n: &U512) -> Result<(), &'static str> {
+        check_element(value, n)
+    }
+
+    // w^x, the value this witness opens for the element prime x, in time
+    // that depends on x (see pow_adaptive).
+    pub fn pow_elem(&self, prime: &ElemPrime, params: &MontyParams<8>) -> AccValue {
+        AccValue(exp::pow_adaptive(&MontyForm::new(&self.0, *params), &prime.0).retrieve())
+    }
+
+    // Deleting the witnessed element makes its witness the new accumulator value.
+    pub fn into_value_after_deletion(self) -> AccValue {
+        AccValue(self.0)
+    }
+}
+
+impl ConstantTimeEq for ElemPrime {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for ElemPrime {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(U256::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl ConstantTimeEq for AccValue {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for AccValue {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(U512::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl PartialEq for AccValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for AccValue {}
+
+impl ConstantTimeEq for WitnessValue {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for WitnessValue {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(U512::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl PartialEq for WitnessValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for WitnessValue {}
+
+// What parse_checked and the checked decoders require of a value of QR_n.
+fn check_element(value: &U512, n: &U512) -> Result<(), &'static str> {
+    if value >= n {
+        return Err("Value is not reduced modulo n");
+    }
+    if !core::is_group_element(value, n) {
+        return Err("Value is not in the quadratic residue subgroup");
+    }
+    if !core::is_nontrivial_element(value, n) {
+        return Err("Value is of small order");
+    }
+    Ok(())
+}
+
+// The big-endian bytes `be` of a fixed-width value as exactly len bytes,
+// adding or dropping leading zeros.
+fn pad_be(be: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+    let significant = be.iter().position(|b| *b != 0).map_or(0, |i| be.len() - i);
+    if significant > len {
+        return Err("Value does not fit in the requested length");
+    }
+    let mut out = vec![0u8; len];
+    out.iter_mut().rev().zip(be.iter().rev()).for_each(|(o, b)| *o = *b);
+    Ok(out)
+}
+
+fn pad_le(be: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+    let mut out = pad_be(be, len)?;
+    out.reverse();
+    Ok(out)
+}
+
+// Exactly len big-endian bytes as the N bytes of a fixed-width value; bytes
+// beyond the width must be zero.
+fn unpad_be<const N: usize>(bytes: &[u8], len: usize) -> Result<[u8; N], &'static str> {
+    if bytes.len() != len {
+        return Err("Encoding is not of the expected length");
+    }
+    let (high, low) = bytes.split_at(len.saturating_sub(N));
+    if high.iter().any(|b| *b != 0) {
+        return Err("Value is too large for its type");
+    }
+    let mut out = [0u8; N];
+    out.iter_mut().rev().zip(low.iter().rev()).for_each(|(o, b)| *o = *b);
+    Ok(out)
+}
+
+fn unpad_le<const N: usize>(bytes: &[u8], len: usize) -> Result<[u8; N], &'static str> {
+    let mut be = bytes.to_vec();
+    be.reverse();
+    unpad_be(&be, len)
+}
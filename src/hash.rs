@@ -0,0 +1,329 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Limb, NonZero, Odd, Uint, U256,
+};
+use crypto_primes::hazmat::MillerRabin;
+use crypto_primes::{is_prime, is_safe_prime};
+use digest::Digest;
+use std::convert::Infallible;
+
+use crate::deadline::Deadline;
+use crate::element;
+
+// Digest used for every internal hash. Recorded in PublicParams so verifiers
+// always recompute element primes and signatures with the matching function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    #[cfg(feature = "sha3")]
+    Sha3_256,
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn id(&self) -> u8 {
+        match self {
+            HashAlgorithm::Sha256 => 1,
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_256 => 2,
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => 3,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self, &'static str> {
+        match id {
+            1 => Ok(HashAlgorithm::Sha256),
+            #[cfg(feature = "sha3")]
+            2 => Ok(HashAlgorithm::Sha3_256),
+            #[cfg(feature = "blake3")]
+            3 => Ok(HashAlgorithm::Blake3),
+            _ => Err("Unsupported hash algorithm"),
+        }
+    }
+
+    // Domain-separated hash of length-prefixed parts.
+    pub fn hash(&self, domain: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+        match self {
+            HashAlgorithm::Sha256 => hash_with::<sha2::Sha256>(domain, parts),
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_256 => hash_with::<sha3::Sha3_256>(domain, parts),
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => hash_with::<blake3::Hasher>(domain, parts),
+        }
+    }
+}
+
+fn hash_with<D: Digest>(domain: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = D::new();
+    hasher.update((domain.len() as u32).to_be_bytes());
+    hasher.update(domain);
+    for part in parts {
+        hasher.update((part.len() as u32).to_be_bytes());
+        hasher.update(part);
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hasher.finalize()[..32]);
+    out
+}
+
+// Frozen definition of hash_to_prime: the candidate sequences of Candidates,
+// the small-prime sieve and Baillie-PSW, at ELEMENT_PRIME_BITS. Any change to
+// which prime an element gets is a new profile, recorded in PublicParams so
+// verifiers refuse parameters whose primes they would compute differently.
+// See HashToPrimeVector for vectors pinning it down.
+pub const HASH_TO_PRIME_PROFILE: u32 = 1;
+
+// The profile parameters up to layout 3 imply.
+pub(crate) const FIRST_PROFILE: u32 = 1;
+
+// Bit length of every element prime produced by hash_to_prime.
+pub(crate) const ELEMENT_PRIME_BITS: u32 = 256;
+
+// Deterministic hash-to-prime: hash (key, x, counter) until the candidate with
+// top and bottom bits forced is prime.
+pub(crate) fn hash_to_prime(alg: HashAlgorithm, key: &U256, x: &[u8]) -> U256 {
+    let Ok(prime) = search(alg, key, x, || Ok::<(), Infallible>(()));
+    prime
+}
+
+// The same search, giving up once the deadline passes.
+pub(crate) fn hash_to_prime_within(
+    alg: HashAlgorithm,
+    key: &U256,
+    x: &[u8],
+    deadline: &Deadline,
+) -> Result<U256, &'static str> {
+    search(alg, key, x, || deadline.check())
+}
+
+// The search itself, running `check` before each candidate.
+fn search<E>(alg: HashAlgorithm, key: &U256, x: &[u8], check: impl Fn() -> Result<(), E>) -> Result<U256, E> {
+    let candidates = Candidates::new(alg, key, x);
+    let mut counter: u32 = 0;
+    loop {
+        check()?;
+        let candidate = candidates.get(counter);
+        if !has_small_factor(&candidate) && is_prime(&candidate) {
+            return Ok(candidate);
+        }
+        counter += 1;
+    }
+}
+
+// The search, also returning the counter of the prime and, for each earlier
+// candidate that passed the small-prime sieve, a base b with b^(c-1) != 1 mod c
+// proving it composite.
+pub(crate) fn hash_to_prime_certified(
+    alg: HashAlgorithm,
+    key: &U256,
+    x: &[u8],
+    deadline: &Deadline,
+) -> Result<(U256, u32, Vec<u8>), &'static str> {
+    let candidates = Candidates::new(alg, key, x);
+    let mut witnesses = Vec::new();
+    let mut counter: u32 = 0;
+    loop {
+        deadline.check()?;
+        let candidate = candidates.get(counter);
+        if !has_small_factor(&candidate) {
+            if is_prime(&candidate) {
+                return Ok((candidate, counter, witnesses));
+            }
+            let base = (2..=u8::MAX).find(|&b| !fermat(&candidate, b)).ok_or("No compositeness witness found")?;
+            witnesses.push(base);
+        }
+        counter += 1;
+    }
+}
+
+// Whether `prime` is the first prime of x's candidate sequence, given the
+// certificate from hash_to_prime_certified: one exponentiation per rejected
+// candidate and one primality test, and the certificate cannot skip a prime.
+pub(crate) fn check_certificate(
+    alg: HashAlgorithm,
+    key: &U256,
+    x: &[u8],
+    prime: &U256,
+    counter: u32,
+    witnesses: &[u8],
+) -> bool {
+    let candidates = Candidates::new(alg, key, x);
+    let mut witnesses = witnesses.iter();
+    for c in 0..counter {
+        let candidate = candidates.get(c);
+        if has_small_factor(&candidate) {
+            continue;
+        }
+        match witnesses.next() {
+            Some(&b) if b >= 2 && !fermat(&candidate, b) => {}
+            _ => return false,
+        }
+    }
+    witnesses.next().is_none() && candidates.get(counter) == *prime && is_prime(prime)
+}
+
+// Candidate `counter` of x's sequence, prime or not.
+pub(crate) fn candidate(alg: HashAlgorithm, key: &U256, x: &[u8], counter: u32) -> U256 {
+    Candidates::new(alg, key, x).get(counter)
+}
+
+// How hash_to_prime_uint decides a candidate is prime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrimalityPolicy {
+    // Baillie-PSW plus a Miller-Rabin round to a random base (crypto-primes'
+    // is_prime); no composite is known to pass
+    #[default]
+    BailliePsw,
+    // Miller-Rabin to the first `rounds` primes as bases (at least one),
+    // deterministic and faster but only for inputs nobody can grind against
+    MillerRabin { rounds: u32 },
+    // A safe prime: (p - 1) / 2 passes Baillie-PSW too
+    SafePrime,
+}
+
+impl PrimalityPolicy {
+    fn accepts<const L: usize>(&self, candidate: &Uint<L>) -> bool {
+        match self {
+            PrimalityPolicy::BailliePsw => is_prime(candidate),
+            PrimalityPolicy::SafePrime => is_safe_prime(candidate),
+            PrimalityPolicy::MillerRabin { rounds } => {
+                let odd: Option<Odd<Uint<L>>> = Odd::new(*candidate).into();
+                let Some(odd) = odd else {
+                    return false;
+                };
+                let test = MillerRabin::new(odd);
+                let mut bases = std::iter::once(2).chain(SMALL_PRIMES).take((*rounds).max(1) as usize);
+                bases.all(|b| test.test(&Uint::from(b)).is_probably_prime())
+            }
+        }
+    }
+}
+
+// Hash-to-prime for other protocols (VDF challenges, CL signature exponents)
+// at any crypto-bigint width: the first candidate of exactly `bits` bits that
+// `policy` accepts, candidate c being hashed from (domain, input, c) and
+// extended by further hashes past 256 bits. Safe primes are searched among
+// candidates that are 3 mod 4. Independent of the element primes, which are
+// keyed and fixed at 256 bits.
+pub fn hash_to_prime_uint<const L: usize>(
+    alg: HashAlgorithm,
+    domain: &[u8],
+    input: &[u8],
+    bits: u32,
+    policy: PrimalityPolicy,
+) -> Result<Uint<L>, &'static str> {
+    hash_to_prime_uint_within(alg, domain, input, bits, policy, &Deadline::none())
+}
+
+// hash_to_prime_uint, giving up once the deadline passes.
+pub fn hash_to_prime_uint_within<const L: usize>(
+    alg: HashAlgorithm,
+    domain: &[u8],
+    input: &[u8],
+    bits: u32,
+    policy: PrimalityPolicy,
+    deadline: &Deadline,
+) -> Result<Uint<L>, &'static str> {
+    if !(16..=Uint::<L>::BITS).contains(&bits) {
+        return Err("Prime size out of range");
+    }
+    let mask = Uint::<L>::MAX.wrapping_shr_vartime(Uint::<L>::BITS - bits);
+    let top = Uint::<L>::ONE.wrapping_shl_vartime(bits - 1);
+    let low = match policy {
+        PrimalityPolicy::SafePrime => Uint::from(3u8),
+        _ => Uint::ONE,
+    };
+    let mut counter: u32 = 0;
+    loop {
+        deadline.check()?;
+        let mut bytes = vec![0u8; Uint::<L>::BYTES];
+        let needed = bits.div_ceil(8) as usize;
+        let blocks: Vec<[u8; 32]> = (0..needed.div_ceil(32) as u32)
+            .map(|block| alg.hash(domain, &[input, &counter.to_be_bytes(), &block.to_be_bytes()]))
+            .collect();
+        let start = bytes.len() - needed;
+        bytes[start..].copy_from_slice(&blocks.concat()[..needed]);
+        let candidate = (Uint::<L>::from_be_slice(&bytes) & mask) | top | low;
+        if !has_small_factor(&candidate) && policy.accepts(&candidate) {
+            return Ok(candidate);
+        }
+        counter = counter.checked_add(1).ok_or("Hash-to-prime search exhausted")?;
+    }
+}
+
+// The candidate sequence of one element. Elements are hashed with the key and
+// a counter; digest elements (see ElementDigest) are not hashed again, their
+// candidates start at the digest masked with a hash of the key and domain
+// and step by two.
+struct Candidates<'a> {
+    alg: HashAlgorithm,
+    key_bytes: [u8; 32],
+    x: &'a [u8],
+    digest_start: Option<U256>,
+}
+
+impl<'a> Candidates<'a> {
+    fn new(alg: HashAlgorithm, key: &U256, x: &'a [u8]) -> Self {
+        let key_bytes = key.to_be_bytes();
+        let digest_start = element::parse_digest(x).map(|(domain, digest)| {
+            let mask = alg.hash(b"braavos-digest-to-prime", &[&key_bytes, domain]);
+            (U256::from_be_slice(digest) ^ U256::from_be_slice(&mask)) | U256::ONE | top_bit()
+        });
+        Self {
+            alg,
+            key_bytes,
+            x,
+            digest_start,
+        }
+    }
+
+    fn get(&self, counter: u32) -> U256 {
+        match &self.digest_start {
+            Some(start) => start.wrapping_add(&U256::from(counter).shl_vartime(1)) | top_bit(),
+            None => {
+                let parts: [&[u8]; 3] = [&self.key_bytes, self.x, &counter.to_be_bytes()];
+                let digest = self.alg.hash(b"braavos-hash-to-prime", &parts);
+                U256::from_be_slice(&digest) | U256::ONE | top_bit()
+            }
+        }
+    }
+}
+
+fn top_bit() -> U256 {
+    U256::ONE.shl_vartime(ELEMENT_PRIME_BITS - 1)
+}
+
+// Fermat test of an odd candidate to base b.
+fn fermat(candidate: &U256, b: u8) -> bool {
+    let odd: Option<Odd<U256>> = Odd::new(*candidate).into();
+    let Some(odd) = odd else {
+        return false; // Even, so composite
+    };
+    let params = MontyParams::new(odd);
+    let exponent = candidate.wrapping_sub(&U256::ONE);
+    MontyForm::new(&U256::from(b), params).pow(&exponent) == MontyForm::one(params)
+}
+
+// Odd primes below 1000, used to discard most candidates before the full test.
+const SMALL_PRIMES: [u32; 167] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+    97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181,
+    191, 193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277,
+    281, 283, 293, 307, 311, 313, 317, 331, 337, 347, 349, 353, 359, 367, 373, 379, 383,
+    389, 397, 401, 409, 419, 421, 431, 433, 439, 443, 449, 457, 461, 463, 467, 479, 487,
+    491, 499, 503, 509, 521, 523, 541, 547, 557, 563, 569, 571, 577, 587, 593, 599, 601,
+    607, 613, 617, 619, 631, 641, 643, 647, 653, 659, 661, 673, 677, 683, 691, 701, 709,
+    719, 727, 733, 739, 743, 751, 757, 761, 769, 773, 787, 797, 809, 811, 821, 823, 827,
+    829, 839, 853, 857, 859, 863, 877, 881, 883, 887, 907, 911, 919, 929, 937, 941, 947,
+    953, 967, 971, 977, 983, 991, 997
+];
+
+fn has_small_factor<const L: usize>(candidate: &Uint<L>) -> bool {
+    SMALL_PRIMES
+        .iter()
+        .filter_map(|&p| Option::<NonZero<Limb>>::from(NonZero::new(Limb::from(p))))
+        .any(|p| candidate.rem_limb(p) == Limb::ZERO)
+}
@@ -0,0 +1,129 @@
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+
+use crate::accumulator::BraavosAccumulator;
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+const SUBREGISTRY_TAG: &[u8] = b"braavos-subregistry-proof";
+const REGISTRY_ELEMENT_TAG: &[u8] = b"braavos-registry:";
+
+impl PublicParams {
+    // The element a root accumulator adds to endorse the registry with these
+    // parameters. It is hashed to a prime like any other element, and text
+    // identifiers never start with the tag's bytes followed by a digest.
+    pub fn registry_element(&self) -> Vec<u8> {
+        [REGISTRY_ELEMENT_TAG, &self.digest()].concat()
+    }
+}
+
+// Two-level membership for federated registries: x is a member of a
+// sub-registry, and the root endorses that sub-registry by having its
+// registry_element as a member. The verifier trusts only the root's
+// parameters and value, plus the sub-registry's current value from wherever
+// it learns it (its checkpoint chain, a ReadReplica), since deletions in the
+// sub-registry change that value without touching the root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubregistryProof {
+    pub sub_params: PublicParams,
+    pub sub_witness: Witness, // x in the sub-registry
+    pub top_witness: Witness, // sub_params.registry_element() in the root
+}
+
+impl SubregistryProof {
+    // Bundles the two witnesses after checking they are for the right
+    // parameters; whether they open the current values is for verify.
+    pub fn prove(
+        root: &PublicParams,
+        sub_params: &PublicParams,
+        sub_witness: Witness,
+        top_witness: Witness,
+    ) -> Result<Self, &'static str> {
+        sub_witness.check_params(sub_params)?;
+        top_witness.check_params(root)?;
+        Ok(Self {
+            sub_params: *sub_params,
+            sub_witness,
+            top_witness,
+        })
+    }
+
+    pub fn verify(
+        &self,
+        root: &PublicParams,
+        root_value: &AccValue,
+        x: impl AsRef<[u8]>,
+        sub_value: &AccValue,
+    ) -> bool {
+        let endorsed = self.sub_params.registry_element();
+        opens(root, root_value, &endorsed, &self.top_witness)
+            && opens(&self.sub_params, sub_value, x.as_ref(), &self.sub_witness)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(SUBREGISTRY_TAG);
+        self.sub_params.encode(&mut w);
+        w.put_bytes(&self.sub_witness.to_bytes());
+        w.put_bytes(&self.top_witness.to_bytes());
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, SUBREGISTRY_TAG, policy)?;
+        let sub_params = PublicParams::decode(&mut r)?;
+        let sub_witness = Witness::from_bytes_with(r.get_bytes()?, policy)?;
+        let top_witness = Witness::from_bytes_with(r.get_bytes()?, policy)?;
+        r.finish()?;
+        Ok(Self {
+            sub_params,
+            sub_witness,
+            top_witness,
+        })
+    }
+}
+
+impl BraavosAccumulator {
+    // Endorses the registry with `sub_params`, returning the witness that
+    // holders of its elements pair with their own (see SubregistryProof).
+    pub fn endorse_registry(&mut self, sub_params: &PublicParams) -> Result<Witness, &'static str> {
+        self.add(sub_params.registry_element())
+    }
+
+    // Run on the root: combines a witness in the sub-registry with the root's
+    // witness for the sub-registry, checking the latter is current. The root
+    // cannot check the sub-registry witness, not knowing that value.
+    pub fn prove_in_subregistry(
+        &mut self,
+        sub_params: &PublicParams,
+        w_sub: Witness,
+        w_top: Witness,
+    ) -> Result<SubregistryProof, &'static str> {
+        if !self.verify(sub_params.registry_element(), w_top) {
+            return Err("Registry is not endorsed by this accumulator");
+        }
+        SubregistryProof::prove(&self.params(), sub_params, w_sub, w_top)
+    }
+
+    pub fn verify_in_subregistry(&self, proof: &SubregistryProof, x: impl AsRef<[u8]>, sub_value: &AccValue) -> bool {
+        proof.verify(&self.params(), &self.value(), x, sub_value)
+    }
+}
+
+// Whether w opens value for x under params, exactly as a verifier would.
+fn opens(params: &PublicParams, value: &AccValue, x: &[u8], w: &Witness) -> bool {
+    let n: Option<Odd<U512>> = Odd::new(params.n).into();
+    let Some(n) = n else {
+        return false;
+    };
+    if w.params_digest != params.digest() || !core::is_group_element(value.as_uint(), &params.n) {
+        return false;
+    }
+    core::opens(&w.value, &params.hash_to_prime(x), value, &MontyParams::new(n))
+}
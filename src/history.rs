@@ -0,0 +1,107 @@
+use crate::audit::{AuditLog, Decision, Operation};
+use crate::chain::{verify_checkpoint_chain, Checkpoint};
+use crate::group::ElemPrime;
+use crate::params::PublicParams;
+use crate::update::UpdateLog;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MembershipChange {
+    Added,
+    Deleted,
+    ReAdded, // Added again after a deletion
+}
+
+// One change to an element's membership, as reconstructed by
+// Manager::history from the audit log. Adds take effect at the epoch they
+// were requested in, as they leave the value alone. A deletion is reported
+// only once the update log shows the element's prime removed, at the epoch
+// of the entry that removed it: after compaction that is the end of the
+// merged entry, the latest it can have happened. Deletions from before a
+// rerandomization, which restarts the log, are not reported.
+//
+// A deletion in a published checkpoint names it, so a relying party holding
+// the checkpoint chain can settle when the element was revoked without
+// trusting the manager's log (see check).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipEvent {
+    pub change: MembershipChange,
+    pub epoch: u64,
+    pub seq: u64,                  // Audit record of the request
+    pub checkpoint: Option<usize>, // Index into the checkpoint chain proving a deletion
+}
+
+impl MembershipEvent {
+    // For a deletion: verifies the chain up to the checkpoint it names and
+    // that the checkpoint removes x at the event's epoch. Other events, and
+    // deletions not yet checkpointed, carry no proof and fail.
+    pub fn check(&self, params: &PublicParams, chain: &[Checkpoint], x: impl AsRef<[u8]>) -> Result<(), &'static str> {
+        if self.change != MembershipChange::Deleted {
+            return Err("Only deletions carry a proof");
+        }
+        let index = self.checkpoint.ok_or("Deletion is not checkpointed yet")?;
+        let chain = chain.get(..=index).ok_or("Checkpoint is not in the chain")?;
+        verify_checkpoint_chain(params, chain)?;
+        let checkpoint = chain.last().ok_or("Checkpoint is not in the chain")?;
+        if !covers(checkpoint, &params.hash_to_prime(x), self.epoch) {
+            return Err("Checkpoint does not delete the element at that epoch");
+        }
+        Ok(())
+    }
+}
+
+// A verified checkpoint deletes one prime per epoch, in order, so it pins
+// the epoch as well as the deletion.
+fn covers(checkpoint: &Checkpoint, prime: &ElemPrime, epoch: u64) -> bool {
+    epoch
+        .checked_sub(checkpoint.from_epoch + 1)
+        .and_then(|i| checkpoint.deleted.get(usize::try_from(i).ok()?))
+        == Some(prime)
+}
+
+// Pairs each authorized deletion of x with the next removal of its prime in
+// the log; a deletion the log never shows (it failed, or is still queued)
+// is left out.
+pub(crate) fn history(
+    audit: &AuditLog,
+    log: &UpdateLog,
+    checkpoints: &[Checkpoint],
+    x: &[u8],
+    prime: &ElemPrime,
+) -> Vec<MembershipEvent> {
+    let mut removals = log
+        .updates()
+        .iter()
+        .flat_map(|u| u.deleted.iter().filter(|p| *p == prime).map(|_| u.to_epoch))
+        .peekable();
+    let mut events: Vec<MembershipEvent> = Vec::new();
+    let authorized = audit
+        .records()
+        .iter()
+        .filter(|r| r.decision == Decision::Allow && r.element == x);
+    for record in authorized {
+        let member = events.last().map(|e| e.change != MembershipChange::Deleted);
+        let (change, epoch) = match (record.operation, member) {
+            (Operation::Add, None) => (MembershipChange::Added, record.epoch),
+            (Operation::Add, Some(false)) => (MembershipChange::ReAdded, record.epoch),
+            (Operation::Delete, _) => {
+                while removals.next_if(|epoch| *epoch <= record.epoch).is_some() {}
+                let Some(epoch) = removals.next() else {
+                    continue;
+                };
+                (MembershipChange::Deleted, epoch)
+            }
+            _ => continue,
+        };
+        let checkpoint = match change {
+            MembershipChange::Deleted => checkpoints.iter().position(|c| covers(c, prime, epoch)),
+            _ => None,
+        };
+        events.push(MembershipEvent {
+            change,
+            epoch,
+            seq: record.seq,
+            checkpoint,
+        });
+    }
+    events
+}
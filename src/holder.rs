@@ -0,0 +1,299 @@
+#[cfg(feature = "store")]
+use crate::encoding::{Reader, Writer};
+use crate::group::ElemPrime;
+use crate::params::PublicParams;
+#[cfg(feature = "service")]
+use crate::stream::UpdateStream;
+use crate::update::{EpochUpdate, UpdateLog, UpdateMessage};
+#[cfg(feature = "websocket")]
+use crate::websocket::UpdateFeed;
+use crate::witness::Witness;
+
+#[cfg(feature = "store")]
+const HOLDER_TAG: &[u8] = b"braavos-witness-holder";
+
+// Holder-side state for one element: keeps its witness current by replaying
+// the manager's update log, without the trapdoor.
+#[derive(Clone, Debug)]
+pub struct WitnessHolder {
+    element: Vec<u8>,
+    prime: ElemPrime,
+    witness: Witness,
+    epoch: u64, // Epoch the witness is valid for
+}
+
+impl WitnessHolder {
+    pub fn new(
+        params: &PublicParams,
+        element: impl AsRef<[u8]>,
+        witness: Witness,
+        epoch: u64,
+    ) -> Result<Self, &'static str> {
+        let element = element.as_ref();
+        witness.check_params(params)?;
+        Ok(Self {
+            element: element.to_vec(),
+            prime: params.hash_to_prime(element),
+            witness,
+            epoch,
+        })
+    }
+
+    pub fn element(&self) -> &[u8] {
+        &self.element
+    }
+
+    pub fn witness(&self) -> Witness {
+        self.witness
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    // The holder with its prime, for WitnessStore, which authenticates what
+    // it decodes and so need not derive the prime again.
+    #[cfg(feature = "store")]
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(HOLDER_TAG);
+        w.put_bytes(&self.element);
+        w.put_u256(self.prime.as_uint());
+        w.put_bytes(&self.witness.to_bytes());
+        w.put_u64(self.epoch);
+        w.finish()
+    }
+
+    #[cfg(feature = "store")]
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader::new(bytes, HOLDER_TAG)?;
+        let element = r.get_bytes()?.to_vec();
+        let prime = r.get_prime()?;
+        let witness = Witness::from_bytes(r.get_bytes()?)?;
+        let epoch = r.get_u64()?;
+        r.finish()?;
+        Ok(Self {
+            element,
+            prime,
+            witness,
+            epoch,
+        })
+    }
+
+    // Applies every update published since our epoch straight from the log.
+    // Same work as applying them coalesced, without cloning the deleted lists.
+    pub fn sync(&mut self, log: &UpdateLog) -> Result<(), &'static str> {
+        self.sync_with(log, &mut ())
+    }
+
+    // sync, reporting progress to `events`. The holder advances one epoch at
+    // a time, so after an error it is left at the last epoch it reached.
+    pub fn sync_with<E: HolderEvents>(&mut self, log: &UpdateLog, events: &mut E) -> Result<(), &'static str> {
+        let pending = match log.pending(self.epoch) {
+            Ok(pending) => pending,
+            Err(e) => {
+                if !log.reaches(self.epoch) {
+                    events.on_invalidated(&self.element, Invalidation::Expired);
+                }
+                return Err(e);
+            }
+        };
+        for update in pending {
+            self.apply(update, events)?;
+        }
+        if !pending.is_empty() {
+            events.on_updated(&self.element, &self.witness, self.epoch);
+        }
+        Ok(())
+    }
+
+    // Waits for the next update from a WebSocket feed and applies it. The
+    // feed reconnects on its own and resubscribes from our epoch, so calling
+    // this in a loop keeps the witness current across dropped connections.
+    #[cfg(feature = "websocket")]
+    pub fn sync_from<E: HolderEvents>(&mut self, feed: &mut UpdateFeed, events: &mut E) -> Result<(), &'static str> {
+        let update = match feed.next(self.epoch) {
+            Ok(update) => update,
+            Err(e) => {
+                if e == "Epoch predates the log" {
+                    events.on_invalidated(&self.element, Invalidation::Expired);
+                }
+                return Err(e);
+            }
+        };
+        self.apply(&update, events)?;
+        events.on_updated(&self.element, &self.witness, self.epoch);
+        Ok(())
+    }
+
+    // Waits for the next update on an async stream and applies it; runs
+    // under any executor. Updates the holder is already past are skipped,
+    // and one that starts after its epoch means it missed some: sync from
+    // the log first.
+    #[cfg(feature = "service")]
+    pub async fn sync_from_stream<E: HolderEvents>(
+        &mut self,
+        stream: &mut UpdateStream,
+        events: &mut E,
+    ) -> Result<(), &'static str> {
+        loop {
+            let update = stream.recv().await.ok_or("Update stream is closed")?;
+            if update.to_epoch <= self.epoch {
+                continue;
+            }
+            if update.from_epoch != self.epoch {
+                return Err("Update does not start at the holder's epoch");
+            }
+            self.apply(&update, events)?;
+            events.on_updated(&self.element, &self.witness, self.epoch);
+            return Ok(());
+        }
+    }
+
+    fn apply<E: HolderEvents>(&mut self, update: &EpochUpdate, events: &mut E) -> Result<(), &'static str> {
+        self.apply_using(update, events, |x, w| update.apply_to_witness(x, w))
+    }
+
+    // apply, with the witness arithmetic supplied by the caller.
+    fn apply_using<E, F>(&mut self, update: &EpochUpdate, events: &mut E, advance: F) -> Result<(), &'static str>
+    where
+        E: HolderEvents,
+        F: FnOnce(&ElemPrime, &Witness) -> Result<Witness, &'static str>,
+    {
+        if update.params_digest != self.witness.params_digest {
+            events.on_invalidated(&self.element, Invalidation::Reissued);
+            return Err("Witness was issued under different parameters");
+        }
+        if update.deleted.contains(&self.prime) {
+            events.on_invalidated(&self.element, Invalidation::Deleted { epoch: update.to_epoch });
+            return Err("Element has been deleted");
+        }
+        self.witness = advance(&self.prime, &self.witness)?;
+        self.epoch = update.to_epoch;
+        events.on_epoch(self.epoch);
+        Ok(())
+    }
+}
+
+// Holders for several elements, such as a device with several credentials,
+// synced together: a multi-deletion update's intermediate values are walked
+// back once for every witness instead of once each.
+#[derive(Clone, Debug, Default)]
+pub struct WitnessSet {
+    holders: Vec<WitnessHolder>,
+}
+
+impl WitnessSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces any holder for the same element.
+    pub fn insert(&mut self, holder: WitnessHolder) {
+        self.holders.retain(|h| h.element != holder.element);
+        self.holders.push(holder);
+    }
+
+    pub fn remove(&mut self, element: impl AsRef<[u8]>) -> Option<WitnessHolder> {
+        let at = self.holders.iter().position(|h| h.element == element.as_ref())?;
+        Some(self.holders.remove(at))
+    }
+
+    pub fn get(&self, element: impl AsRef<[u8]>) -> Option<&WitnessHolder> {
+        self.holders.iter().find(|h| h.element == element.as_ref())
+    }
+
+    pub fn holders(&self) -> &[WitnessHolder] {
+        &self.holders
+    }
+
+    pub fn len(&self) -> usize {
+        self.holders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.holders.is_empty()
+    }
+
+    // WitnessHolder::sync for every holder, in one pass over the log.
+    pub fn sync_all(&mut self, log: &UpdateLog) -> Result<(), &'static str> {
+        self.sync_all_with(log, &mut ())
+    }
+
+    // sync_all, reporting to `events` as each holder's sync_with would. A
+    // holder that cannot be synced is left where it stopped without holding
+    // back the others; the first such error is returned.
+    pub fn sync_all_with<E: HolderEvents>(&mut self, log: &UpdateLog, events: &mut E) -> Result<(), &'static str> {
+        let mut first_error = None;
+        let mut active = vec![false; self.holders.len()];
+        for (holder, active) in self.holders.iter().zip(active.iter_mut()) {
+            match log.pending(holder.epoch) {
+                Ok(_) => *active = true,
+                Err(e) => {
+                    if !log.reaches(holder.epoch) {
+                        events.on_invalidated(&holder.element, Invalidation::Expired);
+                    }
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        let start = self.holders.iter().zip(&active).filter(|(_, a)| **a).map(|(h, _)| h.epoch).min();
+        let pending = match start {
+            Some(epoch) => log.pending(epoch)?,
+            None => &[],
+        };
+        let mut advanced = vec![false; self.holders.len()];
+        for update in pending {
+            let mut shared = None;
+            for (i, holder) in self.holders.iter_mut().enumerate() {
+                if !active[i] || holder.epoch != update.from_epoch {
+                    continue;
+                }
+                let (values, params) = match &shared {
+                    Some(shared) => shared,
+                    None => {
+                        let params = update.monty_params()?;
+                        shared.insert((update.values_after_deletions(&params), params))
+                    }
+                };
+                let result =
+                    holder.apply_using(update, events, |x, w| update.apply_with_values(x, w, values, params));
+                match result {
+                    Ok(()) => advanced[i] = true,
+                    Err(e) => {
+                        active[i] = false;
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+        for (i, holder) in self.holders.iter().enumerate() {
+            if advanced[i] && active[i] {
+                events.on_updated(&holder.element, &holder.witness, holder.epoch);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+// Why a holder's witness can no longer be brought up to date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Invalidation {
+    Deleted { epoch: u64 }, // The element was deleted, effective from this epoch
+    Expired,                // The log no longer reaches back to the holder's epoch
+    Reissued,               // The parameters changed (rerandomization); a new witness is needed
+}
+
+// Hooks run by WitnessHolder::sync_with, so applications can re-request a
+// witness or alert the user. All default to doing nothing.
+pub trait HolderEvents {
+    // The witness is permanently stale; nothing further will be reported.
+    fn on_invalidated(&mut self, _element: &[u8], _reason: Invalidation) {}
+
+    // Sync finished with a new witness, valid at `epoch`.
+    fn on_updated(&mut self, _element: &[u8], _witness: &Witness, _epoch: u64) {}
+
+    // One more update has been applied; the witness is now valid at `epoch`.
+    fn on_epoch(&mut self, _epoch: u64) {}
+}
+
+impl HolderEvents for () {}
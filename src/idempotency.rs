@@ -0,0 +1,147 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::encoding::{Reader, Writer};
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const WINDOW_TAG: &[u8] = b"braavos-idempotency-window";
+const OUTCOME_ADDED: u32 = 0;
+const OUTCOME_DELETED: u32 = 1;
+
+// Keys remembered by a manager unless configured otherwise.
+pub const DEFAULT_IDEMPOTENCY_WINDOW: usize = 1024;
+
+// Answers to recent requests by client-supplied idempotency key, so a request
+// retried after a lost response gets the original answer instead of being
+// applied again. Only the last `capacity` keys are remembered; the oldest is
+// forgotten first. Failed requests are not remembered and may be retried.
+#[derive(Clone, Debug)]
+pub(crate) struct IdempotencyWindow {
+    capacity: usize,
+    entries: HashMap<Vec<u8>, Entry>,
+    order: VecDeque<Vec<u8>>, // Keys in insertion order, for eviction
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    operator: String,
+    element: Vec<u8>,
+    outcome: Outcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Added(Witness),
+    Deleted(EpochUpdate),
+}
+
+impl IdempotencyWindow {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    // The recorded answer for `key`, if it was already used for this very
+    // request. A key reused for another operator, element or operation is
+    // an error rather than a replay.
+    pub(crate) fn replay(
+        &self,
+        key: &[u8],
+        operator: &str,
+        x: &[u8],
+        deleting: bool,
+    ) -> Result<Option<&Outcome>, &'static str> {
+        let Some(entry) = self.entries.get(key) else {
+            return Ok(None);
+        };
+        let same_operation = matches!(entry.outcome, Outcome::Deleted(_)) == deleting;
+        if entry.operator != operator || entry.element != x || !same_operation {
+            return Err("Idempotency key was used for a different request");
+        }
+        Ok(Some(&entry.outcome))
+    }
+
+    pub(crate) fn record(&mut self, key: &[u8], operator: &str, x: &[u8], outcome: Outcome) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        let entry = Entry {
+            operator: operator.to_string(),
+            element: x.to_vec(),
+            outcome,
+        };
+        if self.entries.insert(key.to_vec(), entry).is_none() {
+            self.order.push_back(key.to_vec());
+        }
+    }
+
+    // Forgets the oldest keys beyond the new capacity.
+    #[cfg(feature = "journal")]
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.order.len() > capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    #[cfg(feature = "service")]
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(WINDOW_TAG);
+        let entries: Vec<_> = self.order.iter().filter_map(|k| self.entries.get(k).map(|e| (k, e))).collect();
+        w.put_u32(entries.len() as u32);
+        for (key, entry) in entries {
+            w.put_bytes(key);
+            w.put_bytes(entry.operator.as_bytes());
+            w.put_bytes(&entry.element);
+            match &entry.outcome {
+                Outcome::Added(witness) => {
+                    w.put_u32(OUTCOME_ADDED);
+                    w.put_bytes(&witness.to_bytes());
+                }
+                Outcome::Deleted(update) => {
+                    w.put_u32(OUTCOME_DELETED);
+                    w.put_bytes(&update.to_bytes());
+                }
+            }
+        }
+        w.finish()
+    }
+
+    // Keys past `capacity` are dropped oldest first, as if they had been
+    // recorded into a window of that size.
+    pub(crate) fn from_bytes(bytes: &[u8], capacity: usize) -> Result<Self, &'static str> {
+        let mut r = Reader::new(bytes, WINDOW_TAG)?;
+        let mut window = Self::new(capacity);
+        for _ in 0..r.get_count()? {
+            let key = r.get_bytes()?.to_vec();
+            let operator = String::from_utf8(r.get_bytes()?.to_vec()).map_err(|_| "Operator is not UTF-8")?;
+            let element = r.get_bytes()?.to_vec();
+            let outcome = match r.get_u32()? {
+                OUTCOME_ADDED => Outcome::Added(Witness::from_bytes(r.get_bytes()?)?),
+                OUTCOME_DELETED => Outcome::Deleted(EpochUpdate::from_bytes(r.get_bytes()?)?),
+                _ => return Err("Unknown idempotent outcome"),
+            };
+            window.record(&key, &operator, &element, outcome);
+        }
+        r.finish()?;
+        Ok(window)
+    }
+}
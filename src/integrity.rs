@@ -0,0 +1,42 @@
+use std::error::Error;
+use std::fmt;
+
+// Ways the internal state of an accumulator can be inconsistent, as found by
+// BraavosAccumulator::check_integrity. Any of these means the state was
+// corrupted or tampered with, and witnesses issued from it cannot be trusted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityError {
+    ValueOutOfRange,                                // Value or base is zero or not below the modulus
+    KeyMismatch,                                    // Value or base has order not dividing the secret key
+    MissingPrime { element: Vec<u8> },              // Member without a cached element prime
+    PrimeMismatch { element: Vec<u8> },             // Cached prime is not hash-to-prime of the member
+    EpochMismatch { epoch: u64, deletions: usize }, // Epoch disagrees with the deletion history
+    StillMember { element: Vec<u8> },               // Deleted element is still in the member set
+    NotDeleted { element: Vec<u8> },                // Deletion update does not remove the element's prime
+    WitnessStillOpens { element: Vec<u8> },         // A witness for a deleted element opens the current value
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::ValueOutOfRange => write!(f, "accumulator value or base out of range"),
+            IntegrityError::KeyMismatch => write!(f, "secret key does not match the accumulator value"),
+            IntegrityError::MissingPrime { element } => write!(f, "no element prime cached for member {:02x?}", element),
+            IntegrityError::PrimeMismatch { element } => {
+                write!(f, "cached element prime does not match member {:02x?}", element)
+            }
+            IntegrityError::EpochMismatch { epoch, deletions } => {
+                write!(f, "epoch {} does not match {} recorded deletions", epoch, deletions)
+            }
+            IntegrityError::StillMember { element } => write!(f, "deleted element {:02x?} is still a member", element),
+            IntegrityError::NotDeleted { element } => {
+                write!(f, "deletion update does not remove element {:02x?}", element)
+            }
+            IntegrityError::WitnessStillOpens { element } => {
+                write!(f, "a witness for deleted element {:02x?} still verifies", element)
+            }
+        }
+    }
+}
+
+impl Error for IntegrityError {}
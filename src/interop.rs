@@ -0,0 +1,75 @@
+// Wire-level interop with other accumulator crates.
+//
+// The cambrian `accumulator` crate works over the fixed RSA-2048 challenge
+// modulus with GMP-backed integers, and docknetwork's `vb_accumulator` is a
+// pairing-based construction over BLS12-381. Neither shares group elements
+// with an accumulator over our own modulus, so there is no meaningful
+// conversion between accumulator or witness types. What does carry over is
+// the integer representation: moduli, element primes and witnesses are
+// exchanged as unsigned big-endian integers, which `BigUint` models and which
+// both ecosystems can read.
+//
+// The public number types convert the same way. Conversions into them check
+// only the width, like from_be_bytes: a prime is not checked to be prime, nor
+// a value or witness to be a group element.
+use crypto_bigint::Uint;
+use num_bigint::BigUint;
+
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+
+pub fn to_biguint<const LIMBS: usize>(v: &Uint<LIMBS>) -> BigUint {
+    let bytes: Vec<u8> = v.as_words().iter().flat_map(|w| w.to_le_bytes()).collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+pub fn from_biguint<const LIMBS: usize>(v: &BigUint) -> Result<Uint<LIMBS>, &'static str> {
+    if v.bits() > Uint::<LIMBS>::BITS as u64 {
+        return Err("Integer too large for target width");
+    }
+    let bytes = v.to_bytes_be();
+    let mut padded = vec![0u8; Uint::<LIMBS>::BYTES];
+    padded[Uint::<LIMBS>::BYTES - bytes.len()..].copy_from_slice(&bytes);
+    Ok(Uint::from_be_slice(&padded))
+}
+
+impl From<ElemPrime> for BigUint {
+    fn from(prime: ElemPrime) -> Self {
+        to_biguint(prime.as_uint())
+    }
+}
+
+impl TryFrom<&BigUint> for ElemPrime {
+    type Error = &'static str;
+
+    fn try_from(v: &BigUint) -> Result<Self, Self::Error> {
+        from_biguint(v).map(ElemPrime::new)
+    }
+}
+
+impl From<AccValue> for BigUint {
+    fn from(value: AccValue) -> Self {
+        to_biguint(value.as_uint())
+    }
+}
+
+impl TryFrom<&BigUint> for AccValue {
+    type Error = &'static str;
+
+    fn try_from(v: &BigUint) -> Result<Self, Self::Error> {
+        from_biguint(v).map(AccValue::new)
+    }
+}
+
+impl From<WitnessValue> for BigUint {
+    fn from(w: WitnessValue) -> Self {
+        to_biguint(w.as_uint())
+    }
+}
+
+impl TryFrom<&BigUint> for WitnessValue {
+    type Error = &'static str;
+
+    fn try_from(v: &BigUint) -> Result<Self, Self::Error> {
+        from_biguint(v).map(WitnessValue::new)
+    }
+}
@@ -0,0 +1,485 @@
+// Write-ahead operation journal in SQLite.
+//
+// Every add and delete is recorded before it touches the in-memory
+// accumulator, and the resulting value is filled in once it has been applied.
+// On open, the latest snapshot is loaded and every journalled operation since
+// is replayed: finished ones must reproduce their recorded value, unfinished
+// ones (the process died between the two writes) are completed. Both
+// operations are deterministic given the state, so replay reaches exactly the
+// state the crashed process would have. That includes failing: an unfinished
+// operation that fails on replay (a duplicate add, say, journalled just
+// before the process died instead of aborting) failed the first time too, and
+// is dropped as abort would have dropped it.
+//
+// Operations made with an idempotency key record it in the same transaction
+// as the operation, and replay answers them again, so the window of recent
+// keys is rebuilt from the journal rather than kept beside it. A checkpoint
+// folds the window into the snapshot along with the state. Rerandomization draws fresh
+// randomness and is not journalled.
+//
+// The database holds the secret key and must be protected like one. Binds
+// directly to the system libsqlite3.
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::path::Path;
+use std::ptr;
+
+use crate::accumulator::BraavosAccumulator;
+use crate::builder::AccumulatorBuilder;
+use crate::group::AccValue;
+use crate::idempotency::{IdempotencyWindow, Outcome, DEFAULT_IDEMPOTENCY_WINDOW};
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    PRAGMA synchronous = FULL;
+    CREATE TABLE IF NOT EXISTS snapshot (id INTEGER PRIMARY KEY CHECK (id = 0), state BLOB NOT NULL);
+    CREATE TABLE IF NOT EXISTS ops (
+        seq INTEGER PRIMARY KEY,
+        op INTEGER NOT NULL,
+        element BLOB NOT NULL,
+        value BLOB
+    );
+    CREATE TABLE IF NOT EXISTS idempotency_keys (seq INTEGER PRIMARY KEY, key BLOB NOT NULL);
+    CREATE TABLE IF NOT EXISTS idempotency_window (id INTEGER PRIMARY KEY CHECK (id = 0), entries BLOB NOT NULL);
+";
+
+const OP_ADD: i64 = 0;
+const OP_DELETE: i64 = 1;
+
+// The journal has no operators; every key belongs to its one caller.
+const OPERATOR: &str = "";
+
+// An accumulator whose every mutation is durable before it is applied.
+pub struct JournaledAccumulator {
+    db: Connection,
+    acc: BraavosAccumulator,
+    recovered: usize,
+    idempotency: IdempotencyWindow,
+}
+
+impl JournaledAccumulator {
+    // Opens or creates the journal at `path`. A new journal starts from an
+    // accumulator built by `builder`; an existing one is recovered with the
+    // builder's runtime options.
+    pub fn open(path: impl AsRef<Path>, builder: AccumulatorBuilder) -> Result<Self, &'static str> {
+        let db = Connection::open(path.as_ref())?;
+        db.execute_batch(SCHEMA)?;
+
+        let mut stmt = db.prepare("SELECT state FROM snapshot WHERE id = 0")?;
+        let snapshot = if stmt.step()? { Some(stmt.column_blob(0).to_vec()) } else { None };
+        drop(stmt);
+        let mut acc = match snapshot {
+            Some(state) => BraavosAccumulator::from_state_bytes(&state, &builder)?,
+            None => {
+                let acc = builder.build()?;
+                let mut stmt = db.prepare("INSERT INTO snapshot (id, state) VALUES (0, ?1)")?;
+                stmt.bind_blob(1, &acc.to_state_bytes()?)?;
+                stmt.step()?;
+                acc
+            }
+        };
+
+        let mut stmt = db.prepare("SELECT entries FROM idempotency_window WHERE id = 0")?;
+        let mut idempotency = match stmt.step()? {
+            true => IdempotencyWindow::from_bytes(stmt.column_blob(0), DEFAULT_IDEMPOTENCY_WINDOW)?,
+            false => IdempotencyWindow::new(DEFAULT_IDEMPOTENCY_WINDOW),
+        };
+        drop(stmt);
+
+        let mut ops = Vec::new();
+        let mut stmt = db.prepare(
+            "SELECT ops.seq, op, element, value, key FROM ops
+             LEFT JOIN idempotency_keys ON idempotency_keys.seq = ops.seq ORDER BY ops.seq",
+        )?;
+        while stmt.step()? {
+            let value = (!stmt.column_is_null(3)).then(|| stmt.column_blob(3).to_vec());
+            let key = (!stmt.column_is_null(4)).then(|| stmt.column_blob(4).to_vec());
+            ops.push((stmt.column_int64(0), stmt.column_int64(1), stmt.column_blob(2).to_vec(), value, key));
+        }
+        drop(stmt);
+
+        let mut recovered = 0;
+        for (seq, op, element, value, key) in ops {
+            if op != OP_ADD && op != OP_DELETE {
+                return Err("Unknown operation in journal");
+            }
+            let applied = apply(&mut acc, op, &element);
+            match value {
+                Some(value) if applied.is_err() || value[..] != acc.value().to_be_bytes() => {
+                    return Err("Journal does not match the replayed state");
+                }
+                Some(_) => {}
+                None if applied.is_err() => drop_op(&db, seq)?,
+                None => {
+                    finish(&db, seq, &acc.value())?;
+                    recovered += 1;
+                }
+            }
+            if let (Some(key), Ok(outcome)) = (key, applied) {
+                idempotency.record(&key, OPERATOR, &element, outcome);
+            }
+        }
+        Ok(Self {
+            db,
+            acc,
+            recovered,
+            idempotency,
+        })
+    }
+
+    // How many idempotency keys are remembered, DEFAULT_IDEMPOTENCY_WINDOW
+    // unless set here; the oldest are forgotten if there are more.
+    pub fn with_idempotency_window(mut self, capacity: usize) -> Self {
+        self.idempotency.set_capacity(capacity);
+        self
+    }
+
+    pub fn accumulator(&self) -> &BraavosAccumulator {
+        &self.acc
+    }
+
+    // Operations that were journalled but not finished when the journal was
+    // last closed, and were completed by recovery.
+    pub fn recovered(&self) -> usize {
+        self.recovered
+    }
+
+    pub fn add(&mut self, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        self.add_keyed(None, x.as_ref())
+    }
+
+    pub fn delete(&mut self, x: impl AsRef<[u8]>) -> Result<EpochUpdate, &'static str> {
+        self.delete_keyed(None, x.as_ref())
+    }
+
+    // As add, but a request retried with the same key, before or after a
+    // crash, gets the original witness back instead of being applied again.
+    // Reusing a key for a different request is an error.
+    pub fn add_idempotent(&mut self, key: &[u8], x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        if let Some(Outcome::Added(witness)) = self.idempotency.replay(key, OPERATOR, x, false)? {
+            return Ok(*witness);
+        }
+        self.add_keyed(Some(key), x)
+    }
+
+    // As delete, keyed as add_idempotent.
+    pub fn delete_idempotent(&mut self, key: &[u8], x: impl AsRef<[u8]>) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        if let Some(Outcome::Deleted(update)) = self.idempotency.replay(key, OPERATOR, x, true)? {
+            return Ok(update.clone());
+        }
+        self.delete_keyed(Some(key), x)
+    }
+
+    // Number of idempotency keys currently remembered.
+    pub fn idempotency_keys(&self) -> usize {
+        self.idempotency.len()
+    }
+
+    fn add_keyed(&mut self, key: Option<&[u8]>, x: &[u8]) -> Result<Witness, &'static str> {
+        let seq = self.begin(OP_ADD, x, key)?;
+        let witness = match self.acc.add(x) {
+            Ok(witness) => witness,
+            Err(e) => return self.abort(seq, e),
+        };
+        finish(&self.db, seq, &self.acc.value())?;
+        if let Some(key) = key {
+            self.idempotency.record(key, OPERATOR, x, Outcome::Added(witness));
+        }
+        Ok(witness)
+    }
+
+    fn delete_keyed(&mut self, key: Option<&[u8]>, x: &[u8]) -> Result<EpochUpdate, &'static str> {
+        let seq = self.begin(OP_DELETE, x, key)?;
+        let update = match self.acc.delete(x) {
+            Ok(update) => update,
+            Err(e) => return self.abort(seq, e),
+        };
+        finish(&self.db, seq, &self.acc.value())?;
+        if let Some(key) = key {
+            self.idempotency.record(key, OPERATOR, x, Outcome::Deleted(update.clone()));
+        }
+        Ok(update)
+    }
+
+    pub fn verify(&mut self, x: impl AsRef<[u8]>, w: Witness) -> bool {
+        self.acc.verify(x, w)
+    }
+
+    // Folds the journal into a fresh snapshot, so recovery does not replay
+    // the whole history. The idempotency window goes with it.
+    pub fn checkpoint(&mut self) -> Result<(), &'static str> {
+        self.db.execute_batch("BEGIN IMMEDIATE")?;
+        let result = (|| {
+            let mut stmt = self.db.prepare("UPDATE snapshot SET state = ?1 WHERE id = 0")?;
+            stmt.bind_blob(1, &self.acc.to_state_bytes()?)?;
+            stmt.step()?;
+            drop(stmt);
+            let mut stmt = self.db.prepare("INSERT OR REPLACE INTO idempotency_window (id, entries) VALUES (0, ?1)")?;
+            stmt.bind_blob(1, &self.idempotency.to_bytes())?;
+            stmt.step()?;
+            drop(stmt);
+            self.db.execute_batch("DELETE FROM ops; DELETE FROM idempotency_keys; COMMIT")
+        })();
+        if result.is_err() {
+            let _ = self.db.execute_batch("ROLLBACK");
+        }
+        result
+    }
+
+    // Journals the operation, and its key if it has one, as one transaction.
+    fn begin(&self, op: i64, x: &[u8], key: Option<&[u8]>) -> Result<i64, &'static str> {
+        self.db.execute_batch("BEGIN IMMEDIATE")?;
+        let result = (|| {
+            let mut stmt = self.db.prepare("INSERT INTO ops (op, element) VALUES (?1, ?2)")?;
+            stmt.bind_int64(1, op)?;
+            stmt.bind_blob(2, x)?;
+            stmt.step()?;
+            drop(stmt);
+            let seq = self.db.last_insert_rowid();
+            if let Some(key) = key {
+                let mut stmt = self.db.prepare("INSERT INTO idempotency_keys (seq, key) VALUES (?1, ?2)")?;
+                stmt.bind_int64(1, seq)?;
+                stmt.bind_blob(2, key)?;
+                stmt.step()?;
+            }
+            self.db.execute_batch("COMMIT")?;
+            Ok(seq)
+        })();
+        if result.is_err() {
+            let _ = self.db.execute_batch("ROLLBACK");
+        }
+        result
+    }
+
+    // The operation failed without changing the accumulator; drop its record
+    // so recovery does not retry it.
+    fn abort<T>(&self, seq: i64, error: &'static str) -> Result<T, &'static str> {
+        drop_op(&self.db, seq)?;
+        Err(error)
+    }
+}
+
+fn apply(acc: &mut BraavosAccumulator, op: i64, x: &[u8]) -> Result<Outcome, &'static str> {
+    match op {
+        OP_ADD => acc.add(x).map(Outcome::Added),
+        OP_DELETE => acc.delete(x).map(Outcome::Deleted),
+        _ => Err("Unknown operation in journal"),
+    }
+}
+
+// The key goes first: were the op row deleted alone, its seq could be
+// reused by the next operation, which would inherit the key.
+fn drop_op(db: &Connection, seq: i64) -> Result<(), &'static str> {
+    for sql in ["DELETE FROM idempotency_keys WHERE seq = ?1", "DELETE FROM ops WHERE seq = ?1"] {
+        let mut stmt = db.prepare(sql)?;
+        stmt.bind_int64(1, seq)?;
+        stmt.step()?;
+    }
+    Ok(())
+}
+
+fn finish(db: &Connection, seq: i64, value: &AccValue) -> Result<(), &'static str> {
+    let mut stmt = db.prepare("UPDATE ops SET value = ?1 WHERE seq = ?2")?;
+    stmt.bind_blob(1, &value.to_be_bytes())?;
+    stmt.bind_int64(2, seq)?;
+    stmt.step()?;
+    Ok(())
+}
+
+// Minimal safe wrappers over the handful of libsqlite3 calls used above.
+
+#[repr(C)]
+struct Sqlite3 {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct Sqlite3Stmt {
+    _private: [u8; 0],
+}
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+const SQLITE_NULL: c_int = 5;
+const SQLITE_OPEN_READWRITE: c_int = 0x02;
+const SQLITE_OPEN_CREATE: c_int = 0x04;
+const SQLITE_OPEN_FULLMUTEX: c_int = 0x10000;
+const SQLITE_TRANSIENT: isize = -1; // Makes SQLite copy bound blobs
+
+#[link(name = "sqlite3")]
+extern "C" {
+    fn sqlite3_open_v2(filename: *const c_char, db: *mut *mut Sqlite3, flags: c_int, vfs: *const c_char) -> c_int;
+    fn sqlite3_close(db: *mut Sqlite3) -> c_int;
+    fn sqlite3_exec(
+        db: *mut Sqlite3,
+        sql: *const c_char,
+        callback: *const c_void,
+        arg: *mut c_void,
+        errmsg: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_last_insert_rowid(db: *mut Sqlite3) -> i64;
+    fn sqlite3_prepare_v2(
+        db: *mut Sqlite3,
+        sql: *const c_char,
+        len: c_int,
+        stmt: *mut *mut Sqlite3Stmt,
+        tail: *mut *const c_char,
+    ) -> c_int;
+    fn sqlite3_bind_blob(stmt: *mut Sqlite3Stmt, idx: c_int, data: *const c_void, len: c_int, dtor: isize) -> c_int;
+    fn sqlite3_bind_int64(stmt: *mut Sqlite3Stmt, idx: c_int, value: i64) -> c_int;
+    fn sqlite3_step(stmt: *mut Sqlite3Stmt) -> c_int;
+    fn sqlite3_column_type(stmt: *mut Sqlite3Stmt, col: c_int) -> c_int;
+    fn sqlite3_column_blob(stmt: *mut Sqlite3Stmt, col: c_int) -> *const c_void;
+    fn sqlite3_column_bytes(stmt: *mut Sqlite3Stmt, col: c_int) -> c_int;
+    fn sqlite3_column_int64(stmt: *mut Sqlite3Stmt, col: c_int) -> i64;
+    fn sqlite3_finalize(stmt: *mut Sqlite3Stmt) -> c_int;
+}
+
+struct Connection(*mut Sqlite3);
+
+// SAFETY: the handle is opened with SQLITE_OPEN_FULLMUTEX, so SQLite
+// serializes every call on it and whichever thread owns it may use it.
+unsafe impl Send for Connection {}
+
+impl Connection {
+    fn open(path: &Path) -> Result<Self, &'static str> {
+        let path = path.to_str().and_then(|p| CString::new(p).ok()).ok_or("Invalid journal path")?;
+        let mut db = ptr::null_mut();
+        let flags = SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_FULLMUTEX;
+        // SAFETY: `path` is NUL-terminated and outlives the call, and `db` is
+        // a local out-pointer. SQLite returns a handle even when opening
+        // fails; wrapping it before checking rc closes it on drop either
+        // way, as sqlite3_open_v2 requires.
+        let rc = unsafe { sqlite3_open_v2(path.as_ptr(), &mut db, flags, ptr::null()) };
+        let conn = Self(db);
+        if rc != SQLITE_OK {
+            return Err("Failed to open journal");
+        }
+        Ok(conn)
+    }
+
+    fn execute_batch(&self, sql: &str) -> Result<(), &'static str> {
+        let sql = CString::new(sql).map_err(|_| "Invalid SQL")?;
+        // SAFETY: `self.0` was opened by open() and stays open until drop.
+        // `sql` is NUL-terminated, outlives the call and is not retained.
+        // With no callback the argument pointer is never read, and a null
+        // errmsg stops SQLite allocating a message we would have to free.
+        let rc = unsafe { sqlite3_exec(self.0, sql.as_ptr(), ptr::null(), ptr::null_mut(), ptr::null_mut()) };
+        if rc != SQLITE_OK {
+            return Err("Journal write failed");
+        }
+        Ok(())
+    }
+
+    fn prepare(&self, sql: &str) -> Result<Statement<'_>, &'static str> {
+        let sql = CString::new(sql).map_err(|_| "Invalid SQL")?;
+        let mut stmt = ptr::null_mut();
+        // SAFETY: `self.0` is open (see execute_batch). A length of -1 makes
+        // SQLite read `sql` up to its NUL during the call only, and `stmt`
+        // is a local out-pointer, left null on failure. The Statement
+        // borrows self, so it cannot outlive the connection.
+        let rc = unsafe { sqlite3_prepare_v2(self.0, sql.as_ptr(), -1, &mut stmt, ptr::null_mut()) };
+        if rc != SQLITE_OK {
+            return Err("Failed to prepare journal statement");
+        }
+        Ok(Statement { stmt, _conn: self })
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        // SAFETY: `self.0` is open; the call only reads the connection's
+        // last rowid.
+        unsafe { sqlite3_last_insert_rowid(self.0) }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // SAFETY: each Statement borrows the connection, so all have been
+        // finalized by now and the close cannot fail with SQLITE_BUSY. A
+        // null handle (open could not allocate) is a no-op.
+        unsafe { sqlite3_close(self.0) };
+    }
+}
+
+struct Statement<'a> {
+    stmt: *mut Sqlite3Stmt,
+    _conn: &'a Connection,
+}
+
+impl Statement<'_> {
+    fn bind_blob(&mut self, idx: c_int, data: &[u8]) -> Result<(), &'static str> {
+        let len = c_int::try_from(data.len()).map_err(|_| "Journal entry too large")?;
+        // SAFETY: `self.stmt` came from a successful prepare and is
+        // finalized only on drop. `data` is valid for `len` bytes during the
+        // call, and SQLITE_TRANSIENT makes SQLite copy it before returning,
+        // so the statement keeps no pointer into it.
+        let rc = unsafe { sqlite3_bind_blob(self.stmt, idx, data.as_ptr().cast(), len, SQLITE_TRANSIENT) };
+        if rc != SQLITE_OK {
+            return Err("Failed to bind journal parameter");
+        }
+        Ok(())
+    }
+
+    fn bind_int64(&mut self, idx: c_int, value: i64) -> Result<(), &'static str> {
+        // SAFETY: `self.stmt` is live (see bind_blob); the value is copied.
+        if unsafe { sqlite3_bind_int64(self.stmt, idx, value) } != SQLITE_OK {
+            return Err("Failed to bind journal parameter");
+        }
+        Ok(())
+    }
+
+    // True while rows remain.
+    fn step(&mut self) -> Result<bool, &'static str> {
+        // SAFETY: `self.stmt` is live. Stepping frees the previous row's
+        // buffers; &mut self guarantees no slice from column_blob still
+        // borrows them.
+        match unsafe { sqlite3_step(self.stmt) } {
+            SQLITE_ROW => Ok(true),
+            SQLITE_DONE => Ok(false),
+            _ => Err("Journal query failed"),
+        }
+    }
+
+    fn column_is_null(&self, col: c_int) -> bool {
+        // SAFETY: `self.stmt` is live, callers only ask after step() returned
+        // a row, and `col` indexes the statement's own SELECT list.
+        unsafe { sqlite3_column_type(self.stmt, col) == SQLITE_NULL }
+    }
+
+    fn column_blob(&self, col: c_int) -> &[u8] {
+        // SAFETY: `self.stmt` is live and on a row (see column_is_null).
+        // SQLite owns the buffer until the next step or finalize, which need
+        // &mut self or drop, so the slice borrowed from &self cannot outlive
+        // it. The blob is fetched before its length, as SQLite advises, and
+        // a null pointer (empty or NULL column) never reaches
+        // from_raw_parts.
+        unsafe {
+            let data = sqlite3_column_blob(self.stmt, col);
+            let len = sqlite3_column_bytes(self.stmt, col) as usize;
+            if data.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(data.cast(), len)
+            }
+        }
+    }
+
+    fn column_int64(&self, col: c_int) -> i64 {
+        // SAFETY: `self.stmt` is live and on a row (see column_is_null); the
+        // value is copied out.
+        unsafe { sqlite3_column_int64(self.stmt, col) }
+    }
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `self.stmt` came from a successful prepare and drop runs
+        // once, so it is finalized exactly once, while the connection it
+        // borrows is still open.
+        unsafe { sqlite3_finalize(self.stmt) };
+    }
+}
@@ -0,0 +1,88 @@
+use crate::argon2;
+use crate::element::ElementDigest;
+use crate::params::PublicParams;
+
+const KDF_SALT_TAG: &[u8] = b"braavos-element-kdf";
+const MAX_LANES: u32 = 255;
+const MAX_MEMORY_KIB: u32 = 1 << 22; // 4 GiB
+
+// Argon2id cost: memory in KiB, passes over it and lanes. The default is
+// the 19 MiB, two-pass, one-lane setting recommended for password hashing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Argon2Params {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub lanes: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            lanes: 1,
+        }
+    }
+}
+
+impl Argon2Params {
+    pub fn check(&self) -> Result<(), &'static str> {
+        if !(1..=MAX_LANES).contains(&self.lanes) {
+            return Err("Argon2 lanes must be between 1 and 255");
+        }
+        if self.iterations == 0 {
+            return Err("Argon2 needs at least one iteration");
+        }
+        if self.memory_kib < 8 * self.lanes || self.memory_kib > MAX_MEMORY_KIB {
+            return Err("Argon2 memory must be 8 KiB per lane to 4 GiB");
+        }
+        Ok(())
+    }
+}
+
+// Element derivation for identifiers from a small universe, such as phone
+// numbers. Hash-to-prime is public, so anyone can test whether a guessed
+// identifier is in the set by its prime; deriving the element through
+// Argon2id first makes every guess cost the memory and time of one
+// derivation. That is all it buys: a universe of a million identifiers is
+// still enumerated in hours, and holders and verifiers pay the same cost per
+// derivation. Elements are digest elements whose domain records the cost,
+// so every party derives the same one or visibly a different one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElementKdf {
+    cost: Argon2Params,
+    salt: [u8; 32],
+}
+
+impl ElementKdf {
+    // The salt comes from the parameters' hash-to-prime key, so guesses
+    // computed against one accumulator are no use against another.
+    pub fn new(params: &PublicParams, cost: Argon2Params) -> Result<Self, &'static str> {
+        cost.check()?;
+        Ok(Self {
+            cost,
+            salt: params.hash.hash(KDF_SALT_TAG, &[&params.prf_key.to_be_bytes()]),
+        })
+    }
+
+    pub fn cost(&self) -> Argon2Params {
+        self.cost
+    }
+
+    pub fn domain(&self) -> String {
+        let Argon2Params {
+            memory_kib,
+            iterations,
+            lanes,
+        } = self.cost;
+        format!("argon2id:m={memory_kib},t={iterations},p={lanes}")
+    }
+
+    // The element to accumulate and verify for `id`.
+    pub fn derive(&self, id: impl AsRef<[u8]>) -> Result<ElementDigest, &'static str> {
+        let tag = argon2::argon2id(id.as_ref(), &self.salt, &[], &[], &self.cost, 32)?;
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&tag);
+        ElementDigest::new(&self.domain(), digest)
+    }
+}
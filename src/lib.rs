@@ -0,0 +1,215 @@
+// Library code returns errors instead of aborting: a verifier or manager
+// embedded in a long-running service must never take the process down.
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+#![deny(clippy::todo, clippy::unimplemented, clippy::unreachable)]
+
+mod accumulator;
+mod anchor;
+#[cfg(feature = "argon2")]
+pub mod argon2;
+#[cfg(feature = "service")]
+mod audit;
+mod backend;
+#[cfg(feature = "service")]
+mod backup;
+#[cfg(feature = "service")]
+mod binding;
+mod blind;
+mod builder;
+mod bundle;
+mod cache;
+#[cfg(feature = "cardinality")]
+mod cardinality;
+mod chain;
+mod corpus;
+pub mod core;
+mod cost;
+#[cfg(feature = "service")]
+mod custody;
+mod deadline;
+#[cfg(feature = "zk")]
+mod delegation;
+pub mod der;
+mod device;
+mod decoding;
+mod element;
+mod encoding;
+mod exp;
+mod expiry;
+mod export;
+mod failure;
+#[cfg(feature = "zk")]
+mod freshness;
+mod genesis;
+mod group;
+mod hash;
+mod hierarchy;
+#[cfg(feature = "service")]
+mod history;
+mod holder;
+#[cfg(any(feature = "service", feature = "journal"))]
+mod idempotency;
+mod integrity;
+#[cfg(feature = "interop")]
+pub mod interop;
+#[cfg(feature = "journal")]
+mod journal;
+#[cfg(feature = "argon2")]
+mod kdf;
+#[cfg(feature = "log_commitment")]
+mod log_commitment;
+#[cfg(any(feature = "service", feature = "store"))]
+mod mac;
+#[cfg(feature = "service")]
+mod manager;
+pub mod math;
+pub mod migrate;
+mod params;
+#[cfg(feature = "persistence")]
+mod persistence;
+#[cfg(feature = "pool")]
+mod pool;
+mod preset;
+mod private_update;
+#[cfg(feature = "redis")]
+mod redis;
+mod receipt;
+mod registry;
+#[cfg(feature = "service")]
+mod relay;
+mod replace;
+mod replay;
+mod report;
+mod replica;
+#[cfg(feature = "service")]
+mod response;
+mod rotation;
+#[cfg(feature = "service")]
+mod schedule;
+mod security;
+#[cfg(feature = "service")]
+mod service;
+mod sharded;
+mod sharing;
+mod signature;
+#[cfg(feature = "service")]
+mod simulate;
+#[cfg(feature = "smt")]
+mod smt;
+#[cfg(feature = "service")]
+mod sse;
+#[cfg(feature = "store")]
+mod store;
+#[cfg(feature = "service")]
+mod stream;
+mod sync;
+#[cfg(feature = "threshold")]
+mod threshold;
+mod update;
+mod vectors;
+mod verification;
+#[cfg(feature = "websocket")]
+mod websocket;
+mod witness;
+#[cfg(feature = "zk")]
+mod zk;
+
+pub use accumulator::{BraavosAccumulator, FAULT_DETECTED};
+pub use anchor::{AnchorReceipt, CheckpointAnchor, ReceiptVerifier};
+#[cfg(feature = "service")]
+pub use audit::{batch_digest, AuditLog, AuditRecord, Decision, Operation};
+pub use backend::AccumulatorBackend;
+#[cfg(feature = "service")]
+pub use backup::{verify_backup, BackupSummary};
+#[cfg(feature = "service")]
+pub use binding::{Binding, LinkRecord, Rebinding};
+pub use blind::{BlindIssuance, BlindedPrime, Unblinder};
+pub use builder::{AccumulatorBuilder, DuplicatePolicy};
+pub use bundle::{BundleEntry, StaticBundle};
+pub use cache::VerificationCache;
+#[cfg(feature = "cardinality")]
+pub use cardinality::{CardinalityProof, CountInclusionProof};
+pub use chain::{verify_checkpoint_chain, Checkpoint};
+pub use corpus::{Corpus, CorpusOp, CorpusSpec};
+pub use cost::{CostEstimate, CostModel, CostedOperation};
+#[cfg(feature = "service")]
+pub use custody::CustodyRecord;
+pub use deadline::{CancelHandle, Deadline, CANCELLED, TIMEOUT};
+#[cfg(feature = "zk")]
+pub use delegation::{DelegatedPresentation, Delegation, DeviceKey, DevicePublicKey, DeviceSignature};
+pub use device::DeviceVerifier;
+pub use decoding::DecodingPolicy;
+pub use element::{ElementDigest, IntoElement};
+pub use exp::ExpStrategy;
+pub use expiry::ExpiryPolicy;
+pub use failure::VerifyFailure;
+#[cfg(feature = "zk")]
+pub use freshness::FreshnessProof;
+pub use genesis::{Contribution, GenesisRecord};
+pub use group::{AccValue, ElemPrime, WitnessValue};
+pub use hash::{hash_to_prime_uint, hash_to_prime_uint_within, HashAlgorithm, PrimalityPolicy, HASH_TO_PRIME_PROFILE};
+pub use hierarchy::SubregistryProof;
+#[cfg(feature = "service")]
+pub use history::{MembershipChange, MembershipEvent};
+pub use holder::{HolderEvents, Invalidation, WitnessHolder, WitnessSet};
+#[cfg(any(feature = "service", feature = "journal"))]
+pub use idempotency::DEFAULT_IDEMPOTENCY_WINDOW;
+pub use integrity::IntegrityError;
+#[cfg(feature = "journal")]
+pub use journal::JournaledAccumulator;
+#[cfg(feature = "argon2")]
+pub use kdf::{Argon2Params, ElementKdf};
+#[cfg(feature = "log_commitment")]
+pub use log_commitment::{LogCommitment, LogHead, LogInclusionProof};
+#[cfg(feature = "service")]
+pub use manager::{ActivationTicket, AllowAll, Manager, PendingVerification, PendingWitness, Policy, RateLimit};
+pub use params::{PublicParams, PARAMS_VERSION};
+#[cfg(feature = "persistence")]
+pub use persistence::{FileBackend, MemoryBackend, StateBackend};
+#[cfg(feature = "pool")]
+pub use pool::PrimePool;
+pub use preset::Preset;
+pub use private_update::PrivateEpochUpdate;
+#[cfg(feature = "redis")]
+pub use redis::RedisBackend;
+pub use receipt::{IssuanceProof, IssuanceReceipt, IssuedBatch};
+pub use registry::{ElementRegistry, RegistryEntry};
+pub use report::{ReportedOperation, StateReport, REPORTED_OPERATIONS};
+#[cfg(feature = "service")]
+pub use relay::{UpdateRelay, IMMUTABLE_CACHE_CONTROL};
+pub use replace::{ReplaceProof, Replacement};
+pub use replay::{AuditReplayer, Divergence, LoggedOperation, ReplayVerdict};
+pub use replica::{ReadReplica, ReadySignal};
+#[cfg(feature = "service")]
+pub use response::{SignedState, SignedWitness};
+pub use rotation::{KeyStatus, KeyUsage, RotationPolicy, RotationReason};
+#[cfg(feature = "service")]
+pub use schedule::{Clock, EpochScheduler, ManualClock, SystemClock};
+pub use security::SecurityEstimate;
+#[cfg(feature = "service")]
+pub use service::{Quota, Service, Session};
+pub use sharded::{ShardedAccumulator, ShardedWitness};
+pub use sharing::{KeyShare, SecretKey, ShareCommitments};
+#[cfg(feature = "service")]
+pub use simulate::{Scenario, SimulationReport};
+#[cfg(feature = "smt")]
+pub use smt::{SmtProof, SparseMerkleTree};
+#[cfg(feature = "service")]
+pub use sse::{SseFeed, SSE_CONTENT_TYPE};
+#[cfg(feature = "store")]
+pub use store::{AccessToken, StoreKey, WitnessStore};
+#[cfg(feature = "service")]
+pub use stream::{NextUpdate, UpdateStream};
+#[cfg(feature = "threshold")]
+pub use threshold::{Custodian, RootRequest, ThresholdAccumulator, MAX_CUSTODIANS};
+pub use update::{CompactionPolicy, EpochUpdate, KeepLastEpochs, UpdateLog, UpdateMessage};
+pub use vectors::{HashToPrimeVector, HashToPrimeVectors};
+pub use verification::{CertifiedWitness, VerificationPolicy};
+#[cfg(feature = "websocket")]
+pub use websocket::{UpdateFeed, UpdateServer};
+pub use witness::{EncryptedWitness, NonMembershipWitness, Witness, WitnessEncryptor};
+#[cfg(feature = "zk")]
+pub use zk::{
+    commit_element, verify_membership_of_commitment, CompositeProof, MembershipProof, NonMembershipProof,
+    RerandomizationProof, BLINDING_BITS,
+};
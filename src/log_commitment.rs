@@ -0,0 +1,208 @@
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+
+use crate::accumulator::BraavosAccumulator;
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::params::PublicParams;
+use crate::signature;
+use crate::witness::Witness;
+
+const ENTRY_TAG: &[u8] = b"braavos-log-entry";
+const RECORD_TAG: &[u8] = b"braavos-log-record";
+const HEAD_TAG: &[u8] = b"braavos-log-head";
+const INCLUSION_TAG: &[u8] = b"braavos-log-inclusion";
+
+// Append-only log of records over an accumulator, for timestamping and
+// transparency logs. Each record is accumulated as its digest bound to its
+// position and timestamp, so a proof shows the record is entry `index`, not
+// just somewhere in a set. Nothing is ever deleted, so the value never
+// changes and proofs stay valid; a signed LogHead states the size.
+//
+// The operator holds the trapdoor and could issue a proof for a different
+// record at a used index. Heads are signed, so two that disagree are
+// evidence against it, but clients trust it not to issue conflicting
+// entries, which a Merkle log's consistency proofs would rule out.
+pub struct LogCommitment {
+    acc: BraavosAccumulator,
+    entries: Vec<([u8; 32], u64)>, // Record digest and timestamp, by index
+}
+
+// Signed statement of the log's size and value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogHead {
+    pub params_digest: [u8; 32],
+    pub size: u64,
+    pub last_timestamp: u64, // Timestamp of the last entry, 0 for an empty log
+    pub value: AccValue,
+    pub signature: U512,
+}
+
+// Shows a record is entry `index` of a log, appended at `timestamp`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogInclusionProof {
+    pub index: u64,
+    pub timestamp: u64,
+    pub witness: Witness,
+}
+
+impl LogCommitment {
+    // Takes an empty accumulator, which the log owns from then on.
+    pub fn new(acc: BraavosAccumulator) -> Result<Self, &'static str> {
+        if acc.member_count() > 0 || acc.epoch() > 0 {
+            return Err("Log needs an empty accumulator");
+        }
+        Ok(Self {
+            acc,
+            entries: Vec::new(),
+        })
+    }
+
+    pub fn params(&self) -> PublicParams {
+        self.acc.params()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.entries.len() as u64
+    }
+
+    // Appends the record as the next entry. Timestamps (in whatever unit the
+    // application uses) must not go backwards.
+    pub fn append(&mut self, record: &[u8], timestamp: u64) -> Result<LogInclusionProof, &'static str> {
+        if self.entries.last().is_some_and(|(_, last)| timestamp < *last) {
+            return Err("Timestamp is earlier than the last entry");
+        }
+        let params = self.acc.params();
+        let index = self.size();
+        let digest = record_digest(&params, record);
+        let witness = self.acc.add(entry_element(index, timestamp, &digest))?;
+        self.entries.push((digest, timestamp));
+        Ok(LogInclusionProof {
+            index,
+            timestamp,
+            witness,
+        })
+    }
+
+    // A fresh proof for an earlier entry.
+    pub fn prove(&self, index: u64) -> Result<LogInclusionProof, &'static str> {
+        let (digest, timestamp) = *self.entries.get(index as usize).ok_or("No entry at this index")?;
+        let witness = self.acc.recover_witness(entry_element(index, timestamp, &digest))?;
+        Ok(LogInclusionProof {
+            index,
+            timestamp,
+            witness,
+        })
+    }
+
+    pub fn head(&self) -> Result<LogHead, &'static str> {
+        let mut head = LogHead {
+            params_digest: self.acc.params().digest(),
+            size: self.size(),
+            last_timestamp: self.entries.last().map_or(0, |(_, timestamp)| *timestamp),
+            value: self.acc.value(),
+            signature: U512::ZERO,
+        };
+        head.signature = self.acc.sign(&head.signed_bytes())?;
+        Ok(head)
+    }
+}
+
+impl LogHead {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(HEAD_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u64(self.size);
+        w.put_u64(self.last_timestamp);
+        w.put_u512(self.value.as_uint());
+        w.finish()
+    }
+
+    pub fn verify(&self, params: &PublicParams) -> bool {
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        self.params_digest == params.digest()
+            && n.is_some_and(|n| signature::verify(params.hash, &n, &self.signed_bytes(), &self.signature))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, HEAD_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let size = r.get_u64()?;
+        let last_timestamp = r.get_u64()?;
+        let value = AccValue::new(r.get_element(policy.modulus.as_ref())?);
+        let signature = r.get_residue(policy.modulus.as_ref())?;
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            size,
+            last_timestamp,
+            value,
+            signature,
+        })
+    }
+}
+
+impl LogInclusionProof {
+    // Whether `record` is entry `index` of the log `head` describes. The
+    // head must be signed for these parameters and cover the index, and the
+    // timestamp must not be later than its last one.
+    pub fn verify(&self, params: &PublicParams, head: &LogHead, record: &[u8]) -> bool {
+        if !head.verify(params) || self.index >= head.size || self.timestamp > head.last_timestamp {
+            return false;
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let Some(n) = n else {
+            return false;
+        };
+        if self.witness.params_digest != params.digest() {
+            return false;
+        }
+        let element = entry_element(self.index, self.timestamp, &record_digest(params, record));
+        core::opens(&self.witness.value, &params.hash_to_prime(element), &head.value, &MontyParams::new(n))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(INCLUSION_TAG);
+        w.put_u64(self.index);
+        w.put_u64(self.timestamp);
+        w.put_bytes(&self.witness.to_bytes());
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, INCLUSION_TAG, policy)?;
+        let index = r.get_u64()?;
+        let timestamp = r.get_u64()?;
+        let witness = Witness::from_bytes_with(r.get_bytes()?, policy)?;
+        r.finish()?;
+        Ok(Self {
+            index,
+            timestamp,
+            witness,
+        })
+    }
+}
+
+fn record_digest(params: &PublicParams, record: &[u8]) -> [u8; 32] {
+    params.hash.hash(RECORD_TAG, &[record])
+}
+
+// The accumulated element: fixed width, so no two positions share one.
+fn entry_element(index: u64, timestamp: u64, digest: &[u8; 32]) -> Vec<u8> {
+    [ENTRY_TAG, &index.to_be_bytes(), &timestamp.to_be_bytes(), digest].concat()
+}
@@ -0,0 +1,34 @@
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+// HMAC-SHA256 as a PRF and as a counter-mode keystream, for the encrypt-then-
+// MAC constructions of backups and the witness store.
+
+// XORs data with HMAC-SHA256(key, counter) blocks. The key must be fresh for
+// every message.
+pub(crate) fn apply_keystream(key: &[u8; 32], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(32).enumerate() {
+        let block = prf(key, &[&(i as u64).to_be_bytes()]);
+        chunk.iter_mut().zip(&block).for_each(|(b, k)| *b ^= k);
+    }
+}
+
+pub(crate) fn prf(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = hmac(key);
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+// HMAC keyed from a block-sized key, padded or hashed down as RFC 2104
+// prescribes, which is what new_from_slice does without its Result.
+pub(crate) fn hmac(key: &[u8]) -> Hmac<Sha256> {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    Hmac::<Sha256>::new(&block.into())
+}
@@ -0,0 +1,895 @@
+use crypto_bigint::U512;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::accumulator::BraavosAccumulator;
+use crate::anchor::{AnchorReceipt, CheckpointAnchor};
+use crate::audit::{batch_digest, AuditLog, Decision, Operation};
+use crate::binding::{Binding, LinkRecord, Rebinding};
+use crate::blind::{BlindIssuance, BlindedPrime};
+use crate::chain::Checkpoint;
+use crate::custody::CustodyRecord;
+use crate::deadline::Deadline;
+use crate::expiry::ExpiryPolicy;
+use crate::failure::VerifyFailure;
+use crate::group::{AccValue, ElemPrime};
+use crate::history::{self, MembershipChange, MembershipEvent};
+use crate::idempotency::{IdempotencyWindow, Outcome, DEFAULT_IDEMPOTENCY_WINDOW};
+use crate::receipt::IssuedBatch;
+use crate::relay::UpdateRelay;
+use crate::replace::Replacement;
+use crate::report::{ReportedOperation, StateReport, REPORTED_OPERATIONS};
+use crate::response::{SignedState, SignedWitness};
+use crate::stream::{Publisher, UpdateStream};
+use crate::update::{CompactionPolicy, EpochUpdate, UpdateLog};
+use crate::witness::{EncryptedWitness, Witness, WitnessEncryptor};
+#[cfg(feature = "zk")]
+use crate::zk::RerandomizationProof;
+
+// Decides whether an operator may perform an operation on an element, e.g. by
+// checking an ACL or an issuer signature carried in the operator identity.
+pub trait Policy {
+    fn authorize(&self, operator: &str, operation: Operation, x: &[u8]) -> Decision;
+}
+
+impl<F> Policy for F
+where
+    F: Fn(&str, Operation, &[u8]) -> Decision,
+{
+    fn authorize(&self, operator: &str, operation: Operation, x: &[u8]) -> Decision {
+        self(operator, operation, x)
+    }
+}
+
+// Permits every operation; the default for single-operator deployments.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllowAll;
+
+impl Policy for AllowAll {
+    fn authorize(&self, _operator: &str, _operation: Operation, _x: &[u8]) -> Decision {
+        Decision::Allow
+    }
+}
+
+// How Manager::verify_with_pending accepted a witness, if it did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingVerification {
+    Committed,                  // Opens the published value
+    Provisional { epoch: u64 }, // Opens the value `epoch` will have once the queued deletions commit
+    Rejected,
+}
+
+// A witness issued by Manager::stage_add for an element that becomes a
+// member at the next commit_deletions, opening the value of `epoch`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingWitness {
+    pub element: Vec<u8>,
+    pub epoch: u64,
+    pub witness: Witness,
+}
+
+// Names a staged addition, to re-issue its witness or check it activated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActivationTicket {
+    pub id: u64,
+    pub element: Vec<u8>,
+}
+
+// At most `max` operations per operator within any `window`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max: usize,
+    pub window: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            max: 10,
+            window: Duration::from_secs(3600),
+        }
+    }
+}
+
+// Owns the accumulator on behalf of its operators: every add/delete goes
+// through the policy first and the decision is written to the audit log.
+pub struct Manager<P: Policy = AllowAll> {
+    acc: BraavosAccumulator,
+    policy: P,
+    audit: AuditLog,
+    log: UpdateLog,
+    recovery_limit: RateLimit,
+    recoveries: HashMap<String, VecDeque<Instant>>, // Recent recoveries per operator
+    metadata: BTreeMap<Vec<u8>, Vec<u8>>,           // Opaque per-member records
+    checkpoints: Vec<Checkpoint>,                   // Proof-carrying chain from the parameters
+    anchor: Option<Arc<dyn CheckpointAnchor>>,      // Where checkpoint digests are committed externally
+    receipts: Vec<AnchorReceipt>,                   // One per checkpoint while an anchor is set
+    bindings: BTreeMap<Vec<u8>, Binding>,           // Current holder key of each bound member
+    links: Vec<LinkRecord>,                         // Every rebinding, oldest first
+    pending: Vec<Vec<u8>>,                          // Authorized deletions awaiting commit_deletions
+    idempotency: IdempotencyWindow,                 // Answers to recent keyed requests
+    streams: Publisher,                             // Async subscribers to published updates
+    staged: Option<(AccValue, usize, EpochUpdate)>, // staged_update, for the value and queue length it was made at
+    staged_adds: Vec<(u64, Vec<u8>)>,               // Additions awaiting commit_deletions, by ticket id
+    next_ticket: u64,
+}
+
+impl<P: Policy> Manager<P> {
+    pub fn new(acc: BraavosAccumulator, policy: P) -> Self {
+        let log = UpdateLog::starting_at(acc.epoch());
+        Self {
+            acc,
+            policy,
+            audit: AuditLog::new(),
+            log,
+            recovery_limit: RateLimit::default(),
+            recoveries: HashMap::new(),
+            metadata: BTreeMap::new(),
+            checkpoints: Vec::new(),
+            anchor: None,
+            receipts: Vec::new(),
+            bindings: BTreeMap::new(),
+            links: Vec::new(),
+            pending: Vec::new(),
+            idempotency: IdempotencyWindow::new(DEFAULT_IDEMPOTENCY_WINDOW),
+            streams: Publisher::default(),
+            staged: None,
+            staged_adds: Vec::new(),
+            next_ticket: 0,
+        }
+    }
+
+    pub fn with_recovery_limit(mut self, limit: RateLimit) -> Self {
+        self.recovery_limit = limit;
+        self
+    }
+
+    // Anchors every checkpoint published from now on (see CheckpointAnchor).
+    pub fn with_anchor(mut self, anchor: Arc<dyn CheckpointAnchor>) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    // How many idempotency keys are remembered; 0 turns deduplication off.
+    pub fn with_idempotency_window(mut self, capacity: usize) -> Self {
+        self.idempotency = IdempotencyWindow::new(capacity);
+        self
+    }
+
+    pub fn add(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        self.add_within(operator, x, &Deadline::none())
+    }
+
+    pub fn add_within(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+        deadline: &Deadline,
+    ) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.authorize(operator, Operation::Add, x)?;
+        self.acc.add_within(x, deadline)
+    }
+
+    // Adds a member under a prime derived elsewhere (see
+    // BraavosAccumulator::add_prime); the policy sees the label.
+    pub fn add_prime(&mut self, operator: &str, prime: ElemPrime, label: &[u8]) -> Result<Witness, &'static str> {
+        self.authorize(operator, Operation::Add, label)?;
+        self.acc.add_prime(prime, label)
+    }
+
+    // As add, but a request retried with the same client-supplied key gets
+    // the original witness back instead of being applied again. Replays skip
+    // the policy and are not audited twice; reusing a key for a different
+    // request is an error.
+    pub fn add_idempotent(&mut self, operator: &str, key: &[u8], x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        self.add_idempotent_within(operator, key, x, &Deadline::none())
+    }
+
+    pub fn add_idempotent_within(
+        &mut self,
+        operator: &str,
+        key: &[u8],
+        x: impl AsRef<[u8]>,
+        deadline: &Deadline,
+    ) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        if let Some(Outcome::Added(witness)) = self.idempotency.replay(key, operator, x, false)? {
+            return Ok(*witness);
+        }
+        let witness = self.add_within(operator, x, deadline)?;
+        self.idempotency.record(key, operator, x, Outcome::Added(witness));
+        Ok(witness)
+    }
+
+    // Onboards many members at once (see BraavosAccumulator::issue_witnesses).
+    // The policy still rules on each element as an Add, but the batch is
+    // audited as one IssueWitnesses record holding its batch_digest, with the
+    // element count as metadata.
+    pub fn issue_witnesses<T: AsRef<[u8]>>(
+        &mut self,
+        operator: &str,
+        xs: &[T],
+    ) -> Result<Vec<(ElemPrime, Witness)>, &'static str> {
+        self.authorize_batch(operator, xs)?;
+        self.acc.issue_witnesses(xs)
+    }
+
+    // issue_witnesses, also returning a signed commitment to the batch that
+    // holders can prove their issuance against (see IssuanceReceipt).
+    pub fn issue_witnesses_with_receipt<T: AsRef<[u8]>>(
+        &mut self,
+        operator: &str,
+        xs: &[T],
+    ) -> Result<IssuedBatch, &'static str> {
+        self.authorize_batch(operator, xs)?;
+        self.acc.issue_witnesses_with_receipt(xs)
+    }
+
+    fn authorize_batch<T: AsRef<[u8]>>(&mut self, operator: &str, xs: &[T]) -> Result<(), &'static str> {
+        let decision = xs
+            .iter()
+            .map(|x| self.policy.authorize(operator, Operation::Add, x.as_ref()))
+            .find(|d| *d != Decision::Allow)
+            .unwrap_or(Decision::Allow);
+        let allowed = decision == Decision::Allow;
+        let count = (xs.len() as u64).to_be_bytes();
+        self.audit.append(self.acc.epoch(), operator, Operation::IssueWitnesses, &batch_digest(xs), &count, decision);
+        match allowed {
+            true => Ok(()),
+            false => Err("Operation denied by policy"),
+        }
+    }
+
+    // Adds x with an opaque record (issuer id, issuance time, ...) kept for as
+    // long as x is a member and written to the audit log. Re-adding a member
+    // replaces its record.
+    pub fn add_with_metadata(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+        metadata: &[u8],
+    ) -> Result<Witness, &'static str> {
+        self.add_with_metadata_within(operator, x, metadata, &Deadline::none())
+    }
+
+    pub fn add_with_metadata_within(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+        metadata: &[u8],
+        deadline: &Deadline,
+    ) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.authorize_with_metadata(operator, Operation::Add, x, metadata)?;
+        let witness = self.acc.add_within(x, deadline)?;
+        self.metadata.insert(x.to_vec(), metadata.to_vec());
+        Ok(witness)
+    }
+
+    pub fn get_metadata(&self, x: impl AsRef<[u8]>) -> Option<&[u8]> {
+        self.metadata.get(x.as_ref()).map(Vec::as_slice)
+    }
+
+    pub(crate) fn metadata(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.metadata
+    }
+
+    // Restores records persisted by an earlier run; entries for elements that
+    // are no longer members are dropped.
+    pub(crate) fn restore_metadata(&mut self, metadata: BTreeMap<Vec<u8>, Vec<u8>>) {
+        self.metadata = metadata.into_iter().filter(|(x, _)| self.acc.contains(x)).collect();
+    }
+
+    pub(crate) fn bindings(&self) -> &BTreeMap<Vec<u8>, Binding> {
+        &self.bindings
+    }
+
+    // Restores the audit trail and holder bindings from a backup.
+    pub(crate) fn restore_records(
+        &mut self,
+        audit: AuditLog,
+        bindings: BTreeMap<Vec<u8>, Binding>,
+        links: Vec<LinkRecord>,
+    ) {
+        self.audit = audit;
+        self.bindings = bindings;
+        self.links = links;
+    }
+
+    pub fn delete(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<EpochUpdate, &'static str> {
+        self.delete_within(operator, x, &Deadline::none())
+    }
+
+    // A timed-out deletion is still audited as authorized, but changes nothing.
+    pub fn delete_within(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+        deadline: &Deadline,
+    ) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        self.authorize(operator, Operation::Delete, x)?;
+        let update = self.acc.delete_within(x, deadline)?;
+        self.forget(x);
+        self.publish(update)
+    }
+
+    // The policy rules on x_old as a Delete and x_new as an Add, both
+    // audited; the single update is published like a deletion's.
+    pub fn replace(
+        &mut self,
+        operator: &str,
+        x_old: impl AsRef<[u8]>,
+        x_new: impl AsRef<[u8]>,
+    ) -> Result<Replacement, &'static str> {
+        let (x_old, x_new) = (x_old.as_ref(), x_new.as_ref());
+        self.authorize(operator, Operation::Delete, x_old)?;
+        self.authorize(operator, Operation::Add, x_new)?;
+        let replacement = self.acc.replace(x_old, x_new)?;
+        self.forget(x_old);
+        self.publish(replacement.update.clone())?;
+        Ok(replacement)
+    }
+
+    // As delete, with the original update returned for a retried key, so a
+    // retry never deletes an element that was re-added in between.
+    pub fn delete_idempotent(
+        &mut self,
+        operator: &str,
+        key: &[u8],
+        x: impl AsRef<[u8]>,
+    ) -> Result<EpochUpdate, &'static str> {
+        self.delete_idempotent_within(operator, key, x, &Deadline::none())
+    }
+
+    pub fn delete_idempotent_within(
+        &mut self,
+        operator: &str,
+        key: &[u8],
+        x: impl AsRef<[u8]>,
+        deadline: &Deadline,
+    ) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        if let Some(Outcome::Deleted(update)) = self.idempotency.replay(key, operator, x, true)? {
+            return Ok(update.clone());
+        }
+        let update = self.delete_within(operator, x, deadline)?;
+        self.idempotency.record(key, operator, x, Outcome::Deleted(update.clone()));
+        Ok(update)
+    }
+
+    // Number of idempotency keys currently remembered.
+    pub fn idempotency_keys(&self) -> usize {
+        self.idempotency.len()
+    }
+
+    pub(crate) fn idempotency(&self) -> &IdempotencyWindow {
+        &self.idempotency
+    }
+
+    // Restores the keys persisted by an earlier run into the configured window.
+    pub(crate) fn restore_idempotency(&mut self, bytes: &[u8]) -> Result<(), &'static str> {
+        self.idempotency = IdempotencyWindow::from_bytes(bytes, self.idempotency.capacity())?;
+        Ok(())
+    }
+
+    // Deletes every element as one epoch. All are authorized first, so a
+    // denial deletes nothing.
+    pub fn delete_batch<T: AsRef<[u8]>>(&mut self, operator: &str, xs: &[T]) -> Result<EpochUpdate, &'static str> {
+        for x in xs {
+            self.authorize(operator, Operation::Delete, x.as_ref())?;
+        }
+        let update = self.acc.delete_batch(xs)?;
+        for x in xs {
+            self.forget(x.as_ref());
+        }
+        self.publish(update)
+    }
+
+    // Authorizes a deletion now and holds it for the next commit_deletions,
+    // which publishes everything queued as one epoch (see EpochScheduler).
+    pub fn queue_delete(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<(), &'static str> {
+        let x = x.as_ref();
+        if self.pending.iter().any(|p| p == x) {
+            return Err("Deletion is already queued");
+        }
+        // A non-member would fail the whole batch at commit
+        if !self.acc.contains(x) {
+            return Err("Element is not a member");
+        }
+        self.authorize(operator, Operation::Delete, x)?;
+        self.pending.push(x.to_vec());
+        Ok(())
+    }
+
+    pub fn pending_deletions(&self) -> &[Vec<u8>] {
+        &self.pending
+    }
+
+    // Re-queues deletions authorized before a restart.
+    pub(crate) fn restore_pending(&mut self, pending: Vec<Vec<u8>>) {
+        self.pending = pending;
+        self.staged = None;
+    }
+
+    // The update commit_deletions would publish now, for verifiers and
+    // issuers co-located with the manager; None when nothing is queued.
+    // Computed once per queue and value, at one root per queued element.
+    pub fn staged_update(&mut self) -> Result<Option<&EpochUpdate>, &'static str> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+        let (value, queued) = (self.acc.value(), self.pending.len());
+        if !self.staged.as_ref().is_some_and(|(v, q, _)| *v == value && *q == queued) {
+            let update = self.acc.preview_deletion(&self.pending)?;
+            self.staged = Some((value, queued, update));
+        }
+        Ok(self.staged.as_ref().map(|(_, _, update)| update))
+    }
+
+    // verify, also accepting witnesses for the staged value, flagged as
+    // provisional: one issued or updated for the next epoch works before it
+    // is committed. Elements queued for deletion are never provisional
+    // members, since the current value is a witness for them in the next.
+    pub fn verify_with_pending(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+    ) -> Result<PendingVerification, &'static str> {
+        let x = x.as_ref();
+        let staged = self.staged_adds.iter().any(|(_, staged)| staged == x);
+        if !staged && self.acc.verify(x, w) {
+            return Ok(PendingVerification::Committed);
+        }
+        if self.pending.iter().any(|p| p == x) {
+            return Ok(PendingVerification::Rejected);
+        }
+        let (epoch, value) = match self.staged_update()? {
+            Some(update) => (update.to_epoch, update.value),
+            None if staged => (self.acc.epoch(), self.acc.value()),
+            None => return Ok(PendingVerification::Rejected),
+        };
+        Ok(match self.acc.opens(x, &w, &value) {
+            true => PendingVerification::Provisional { epoch },
+            false => PendingVerification::Rejected,
+        })
+    }
+
+    // Deletes what is queued as one epoch, then adds the staged additions;
+    // None when no deletions are queued. On failure the queue is kept.
+    pub fn commit_deletions(&mut self) -> Result<Option<EpochUpdate>, &'static str> {
+        let mut update = None;
+        if !self.pending.is_empty() {
+            let deletion = self.acc.delete_batch(&self.pending)?;
+            for x in std::mem::take(&mut self.pending) {
+                self.forget(&x);
+            }
+            update = Some(self.publish(deletion)?);
+        }
+        // Staged elements added directly in the meantime are members already.
+        // Each leaves the staging area once added, so a failure keeps the rest.
+        let mut added = 0;
+        let result = self.staged_adds.iter().try_for_each(|(_, x)| {
+            if !self.acc.contains(x) {
+                self.acc.add(x)?;
+            }
+            added += 1;
+            Ok(())
+        });
+        self.staged_adds.drain(..added);
+        result.map(|()| update)
+    }
+
+    // Authorizes adding x now and returns its witness for the value after
+    // the next commit_deletions, which makes x a member. Issuance pipelines
+    // can hand it out with a credential ahead of the epoch boundary; before
+    // it, verify_with_pending accepts it only provisionally. With no
+    // deletions queued the value does not change at the commit, so the
+    // witness opens the current one already. Deletions queued later change
+    // the value again: reissue_pending re-issues. Staged additions are kept
+    // in memory only.
+    pub fn stage_add(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+    ) -> Result<(PendingWitness, ActivationTicket), &'static str> {
+        let x = x.as_ref();
+        if self.staged_adds.iter().any(|(_, staged)| staged == x) {
+            return Err("Element is already staged");
+        }
+        if self.pending.iter().any(|p| p == x) {
+            return Err("Element is queued for deletion");
+        }
+        self.authorize(operator, Operation::Add, x)?;
+        let ticket = ActivationTicket {
+            id: self.next_ticket,
+            element: x.to_vec(),
+        };
+        let witness = self.pending_witness(&ticket.element)?;
+        self.next_ticket += 1;
+        self.staged_adds.push((ticket.id, ticket.element.clone()));
+        Ok((witness, ticket))
+    }
+
+    // The staged element's witness for the value after the next commit, as
+    // it stands now.
+    pub fn reissue_pending(&mut self, ticket: &ActivationTicket) -> Result<PendingWitness, &'static str> {
+        if !self.staged_adds.iter().any(|(id, _)| *id == ticket.id) {
+            return Err("Ticket is not staged");
+        }
+        self.pending_witness(&ticket.element)
+    }
+
+    // Drops a staged addition before it activates.
+    pub fn unstage(&mut self, ticket: &ActivationTicket) -> Result<(), &'static str> {
+        let at = self.staged_adds.iter().position(|(id, _)| *id == ticket.id).ok_or("Ticket is not staged")?;
+        self.staged_adds.remove(at);
+        Ok(())
+    }
+
+    // Whether the ticket's element has become a member through its commit.
+    pub fn is_active(&self, ticket: &ActivationTicket) -> bool {
+        ticket.id < self.next_ticket
+            && !self.staged_adds.iter().any(|(id, _)| *id == ticket.id)
+            && self.acc.contains(&ticket.element)
+    }
+
+    fn pending_witness(&mut self, x: &[u8]) -> Result<PendingWitness, &'static str> {
+        let (epoch, value) = match self.staged_update()? {
+            Some(update) => (update.to_epoch, update.value),
+            None => (self.acc.epoch(), self.acc.value()),
+        };
+        Ok(PendingWitness {
+            element: x.to_vec(),
+            epoch,
+            witness: self.acc.witness_against(x, &value)?,
+        })
+    }
+
+    // Holder-initiated removal; still subject to the policy and audited.
+    pub fn delete_with_witness(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+    ) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        self.authorize(operator, Operation::Delete, x)?;
+        let update = self.acc.delete_with_witness(x, w)?;
+        self.forget(x);
+        self.publish(update)
+    }
+
+    // Support path for holders who lost their witness. Uses the trapdoor, so it
+    // is policy-checked and rate-limited per operator.
+    pub fn recover_witness(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        let now = Instant::now();
+        let recent = self.recoveries.entry(operator.to_string()).or_default();
+        while recent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.recovery_limit.window)
+        {
+            recent.pop_front();
+        }
+        if recent.len() >= self.recovery_limit.max {
+            let decision = Decision::Deny("Recovery rate limit exceeded".to_string());
+            self.audit.append(self.acc.epoch(), operator, Operation::RecoverWitness, x, &[], decision);
+            return Err("Recovery rate limit exceeded");
+        }
+        self.authorize(operator, Operation::RecoverWitness, x)?;
+        self.recoveries.entry(operator.to_string()).or_default().push_back(now);
+        self.acc.recover_witness(x)
+    }
+
+    // Blind issuance (see BlindedPrime). The policy and the audit log see the
+    // encoded blinded prime, so a policy can count requests per operator but
+    // not rule on the element.
+    pub fn issue_blind(&mut self, operator: &str, blinded: &BlindedPrime) -> Result<BlindIssuance, &'static str> {
+        self.authorize(operator, Operation::IssueBlind, &blinded.to_bytes())?;
+        self.acc.issue_blind(blinded)
+    }
+
+    // Binds a member to the key of the holder it was issued to. Each member
+    // is bound once; later moves go through rebind_witness.
+    pub fn bind(&mut self, operator: &str, x: impl AsRef<[u8]>, holder_key: &[u8]) -> Result<Binding, &'static str> {
+        let x = x.as_ref();
+        self.authorize(operator, Operation::Bind, x)?;
+        if !self.acc.contains(x) {
+            return Err("Element is not a member");
+        }
+        if self.bindings.contains_key(x) {
+            return Err("Element is already bound");
+        }
+        let binding = self.sign_binding(x, holder_key, 0)?;
+        self.bindings.insert(x.to_vec(), binding.clone());
+        Ok(binding)
+    }
+
+    // Moves a membership to a new holder key (device migration) without
+    // deleting the element: the old binding must be the current one, and the
+    // new device gets a fresh witness, a binding and the link record, which
+    // is also kept in links() for verifiers.
+    pub fn rebind_witness(
+        &mut self,
+        operator: &str,
+        old: &Binding,
+        new_key: &[u8],
+    ) -> Result<Rebinding, &'static str> {
+        let x = old.element.as_slice();
+        self.authorize(operator, Operation::Rebind, x)?;
+        if self.bindings.get(x) != Some(old) {
+            return Err("Binding is not current");
+        }
+        let sequence = old.sequence.checked_add(1).ok_or("Too many rebindings")?;
+        let mut link = LinkRecord {
+            element: x.to_vec(),
+            old_key: old.holder_key.clone(),
+            new_key: new_key.to_vec(),
+            sequence,
+            epoch: self.acc.epoch(),
+            signature: U512::ZERO,
+        };
+        link.signature = self.acc.sign(&link.signed_bytes())?;
+        let binding = self.sign_binding(x, new_key, sequence)?;
+        let witness = self.acc.recover_witness(x)?;
+        self.bindings.insert(x.to_vec(), binding.clone());
+        self.links.push(link.clone());
+        Ok(Rebinding { witness, binding, link })
+    }
+
+    pub fn binding(&self, x: impl AsRef<[u8]>) -> Option<&Binding> {
+        self.bindings.get(x.as_ref())
+    }
+
+    pub fn links(&self) -> &[LinkRecord] {
+        &self.links
+    }
+
+    fn sign_binding(&self, x: &[u8], holder_key: &[u8], sequence: u32) -> Result<Binding, &'static str> {
+        let mut binding = Binding {
+            element: x.to_vec(),
+            holder_key: holder_key.to_vec(),
+            sequence,
+            signature: U512::ZERO,
+        };
+        binding.signature = self.acc.sign(&binding.signed_bytes())?;
+        Ok(binding)
+    }
+
+    pub fn export_witnesses_encrypted<E: WitnessEncryptor>(
+        &mut self,
+        operator: &str,
+        encryptor: &E,
+    ) -> Result<Vec<EncryptedWitness>, &'static str> {
+        self.authorize(operator, Operation::ExportWitnesses, &[])?;
+        self.acc.export_witnesses_encrypted(encryptor)
+    }
+
+    // The update log restarts at the current epoch under the new parameters, so
+    // holders behind it have to fetch a fresh witness.
+    #[cfg(feature = "zk")]
+    pub fn rerandomize(&mut self, operator: &str) -> Result<RerandomizationProof, &'static str> {
+        self.authorize(operator, Operation::Rerandomize, &[])?;
+        let proof = self.acc.rerandomize()?;
+        self.log = UpdateLog::starting_at(self.acc.epoch());
+        self.checkpoints.clear();
+        self.receipts.clear();
+        Ok(proof)
+    }
+
+    // Extends the checkpoint chain to the current epoch, proving the
+    // deletions since the last checkpoint. The chain starts at the
+    // parameters, so it needs the update log from epoch 0 and is dropped on
+    // rerandomization.
+    pub fn publish_checkpoint(&mut self) -> Result<&Checkpoint, &'static str> {
+        let params = self.acc.params();
+        let previous = self.checkpoints.last();
+        let from_epoch = previous.map_or(0, |c| c.to_epoch);
+        let update = self.log.since(from_epoch)?.ok_or("No deletions since the last checkpoint")?;
+        let checkpoint = Checkpoint::prove(&params, previous, &update)?;
+        if let Some(anchor) = &self.anchor {
+            let digest = checkpoint.digest(&params);
+            let evidence = anchor.anchor(&checkpoint, &digest)?;
+            self.receipts.push(AnchorReceipt {
+                index: self.checkpoints.len() as u64,
+                to_epoch: checkpoint.to_epoch,
+                digest,
+                evidence,
+            });
+        }
+        self.checkpoints.push(checkpoint);
+        self.checkpoints.last().ok_or("Checkpoint chain is empty")
+    }
+
+    pub fn checkpoint_chain(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    // The receipt for the checkpoint at `index` in the chain, if it was
+    // published with an anchor set.
+    pub fn anchor_receipt(&self, index: usize) -> Option<&AnchorReceipt> {
+        self.receipts.iter().find(|r| r.index == index as u64)
+    }
+
+    // When x was added, deleted and re-added, oldest first, from the audit
+    // log; deletions name the checkpoint proving them (see MembershipEvent).
+    pub fn history(&self, x: impl AsRef<[u8]>) -> Vec<MembershipEvent> {
+        let x = x.as_ref();
+        let prime = self.acc.params().hash_to_prime(x);
+        history::history(&self.audit, &self.log, &self.checkpoints, x, &prime)
+    }
+
+    // Compacts the update log as the policy says, but never past the last
+    // checkpoint, as the next one is proven from that epoch on. Publishing a
+    // checkpoint first lets the whole log be compacted.
+    pub fn compact_update_log<C: CompactionPolicy>(&mut self, policy: &C) -> Result<usize, &'static str> {
+        let Some(before_epoch) = policy.compact_before(&self.log) else {
+            return Ok(0);
+        };
+        match self.checkpoints.last() {
+            Some(last) => self.log.compact(before_epoch.min(last.to_epoch)),
+            None => self.log.compact(before_epoch),
+        }
+    }
+
+    // Signs a witness for x at the current epoch as a service response (see
+    // SignedWitness). The witness itself is not checked.
+    pub fn sign_witness(&self, x: impl AsRef<[u8]>, witness: Witness) -> Result<SignedWitness, &'static str> {
+        let mut response = SignedWitness {
+            element: x.as_ref().to_vec(),
+            epoch: self.acc.epoch(),
+            witness,
+            signature: U512::ZERO,
+        };
+        response.signature = self.acc.sign(&response.signed_bytes())?;
+        Ok(response)
+    }
+
+    // The chain of custody of x's current witness, for compliance (see
+    // CustodyRecord). Uses the trapdoor, so it is policy-checked and
+    // audited; fails once the update log is compacted past x's issuance.
+    pub fn custody_record(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<CustodyRecord, &'static str> {
+        let x = x.as_ref();
+        self.authorize(operator, Operation::ExportCustody, x)?;
+        let issued = self.history(x).pop().filter(|e| e.change != MembershipChange::Deleted);
+        let seq = issued.ok_or("Element is not a member")?.seq;
+        let issuance = self.audit.records().get(seq as usize).ok_or("Issuance record is missing")?.clone();
+        let witness = self.acc.recover_witness(x)?;
+        let mut record = CustodyRecord {
+            element: x.to_vec(),
+            derivation: self.acc.certify(x, witness)?.entry,
+            updates: self.log.pending(issuance.epoch)?.to_vec(),
+            issuance,
+            current: self.sign_witness(x, witness)?,
+            state: self.signed_state()?,
+            signature: U512::ZERO,
+        };
+        record.signature = self.acc.sign(&record.signed_bytes())?;
+        Ok(record)
+    }
+
+    pub fn signed_state(&self) -> Result<SignedState, &'static str> {
+        let mut response = SignedState {
+            params_digest: self.acc.params().digest(),
+            epoch: self.acc.epoch(),
+            value: self.acc.value(),
+            signature: U512::ZERO,
+        };
+        response.signature = self.acc.sign(&response.signed_bytes())?;
+        Ok(response)
+    }
+
+    pub fn accumulator(&self) -> &BraavosAccumulator {
+        &self.acc
+    }
+
+    pub fn verify(&mut self, x: impl AsRef<[u8]>, w: Witness) -> bool {
+        self.acc.verify(x, w)
+    }
+
+    pub fn verify_detailed(&mut self, x: impl AsRef<[u8]>, w: Witness) -> Result<(), VerifyFailure> {
+        self.acc.verify_detailed(x, w)
+    }
+
+    pub fn verify_with_expiry(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        policy: &ExpiryPolicy,
+    ) -> Result<(), VerifyFailure> {
+        self.acc.verify_with_expiry(x, w, policy)
+    }
+
+    pub fn verify_within(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        w: Witness,
+        deadline: &Deadline,
+    ) -> Result<bool, &'static str> {
+        self.acc.verify_within(x, w, deadline)
+    }
+
+    pub fn verify_at_epoch(&mut self, x: impl AsRef<[u8]>, w: Witness, epoch: u64) -> bool {
+        self.acc.verify_at_epoch(x, w, epoch)
+    }
+
+    // The accumulator's report, with the last operations from the audit log.
+    pub fn report(&self) -> StateReport {
+        let mut report = self.acc.report();
+        let records = self.audit.records();
+        report.recent_operations = records[records.len().saturating_sub(REPORTED_OPERATIONS)..]
+            .iter()
+            .map(|r| ReportedOperation {
+                seq: r.seq,
+                epoch: r.epoch,
+                operator: r.operator.clone(),
+                operation: format!("{:?}", r.operation),
+                allowed: r.decision == Decision::Allow,
+            })
+            .collect();
+        report
+    }
+
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    pub fn update_log(&self) -> &UpdateLog {
+        &self.log
+    }
+
+    // Every update published from now on, as an async stream (see
+    // UpdateStream). Earlier ones are in the update log.
+    pub fn subscribe(&mut self) -> UpdateStream {
+        self.streams.subscribe()
+    }
+
+    // A relay serving this manager's updates to holders (see UpdateRelay).
+    pub fn relay(&mut self) -> UpdateRelay {
+        let stream = self.subscribe();
+        UpdateRelay::new(self.log.clone(), stream)
+    }
+
+    // Drops the records of a deleted element, unless it is still a member
+    // through another multiset copy.
+    fn forget(&mut self, x: &[u8]) {
+        if !self.acc.contains(x) {
+            self.metadata.remove(x);
+            self.bindings.remove(x);
+        }
+    }
+
+    // A delete that only dropped a multiset copy changes nothing holders see,
+    // so it is not logged.
+    fn publish(&mut self, update: EpochUpdate) -> Result<EpochUpdate, &'static str> {
+        if !update.deleted.is_empty() {
+            self.log.push(update.clone())?;
+            self.streams.send(&update);
+        }
+        Ok(update)
+    }
+
+    fn authorize(&mut self, operator: &str, operation: Operation, x: &[u8]) -> Result<(), &'static str> {
+        self.authorize_with_metadata(operator, operation, x, &[])
+    }
+
+    fn authorize_with_metadata(
+        &mut self,
+        operator: &str,
+        operation: Operation,
+        x: &[u8],
+        metadata: &[u8],
+    ) -> Result<(), &'static str> {
+        let decision = self.policy.authorize(operator, operation, x);
+        let allowed = decision == Decision::Allow;
+        self.audit.append(self.acc.epoch(), operator, operation, x, metadata, decision);
+        if allowed {
+            Ok(())
+        } else {
+            Err("Operation denied by policy")
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    I512, NonZero, Odd, U256, U512,
+};
+
+use crate::accumulator::pad_u256_to_u512;
+use crate::core;
+use crate::group::{ElemPrime, WitnessValue};
+
+// Extended Euclid: (g, s, t) with g = gcd(a, b) and s * a + t * b = g. The
+// coefficients are bounded by max(a, b) in absolute value, hence 512-bit signed.
+pub fn ext_gcd(a: U256, b: U256) -> (U256, I512, I512) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (I512::ONE, I512::ZERO);
+    let (mut old_t, mut t) = (I512::ZERO, I512::ONE);
+    while let Some(divisor) = Option::<NonZero<U256>>::from(NonZero::new(r)) {
+        let (q, rem) = old_r.div_rem(&divisor);
+        let q = to_signed(&q);
+        (old_r, r) = (r, rem);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    (old_r, old_s, old_t)
+}
+
+// Given witnesses for coprime x1 and x2 against the same value a, a witness
+// for x1 * x2: with s * x1 + t * x2 = 1, (w1^t w2^s)^(x1 x2) = a^(t x2 + s x1) = a.
+// None when the primes share a factor or the witnesses open different values
+// (up to sign, see core).
+pub fn shamir_trick(
+    w1: &WitnessValue,
+    w2: &WitnessValue,
+    x1: &ElemPrime,
+    x2: &ElemPrime,
+    params: &MontyParams<8>,
+) -> Option<WitnessValue> {
+    let n = params.modulus().get();
+    if !core::same_class(w1.pow_elem(x1, params).as_uint(), w2.pow_elem(x2, params).as_uint(), &n) {
+        return None;
+    }
+    let (g, s, t) = ext_gcd(*x1.as_uint(), *x2.as_uint());
+    if g != U256::ONE {
+        return None;
+    }
+    let w1 = signed_pow(&MontyForm::new(w1.as_uint(), *params), &t)?;
+    let w2 = signed_pow(&MontyForm::new(w2.as_uint(), *params), &s)?;
+    Some(WitnessValue::new(w1.mul(&w2).retrieve()))
+}
+
+// Jacobi symbol (a/n) for odd n: 1, -1, or 0 when they share a factor.
+// Variable time; only used on public values.
+pub fn jacobi(a: &U512, n: &U512) -> i8 {
+    let Some(modulus) = Option::<NonZero<U512>>::from(NonZero::new(*n)) else {
+        return 0;
+    };
+    let (mut a, mut n) = (a.rem(&modulus), *n);
+    let mut t = 1;
+    while a != U512::ZERO {
+        let zeros = a.trailing_zeros_vartime();
+        a = a.shr_vartime(zeros);
+        // (2/n) = -1 exactly when n = 3 or 5 mod 8
+        let n_mod_8 = n.as_words()[0] & 7;
+        if zeros % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            t = -t;
+        }
+        // Quadratic reciprocity: flips when both are 3 mod 4
+        if a.as_words()[0] & 3 == 3 && n_mod_8 & 3 == 3 {
+            t = -t;
+        }
+        (a, n) = (n, a);
+        // n is odd after the swap, so nonzero
+        let n_nz: Option<NonZero<U512>> = NonZero::new(n).into();
+        let Some(n_nz) = n_nz else {
+            return 0;
+        };
+        a = a.rem(&n_nz);
+    }
+    if n == U512::ONE {
+        t
+    } else {
+        0
+    }
+}
+
+// v fits in the low half, so the sign bit is clear.
+fn to_signed(v: &U256) -> I512 {
+    I512::from_words(v.resize::<8>().to_words())
+}
+
+fn signed_pow(base: &MontyForm<8>, e: &I512) -> Option<MontyForm<8>> {
+    let (abs, negative) = e.abs_sign();
+    if bool::from(negative) {
+        let inv: Option<MontyForm<8>> = base.inv().into();
+        Some(inv?.pow(&abs))
+    } else {
+        Some(base.pow(&abs))
+    }
+}
+
+// Bezout coefficients for coprime x and odd y, returned as (alpha, beta) with
+// alpha * x - beta * y = 1 and both non-negative.
+pub(crate) fn bezout(x: &U256, y: &U256) -> Option<(U256, U256)> {
+    let y_odd: Option<Odd<U256>> = Odd::new(*y).into();
+    let y_odd = y_odd?;
+    let x_reduced = x.rem(y_odd.as_nz_ref());
+    let alpha: Option<U256> = x_reduced.inv_odd_mod(&y_odd).into();
+    let alpha = alpha?;
+
+    // beta = (alpha * x - 1) / y, which is exact and smaller than x
+    let ax = pad_u256_to_u512(alpha).wrapping_mul(&pad_u256_to_u512(*x));
+    let y_512: Option<NonZero<U512>> = NonZero::new(pad_u256_to_u512(*y)).into();
+    let beta = (ax - U512::ONE).wrapping_div(&y_512?);
+    let (beta_low, _) = beta.split();
+    Some((alpha, beta_low))
+}
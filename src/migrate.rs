@@ -0,0 +1,112 @@
+// Persisted accumulator state from any release of this crate. Each layout
+// has a module that decodes it into the State of this release, so files
+// written by older versions keep loading; to_state_bytes always writes the
+// latest layout, which restoring and re-persisting upgrades to.
+//
+// Version 1 predates the version field and is recognised by its tag.
+// Version 2 puts the layout version behind a tag of its own.
+// Version 3 appends the copy counts of multiset members.
+// Version 4 appends the usage of the secret key.
+// Version 5 appends the members whose primes were supplied to add_prime.
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime};
+use crate::params::PublicParams;
+use crate::rotation::KeyUsage;
+use crate::sharing::SecretKey;
+
+mod v1;
+mod v2;
+mod v3;
+mod v4;
+mod v5;
+
+pub const STATE_VERSION: u32 = 5;
+
+// Everything to_state_bytes persists, decoded. The element cache only keeps
+// member primes; checkpoints and runtime options are not part of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    pub params: PublicParams,
+    pub secret_key: SecretKey,
+    pub value: AccValue,
+    pub epoch: u64,
+    pub members: Vec<(Vec<u8>, ElemPrime)>, // Accumulated elements with their primes, sorted
+    pub deleted: Vec<ElemPrime>,            // Primes of deleted elements, in deletion order
+    pub copies: Vec<(Vec<u8>, u64)>,        // Copies beyond the first, multiset mode only, sorted
+    pub key_usage: KeyUsage,
+    pub external: Vec<Vec<u8>>, // Members whose primes were supplied rather than derived, sorted
+}
+
+// Decodes state in any supported layout.
+pub fn upgrade(bytes: &[u8]) -> Result<State, &'static str> {
+    match version(bytes)? {
+        1 => v1::decode(bytes),
+        2 => v2::decode(bytes),
+        3 => v3::decode(bytes),
+        4 => v4::decode(bytes),
+        5 => v5::decode(bytes),
+        _ => Err("Unsupported state version"),
+    }
+}
+
+// Layout version of persisted state, without decoding the rest.
+pub fn version(bytes: &[u8]) -> Result<u32, &'static str> {
+    if Reader::new(bytes, v1::TAG).is_ok() {
+        return Ok(1);
+    }
+    Reader::new(bytes, v2::TAG)?.get_u32()
+}
+
+impl State {
+    // Encodes in the latest layout.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        v5::encode(self)
+    }
+}
+
+// Members and deleted primes, shared by every layout so far.
+fn put_body(w: &mut Writer, state: &State) {
+    state.params.encode(w);
+    w.put_u256(state.secret_key.as_uint());
+    w.put_u512(state.value.as_uint());
+    w.put_u64(state.epoch);
+    w.put_u32(state.members.len() as u32);
+    for (x, prime) in &state.members {
+        w.put_bytes(x);
+        w.put_u256(prime.as_uint());
+    }
+    w.put_u32(state.deleted.len() as u32);
+    for prime in &state.deleted {
+        w.put_u256(prime.as_uint());
+    }
+}
+
+fn get_body(r: &mut Reader) -> Result<State, &'static str> {
+    let params = PublicParams::decode(r)?;
+    let secret_key = SecretKey::new(r.get_u256()?);
+    let value = AccValue::new(r.get_u512()?);
+    let epoch = r.get_u64()?;
+    let mut members = Vec::new();
+    for _ in 0..r.get_count()? {
+        let x = r.get_bytes()?.to_vec();
+        members.push((x, r.get_prime()?));
+    }
+    let mut deleted = Vec::new();
+    for _ in 0..r.get_count()? {
+        deleted.push(r.get_prime()?);
+    }
+    Ok(State {
+        params,
+        secret_key,
+        value,
+        epoch,
+        members,
+        deleted,
+        copies: Vec::new(),
+        key_usage: KeyUsage {
+            operations: 0,
+            created_at: None,
+        },
+        external: Vec::new(),
+    })
+}
@@ -0,0 +1,12 @@
+// Unversioned layout of the first releases: the tag, then the body.
+use super::State;
+use crate::encoding::Reader;
+
+pub(super) const TAG: &[u8] = b"braavos-accumulator-state";
+
+pub(super) fn decode(bytes: &[u8]) -> Result<State, &'static str> {
+    let mut r = Reader::new(bytes, TAG)?;
+    let state = super::get_body(&mut r)?;
+    r.finish()?;
+    Ok(state)
+}
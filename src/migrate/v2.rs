@@ -0,0 +1,16 @@
+// The version 1 body behind a tag and layout version of its own.
+use super::State;
+use crate::encoding::Reader;
+
+pub(super) const TAG: &[u8] = b"braavos-state";
+const VERSION: u32 = 2;
+
+pub(super) fn decode(bytes: &[u8]) -> Result<State, &'static str> {
+    let mut r = Reader::new(bytes, TAG)?;
+    if r.get_u32()? != VERSION {
+        return Err("Unsupported state version");
+    }
+    let state = super::get_body(&mut r)?;
+    r.finish()?;
+    Ok(state)
+}
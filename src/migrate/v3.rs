@@ -0,0 +1,25 @@
+// Version 2 followed by the extra copies of each multiset member.
+use super::State;
+use crate::encoding::Reader;
+
+const VERSION: u32 = 3;
+
+pub(super) fn decode(bytes: &[u8]) -> Result<State, &'static str> {
+    let mut r = Reader::new(bytes, super::v2::TAG)?;
+    if r.get_u32()? != VERSION {
+        return Err("Unsupported state version");
+    }
+    let state = get_body(&mut r)?;
+    r.finish()?;
+    Ok(state)
+}
+
+// The version 3 body, which later layouts extend.
+pub(super) fn get_body(r: &mut Reader) -> Result<State, &'static str> {
+    let mut state = super::get_body(r)?;
+    for _ in 0..r.get_count()? {
+        let x = r.get_bytes()?.to_vec();
+        state.copies.push((x, r.get_u64()?));
+    }
+    Ok(state)
+}
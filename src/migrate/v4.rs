@@ -0,0 +1,41 @@
+// Version 3 followed by the operation count and creation time of the key.
+use super::State;
+use crate::encoding::{Reader, Writer};
+
+const VERSION: u32 = 4;
+
+pub(super) fn decode(bytes: &[u8]) -> Result<State, &'static str> {
+    let mut r = Reader::new(bytes, super::v2::TAG)?;
+    if r.get_u32()? != VERSION {
+        return Err("Unsupported state version");
+    }
+    let state = get_body(&mut r)?;
+    r.finish()?;
+    Ok(state)
+}
+
+// The version 4 body, which later layouts extend.
+pub(super) fn put_body(w: &mut Writer, state: &State) {
+    super::put_body(w, state);
+    w.put_u32(state.copies.len() as u32);
+    for (x, copies) in &state.copies {
+        w.put_bytes(x);
+        w.put_u64(*copies);
+    }
+    w.put_u64(state.key_usage.operations);
+    w.put_u32(state.key_usage.created_at.is_some() as u32);
+    if let Some(created_at) = state.key_usage.created_at {
+        w.put_u64(created_at);
+    }
+}
+
+pub(super) fn get_body(r: &mut Reader) -> Result<State, &'static str> {
+    let mut state = super::v3::get_body(r)?;
+    state.key_usage.operations = r.get_u64()?;
+    state.key_usage.created_at = match r.get_u32()? {
+        0 => None,
+        1 => Some(r.get_u64()?),
+        _ => return Err("Invalid key creation time in state"),
+    };
+    Ok(state)
+}
@@ -0,0 +1,29 @@
+// Version 4 followed by the members whose primes were supplied to add_prime.
+use super::State;
+use crate::encoding::{Reader, Writer};
+
+const VERSION: u32 = 5;
+
+pub(super) fn encode(state: &State) -> Vec<u8> {
+    let mut w = Writer::new(super::v2::TAG);
+    w.put_u32(VERSION);
+    super::v4::put_body(&mut w, state);
+    w.put_u32(state.external.len() as u32);
+    for x in &state.external {
+        w.put_bytes(x);
+    }
+    w.finish()
+}
+
+pub(super) fn decode(bytes: &[u8]) -> Result<State, &'static str> {
+    let mut r = Reader::new(bytes, super::v2::TAG)?;
+    if r.get_u32()? != VERSION {
+        return Err("Unsupported state version");
+    }
+    let mut state = super::v4::get_body(&mut r)?;
+    for _ in 0..r.get_count()? {
+        state.external.push(r.get_bytes()?.to_vec());
+    }
+    r.finish()?;
+    Ok(state)
+}
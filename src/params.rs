@@ -0,0 +1,152 @@
+use crypto_bigint::{modular::MontyParams, Odd, U256, U512};
+
+use crate::core;
+use crate::deadline::Deadline;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime};
+use crate::hash::{self, HashAlgorithm};
+
+// Bumped whenever the meaning of any parameter or the digest layout changes.
+// Parameters still encode with the lowest version that holds them (1 without
+// a seed, 2 without a genesis record, 3 under the first hash-to-prime
+// profile), so their digests (and the witnesses carrying them) are unchanged.
+pub const PARAMS_VERSION: u32 = 4;
+
+const PARAMS_TAG: &[u8] = b"braavos-public-params";
+
+// Everything a verifier needs to check witnesses: the modulus, the initial
+// accumulator value and how element primes are derived.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicParams {
+    pub n: U512,                // RSA modulus
+    pub base: AccValue,         // Accumulator value at setup
+    pub hash: HashAlgorithm,    // Digest for hash-to-prime and signatures
+    pub prf_key: U256,          // Key mixed into hash-to-prime
+    pub seed: Option<[u8; 32]>, // Public seed the base is derived from; None after rerandomization
+    pub genesis: Option<[u8; 32]>, // GenesisRecord digest, if the setup was documented
+    pub h2p_profile: u32,          // Hash-to-prime profile, HASH_TO_PRIME_PROFILE
+}
+
+impl PublicParams {
+    pub fn hash_to_prime(&self, x: impl AsRef<[u8]>) -> ElemPrime {
+        core::hash_to_prime(self.hash, &self.prf_key, x.as_ref())
+    }
+
+    // As hash_to_prime, failing with TIMEOUT or CANCELLED instead of searching
+    // past the deadline.
+    pub fn hash_to_prime_within(&self, x: impl AsRef<[u8]>, deadline: &Deadline) -> Result<ElemPrime, &'static str> {
+        hash::hash_to_prime_within(self.hash, &self.prf_key, x.as_ref(), deadline).map(ElemPrime::new)
+    }
+
+    // Whether the base is the one derived from the seed and the modulus, so it
+    // was not picked by whoever generated the parameters. False without a seed.
+    pub fn verify_base(&self) -> bool {
+        let Some(seed) = &self.seed else {
+            return false;
+        };
+        let n: Option<Odd<U512>> = Odd::new(self.n).into();
+        n.is_some_and(|n| core::derive_base(self.hash, seed, &MontyParams::new(n)) == self.base)
+    }
+
+    // Constant-size fingerprint of the parameters. Witnesses and update messages
+    // carry it so a mismatch is reported as such rather than as a bad witness.
+    pub fn digest(&self) -> [u8; 32] {
+        self.hash.hash(PARAMS_TAG, &[&self.to_bytes()])
+    }
+
+    // The encoding the digest is taken over, as verifiers load it (such as
+    // the accumulators-verify crate's Verifier).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PARAMS_TAG);
+        self.encode(&mut w);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, PARAMS_TAG, policy)?;
+        let params = Self::decode(&mut r)?;
+        r.finish()?;
+        Ok(params)
+    }
+
+    pub(crate) fn encode(&self, w: &mut Writer) {
+        let version = match (&self.seed, &self.genesis) {
+            _ if self.h2p_profile != hash::FIRST_PROFILE => 4,
+            (_, Some(_)) => 3,
+            (Some(_), None) => 2,
+            (None, None) => 1,
+        };
+        w.put_u32(version);
+        w.put_u512(&self.n);
+        w.put_u512(self.base.as_uint());
+        w.put_u32(self.hash.id() as u32);
+        w.put_u32(hash::ELEMENT_PRIME_BITS);
+        w.put_u256(&self.prf_key);
+        match (version, &self.seed, &self.genesis) {
+            (3.., seed, genesis) => {
+                w.put_bytes(seed.as_ref().map_or(&[][..], |s| &s[..]));
+                w.put_bytes(genesis.as_ref().map_or(&[][..], |g| &g[..]));
+            }
+            (_, Some(seed), _) => w.put_bytes(seed),
+            _ => {}
+        }
+        if version == 4 {
+            w.put_u32(self.h2p_profile);
+        }
+    }
+
+    pub(crate) fn decode(r: &mut Reader) -> Result<Self, &'static str> {
+        let version = r.get_u32()?;
+        if version == 0 || version > PARAMS_VERSION {
+            return Err("Unsupported parameter version");
+        }
+        let n = r.get_u512()?;
+        if r.policy().canonical && (!n.bit_vartime(0) || n == U512::ONE) {
+            return Err("Modulus must be odd");
+        }
+        let base = AccValue::new(r.get_element(Some(&n))?);
+        let id = r.get_u32()?;
+        let hash = HashAlgorithm::from_id(u8::try_from(id).map_err(|_| "Unsupported hash algorithm")?)?;
+        if r.get_u32()? != hash::ELEMENT_PRIME_BITS {
+            return Err("Unsupported element prime size");
+        }
+        let prf_key = r.get_u256()?;
+        let (seed, genesis) = match version {
+            1 => (None, None),
+            2 => (Some(r.get_bytes()?.try_into().map_err(|_| "Invalid base seed")?), None),
+            _ => {
+                let seed = match r.get_bytes()? {
+                    [] => None,
+                    seed => Some(seed.try_into().map_err(|_| "Invalid base seed")?),
+                };
+                let genesis = match r.get_bytes()? {
+                    [] if version == 4 => None,
+                    genesis => Some(genesis.try_into().map_err(|_| "Invalid genesis digest")?),
+                };
+                (seed, genesis)
+            }
+        };
+        let h2p_profile = match version {
+            4 => r.get_u32()?,
+            _ => hash::FIRST_PROFILE,
+        };
+        // Primes under a profile this build does not know would silently differ
+        if h2p_profile != hash::HASH_TO_PRIME_PROFILE {
+            return Err("Unsupported hash-to-prime profile");
+        }
+        Ok(Self {
+            n,
+            base,
+            hash,
+            prf_key,
+            seed,
+            genesis,
+            h2p_profile,
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use crate::accumulator::BraavosAccumulator;
+use crate::builder::AccumulatorBuilder;
+use crate::update::EpochUpdate;
+
+const STATE_FILE: &str = "state";
+const UPDATES_FILE: &str = "updates";
+
+// Where an accumulator's state and its update log are kept. The state holds
+// the secret key: whatever stores it must be protected like one. Backends
+// keep updates as given; an UpdateLog rebuilt from read_updates checks that
+// they are consecutive.
+pub trait StateBackend {
+    fn load_state(&self) -> Result<Option<Vec<u8>>, &'static str>;
+    fn save_state(&mut self, state: &[u8]) -> Result<(), &'static str>;
+    fn append_update(&mut self, update: &EpochUpdate) -> Result<(), &'static str>;
+    // Updates within [from, to], in the order they were appended.
+    fn read_updates(&self, from: u64, to: u64) -> Result<Vec<EpochUpdate>, &'static str>;
+}
+
+impl BraavosAccumulator {
+    pub fn save_to(&self, backend: &mut impl StateBackend) -> Result<(), &'static str> {
+        backend.save_state(&self.to_state_bytes()?)
+    }
+}
+
+impl AccumulatorBuilder {
+    // restore, from the state a backend holds.
+    pub fn load_from(&self, backend: &impl StateBackend) -> Result<BraavosAccumulator, &'static str> {
+        self.restore(&backend.load_state()?.ok_or("Backend holds no state")?)
+    }
+}
+
+pub(crate) fn within(update: &EpochUpdate, from: u64, to: u64) -> bool {
+    update.from_epoch >= from && update.to_epoch <= to
+}
+
+// Keeps everything in memory, for tests and short-lived processes.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBackend {
+    state: Option<Vec<u8>>,
+    updates: Vec<EpochUpdate>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateBackend for MemoryBackend {
+    fn load_state(&self) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(self.state.clone())
+    }
+
+    fn save_state(&mut self, state: &[u8]) -> Result<(), &'static str> {
+        self.state = Some(state.to_vec());
+        Ok(())
+    }
+
+    fn append_update(&mut self, update: &EpochUpdate) -> Result<(), &'static str> {
+        self.updates.push(update.clone());
+        Ok(())
+    }
+
+    fn read_updates(&self, from: u64, to: u64) -> Result<Vec<EpochUpdate>, &'static str> {
+        Ok(self.updates.iter().filter(|u| within(u, from, to)).cloned().collect())
+    }
+}
+
+// A directory holding the state, replaced atomically on every save, and the
+// updates appended to one file as length-prefixed encodings.
+#[derive(Clone, Debug)]
+pub struct FileBackend {
+    dir: PathBuf,
+}
+
+impl FileBackend {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, &'static str> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|_| "Failed to create backend directory")?;
+        Ok(Self { dir })
+    }
+}
+
+impl StateBackend for FileBackend {
+    fn load_state(&self) -> Result<Option<Vec<u8>>, &'static str> {
+        match fs::read(self.dir.join(STATE_FILE)) {
+            Ok(state) => Ok(Some(state)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(_) => Err("Failed to read state"),
+        }
+    }
+
+    fn save_state(&mut self, state: &[u8]) -> Result<(), &'static str> {
+        let tmp = self.dir.join(format!("{}.tmp", STATE_FILE));
+        fs::write(&tmp, state).map_err(|_| "Failed to write state")?;
+        fs::rename(&tmp, self.dir.join(STATE_FILE)).map_err(|_| "Failed to write state")
+    }
+
+    fn append_update(&mut self, update: &EpochUpdate) -> Result<(), &'static str> {
+        let bytes = update.to_bytes();
+        let mut record = (bytes.len() as u32).to_be_bytes().to_vec();
+        record.extend_from_slice(&bytes);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(UPDATES_FILE))
+            .map_err(|_| "Failed to open update log")?;
+        // One write per record, so a crash leaves at most a torn last record
+        file.write_all(&record).and_then(|_| file.sync_data()).map_err(|_| "Failed to append update")
+    }
+
+    fn read_updates(&self, from: u64, to: u64) -> Result<Vec<EpochUpdate>, &'static str> {
+        let mut bytes = Vec::new();
+        match File::open(self.dir.join(UPDATES_FILE)) {
+            Ok(mut file) => file.read_to_end(&mut bytes).map_err(|_| "Failed to read update log")?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(_) => return Err("Failed to read update log"),
+        };
+        let mut updates = Vec::new();
+        let mut rest = &bytes[..];
+        while let Some((len, tail)) = rest.split_first_chunk::<4>() {
+            let len = u32::from_be_bytes(*len) as usize;
+            let record = tail.get(..len).ok_or("Update log ends in a torn record")?;
+            let update = EpochUpdate::from_bytes(record)?;
+            if within(&update, from, to) {
+                updates.push(update);
+            }
+            rest = &tail[len..];
+        }
+        if !rest.is_empty() {
+            return Err("Update log ends in a torn record");
+        }
+        Ok(updates)
+    }
+}
@@ -0,0 +1,143 @@
+use crypto_bigint::Uint;
+use crypto_primes::{generate_safe_prime, is_safe_prime};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use crate::sync::{lock, wait};
+
+struct Shared<const LIMBS: usize> {
+    primes: Mutex<VecDeque<Uint<LIMBS>>>,
+    changed: Condvar, // Signalled on every push and take
+    shutdown: AtomicBool,
+}
+
+// Safe primes generated ahead of time by a background worker, so key
+// generation only has to pop two values from a bounded queue. With a
+// persistence path, unused primes survive restarts; that file holds the
+// factors of future moduli and must be protected like a secret key.
+pub struct PrimePool<const LIMBS: usize> {
+    bits: u32,
+    capacity: usize,
+    path: Option<PathBuf>,
+    shared: Arc<Shared<LIMBS>>,
+}
+
+impl<const LIMBS: usize> PrimePool<LIMBS> {
+    pub fn spawn(bits: u32, capacity: usize, path: Option<PathBuf>) -> io::Result<Self> {
+        if bits > Uint::<LIMBS>::BITS || capacity == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid prime pool configuration"));
+        }
+        let mut primes = VecDeque::with_capacity(capacity);
+        if let Some(path) = &path {
+            primes.extend(load(path, bits)?.into_iter().take(capacity));
+        }
+        let shared = Arc::new(Shared {
+            primes: Mutex::new(primes),
+            changed: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+
+        // The worker holds its own Arc, so dropping the pool never waits on an
+        // in-flight generation; the thread exits once it notices the shutdown.
+        let worker = Arc::clone(&shared);
+        thread::spawn(move || loop {
+            {
+                let mut primes = lock(&worker.primes);
+                while primes.len() >= capacity && !worker.shutdown.load(Ordering::Acquire) {
+                    primes = wait(&worker.changed, primes);
+                }
+            }
+            if worker.shutdown.load(Ordering::Acquire) {
+                return;
+            }
+            let prime = generate_safe_prime::<Uint<LIMBS>>(bits);
+            lock(&worker.primes).push_back(prime);
+            worker.changed.notify_all();
+        });
+
+        Ok(Self { bits, capacity, path, shared })
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn available(&self) -> usize {
+        lock(&self.shared.primes).len()
+    }
+
+    // Blocks until a prime is available.
+    pub fn take(&self) -> Uint<LIMBS> {
+        let mut primes = lock(&self.shared.primes);
+        loop {
+            if let Some(prime) = primes.pop_front() {
+                self.shared.changed.notify_all();
+                return prime;
+            }
+            primes = wait(&self.shared.changed, primes);
+        }
+    }
+
+    pub fn try_take(&self) -> Option<Uint<LIMBS>> {
+        let prime = lock(&self.shared.primes).pop_front();
+        if prime.is_some() {
+            self.shared.changed.notify_all();
+        }
+        prime
+    }
+
+    // Writes the queued primes to the persistence path, if one was given.
+    pub fn persist(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let primes = lock(&self.shared.primes);
+        let mut bytes = Vec::with_capacity(primes.len() * Uint::<LIMBS>::BYTES);
+        for prime in primes.iter() {
+            bytes.extend(prime.as_words().iter().flat_map(|w| w.to_le_bytes()));
+        }
+        fs::write(path, bytes)
+    }
+}
+
+impl<const LIMBS: usize> fmt::Debug for PrimePool<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrimePool")
+            .field("bits", &self.bits)
+            .field("capacity", &self.capacity)
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+impl<const LIMBS: usize> Drop for PrimePool<LIMBS> {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Release);
+        self.shared.changed.notify_all();
+        let _ = self.persist();
+    }
+}
+
+// Persisted primes are re-checked, since the file lives outside our control.
+fn load<const LIMBS: usize>(path: &PathBuf, bits: u32) -> io::Result<Vec<Uint<LIMBS>>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(bytes
+        .chunks_exact(Uint::<LIMBS>::BYTES)
+        .map(Uint::<LIMBS>::from_le_slice)
+        .filter(|p| p.bits() == bits && is_safe_prime(p))
+        .collect())
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use crate::hash::{HashAlgorithm, ELEMENT_PRIME_BITS};
+use crate::params::PublicParams;
+use crate::security::SecurityEstimate;
+
+// Widest modulus this build's fixed-width arithmetic holds: sk = p'q' must
+// fit in 256 bits (see AccumulatorBuilder::prime_bits).
+const SUPPORTED_MODULUS_BITS: u32 = 256;
+
+// Named configurations, so deployments pick one instead of assembling sizes
+// and hashes by hand. The suffix is the collision resistance of the element
+// primes: P128 means 256-bit primes, P256 512-bit ones. Each pins the
+// modulus size, element prime length and hash, and turns on strict mode and
+// a minimum security level so a later prime_bits cannot weaken it.
+//
+// The RSA presets need wider arithmetic than this build has, and build()
+// refuses them rather than silently falling back to a smaller modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Preset {
+    Rsa2048Sha256P128,
+    #[cfg(feature = "sha3")]
+    Rsa3072Sha3P256,
+    // 128-bit modulus for tests and examples; no security at all
+    Demo,
+}
+
+impl Preset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Rsa2048Sha256P128 => "rsa2048-sha256-p128",
+            #[cfg(feature = "sha3")]
+            Preset::Rsa3072Sha3P256 => "rsa3072-sha3-p256",
+            Preset::Demo => "demo",
+        }
+    }
+
+    pub fn modulus_bits(&self) -> u32 {
+        match self {
+            Preset::Rsa2048Sha256P128 => 2048,
+            #[cfg(feature = "sha3")]
+            Preset::Rsa3072Sha3P256 => 3072,
+            Preset::Demo => 128,
+        }
+    }
+
+    pub fn element_prime_bits(&self) -> u32 {
+        match self {
+            Preset::Rsa2048Sha256P128 | Preset::Demo => 256,
+            #[cfg(feature = "sha3")]
+            Preset::Rsa3072Sha3P256 => 512,
+        }
+    }
+
+    pub fn hash(&self) -> HashAlgorithm {
+        match self {
+            Preset::Rsa2048Sha256P128 | Preset::Demo => HashAlgorithm::Sha256,
+            #[cfg(feature = "sha3")]
+            Preset::Rsa3072Sha3P256 => HashAlgorithm::Sha3_256,
+        }
+    }
+
+    // The SecurityEstimate the builder must reach; 0 for Demo.
+    pub fn min_security_bits(&self) -> u32 {
+        match self {
+            Preset::Demo => 0,
+            _ => SecurityEstimate::for_modulus_bits(self.modulus_bits()).factoring_bits,
+        }
+    }
+
+    pub fn strict(&self) -> bool {
+        *self != Preset::Demo
+    }
+
+    // Whether this build can generate keys for the preset.
+    pub fn check_supported(&self) -> Result<(), &'static str> {
+        if self.modulus_bits() > SUPPORTED_MODULUS_BITS {
+            return Err("Preset needs a wider modulus than this build supports");
+        }
+        if self.element_prime_bits() != ELEMENT_PRIME_BITS {
+            return Err("Preset needs a different element prime length than this build supports");
+        }
+        Ok(())
+    }
+
+    // For verifiers pinning a preset: whether published parameters match it.
+    pub fn check_params(&self, params: &PublicParams) -> Result<(), &'static str> {
+        // A product of two k-bit primes has 2k - 1 or 2k bits
+        if !(self.modulus_bits() - 1..=self.modulus_bits()).contains(&params.n.bits()) {
+            return Err("Modulus size does not match the preset");
+        }
+        if params.hash != self.hash() {
+            return Err("Hash does not match the preset");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
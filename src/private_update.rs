@@ -0,0 +1,195 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Limb, NonZero, Odd, U256, U512,
+};
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::update::{EpochUpdate, UpdateMessage};
+use crate::witness::Witness;
+
+const PRIVATE_UPDATE_TAG: &[u8] = b"braavos-private-update";
+
+// An epoch update that publishes the product of the deleted primes instead
+// of the primes, so the feed does not list who was removed. Witnesses follow
+// it with a single Bezout step against the product. An observer can still
+// test a guessed element, whose prime anyone can derive from the parameters,
+// by whether it divides the product: this only hides elements an observer
+// cannot enumerate, and the product's size gives away about how many were
+// deleted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivateEpochUpdate {
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    pub n: U512,
+    pub params_digest: [u8; 32],
+    pub product: Vec<U256>, // Product of the deleted primes, least significant limb first
+    pub value: AccValue,    // Accumulator value at to_epoch
+}
+
+impl EpochUpdate {
+    // The same update with the deleted primes multiplied together.
+    pub fn to_private(&self) -> PrivateEpochUpdate {
+        let mut product = vec![U256::ONE];
+        for prime in &self.deleted {
+            product = mul(&product, std::slice::from_ref(prime.as_uint()));
+        }
+        PrivateEpochUpdate {
+            from_epoch: self.from_epoch,
+            to_epoch: self.to_epoch,
+            n: self.n,
+            params_digest: self.params_digest,
+            product,
+            value: self.value,
+        }
+    }
+}
+
+impl PrivateEpochUpdate {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PRIVATE_UPDATE_TAG);
+        w.put_u64(self.from_epoch);
+        w.put_u64(self.to_epoch);
+        w.put_u512(&self.n);
+        w.put_bytes(&self.params_digest);
+        w.put_u32(self.product.len() as u32);
+        for limb in &self.product {
+            w.put_u256(limb);
+        }
+        w.put_u512(self.value.as_uint());
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, PRIVATE_UPDATE_TAG, policy)?;
+        let from_epoch = r.get_u64()?;
+        let to_epoch = r.get_u64()?;
+        let n = r.get_u512()?;
+        if policy.modulus.is_some_and(|m| m != n) {
+            return Err("Update is for a different modulus");
+        }
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let mut product = Vec::new();
+        for _ in 0..r.get_count()? {
+            product.push(r.get_u256()?);
+        }
+        let value = AccValue::new(r.get_element(Some(&n))?);
+        r.finish()?;
+        if to_epoch < from_epoch {
+            return Err("Update ends before it starts");
+        }
+        if product.last().is_none_or(|limb| *limb == U256::ZERO) {
+            return Err("Deleted prime product is not normalized");
+        }
+        Ok(Self {
+            from_epoch,
+            to_epoch,
+            n,
+            params_digest,
+            product,
+            value,
+        })
+    }
+}
+
+impl UpdateMessage for PrivateEpochUpdate {
+    fn merge(&self, other: &Self) -> Result<Self, &'static str> {
+        if self.params_digest != other.params_digest {
+            return Err("Update messages are for different parameters");
+        }
+        if self.to_epoch != other.from_epoch {
+            return Err("Update messages are not consecutive");
+        }
+        Ok(Self {
+            from_epoch: self.from_epoch,
+            to_epoch: other.to_epoch,
+            n: self.n,
+            params_digest: self.params_digest,
+            product: mul(&self.product, &other.product),
+            value: other.value,
+        })
+    }
+
+    // With D the product and a' the new value: beta = D^-1 mod x and
+    // k = (beta D - 1) / x, so w' = w^beta / a'^k. Writing D = q x + r,
+    // k = beta q + (beta r - 1) / x keeps every division within 512 bits.
+    fn apply_to_witness(&self, x: &ElemPrime, w: &Witness) -> Result<Witness, &'static str> {
+        if w.params_digest != self.params_digest {
+            return Err("Witness was issued under different parameters");
+        }
+        let n: Option<Odd<U512>> = Odd::new(self.n).into();
+        let params = MontyParams::new(n.ok_or("Modulus must be odd")?);
+        let divisor: Option<NonZero<U256>> = NonZero::new(*x.as_uint()).into();
+        let divisor = divisor.ok_or("Element prime is zero")?;
+
+        let (q, r) = div_rem(&self.product, &divisor);
+        if r == U256::ZERO {
+            return Err("Element was deleted in this update");
+        }
+        let beta: Option<U256> = r.inv_mod(x.as_uint()).into();
+        let beta = beta.ok_or("Element prime is not prime")?;
+        let (lo, hi) = beta.split_mul(&r);
+        let (t, _) = lo.concat(&hi).wrapping_sub(&U512::ONE).div_rem_vartime(&divisor);
+
+        let value = MontyForm::new(self.value.as_uint(), params);
+        let k = pow_limbs(&value, &q).pow(&beta).mul(&value.pow(&t));
+        let k_inv: Option<MontyForm<8>> = k.inv().into();
+        let w = MontyForm::new(w.value.as_uint(), params).pow(&beta);
+        Ok(Witness {
+            params_digest: self.params_digest,
+            value: WitnessValue::new(w.mul(&k_inv.ok_or("Witness not invertible modulo n")?).retrieve()),
+        })
+    }
+}
+
+// Product of two multi-limb numbers, least significant limb first, without
+// leading zero limbs.
+fn mul(a: &[U256], b: &[U256]) -> Vec<U256> {
+    let mut out = vec![U256::ZERO; a.len() + b.len()];
+    for (j, y) in b.iter().enumerate() {
+        let mut carry = U256::ZERO;
+        for (i, x) in a.iter().enumerate() {
+            let (lo, hi) = x.split_mul(y);
+            let (sum, c1) = out[i + j].adc(&lo, Limb::ZERO);
+            let (sum, c2) = sum.adc(&carry, Limb::ZERO);
+            out[i + j] = sum;
+            // hi is at most 2^256 - 2, so adding both carries cannot overflow
+            carry = hi.wrapping_add(&U256::from_word(c1.0)).wrapping_add(&U256::from_word(c2.0));
+        }
+        out[j + a.len()] = carry;
+    }
+    while out.len() > 1 && out.last() == Some(&U256::ZERO) {
+        out.pop();
+    }
+    out
+}
+
+// Long division by a single limb: quotient limbs and the remainder.
+fn div_rem(a: &[U256], divisor: &NonZero<U256>) -> (Vec<U256>, U256) {
+    let mut quotient = vec![U256::ZERO; a.len()];
+    let mut rem = U256::ZERO;
+    for (i, limb) in a.iter().enumerate().rev() {
+        // rem < divisor, so the quotient digit fits one limb
+        let (q, r) = limb.concat(&rem).div_rem_vartime(divisor);
+        quotient[i] = q.resize();
+        rem = r;
+    }
+    (quotient, rem)
+}
+
+// base^e for a multi-limb exponent, least significant limb first.
+fn pow_limbs(base: &MontyForm<8>, e: &[U256]) -> MontyForm<8> {
+    let mut acc = MontyForm::one(*base.params());
+    for limb in e.iter().rev() {
+        for _ in 0..U256::BITS {
+            acc = acc.square();
+        }
+        acc = acc.mul(&base.pow(limb));
+    }
+    acc
+}
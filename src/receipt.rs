@@ -0,0 +1,200 @@
+use crypto_bigint::{Odd, U512};
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::ElemPrime;
+use crate::hash::HashAlgorithm;
+use crate::params::PublicParams;
+use crate::signature;
+use crate::witness::Witness;
+
+const LEAF_DOMAIN: &[u8] = b"braavos-issuance-leaf";
+const NODE_DOMAIN: &[u8] = b"braavos-issuance-node";
+const WITNESS_DOMAIN: &[u8] = b"braavos-issuance-witness";
+const RECEIPT_TAG: &[u8] = b"braavos-issuance-receipt";
+const PROOF_TAG: &[u8] = b"braavos-issuance-proof";
+
+// Signed commitment to one batch from issue_witnesses_with_receipt: the
+// root of a Merkle tree over the issued (element, witness digest) pairs in
+// input order. Shaped as in RFC 9162, a last node without a sibling moving
+// up a level unchanged. A holder keeping the receipt and its IssuanceProof
+// can later show it was issued in the batch without the manager, which
+// neither learns of the check nor can disown the signed root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuanceReceipt {
+    pub params_digest: [u8; 32],
+    pub epoch: u64, // Epoch the witnesses were issued at
+    pub size: u64,  // Elements in the batch
+    pub root: [u8; 32],
+    pub signature: U512,
+}
+
+// Path from one leaf to the receipt's root, leaf side first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuanceProof {
+    pub index: u64, // Position of the element in the batch
+    pub siblings: Vec<[u8; 32]>,
+}
+
+// What issue_witnesses_with_receipt returns: each element's prime, witness
+// and proof, in input order, and the receipt they all prove against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuedBatch {
+    pub receipt: IssuanceReceipt,
+    pub issued: Vec<(ElemPrime, Witness, IssuanceProof)>,
+}
+
+impl IssuanceReceipt {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(RECEIPT_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u64(self.epoch);
+        w.put_u64(self.size);
+        w.put_bytes(&self.root);
+        w.finish()
+    }
+
+    pub fn verify(&self, params: &PublicParams) -> bool {
+        if self.params_digest != params.digest() {
+            return false;
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        n.is_some_and(|n| signature::verify(params.hash, &n, &self.signed_bytes(), &self.signature))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, RECEIPT_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let epoch = r.get_u64()?;
+        let size = r.get_u64()?;
+        let root = r.get_bytes()?.try_into().map_err(|_| "Invalid issuance root")?;
+        let signature = r.get_residue(policy.modulus.as_ref())?;
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            epoch,
+            size,
+            root,
+            signature,
+        })
+    }
+}
+
+impl IssuanceProof {
+    // Whether x was issued `witness` in the batch the receipt signs.
+    pub fn verify(
+        &self,
+        params: &PublicParams,
+        receipt: &IssuanceReceipt,
+        x: impl AsRef<[u8]>,
+        witness: &Witness,
+    ) -> bool {
+        let leaf = leaf(params.hash, x.as_ref(), witness);
+        let root = root_from_path(params.hash, leaf, self.index, receipt.size, &self.siblings);
+        receipt.verify(params) && root == Some(receipt.root)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PROOF_TAG);
+        w.put_u64(self.index);
+        w.put_u32(self.siblings.len() as u32);
+        for sibling in &self.siblings {
+            w.put_bytes(sibling);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, PROOF_TAG, policy)?;
+        let index = r.get_u64()?;
+        let mut siblings = Vec::new();
+        for _ in 0..r.get_count()? {
+            siblings.push(r.get_bytes()?.try_into().map_err(|_| "Invalid issuance sibling")?);
+        }
+        r.finish()?;
+        Ok(Self { index, siblings })
+    }
+}
+
+pub(crate) fn leaf(hash: HashAlgorithm, x: &[u8], witness: &Witness) -> [u8; 32] {
+    let witness = hash.hash(WITNESS_DOMAIN, &[&witness.to_bytes()]);
+    hash.hash(LEAF_DOMAIN, &[x, &witness])
+}
+
+fn node(hash: HashAlgorithm, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hash.hash(NODE_DOMAIN, &[left, right])
+}
+
+// The root over `leaves` and every leaf's path, built a level at a time.
+pub(crate) fn commit(hash: HashAlgorithm, leaves: Vec<[u8; 32]>) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut paths = vec![Vec::new(); leaves.len()];
+    let mut level = leaves;
+    // Leaves under each node of the current level
+    let mut spans: Vec<(usize, usize)> = (0..level.len()).map(|i| (i, i + 1)).collect();
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity(level.len().div_ceil(2));
+        let mut next_spans = Vec::with_capacity(next.capacity());
+        for (pair, span) in level.chunks(2).zip(spans.chunks(2)) {
+            match (pair, span) {
+                ([left, right], [(start, mid), (_, end)]) => {
+                    for path in paths.iter_mut().take(*mid).skip(*start) {
+                        path.push(*right);
+                    }
+                    for path in paths.iter_mut().take(*end).skip(*mid) {
+                        path.push(*left);
+                    }
+                    next.push(node(hash, left, right));
+                    next_spans.push((*start, *end));
+                }
+                _ => {
+                    next.extend_from_slice(pair);
+                    next_spans.extend_from_slice(span);
+                }
+            }
+        }
+        level = next;
+        spans = next_spans;
+    }
+    (level.first().copied().unwrap_or([0; 32]), paths)
+}
+
+// RFC 9162 section 2.1.3.2: the root a leaf's path leads to, or None when
+// the path does not fit a tree of that size.
+fn root_from_path(hash: HashAlgorithm, leaf: [u8; 32], index: u64, size: u64, path: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if index >= size {
+        return None;
+    }
+    let (mut i, mut last) = (index, size - 1);
+    let mut r = leaf;
+    for p in path {
+        if last == 0 {
+            return None;
+        }
+        if i & 1 == 1 || i == last {
+            r = node(hash, p, &r);
+            while i & 1 == 0 && i != 0 {
+                i >>= 1;
+                last >>= 1;
+            }
+        } else {
+            r = node(hash, &r, p);
+        }
+        i >>= 1;
+        last >>= 1;
+    }
+    (last == 0).then_some(r)
+}
@@ -0,0 +1,143 @@
+// StateBackend on Redis, speaking RESP2 over a plain TCP connection: no TLS,
+// and AUTH only as the password form of the command. The state is one
+// string key and the updates one list, both under a caller-chosen prefix so
+// several accumulators can share a database.
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::persistence::{within, StateBackend};
+use crate::update::EpochUpdate;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+// Redis's own default proto-max-bulk-len.
+const MAX_BULK_LEN: u64 = 512 << 20;
+
+#[derive(Debug)]
+pub struct RedisBackend {
+    stream: TcpStream,
+    state_key: String,
+    updates_key: String,
+}
+
+// One RESP2 reply. An error reply is read whole, so the connection stays in
+// step; command turns it into Err.
+enum Reply {
+    Simple,
+    Error,
+    Integer,
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<Reply>),
+}
+
+impl RedisBackend {
+    pub fn connect(addr: impl ToSocketAddrs, prefix: &str) -> Result<Self, &'static str> {
+        let stream = TcpStream::connect(addr).map_err(|_| "Failed to connect to Redis")?;
+        stream.set_read_timeout(Some(TIMEOUT)).map_err(|_| "Failed to connect to Redis")?;
+        stream.set_write_timeout(Some(TIMEOUT)).map_err(|_| "Failed to connect to Redis")?;
+        Ok(Self {
+            stream,
+            state_key: format!("{}:state", prefix),
+            updates_key: format!("{}:updates", prefix),
+        })
+    }
+
+    pub fn auth(&self, password: &str) -> Result<(), &'static str> {
+        self.command(&[b"AUTH", password.as_bytes()]).map(|_| ())
+    }
+
+    fn command(&self, args: &[&[u8]]) -> Result<Reply, &'static str> {
+        let mut request = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            request.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            request.extend_from_slice(arg);
+            request.extend_from_slice(b"\r\n");
+        }
+        (&self.stream).write_all(&request).map_err(|_| "Failed to write to Redis")?;
+        // Requests are answered in turn, so nothing past this reply is buffered.
+        // A reply that fails to read leaves the rest of it on the wire, where
+        // the next command would take it for its own: the connection is shut
+        // down instead, and the backend has to be connected again.
+        match read_reply(&mut BufReader::new(&self.stream)) {
+            Ok(Reply::Error) => Err("Redis returned an error"),
+            Ok(reply) => Ok(reply),
+            Err(e) => {
+                let _ = self.stream.shutdown(Shutdown::Both);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl StateBackend for RedisBackend {
+    fn load_state(&self) -> Result<Option<Vec<u8>>, &'static str> {
+        match self.command(&[b"GET", self.state_key.as_bytes()])? {
+            Reply::Bulk(state) => Ok(state),
+            _ => Err("Unexpected reply from Redis"),
+        }
+    }
+
+    fn save_state(&mut self, state: &[u8]) -> Result<(), &'static str> {
+        self.command(&[b"SET", self.state_key.as_bytes(), state]).map(|_| ())
+    }
+
+    fn append_update(&mut self, update: &EpochUpdate) -> Result<(), &'static str> {
+        self.command(&[b"RPUSH", self.updates_key.as_bytes(), &update.to_bytes()]).map(|_| ())
+    }
+
+    fn read_updates(&self, from: u64, to: u64) -> Result<Vec<EpochUpdate>, &'static str> {
+        let Reply::Array(items) = self.command(&[b"LRANGE", self.updates_key.as_bytes(), b"0", b"-1"])? else {
+            return Err("Unexpected reply from Redis");
+        };
+        let mut updates = Vec::new();
+        for item in items {
+            let Reply::Bulk(Some(bytes)) = item else {
+                return Err("Unexpected reply from Redis");
+            };
+            let update = EpochUpdate::from_bytes(&bytes)?;
+            if within(&update, from, to) {
+                updates.push(update);
+            }
+        }
+        Ok(updates)
+    }
+}
+
+fn read_line(r: &mut impl BufRead) -> Result<String, &'static str> {
+    let mut line = String::new();
+    r.read_line(&mut line).map_err(|_| "Failed to read from Redis")?;
+    line.strip_suffix("\r\n").map(str::to_string).ok_or("Malformed reply from Redis")
+}
+
+fn read_reply(r: &mut impl BufRead) -> Result<Reply, &'static str> {
+    let line = read_line(r)?;
+    let (kind, rest) = line.split_at_checked(1).ok_or("Malformed reply from Redis")?;
+    let count = || rest.parse::<i64>().map_err(|_| "Malformed reply from Redis");
+    match kind {
+        "+" => Ok(Reply::Simple),
+        "-" => Ok(Reply::Error),
+        ":" => count().map(|_| Reply::Integer),
+        "$" => {
+            let Ok(len) = usize::try_from(count()?) else {
+                return Ok(Reply::Bulk(None));
+            };
+            if len as u64 > MAX_BULK_LEN {
+                return Err("Reply from Redis is too large");
+            }
+            let mut data = Vec::new();
+            r.take(len as u64 + 2).read_to_end(&mut data).map_err(|_| "Failed to read from Redis")?;
+            if data.len() != len + 2 || !data.ends_with(b"\r\n") {
+                return Err("Malformed reply from Redis");
+            }
+            data.truncate(len);
+            Ok(Reply::Bulk(Some(data)))
+        }
+        "*" => {
+            let Ok(len) = usize::try_from(count()?) else {
+                return Ok(Reply::Array(Vec::new()));
+            };
+            (0..len).map(|_| read_reply(r)).collect::<Result<_, _>>().map(Reply::Array)
+        }
+        _ => Err("Malformed reply from Redis"),
+    }
+}
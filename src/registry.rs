@@ -0,0 +1,138 @@
+use crypto_bigint::{Random, U256};
+use rand::rngs::OsRng;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::deadline::Deadline;
+use crate::group::ElemPrime;
+use crate::hash::{self, HashAlgorithm};
+use crate::params::PublicParams;
+use crate::sync::lock;
+
+// Element primes shared by every accumulator built with the registry (see
+// AccumulatorBuilder::element_registry) and by verifiers, so an element on
+// both an allow-list and a deny-list is hashed to a prime once. Sharing
+// primes means sharing the hash-to-prime key: accumulators built with a
+// registry use its key instead of drawing their own. Share it with Arc.
+pub struct ElementRegistry {
+    hash: HashAlgorithm,
+    key: U256,
+    entries: Mutex<HashMap<Vec<u8>, RegistryEntry>>,
+}
+
+// An element's prime with a certificate that it is the right one: its
+// position in the element's candidate sequence and, for each earlier candidate
+// that survives the small-prime sieve, a Fermat base proving it composite.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistryEntry {
+    pub prime: ElemPrime,
+    pub counter: u32,
+    pub certificate: Vec<u8>,
+}
+
+impl ElementRegistry {
+    // A registry with a fresh random hash-to-prime key.
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Self::with_key(hash, U256::random(&mut OsRng))
+    }
+
+    // A registry for existing parameters, to share their primes among
+    // verifiers or accumulators restored from state with the same key.
+    pub fn for_params(params: &PublicParams) -> Self {
+        Self::with_key(params.hash, params.prf_key)
+    }
+
+    fn with_key(hash: HashAlgorithm, key: U256) -> Self {
+        Self {
+            hash,
+            key,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    pub fn key(&self) -> U256 {
+        self.key
+    }
+
+    // Whether primes from this registry are valid under the parameters.
+    pub fn matches(&self, params: &PublicParams) -> bool {
+        params.hash == self.hash && params.prf_key == self.key
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.entries).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn entry(&self, x: impl AsRef<[u8]>) -> Option<RegistryEntry> {
+        lock(&self.entries).get(x.as_ref()).cloned()
+    }
+
+    pub fn prime(&self, x: impl AsRef<[u8]>) -> Result<ElemPrime, &'static str> {
+        self.prime_within(x, &Deadline::none())
+    }
+
+    // x's prime, searched for and recorded on first use. The lock is not
+    // held during the search, so two threads may both search for a new
+    // element; they find the same prime.
+    pub fn prime_within(&self, x: impl AsRef<[u8]>, deadline: &Deadline) -> Result<ElemPrime, &'static str> {
+        let x = x.as_ref();
+        if let Some(entry) = lock(&self.entries).get(x) {
+            return Ok(entry.prime);
+        }
+        let entry = RegistryEntry::search(self.hash, &self.key, x, deadline)?;
+        let prime = entry.prime;
+        lock(&self.entries).insert(x.to_vec(), entry);
+        Ok(prime)
+    }
+
+    // Adds an entry computed elsewhere (another process's registry with the
+    // same key) after checking its certificate.
+    pub fn import(&self, x: impl AsRef<[u8]>, entry: RegistryEntry) -> Result<(), &'static str> {
+        let x = x.as_ref();
+        if !entry.check(self.hash, &self.key, x) {
+            return Err("Registry entry certificate is invalid");
+        }
+        lock(&self.entries).insert(x.to_vec(), entry);
+        Ok(())
+    }
+
+    pub fn clear(&self) {
+        lock(&self.entries).clear();
+    }
+}
+
+impl fmt::Debug for ElementRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElementRegistry")
+            .field("hash", &self.hash)
+            .field("entries", &self.len())
+            .finish()
+    }
+}
+
+impl RegistryEntry {
+    // x's prime and certificate, found by the hash-to-prime search.
+    pub(crate) fn search(hash: HashAlgorithm, key: &U256, x: &[u8], deadline: &Deadline) -> Result<Self, &'static str> {
+        let (prime, counter, certificate) = hash::hash_to_prime_certified(hash, key, x, deadline)?;
+        Ok(Self {
+            prime: ElemPrime::new(prime),
+            counter,
+            certificate,
+        })
+    }
+
+    // Whether this is x's prime under the given digest and key, without
+    // repeating the primality tests of the rejected candidates.
+    pub fn check(&self, hash: HashAlgorithm, key: &U256, x: impl AsRef<[u8]>) -> bool {
+        hash::check_certificate(hash, key, x.as_ref(), self.prime.as_uint(), self.counter, &self.certificate)
+    }
+}
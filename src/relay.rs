@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::stream::UpdateStream;
+use crate::sync::lock;
+use crate::update::{EpochUpdate, UpdateLog, UpdateMessage};
+
+// Cache-Control for answers to range paths, whose content never changes.
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+// Coalesced ranges kept ready to serve; the cache is dropped whole when full.
+const MAX_CACHED_RANGES: usize = 1024;
+
+// Sits between a manager and many holders so the manager publishes each
+// update once. The relay follows the manager's stream into its own copy of
+// the log and answers catch-up requests with one coalesced update per
+// holder epoch, merged once and shared by every holder at that epoch.
+//
+// Ranges have paths of the form /updates/{from}-{to}: the update taking a
+// holder from `from` to `to` never changes, so HTTP caches and CDNs can keep
+// it for good (IMMUTABLE_CACHE_CONTROL). A holder asks path_for(epoch) for
+// the range that brings it current, typically behind a short-lived
+// redirect, then fetches the range itself.
+#[derive(Debug)]
+pub struct UpdateRelay {
+    stream: UpdateStream,
+    log: UpdateLog,
+    cache: Mutex<HashMap<(u64, u64), Vec<u8>>>,
+}
+
+impl UpdateRelay {
+    // A relay from the manager's log and a stream opened before the log was
+    // copied (see Manager::relay); updates the log already has are skipped.
+    pub fn new(log: UpdateLog, stream: UpdateStream) -> Self {
+        Self {
+            stream,
+            log,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Appends whatever the manager has published since; how many updates.
+    pub fn poll(&mut self) -> Result<usize, &'static str> {
+        let mut appended = 0;
+        while let Some(update) = self.stream.try_recv() {
+            appended += self.append(update)? as usize;
+        }
+        Ok(appended)
+    }
+
+    // Waits for the manager's next update and appends it; false once the
+    // manager is gone.
+    pub async fn follow(&mut self) -> Result<bool, &'static str> {
+        loop {
+            let Some(update) = self.stream.recv().await else {
+                return Ok(false);
+            };
+            if self.append(update)? {
+                return Ok(true);
+            }
+        }
+    }
+
+    fn append(&mut self, update: EpochUpdate) -> Result<bool, &'static str> {
+        if update.to_epoch <= self.log.latest_epoch() {
+            return Ok(false);
+        }
+        self.log.push(update)?;
+        Ok(true)
+    }
+
+    pub fn latest_epoch(&self) -> u64 {
+        self.log.latest_epoch()
+    }
+
+    pub fn log(&self) -> &UpdateLog {
+        &self.log
+    }
+
+    // The path of the range bringing a holder at `epoch` current; None when
+    // it already is.
+    pub fn path_for(&self, epoch: u64) -> Result<Option<String>, &'static str> {
+        let latest = self.log.latest_epoch();
+        if epoch == latest {
+            return Ok(None);
+        }
+        self.log.pending(epoch)?;
+        Ok(Some(range_path(epoch, latest)))
+    }
+
+    // The encoded update for a range path, merged on first request.
+    pub fn get(&self, path: &str) -> Result<Vec<u8>, &'static str> {
+        let (from, to) = parse_range_path(path)?;
+        self.range(from, to)
+    }
+
+    // The encoded update taking a holder from `from` to `to`.
+    pub fn range(&self, from: u64, to: u64) -> Result<Vec<u8>, &'static str> {
+        if let Some(bytes) = lock(&self.cache).get(&(from, to)) {
+            return Ok(bytes.clone());
+        }
+        let pending = self.log.pending(from)?;
+        let end = pending.partition_point(|u| u.to_epoch <= to);
+        let updates = &pending[..end];
+        let (Some(first), Some(last)) = (updates.first(), updates.last()) else {
+            return Err("Range is empty");
+        };
+        if last.to_epoch != to {
+            return Err("Epoch is not on an update boundary");
+        }
+        let merged = updates[1..].iter().try_fold(first.clone(), |acc, u| acc.merge(u))?;
+        let bytes = merged.to_bytes();
+        let mut cache = lock(&self.cache);
+        if cache.len() >= MAX_CACHED_RANGES {
+            cache.clear();
+        }
+        cache.insert((from, to), bytes.clone());
+        Ok(bytes)
+    }
+
+    // Ranges merged and kept ready.
+    pub fn cached_ranges(&self) -> usize {
+        lock(&self.cache).len()
+    }
+}
+
+fn range_path(from: u64, to: u64) -> String {
+    format!("/updates/{}-{}", from, to)
+}
+
+fn parse_range_path(path: &str) -> Result<(u64, u64), &'static str> {
+    let range = path.strip_prefix("/updates/").ok_or("Not an update range path")?;
+    let (from, to) = range.split_once('-').ok_or("Not an update range path")?;
+    let from: u64 = from.parse().map_err(|_| "Not an update range path")?;
+    let to: u64 = to.parse().map_err(|_| "Not an update range path")?;
+    if to <= from {
+        return Err("Range is empty");
+    }
+    Ok((from, to))
+}
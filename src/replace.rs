@@ -0,0 +1,117 @@
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime};
+use crate::params::PublicParams;
+use crate::signature;
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const REPLACE_TAG: &[u8] = b"braavos-replace-proof";
+
+// What BraavosAccumulator::replace returns: the one update every other holder
+// applies, the new element's witness at its epoch and the signed statement
+// tying the two operations together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replacement {
+    pub update: EpochUpdate,
+    pub witness: Witness, // For the new element, at update.to_epoch
+    pub proof: ReplaceProof,
+}
+
+// Signed statement that `added` took the place of `removed` in the
+// single epoch an update ends at, so a relying party can tell the new member
+// is a replacement rather than a fresh one, and that no epoch held both or
+// neither. Names primes, not elements; see is_for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplaceProof {
+    pub params_digest: [u8; 32],
+    pub epoch: u64, // Epoch the replacement took effect at
+    pub removed: ElemPrime,
+    pub added: ElemPrime,
+    pub value: AccValue, // Accumulator value at epoch
+    pub signature: U512,
+}
+
+impl ReplaceProof {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(REPLACE_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u64(self.epoch);
+        w.put_u256(self.removed.as_uint());
+        w.put_u256(self.added.as_uint());
+        w.put_u512(self.value.as_uint());
+        w.finish()
+    }
+
+    // Checks the signature and that `update` is the one the statement is
+    // about: a single epoch deleting exactly the removed prime.
+    pub fn verify(&self, params: &PublicParams, update: &EpochUpdate) -> Result<(), &'static str> {
+        if self.params_digest != params.digest() || update.params_digest != self.params_digest {
+            return Err("Replacement is for different parameters");
+        }
+        let single = update.from_epoch.checked_add(1) == Some(update.to_epoch);
+        if !single || update.to_epoch != self.epoch || update.value != self.value {
+            return Err("Update is not the replacement's epoch");
+        }
+        if update.deleted != [self.removed] {
+            return Err("Update does not delete the replaced element");
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        match n.is_some_and(|n| signature::verify(params.hash, &n, &self.signed_bytes(), &self.signature)) {
+            true => Ok(()),
+            false => Err("Replacement signature is invalid"),
+        }
+    }
+
+    // Whether the statement is about these two elements.
+    pub fn is_for(&self, params: &PublicParams, old: impl AsRef<[u8]>, new: impl AsRef<[u8]>) -> bool {
+        params.hash_to_prime(old) == self.removed && params.hash_to_prime(new) == self.added
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, REPLACE_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let epoch = r.get_u64()?;
+        let removed = r.get_prime()?;
+        let added = r.get_prime()?;
+        let value = AccValue::new(r.get_residue(policy.modulus.as_ref())?);
+        let signature = r.get_residue(policy.modulus.as_ref())?;
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            epoch,
+            removed,
+            added,
+            value,
+            signature,
+        })
+    }
+}
+
+impl Replacement {
+    // verify on the proof, and that the witness opens the new value for the
+    // added prime.
+    pub fn verify(&self, params: &PublicParams) -> Result<(), &'static str> {
+        self.proof.verify(params, &self.update)?;
+        self.witness.check_params(params)?;
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let monty = MontyParams::new(n.ok_or("Modulus must be odd")?);
+        if !core::opens(&self.witness.value, &self.proof.added, &self.proof.value, &monty) {
+            return Err("Witness does not open the replacement's value");
+        }
+        Ok(())
+    }
+}
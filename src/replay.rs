@@ -0,0 +1,216 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, U512,
+};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::core;
+use crate::genesis::GenesisRecord;
+use crate::group::AccValue;
+use crate::params::PublicParams;
+use crate::report::hex;
+
+// One operation from the manager's log with the value it claims to have
+// left, as the journal records them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoggedOperation {
+    Add { element: Vec<u8>, value: AccValue },
+    Delete { elements: Vec<Vec<u8>>, value: AccValue }, // One epoch, as delete_batch publishes it
+}
+
+// Where a replay first disagreed with the log: the operation's index, or the
+// log's length for the claimed final state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,
+    pub reason: &'static str,
+    pub expected: Option<AccValue>, // None where only the trapdoor could compute it
+    pub claimed: AccValue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayVerdict {
+    Consistent { operations: usize, epoch: u64, members: usize },
+    Diverged(Divergence),
+}
+
+// Replays an operation log from the genesis parameters with public
+// information only, so a manager and its auditors can settle whether the log
+// explains the published state. Adds leave the value unchanged; a deletion's
+// claimed value is checked by raising it to the deleted primes, which must
+// give the previous value. Elements must be hash-to-prime elements added
+// once: members from add_prime, multiset copies and rerandomization are not
+// replayable.
+#[derive(Clone, Debug)]
+pub struct AuditReplayer {
+    params: PublicParams,
+    monty_params: MontyParams<8>,
+    value: AccValue,
+    epoch: u64,
+    members: BTreeSet<Vec<u8>>,
+    replayed: usize,
+    divergence: Option<Divergence>,
+}
+
+impl AuditReplayer {
+    // Starts at the parameters' base at epoch 0, once the genesis record
+    // is shown to describe the parameters.
+    pub fn new(genesis: &GenesisRecord, params: &PublicParams) -> Result<Self, &'static str> {
+        if !genesis.matches(params) {
+            return Err("Parameters do not match the genesis record");
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        Ok(Self {
+            params: *params,
+            monty_params: MontyParams::new(n.ok_or("Modulus must be odd")?),
+            value: params.base,
+            epoch: 0,
+            members: BTreeSet::new(),
+            replayed: 0,
+            divergence: None,
+        })
+    }
+
+    // Applies the next operation if it follows from the replay so far.
+    // After the first that does not, nothing more is applied and the
+    // divergence is kept for the verdict.
+    pub fn step(&mut self, op: &LoggedOperation) -> bool {
+        if self.divergence.is_none() {
+            self.divergence = self.apply(op);
+        }
+        self.divergence.is_none()
+    }
+
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
+    // Why op does not follow, or None once it is applied.
+    fn apply(&mut self, op: &LoggedOperation) -> Option<Divergence> {
+        let index = self.replayed;
+        let diverged = |reason, expected, claimed| Divergence {
+            index,
+            reason,
+            expected,
+            claimed,
+        };
+        match op {
+            LoggedOperation::Add { element, value } => {
+                if self.members.contains(element) {
+                    return Some(diverged("Element added twice", Some(self.value), *value));
+                }
+                if *value != self.value {
+                    return Some(diverged("Add changed the value", Some(self.value), *value));
+                }
+                self.members.insert(element.clone());
+            }
+            LoggedOperation::Delete { elements, value } => {
+                let distinct: BTreeSet<_> = elements.iter().collect();
+                if elements.is_empty() || distinct.len() != elements.len() {
+                    return Some(diverged("Deletion is empty or repeats an element", None, *value));
+                }
+                if elements.iter().any(|x| !self.members.contains(x)) {
+                    return Some(diverged("Deleted element is not a member", None, *value));
+                }
+                if !core::is_group_element(value.as_uint(), &self.params.n) {
+                    return Some(diverged("Claimed value is not a group element", None, *value));
+                }
+                let mut raised = MontyForm::new(value.as_uint(), self.monty_params);
+                for x in elements {
+                    raised = raised.pow(self.params.hash_to_prime(x).as_uint());
+                }
+                if raised.retrieve() != *self.value.as_uint() {
+                    return Some(diverged("Claimed value is not the root of the previous value", None, *value));
+                }
+                for x in elements {
+                    self.members.remove(x);
+                }
+                self.value = *value;
+                self.epoch += 1;
+            }
+        }
+        self.replayed += 1;
+        None
+    }
+
+    // The verdict once the log is replayed, against the claimed final state.
+    pub fn finish(&self, epoch: u64, value: &AccValue) -> ReplayVerdict {
+        if let Some(divergence) = &self.divergence {
+            return ReplayVerdict::Diverged(divergence.clone());
+        }
+        let diverged = |reason| {
+            ReplayVerdict::Diverged(Divergence {
+                index: self.replayed,
+                reason,
+                expected: Some(self.value),
+                claimed: *value,
+            })
+        };
+        if epoch != self.epoch {
+            return diverged("Final epoch differs from the replay");
+        }
+        if *value != self.value {
+            return diverged("Final value differs from the replay");
+        }
+        ReplayVerdict::Consistent {
+            operations: self.replayed,
+            epoch: self.epoch,
+            members: self.members.len(),
+        }
+    }
+
+    // The whole replay: the verdict for the log and the claimed final state.
+    pub fn replay(
+        genesis: &GenesisRecord,
+        params: &PublicParams,
+        log: &[LoggedOperation],
+        epoch: u64,
+        value: &AccValue,
+    ) -> Result<ReplayVerdict, &'static str> {
+        let mut replayer = Self::new(genesis, params)?;
+        log.iter().all(|op| replayer.step(op));
+        Ok(replayer.finish(epoch, value))
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn value(&self) -> AccValue {
+        self.value
+    }
+}
+
+impl ReplayVerdict {
+    pub fn is_consistent(&self) -> bool {
+        matches!(self, ReplayVerdict::Consistent { .. })
+    }
+
+    // One JSON object, with values as lowercase hex.
+    pub fn to_json(&self) -> String {
+        match self {
+            ReplayVerdict::Consistent {
+                operations,
+                epoch,
+                members,
+            } => format!(
+                "{{\"verdict\":\"consistent\",\"operations\":{},\"epoch\":{},\"members\":{}}}",
+                operations, epoch, members
+            ),
+            ReplayVerdict::Diverged(d) => {
+                let mut out = String::from("{\"verdict\":\"diverged\",");
+                let _ = write!(out, "\"index\":{},", d.index);
+                let _ = write!(out, "\"reason\":\"{}\",", d.reason);
+                match &d.expected {
+                    Some(expected) => {
+                        let _ = write!(out, "\"expected\":\"{}\",", hex(&expected.to_be_bytes()));
+                    }
+                    None => out.push_str("\"expected\":null,"),
+                }
+                let _ = write!(out, "\"claimed\":\"{}\"}}", hex(&d.claimed.to_be_bytes()));
+                out
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+
+use crate::cache::VerificationCache;
+use crate::chain::{self, Checkpoint};
+use crate::core;
+use crate::group::{AccValue, ElemPrime};
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+// A verification-only copy of an accumulator, following the primary through
+// its published checkpoints. Creating one is cheap; warm_up does the startup
+// work: checking the chain from the genesis parameters, deriving Montgomery
+// parameters, hashing the expected hot elements to primes and filling the
+// verification cache with their results. Verification exponentiates the
+// witness rather than a fixed base, so unlike the issuer there is no
+// fixed-base table to build. Until warm_up succeeds verify refuses and the
+// ReadySignal reads false, so health checks keep traffic on warmed replicas.
+#[derive(Debug)]
+pub struct ReadReplica {
+    params: PublicParams,
+    params_digest: [u8; 32],
+    chain: Vec<Checkpoint>,
+    cache: Arc<VerificationCache>,
+    ready: ReadySignal,
+    warm: Option<Warm>,
+}
+
+#[derive(Debug)]
+struct Warm {
+    monty_params: MontyParams<8>,
+    epoch: u64,
+    value: AccValue,
+    primes: HashMap<Vec<u8>, ElemPrime>, // Hot elements hashed during warm-up
+}
+
+// Readiness of a replica, cloned out to whatever answers health checks.
+#[derive(Clone, Debug, Default)]
+pub struct ReadySignal(Arc<AtomicBool>);
+
+impl ReadySignal {
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    fn set(&self, ready: bool) {
+        self.0.store(ready, Ordering::Release);
+    }
+}
+
+impl ReadReplica {
+    // `chain` is the primary's checkpoint chain (Manager::checkpoint_chain);
+    // nothing is checked until warm_up.
+    pub fn new(genesis: PublicParams, chain: Vec<Checkpoint>, cache: Arc<VerificationCache>) -> Self {
+        Self {
+            params_digest: genesis.digest(),
+            params: genesis,
+            chain,
+            cache,
+            ready: ReadySignal::default(),
+            warm: None,
+        }
+    }
+
+    // Checks the chain and precomputes for `hot`, elements expected to be
+    // presented often with their current witnesses. Fails, leaving the
+    // replica unready, if the chain does not verify.
+    pub fn warm_up<X: AsRef<[u8]>>(&mut self, hot: &[(X, Witness)]) -> Result<(), &'static str> {
+        self.ready.set(false);
+        self.warm = None;
+        let (epoch, value) = chain::verify_checkpoint_chain(&self.params, &self.chain)?;
+        let n: Option<Odd<U512>> = Odd::new(self.params.n).into();
+        let mut warm = Warm {
+            monty_params: MontyParams::new(n.ok_or("Modulus must be odd")?),
+            epoch,
+            value,
+            primes: HashMap::new(),
+        };
+        for (x, w) in hot {
+            let x = x.as_ref();
+            let prime = self.params.hash_to_prime(x);
+            warm.primes.insert(x.to_vec(), prime);
+            self.verify_warm(&warm, x, w)?;
+        }
+        self.warm = Some(warm);
+        self.ready.set(true);
+        Ok(())
+    }
+
+    pub fn ready(&self) -> bool {
+        self.ready.is_ready()
+    }
+
+    pub fn ready_signal(&self) -> ReadySignal {
+        self.ready.clone()
+    }
+
+    // Epoch and value the replica verifies against, once warmed.
+    pub fn current(&self) -> Option<(u64, AccValue)> {
+        self.warm.as_ref().map(|warm| (warm.epoch, warm.value))
+    }
+
+    // Follows the primary to its next checkpoint. A warmed replica checks
+    // the new link only and stays ready.
+    pub fn advance(&mut self, checkpoint: Checkpoint) -> Result<(), &'static str> {
+        if let Some(warm) = &mut self.warm {
+            chain::verify_link(&self.params, self.chain.last(), &checkpoint, &warm.monty_params)?;
+            warm.epoch = checkpoint.to_epoch;
+            warm.value = checkpoint.value;
+        }
+        self.chain.push(checkpoint);
+        Ok(())
+    }
+
+    // Whether x is a member at the current checkpoint.
+    pub fn verify(&self, x: impl AsRef<[u8]>, w: &Witness) -> Result<bool, &'static str> {
+        let warm = self.warm.as_ref().ok_or("Replica is not warmed up")?;
+        self.verify_warm(warm, x.as_ref(), w)
+    }
+
+    fn verify_warm(&self, warm: &Warm, x: &[u8], w: &Witness) -> Result<bool, &'static str> {
+        if w.params_digest != self.params_digest {
+            return Ok(false);
+        }
+        self.cache.verify_with(&self.params_digest, &warm.value, x, w, || {
+            let prime = match warm.primes.get(x) {
+                Some(prime) => *prime,
+                None => self.params.hash_to_prime(x),
+            };
+            Ok(core::opens(&w.value, &prime, &warm.value, &warm.monty_params))
+        })
+    }
+}
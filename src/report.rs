@@ -0,0 +1,110 @@
+use std::fmt::Write;
+
+use crate::group::ElemPrime;
+use crate::hash::HashAlgorithm;
+use crate::rotation::KeyStatus;
+
+// Deletions and operations listed in a report, at most.
+pub const REPORTED_OPERATIONS: usize = 16;
+
+// Diagnostic snapshot to attach to a bug report: sizes, counters and the
+// most recent operations. It holds nothing secret and no elements, which may
+// be personal data; deleted primes are listed since every update message
+// publishes them anyway.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateReport {
+    pub modulus_bits: u32,
+    pub hash: HashAlgorithm,
+    pub params_digest: [u8; 32],
+    pub epoch: u64,
+    pub member_count: usize,
+    pub deleted_count: usize,
+    pub element_cache_entries: usize,               // Element primes cached by the accumulator
+    pub precomputed_bytes: usize,                   // Fixed-base table, zero when off
+    pub verification_cache_entries: Option<usize>,  // None without a VerificationCache
+    pub checkpoints_kept: usize,                    // Past values kept for verify_at_epoch
+    pub key_operations: u64,                        // Trapdoor operations with the key, see KeyUsage
+    pub key_age_secs: Option<u64>,                  // None if the key's creation time is unknown
+    pub key_status: KeyStatus,                      // Against the builder's rotation thresholds
+    pub recent_deletions: Vec<ElemPrime>,           // Last deleted primes, oldest first
+    pub recent_operations: Vec<ReportedOperation>,  // From a manager's audit log, empty otherwise
+}
+
+// One audit log entry, without its element or metadata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportedOperation {
+    pub seq: u64,
+    pub epoch: u64,
+    pub operator: String,
+    pub operation: String, // Operation name, as in the audit log
+    pub allowed: bool,
+}
+
+impl StateReport {
+    // One JSON object, with digests and primes as lowercase hex.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        let _ = write!(out, "\"modulus_bits\":{},", self.modulus_bits);
+        let _ = write!(out, "\"hash\":\"{:?}\",", self.hash);
+        let _ = write!(out, "\"params_digest\":\"{}\",", hex(&self.params_digest));
+        let _ = write!(out, "\"epoch\":{},", self.epoch);
+        let _ = write!(out, "\"member_count\":{},", self.member_count);
+        let _ = write!(out, "\"deleted_count\":{},", self.deleted_count);
+        let _ = write!(out, "\"element_cache_entries\":{},", self.element_cache_entries);
+        let _ = write!(out, "\"precomputed_bytes\":{},", self.precomputed_bytes);
+        match self.verification_cache_entries {
+            Some(entries) => {
+                let _ = write!(out, "\"verification_cache_entries\":{},", entries);
+            }
+            None => out.push_str("\"verification_cache_entries\":null,"),
+        }
+        let _ = write!(out, "\"checkpoints_kept\":{},", self.checkpoints_kept);
+        let _ = write!(out, "\"key_operations\":{},", self.key_operations);
+        match self.key_age_secs {
+            Some(age) => {
+                let _ = write!(out, "\"key_age_secs\":{},", age);
+            }
+            None => out.push_str("\"key_age_secs\":null,"),
+        }
+        let _ = write!(out, "\"key_status\":\"{}\",", self.key_status.as_str());
+        let deletions: Vec<_> = self.recent_deletions.iter().map(|p| json_string(&hex(&p.to_be_bytes()))).collect();
+        let _ = write!(out, "\"recent_deletions\":[{}],", deletions.join(","));
+        let operations: Vec<_> = self
+            .recent_operations
+            .iter()
+            .map(|op| {
+                format!(
+                    "{{\"seq\":{},\"epoch\":{},\"operator\":{},\"operation\":{},\"allowed\":{}}}",
+                    op.seq,
+                    op.epoch,
+                    json_string(&op.operator),
+                    json_string(&op.operation),
+                    op.allowed
+                )
+            })
+            .collect();
+        let _ = write!(out, "\"recent_operations\":[{}]}}", operations.join(","));
+        out
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// A JSON string literal, escaping quotes, backslashes and control characters.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
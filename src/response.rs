@@ -0,0 +1,112 @@
+use crypto_bigint::U512;
+
+use crate::binding::verify_signature;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::params::PublicParams;
+use crate::witness::Witness;
+
+const WITNESS_RESPONSE_TAG: &[u8] = b"braavos-witness-response";
+const STATE_RESPONSE_TAG: &[u8] = b"braavos-state-response";
+
+// A witness as answered by the service, with a detached manager signature
+// over its canonical encoding. Clients that pin the parameters check it with
+// verify, so a response relayed through a cache or CDN cannot be altered or
+// swapped for another element's; an old one can still be replayed, which the
+// epoch lets the client notice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedWitness {
+    pub element: Vec<u8>,
+    pub epoch: u64, // Epoch the witness is valid at
+    pub witness: Witness,
+    pub signature: U512,
+}
+
+// The accumulator state as answered by the service, signed likewise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedState {
+    pub params_digest: [u8; 32],
+    pub epoch: u64,
+    pub value: AccValue,
+    pub signature: U512,
+}
+
+impl SignedWitness {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(WITNESS_RESPONSE_TAG);
+        w.put_bytes(&self.element);
+        w.put_u64(self.epoch);
+        w.put_bytes(&self.witness.to_bytes());
+        w.finish()
+    }
+
+    // Checks the signature and that the witness is for these parameters.
+    pub fn verify(&self, params: &PublicParams) -> bool {
+        self.witness.params_digest == params.digest() && verify_signature(params, &self.signed_bytes(), &self.signature)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, WITNESS_RESPONSE_TAG, policy)?;
+        let element = r.get_bytes()?.to_vec();
+        let epoch = r.get_u64()?;
+        let witness = Witness::from_bytes_with(r.get_bytes()?, policy)?;
+        let signature = r.get_residue(policy.modulus.as_ref())?;
+        r.finish()?;
+        Ok(Self {
+            element,
+            epoch,
+            witness,
+            signature,
+        })
+    }
+}
+
+impl SignedState {
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(STATE_RESPONSE_TAG);
+        w.put_bytes(&self.params_digest);
+        w.put_u64(self.epoch);
+        w.put_u512(self.value.as_uint());
+        w.finish()
+    }
+
+    pub fn verify(&self, params: &PublicParams) -> bool {
+        self.params_digest == params.digest() && verify_signature(params, &self.signed_bytes(), &self.signature)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, STATE_RESPONSE_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let epoch = r.get_u64()?;
+        let value = AccValue::new(r.get_element(policy.modulus.as_ref())?);
+        let signature = r.get_residue(policy.modulus.as_ref())?;
+        r.finish()?;
+        Ok(Self {
+            params_digest,
+            epoch,
+            value,
+            signature,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// How much the secret key has been used: trapdoor operations (roots,
+// inverses mod p'q' and signatures, one per element in a batch) and when it
+// was generated. Kept by the accumulator alongside the key and persisted
+// with it; state written before usage was tracked restores with a zero
+// count and no creation time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyUsage {
+    pub operations: u64,
+    pub created_at: Option<u64>, // Unix seconds, None if unknown
+}
+
+impl KeyUsage {
+    // Time since the key was generated, if known and not in the future.
+    pub fn age(&self, now: SystemTime) -> Option<Duration> {
+        let now = now.duration_since(UNIX_EPOCH).ok()?;
+        now.checked_sub(Duration::from_secs(self.created_at?))
+    }
+}
+
+// Thresholds past which rotating to a new key is advised; none by default.
+// Nothing is enforced: the key keeps working and the status is reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RotationPolicy {
+    pub max_operations: Option<u64>,
+    pub max_age: Option<Duration>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationReason {
+    Operations, // More trapdoor operations than max_operations
+    Age,        // Older than max_age
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyStatus {
+    Ok,
+    RotationAdvised(RotationReason),
+}
+
+impl RotationPolicy {
+    // A key of unknown age is only judged by its operation count.
+    pub fn status(&self, usage: &KeyUsage, now: SystemTime) -> KeyStatus {
+        if self.max_operations.is_some_and(|max| usage.operations > max) {
+            return KeyStatus::RotationAdvised(RotationReason::Operations);
+        }
+        if self.max_age.zip(usage.age(now)).is_some_and(|(max, age)| age > max) {
+            return KeyStatus::RotationAdvised(RotationReason::Age);
+        }
+        KeyStatus::Ok
+    }
+}
+
+impl KeyStatus {
+    // Name used in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStatus::Ok => "ok",
+            KeyStatus::RotationAdvised(RotationReason::Operations) => "rotation_advised_operations",
+            KeyStatus::RotationAdvised(RotationReason::Age) => "rotation_advised_age",
+        }
+    }
+}
+
+// Now, in the resolution KeyUsage keeps.
+pub(crate) fn unix_now() -> Option<u64> {
+    SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
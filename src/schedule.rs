@@ -0,0 +1,99 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::sync::lock;
+
+// Source of time for scheduling and quotas, so tests and simulations can run
+// hours of epochs without waiting for them.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// A clock that only moves when told to.
+pub struct ManualClock {
+    now: Mutex<Instant>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            now: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *lock(&self.now) += by;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *lock(&self.now)
+    }
+}
+
+impl fmt::Debug for ManualClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManualClock").finish_non_exhaustive()
+    }
+}
+
+// When queued deletions are committed as one epoch: once the oldest has
+// waited `interval`, or once `max_pending` are queued, whichever comes first.
+// With neither set every deletion is its own epoch, as without a scheduler.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EpochScheduler {
+    pub interval: Option<Duration>,
+    pub max_pending: Option<usize>,
+}
+
+impl EpochScheduler {
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval: Some(interval),
+            max_pending: None,
+        }
+    }
+
+    pub fn after_operations(max_pending: usize) -> Self {
+        Self {
+            interval: None,
+            max_pending: Some(max_pending),
+        }
+    }
+
+    pub fn or_after_operations(mut self, max_pending: usize) -> Self {
+        self.max_pending = Some(max_pending);
+        self
+    }
+
+    // Whether `pending` deletions, the oldest queued at `oldest`, are due.
+    pub fn is_due(&self, pending: usize, oldest: Instant, now: Instant) -> bool {
+        if pending == 0 {
+            return false;
+        }
+        match (self.interval, self.max_pending) {
+            (None, None) => true,
+            (interval, max_pending) => {
+                interval.is_some_and(|i| now.saturating_duration_since(oldest) >= i)
+                    || max_pending.is_some_and(|m| pending >= m)
+            }
+        }
+    }
+}
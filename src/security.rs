@@ -0,0 +1,54 @@
+use std::fmt;
+
+use crate::hash::ELEMENT_PRIME_BITS;
+use crate::params::PublicParams;
+
+// Rough classical security of a configuration: the weaker of factoring the
+// modulus and finding two elements that hash to the same prime. Factoring is
+// the GNFS heuristic L_n[1/3, (64/9)^(1/3)], shifted so a 2048-bit modulus
+// comes out at NIST's 112 bits (SP 800-57); it flatters moduli far below
+// that size, which fall to simpler methods. A planning aid, not a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecurityEstimate {
+    pub modulus_bits: u32,
+    pub factoring_bits: u32,
+    pub element_prime_bits: u32,
+    pub collision_bits: u32, // Birthday bound on element prime collisions
+    pub bits: u32,           // The minimum of the two
+}
+
+impl SecurityEstimate {
+    pub fn for_params(params: &PublicParams) -> Self {
+        Self::for_modulus_bits(params.n.bits())
+    }
+
+    // The estimate for a modulus of this size, e.g. 2 * prime_bits before
+    // the keys are generated.
+    pub fn for_modulus_bits(modulus_bits: u32) -> Self {
+        let factoring_bits = (gnfs_bits(modulus_bits) - gnfs_bits(2048) + 112.0).max(0.0) as u32;
+        let collision_bits = ELEMENT_PRIME_BITS / 2;
+        Self {
+            modulus_bits,
+            factoring_bits,
+            element_prime_bits: ELEMENT_PRIME_BITS,
+            collision_bits,
+            bits: factoring_bits.min(collision_bits),
+        }
+    }
+}
+
+// log2 of L_n[1/3, (64/9)^(1/3)] for an n of `bits` bits.
+fn gnfs_bits(bits: u32) -> f64 {
+    let ln_n = bits as f64 * std::f64::consts::LN_2;
+    (64.0f64 / 9.0).cbrt() * ln_n.cbrt() * ln_n.ln().powf(2.0 / 3.0) / std::f64::consts::LN_2
+}
+
+impl fmt::Display for SecurityEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "~{} bits ({}-bit modulus: ~{} bits against factoring; {}-bit element primes: ~{} bits against collisions)",
+            self.bits, self.modulus_bits, self.factoring_bits, self.element_prime_bits, self.collision_bits
+        )
+    }
+}
@@ -0,0 +1,488 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+
+use crate::accumulator::BraavosAccumulator;
+use crate::builder::AccumulatorBuilder;
+use crate::deadline::Deadline;
+use crate::encoding::{Reader, Writer};
+use crate::hash::HashAlgorithm;
+use crate::idempotency::DEFAULT_IDEMPOTENCY_WINDOW;
+use crate::manager::{AllowAll, Manager, Policy, RateLimit};
+use crate::response::{SignedState, SignedWitness};
+use crate::schedule::{Clock, EpochScheduler, SystemClock};
+use crate::sse::SseFeed;
+use crate::stream::UpdateStream;
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const TOKEN_DOMAIN: &[u8] = b"braavos-tenant-token";
+const TENANT_FILE: &str = "tenant";
+const TENANT_TAG: &[u8] = b"braavos-tenant-record";
+const METADATA_TAG: &[u8] = b"braavos-tenant-metadata";
+const PENDING_TAG: &[u8] = b"braavos-tenant-pending";
+// Tenants persisted before the single record kept one file per part
+const LEGACY_FILES: [&str; 4] = ["state", "metadata", "pending", "idempotency"];
+
+// Limits applied to one tenant across all of its operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+    pub max_members: usize,
+    pub requests: RateLimit,
+}
+
+impl Default for Quota {
+    fn default() -> Self {
+        Self {
+            max_members: 100_000,
+            requests: RateLimit {
+                max: 1000,
+                window: Duration::from_secs(60),
+            },
+        }
+    }
+}
+
+struct Tenant<P: Policy> {
+    token_digest: [u8; 32],
+    quota: Quota,
+    dir: PathBuf,
+    manager: Manager<P>,
+    requests: VecDeque<Instant>, // Recent requests, for the quota
+    batch_started: Instant,      // When the oldest queued deletion was queued
+}
+
+// Hosts one accumulator per tenant, each with its own keys, policy, quota and
+// state directory under the service root. Tenants only reach their own
+// accumulator, through a session opened with their token.
+pub struct Service<P: Policy = AllowAll> {
+    root: PathBuf,
+    tenants: HashMap<String, Tenant<P>>,
+    scheduler: EpochScheduler,
+    clock: Arc<dyn Clock>,
+    idempotency_window: usize,
+}
+
+impl<P: Policy> Service<P> {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, &'static str> {
+        let root = root.into();
+        fs::create_dir_all(&root).map_err(|_| "Failed to create service root")?;
+        Ok(Self {
+            root,
+            tenants: HashMap::new(),
+            scheduler: EpochScheduler::default(),
+            clock: Arc::new(SystemClock),
+            idempotency_window: DEFAULT_IDEMPOTENCY_WINDOW,
+        })
+    }
+
+    // Sets when deletions made through Session::schedule_delete are committed
+    // as an epoch, for every tenant. The default commits each one at once.
+    pub fn with_scheduler(mut self, scheduler: EpochScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    // The clock behind scheduling and request quotas.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // How many idempotency keys each tenant remembers (see
+    // Session::add_idempotent); applies to tenants opened afterwards.
+    pub fn with_idempotency_window(mut self, capacity: usize) -> Self {
+        self.idempotency_window = capacity;
+        self
+    }
+
+    // Hosts `name`. State left in its directory by an earlier run is restored
+    // with its keys; otherwise the builder generates fresh ones.
+    pub fn open_tenant(
+        &mut self,
+        name: &str,
+        token: &[u8],
+        quota: Quota,
+        policy: P,
+        builder: AccumulatorBuilder,
+    ) -> Result<(), &'static str> {
+        let valid_name = !name.is_empty()
+            && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        if !valid_name {
+            return Err("Tenant names may only contain letters, digits, '-' and '_'");
+        }
+        if self.tenants.contains_key(name) {
+            return Err("Tenant is already open");
+        }
+        let dir = self.root.join(name);
+        fs::create_dir_all(&dir).map_err(|_| "Failed to create tenant directory")?;
+        let [state, metadata, pending, idempotency] = read_record(&dir)?;
+        let acc = match state {
+            Some(bytes) => BraavosAccumulator::from_state_bytes(&bytes, &builder)?,
+            None => builder.build()?,
+        };
+        let mut manager = Manager::new(acc, policy).with_idempotency_window(self.idempotency_window);
+        if let Some(bytes) = metadata {
+            manager.restore_metadata(decode_metadata(&bytes)?);
+        }
+        // Deletions queued before a restart were authorized then; the batch
+        // restarts its interval
+        if let Some(bytes) = pending {
+            manager.restore_pending(decode_pending(&bytes)?);
+        }
+        // Keys of requests answered before a restart, so their retries are
+        // still recognised
+        if let Some(bytes) = idempotency {
+            manager.restore_idempotency(&bytes)?;
+        }
+        let tenant = Tenant {
+            token_digest: token_digest(name, token),
+            quota,
+            dir,
+            manager,
+            requests: VecDeque::new(),
+            batch_started: self.clock.now(),
+        };
+        tenant.persist()?;
+        self.tenants.insert(name.to_string(), tenant);
+        Ok(())
+    }
+
+    pub fn tenants(&self) -> impl Iterator<Item = &str> {
+        self.tenants.keys().map(String::as_str)
+    }
+
+    // Commits every tenant's queued deletions that the scheduler says are
+    // due, returning the published updates by tenant. Call it periodically;
+    // schedule_delete only commits when a deletion arrives.
+    pub fn tick(&mut self) -> Result<Vec<(String, EpochUpdate)>, &'static str> {
+        let now = self.clock.now();
+        let mut published = Vec::new();
+        let mut tenants: Vec<_> = self.tenants.iter_mut().collect();
+        tenants.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, tenant) in tenants {
+            if let Some(update) = tenant.commit_if_due(&self.scheduler, now)? {
+                published.push((name.clone(), update));
+            }
+        }
+        Ok(published)
+    }
+
+    // How long until tick has a batch to commit, for callers driving the
+    // service from an async runtime: sleep this long on the runtime's own
+    // timer, then tick. None when no queued batch is waiting on the interval.
+    pub fn next_tick(&self) -> Option<Duration> {
+        let interval = self.scheduler.interval?;
+        let now = self.clock.now();
+        self.tenants
+            .values()
+            .filter(|tenant| !tenant.manager.pending_deletions().is_empty())
+            .map(|tenant| interval.saturating_sub(now.saturating_duration_since(tenant.batch_started)))
+            .min()
+    }
+
+    // Unknown tenants and wrong tokens fail alike, so names cannot be probed.
+    pub fn session(&mut self, name: &str, token: &[u8]) -> Result<Session<'_, P>, &'static str> {
+        let digest = token_digest(name, token);
+        match self.tenants.get_mut(name) {
+            Some(tenant) if bool::from(tenant.token_digest.ct_eq(&digest)) => Ok(Session {
+                tenant,
+                deadline: Deadline::none(),
+                scheduler: self.scheduler,
+                clock: self.clock.as_ref(),
+            }),
+            _ => Err("Authentication failed"),
+        }
+    }
+}
+
+// Authenticated access to one tenant's manager.
+pub struct Session<'a, P: Policy> {
+    tenant: &'a mut Tenant<P>,
+    deadline: Deadline,
+    scheduler: EpochScheduler,
+    clock: &'a dyn Clock,
+}
+
+impl<P: Policy> Session<'_, P> {
+    // Bounds each add, delete and verify of the session, for request-scoped
+    // callers: past the deadline they fail with TIMEOUT. Quota is still charged.
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub fn add(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        let acc = self.tenant.manager.accumulator();
+        if !acc.contains(x) && acc.member_count() >= self.tenant.quota.max_members {
+            return Err("Tenant member quota exceeded");
+        }
+        let witness = self.tenant.manager.add_within(operator, x, &self.deadline)?;
+        self.tenant.persist()?;
+        Ok(witness)
+    }
+
+    pub fn add_with_metadata(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+        metadata: &[u8],
+    ) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        let acc = self.tenant.manager.accumulator();
+        if !acc.contains(x) && acc.member_count() >= self.tenant.quota.max_members {
+            return Err("Tenant member quota exceeded");
+        }
+        let witness = self.tenant.manager.add_with_metadata_within(operator, x, metadata, &self.deadline)?;
+        self.tenant.persist()?;
+        Ok(witness)
+    }
+
+    pub fn delete(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        let update = self.tenant.manager.delete_within(operator, x, &self.deadline)?;
+        self.tenant.persist()?;
+        Ok(update)
+    }
+
+    // As add and delete, keyed by a client-supplied idempotency key: a
+    // retried request gets the original answer and changes nothing. Keys are
+    // persisted with the tenant, so retries across a restart are recognised.
+    pub fn add_idempotent(&mut self, operator: &str, key: &[u8], x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        let acc = self.tenant.manager.accumulator();
+        if !acc.contains(x) && acc.member_count() >= self.tenant.quota.max_members {
+            return Err("Tenant member quota exceeded");
+        }
+        let witness = self.tenant.manager.add_idempotent_within(operator, key, x, &self.deadline)?;
+        self.tenant.persist()?;
+        Ok(witness)
+    }
+
+    pub fn delete_idempotent(
+        &mut self,
+        operator: &str,
+        key: &[u8],
+        x: impl AsRef<[u8]>,
+    ) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        let update = self.tenant.manager.delete_idempotent_within(operator, key, x, &self.deadline)?;
+        self.tenant.persist()?;
+        Ok(update)
+    }
+
+    // Queues the deletion for the tenant's next epoch, committing the batch
+    // if the scheduler says it is due; the update when it was.
+    pub fn schedule_delete(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+    ) -> Result<Option<EpochUpdate>, &'static str> {
+        let now = self.clock.now();
+        self.tenant.charge(now)?;
+        if self.tenant.manager.pending_deletions().is_empty() {
+            self.tenant.batch_started = now;
+        }
+        self.tenant.manager.queue_delete(operator, x)?;
+        self.tenant.persist()?;
+        self.tenant.commit_if_due(&self.scheduler, now)
+    }
+
+    // Commits whatever is queued now, due or not.
+    pub fn commit(&mut self) -> Result<Option<EpochUpdate>, &'static str> {
+        let update = self.tenant.manager.commit_deletions()?;
+        self.tenant.persist()?;
+        Ok(update)
+    }
+
+    pub fn recover_witness(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        self.tenant.manager.recover_witness(operator, x)
+    }
+
+    // add and recover_witness, answered with a signed response for clients
+    // reached through untrusted relays.
+    pub fn add_signed(&mut self, operator: &str, x: impl AsRef<[u8]>) -> Result<SignedWitness, &'static str> {
+        let x = x.as_ref();
+        let witness = self.add(operator, x)?;
+        self.tenant.manager.sign_witness(x, witness)
+    }
+
+    pub fn recover_witness_signed(
+        &mut self,
+        operator: &str,
+        x: impl AsRef<[u8]>,
+    ) -> Result<SignedWitness, &'static str> {
+        let x = x.as_ref();
+        let witness = self.recover_witness(operator, x)?;
+        self.tenant.manager.sign_witness(x, witness)
+    }
+
+    // The tenant's current epoch and value, signed.
+    pub fn state(&mut self) -> Result<SignedState, &'static str> {
+        self.tenant.charge(self.clock.now())?;
+        self.tenant.manager.signed_state()
+    }
+
+    pub fn verify(&mut self, x: impl AsRef<[u8]>, w: Witness) -> Result<bool, &'static str> {
+        let x = x.as_ref();
+        self.tenant.charge(self.clock.now())?;
+        self.tenant.manager.verify_within(x, w, &self.deadline)
+    }
+
+    // The tenant's updates as they are published (see UpdateStream).
+    pub fn subscribe(&mut self) -> UpdateStream {
+        self.tenant.manager.subscribe()
+    }
+
+    // The tenant's updates as server-sent events, resuming after the
+    // client's Last-Event-ID header if it sent one (see SseFeed).
+    pub fn sse_feed(&mut self, last_event_id: Option<&str>) -> Result<SseFeed, &'static str> {
+        self.tenant.charge(self.clock.now())?;
+        let manager = &mut self.tenant.manager;
+        let stream = manager.subscribe();
+        SseFeed::resume(stream, manager.update_log(), last_event_id, manager.accumulator().epoch())
+    }
+
+    pub fn manager(&self) -> &Manager<P> {
+        &self.tenant.manager
+    }
+}
+
+impl<P: Policy> Tenant<P> {
+    fn charge(&mut self, now: Instant) -> Result<(), &'static str> {
+        let window = self.quota.requests.window;
+        while self.requests.front().is_some_and(|t| now.duration_since(*t) >= window) {
+            self.requests.pop_front();
+        }
+        if self.requests.len() >= self.quota.requests.max {
+            return Err("Tenant request quota exceeded");
+        }
+        self.requests.push_back(now);
+        Ok(())
+    }
+
+    // The record holds the tenant's secret key. State, metadata, pending
+    // deletions and the idempotency window are written as one file, so a
+    // restart never sees an update without the key that answered it.
+    fn persist(&self) -> Result<(), &'static str> {
+        let mut metadata = Writer::new(METADATA_TAG);
+        metadata.put_u32(self.manager.metadata().len() as u32);
+        for (x, record) in self.manager.metadata() {
+            metadata.put_bytes(x);
+            metadata.put_bytes(record);
+        }
+        let mut pending = Writer::new(PENDING_TAG);
+        pending.put_u32(self.manager.pending_deletions().len() as u32);
+        for x in self.manager.pending_deletions() {
+            pending.put_bytes(x);
+        }
+        let mut w = Writer::new(TENANT_TAG);
+        w.put_bytes(&self.manager.accumulator().to_state_bytes()?);
+        w.put_bytes(&metadata.finish());
+        w.put_bytes(&pending.finish());
+        w.put_bytes(&self.manager.idempotency().to_bytes());
+        write_durable(&self.dir, TENANT_FILE, &w.finish())?;
+        for name in LEGACY_FILES {
+            match fs::remove_file(self.dir.join(name)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err("Failed to write tenant state"),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn commit_if_due(&mut self, scheduler: &EpochScheduler, now: Instant) -> Result<Option<EpochUpdate>, &'static str> {
+        let pending = self.manager.pending_deletions().len();
+        if !scheduler.is_due(pending, self.batch_started, now) {
+            return Ok(None);
+        }
+        let update = self.manager.commit_deletions()?;
+        self.persist()?;
+        Ok(update)
+    }
+}
+
+// Replaces dir/name with bytes: the new contents are synced to disk before
+// the rename, and the directory after it, so a crash leaves either the old
+// file or the new one, and a completed write survives power loss.
+fn write_durable(dir: &Path, name: &str, bytes: &[u8]) -> Result<(), &'static str> {
+    let tmp = dir.join(format!("{}.tmp", name));
+    let mut file = fs::File::create(&tmp).map_err(|_| "Failed to write tenant state")?;
+    file.write_all(bytes).and_then(|_| file.sync_all()).map_err(|_| "Failed to write tenant state")?;
+    fs::rename(&tmp, dir.join(name)).map_err(|_| "Failed to write tenant state")?;
+    sync_dir(dir)
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<(), &'static str> {
+    fs::File::open(dir).and_then(|d| d.sync_all()).map_err(|_| "Failed to write tenant state")
+}
+
+// Directories cannot be opened as files here; the rename is durable once
+// it returns
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> Result<(), &'static str> {
+    Ok(())
+}
+
+// The parts of the tenant record (state, metadata, pending deletions,
+// idempotency window), each None when the tenant is new. Falls back to the
+// per-part files of older versions.
+fn read_record(dir: &Path) -> Result<[Option<Vec<u8>>; 4], &'static str> {
+    if let Some(bytes) = read_optional(&dir.join(TENANT_FILE))? {
+        let mut r = Reader::new(&bytes, TENANT_TAG)?;
+        let mut parts = [None, None, None, None];
+        for part in &mut parts {
+            *part = Some(r.get_bytes()?.to_vec());
+        }
+        r.finish()?;
+        return Ok(parts);
+    }
+    let mut parts = [None, None, None, None];
+    for (part, name) in parts.iter_mut().zip(LEGACY_FILES) {
+        *part = read_optional(&dir.join(name))?;
+    }
+    Ok(parts)
+}
+
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>, &'static str> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(_) => Err("Failed to read tenant state"),
+    }
+}
+
+fn decode_metadata(bytes: &[u8]) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, &'static str> {
+    let mut r = Reader::new(bytes, METADATA_TAG)?;
+    let mut metadata = BTreeMap::new();
+    for _ in 0..r.get_count()? {
+        let x = r.get_bytes()?.to_vec();
+        metadata.insert(x, r.get_bytes()?.to_vec());
+    }
+    r.finish()?;
+    Ok(metadata)
+}
+
+fn decode_pending(bytes: &[u8]) -> Result<Vec<Vec<u8>>, &'static str> {
+    let mut r = Reader::new(bytes, PENDING_TAG)?;
+    let pending = (0..r.get_count()?).map(|_| r.get_bytes().map(<[u8]>::to_vec)).collect::<Result<_, _>>()?;
+    r.finish()?;
+    Ok(pending)
+}
+
+fn token_digest(name: &str, token: &[u8]) -> [u8; 32] {
+    HashAlgorithm::Sha256.hash(TOKEN_DOMAIN, &[name.as_bytes(), token])
+}
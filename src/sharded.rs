@@ -0,0 +1,189 @@
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+use std::thread;
+
+use crate::accumulator::BraavosAccumulator;
+use crate::builder::AccumulatorBuilder;
+use crate::core;
+use crate::group::AccValue;
+use crate::hash::HashAlgorithm;
+use crate::params::PublicParams;
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const SHARD_DOMAIN: &[u8] = b"braavos-shard";
+
+// Elements spread over independent accumulators by a hash of the element, so
+// trapdoor operations on different shards run in parallel. Each shard has its
+// own keys, value and epochs; a witness is only valid in its element's shard,
+// and holders follow only that shard's updates.
+pub struct ShardedAccumulator {
+    hash: HashAlgorithm,
+    shards: Vec<BraavosAccumulator>,
+}
+
+// A witness together with the shard it was issued by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShardedWitness {
+    pub shard: u32,
+    pub witness: Witness,
+}
+
+impl ShardedAccumulator {
+    // Generates `shards` accumulators from the builder, in parallel.
+    pub fn new(shards: u32, builder: AccumulatorBuilder) -> Result<Self, &'static str> {
+        if shards == 0 {
+            return Err("At least one shard is needed");
+        }
+        let hash = builder.hash;
+        let shards = thread::scope(|s| {
+            let handles: Vec<_> = (0..shards).map(|_| s.spawn(|| builder.clone().build())).collect();
+            handles.into_iter().map(|h| h.join().unwrap_or(Err("Key generation panicked"))).collect::<Result<_, _>>()
+        })?;
+        Ok(Self { hash, shards })
+    }
+
+    pub fn shard_count(&self) -> u32 {
+        self.shards.len() as u32
+    }
+
+    // The shard responsible for x.
+    pub fn shard_of(&self, x: impl AsRef<[u8]>) -> u32 {
+        route(self.hash, self.shard_count(), x.as_ref())
+    }
+
+    pub fn shard(&self, index: u32) -> Option<&BraavosAccumulator> {
+        self.shards.get(index as usize)
+    }
+
+    // Parameters and current value of every shard, in shard order: what a
+    // verifier needs for ShardedWitness::verify.
+    pub fn params(&self) -> Vec<PublicParams> {
+        self.shards.iter().map(BraavosAccumulator::params).collect()
+    }
+
+    pub fn values(&self) -> Vec<AccValue> {
+        self.shards.iter().map(BraavosAccumulator::value).collect()
+    }
+
+    pub fn epochs(&self) -> Vec<u64> {
+        self.shards.iter().map(BraavosAccumulator::epoch).collect()
+    }
+
+    pub fn member_count(&self) -> usize {
+        self.shards.iter().map(BraavosAccumulator::member_count).sum()
+    }
+
+    pub fn contains(&self, x: impl AsRef<[u8]>) -> bool {
+        let x = x.as_ref();
+        self.shards[self.shard_of(x) as usize].contains(x)
+    }
+
+    pub fn add(&mut self, x: impl AsRef<[u8]>) -> Result<ShardedWitness, &'static str> {
+        let x = x.as_ref();
+        let shard = self.shard_of(x);
+        let witness = self.shards[shard as usize].add(x)?;
+        Ok(ShardedWitness { shard, witness })
+    }
+
+    // The update is for holders in the returned shard only.
+    pub fn delete(&mut self, x: impl AsRef<[u8]>) -> Result<(u32, EpochUpdate), &'static str> {
+        let x = x.as_ref();
+        let shard = self.shard_of(x);
+        Ok((shard, self.shards[shard as usize].delete(x)?))
+    }
+
+    pub fn verify(&mut self, x: impl AsRef<[u8]>, w: &ShardedWitness) -> bool {
+        let x = x.as_ref();
+        w.shard == self.shard_of(x) && self.shards[w.shard as usize].verify(x, w.witness)
+    }
+
+    // Every pair must verify.
+    pub fn verify_all(&mut self, items: &[(&[u8], ShardedWitness)]) -> bool {
+        items.iter().all(|(x, w)| self.verify(x, w))
+    }
+
+    // Adds the elements with one thread per shard touched. Results are in
+    // input order.
+    pub fn add_batch(&mut self, xs: &[&[u8]]) -> Vec<Result<ShardedWitness, &'static str>> {
+        self.per_shard(xs, |acc, x| acc.add(x))
+            .into_iter()
+            .map(|(shard, r)| r.map(|witness| ShardedWitness { shard, witness }))
+            .collect()
+    }
+
+    // Deletes the elements with one thread per shard touched; within a shard
+    // they are deleted in input order. Results are in input order, each with
+    // the shard whose holders need the update.
+    pub fn delete_batch(&mut self, xs: &[&[u8]]) -> Vec<Result<(u32, EpochUpdate), &'static str>> {
+        self.per_shard(xs, |acc, x| acc.delete(x))
+            .into_iter()
+            .map(|(shard, r)| r.map(|update| (shard, update)))
+            .collect()
+    }
+
+    fn per_shard<T: Send>(
+        &mut self,
+        xs: &[&[u8]],
+        op: impl Fn(&mut BraavosAccumulator, &[u8]) -> Result<T, &'static str> + Sync,
+    ) -> Vec<(u32, Result<T, &'static str>)> {
+        let count = self.shard_count();
+        let routes: Vec<u32> = xs.iter().map(|x| route(self.hash, count, x)).collect();
+        let mut results: Vec<Option<Result<T, &'static str>>> = xs.iter().map(|_| None).collect();
+        thread::scope(|s| {
+            let handles: Vec<_> = self
+                .shards
+                .iter_mut()
+                .enumerate()
+                .filter(|(i, _)| routes.contains(&(*i as u32)))
+                .map(|(i, acc)| {
+                    let (routes, op) = (&routes, &op);
+                    s.spawn(move || {
+                        let mine = (0..xs.len()).filter(|&j| routes[j] == i as u32);
+                        mine.map(|j| (j, op(acc, xs[j]))).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            // A shard that panicked leaves its elements unanswered
+            for done in handles.into_iter().filter_map(|h| h.join().ok()) {
+                for (j, result) in done {
+                    results[j] = Some(result);
+                }
+            }
+        });
+        routes
+            .into_iter()
+            .zip(results)
+            .map(|(shard, r)| (shard, r.unwrap_or(Err("Shard operation panicked"))))
+            .collect()
+    }
+}
+
+impl ShardedWitness {
+    // Checks the witness offline against published shard parameters and
+    // values (ShardedAccumulator::params and values).
+    pub fn verify(&self, params: &[PublicParams], values: &[AccValue], x: impl AsRef<[u8]>) -> bool {
+        let x = x.as_ref();
+        let shard = self.shard as usize;
+        let (Some(shard_params), Some(value)) = (params.get(shard), values.get(shard)) else {
+            return false;
+        };
+        if params.len() != values.len() || route(shard_params.hash, params.len() as u32, x) != self.shard {
+            return false;
+        }
+        if self.witness.check_params(shard_params).is_err() {
+            return false;
+        }
+        let n: Option<Odd<U512>> = Odd::new(shard_params.n).into();
+        let Some(n) = n else {
+            return false;
+        };
+        core::opens(&self.witness.value, &shard_params.hash_to_prime(x), value, &MontyParams::new(n))
+    }
+}
+
+fn route(hash: HashAlgorithm, shards: u32, x: &[u8]) -> u32 {
+    let digest = hash.hash(SHARD_DOMAIN, &[x]);
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(head) % shards as u64) as u32
+}
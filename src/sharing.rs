@@ -0,0 +1,282 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    CheckedSub, Odd, RandomMod, U2048, U256, U512,
+};
+use rand::rngs::OsRng;
+use std::fmt;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::params::PublicParams;
+
+const SHARE_TAG: &[u8] = b"braavos-key-share";
+const COMMITMENTS_TAG: &[u8] = b"braavos-share-commitments";
+
+// Feldman commitments live in the 2048-bit MODP group of RFC 3526 (group 14):
+// P = 2q + 1 is a safe prime and 2 generates the subgroup of prime order q.
+// Shares are points of a polynomial over GF(q), so they are 2047-bit numbers.
+pub(crate) const GROUP_MODULUS: U2048 = U2048::from_be_hex(concat!(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+    "3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF",
+));
+pub(crate) const GROUP_ORDER: U2048 = GROUP_MODULUS.shr_vartime(1);
+
+// The trapdoor p'q'. Only leaves the accumulator for key ceremonies, and
+// compares in constant time.
+#[derive(Clone, Copy)]
+pub struct SecretKey(U256);
+
+// One custodian's share of a secret key; compares in constant time.
+#[derive(Clone, Debug)]
+pub struct KeyShare {
+    pub index: u32,   // Evaluation point, 1-based and distinct per custodian
+    pub value: U2048, // Polynomial value at index, mod q
+}
+
+// Published by the dealer alongside the shares: g^a_j for every coefficient of
+// the sharing polynomial, so each custodian can check their share on receipt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareCommitments {
+    pub commitments: Vec<U2048>, // One per coefficient, constant term first
+}
+
+impl SecretKey {
+    pub(crate) fn new(sk: U256) -> Self {
+        Self(sk)
+    }
+
+    pub(crate) fn as_uint(&self) -> &U256 {
+        &self.0
+    }
+
+    // Whether this is the trapdoor of the given modulus: with n = (2p' + 1)(2q' + 1)
+    // and sk = p'q', p' + q' = (n - 1 - 4sk) / 2 and p', q' follow as the
+    // roots of a quadratic.
+    pub fn matches(&self, params: &PublicParams) -> bool {
+        let sk: U512 = self.0.resize();
+        let four_sk = sk.shl_vartime(2);
+        if params.n <= four_sk || self.0 == U256::ZERO {
+            return false;
+        }
+        let twice_sum = params.n.wrapping_sub(&four_sk).wrapping_sub(&U512::ONE);
+        let sum = twice_sum.shr_vartime(1);
+        // p' + q' is at most 129 bits for 128-bit primes; anything wider is no match
+        if twice_sum.bit_vartime(0) || sum.bits() > 256 {
+            return false;
+        }
+        let Some(disc) = sum.wrapping_mul(&sum).checked_sub(&four_sk).into_option() else {
+            return false;
+        };
+        let root = disc.sqrt_vartime();
+        if root.wrapping_mul(&root) != disc || sum < root {
+            return false;
+        }
+        let p = sum.wrapping_sub(&root).shr_vartime(1);
+        let q = sum.wrapping_add(&root).shr_vartime(1);
+        p.wrapping_mul(&q).ct_eq(&sk).into()
+    }
+
+    // Splits the key so that any `threshold` of the `shares` reconstruct it and
+    // fewer learn nothing.
+    pub fn split(&self, threshold: u32, shares: u32) -> Result<(Vec<KeyShare>, ShareCommitments), &'static str> {
+        if threshold == 0 || threshold > shares {
+            return Err("Threshold must be between 1 and the number of shares");
+        }
+        let field = field_params()?;
+        let order = field.modulus().as_nz_ref();
+        let mut coefficients = vec![MontyForm::new(&self.0.resize(), field)];
+        for _ in 1..threshold {
+            coefficients.push(MontyForm::new(&U2048::random_mod(&mut OsRng, order), field));
+        }
+
+        let group = group_params()?;
+        let g = MontyForm::new(&U2048::from(2u8), group);
+        let commitments = coefficients.iter().map(|a| g.pow(&a.retrieve()).retrieve()).collect();
+        let shares = (1..=shares)
+            .map(|index| {
+                let x = MontyForm::new(&U2048::from(index), field);
+                let value = coefficients.iter().rev().fold(MontyForm::zero(field), |acc, a| acc.mul(&x).add(a));
+                KeyShare {
+                    index,
+                    value: value.retrieve(),
+                }
+            })
+            .collect();
+        Ok((shares, ShareCommitments { commitments }))
+    }
+
+    // Lagrange interpolation at zero. Needs at least the threshold number of
+    // shares; check each with ShareCommitments::verify first, since a single
+    // bad share silently yields a wrong key.
+    pub fn combine(shares: &[KeyShare]) -> Result<Self, &'static str> {
+        if shares.is_empty() {
+            return Err("No shares to combine");
+        }
+        let field = field_params()?;
+        let mut secret = MontyForm::zero(field);
+        for (i, share) in shares.iter().enumerate() {
+            if share.index == 0 || shares[..i].iter().any(|s| s.index == share.index) {
+                return Err("Share indices must be distinct and nonzero");
+            }
+            if share.value >= GROUP_ORDER {
+                return Err("Share value out of range");
+            }
+            let x_i = MontyForm::new(&U2048::from(share.index), field);
+            let mut lambda = MontyForm::one(field);
+            for other in shares.iter().filter(|s| s.index != share.index) {
+                let x_j = MontyForm::new(&U2048::from(other.index), field);
+                let inv: Option<MontyForm<32>> = x_j.sub(&x_i).inv().into();
+                lambda = lambda.mul(&x_j).mul(&inv.ok_or("Share indices must be distinct and nonzero")?);
+            }
+            secret = secret.add(&lambda.mul(&MontyForm::new(&share.value, field)));
+        }
+        let secret = secret.retrieve();
+        if secret.bits() > U256::BITS {
+            return Err("Shares do not reconstruct a secret key");
+        }
+        Ok(Self(secret.resize()))
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+impl ConstantTimeEq for SecretKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for SecretKey {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(U256::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl ConstantTimeEq for KeyShare {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.index.ct_eq(&other.index) & self.value.ct_eq(&other.value)
+    }
+}
+
+impl PartialEq for KeyShare {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for KeyShare {}
+
+impl KeyShare {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(SHARE_TAG);
+        w.put_u32(self.index);
+        w.put_u2048(&self.value);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, SHARE_TAG, policy)?;
+        let index = r.get_u32()?;
+        let value = r.get_u2048()?;
+        r.finish()?;
+        if policy.canonical && (index == 0 || value >= GROUP_ORDER) {
+            return Err("Share is not canonical");
+        }
+        Ok(Self { index, value })
+    }
+}
+
+impl ShareCommitments {
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+
+    // g^share = prod C_j^(index^j), evaluated Horner-style in the exponent.
+    pub fn verify(&self, share: &KeyShare) -> bool {
+        let Some((last, rest)) = self.commitments.split_last() else {
+            return false;
+        };
+        if share.index == 0 || share.value >= GROUP_ORDER || self.commitments.iter().any(|c| *c >= GROUP_MODULUS) {
+            return false;
+        }
+        let Ok(group) = group_params() else {
+            return false;
+        };
+        let index = U2048::from(share.index);
+        let expected = rest
+            .iter()
+            .rev()
+            .fold(MontyForm::new(last, group), |acc, c| acc.pow_bounded_exp(&index, u32::BITS).mul(&MontyForm::new(c, group)));
+        MontyForm::new(&U2048::from(2u8), group).pow(&share.value) == expected
+    }
+
+    // Whether a reconstructed key is the one that was shared.
+    pub fn verify_secret(&self, sk: &SecretKey) -> bool {
+        let Ok(group) = group_params() else {
+            return false;
+        };
+        let g = MontyForm::new(&U2048::from(2u8), group);
+        self.commitments.first() == Some(&g.pow(&sk.0.resize::<32>()).retrieve())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(COMMITMENTS_TAG);
+        w.put_u32(self.commitments.len() as u32);
+        for c in &self.commitments {
+            w.put_u2048(c);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, COMMITMENTS_TAG, policy)?;
+        let count = r.get_count()?;
+        let mut commitments = Vec::new();
+        for _ in 0..count {
+            let c = r.get_u2048()?;
+            if policy.canonical && (c == U2048::ZERO || c >= GROUP_MODULUS) {
+                return Err("Commitment is not canonical");
+            }
+            commitments.push(c);
+        }
+        r.finish()?;
+        Ok(Self { commitments })
+    }
+}
+
+pub(crate) fn field_params() -> Result<MontyParams<32>, &'static str> {
+    let order: Option<Odd<U2048>> = Odd::new(GROUP_ORDER).into();
+    Ok(MontyParams::new(order.ok_or("Group order must be odd")?))
+}
+
+pub(crate) fn group_params() -> Result<MontyParams<32>, &'static str> {
+    let modulus: Option<Odd<U2048>> = Odd::new(GROUP_MODULUS).into();
+    Ok(MontyParams::new(modulus.ok_or("Group modulus must be odd")?))
+}
@@ -0,0 +1,36 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, U256, U512,
+};
+
+use crate::hash::HashAlgorithm;
+
+// Public RSA exponent used for manager signatures under the accumulator modulus.
+// Element primes are 256-bit, so e never collides with an accumulated element.
+pub(crate) const EXPONENT: u32 = 65537;
+
+// Domain for messages signed by the manager.
+pub(crate) const SIGNATURE_DOMAIN: &[u8] = b"braavos-signature";
+
+// Expand H(msg) to 512 bits, reduce mod n and square, giving an element
+// of QR_n where e-th roots are unique.
+pub(crate) fn hash_to_qr(hash: HashAlgorithm, n: &Odd<U512>, domain: &[u8], msg: &[u8]) -> U512 {
+    let mut wide = [0u8; 64];
+    for (i, chunk) in wide.chunks_mut(32).enumerate() {
+        chunk.copy_from_slice(&hash.hash(domain, &[&[i as u8], msg]));
+    }
+    let h = U512::from_be_slice(&wide).rem(n.as_nz_ref());
+    let params = MontyParams::new(*n);
+    let h_monty = MontyForm::new(&h, params);
+    h_monty.square().retrieve()
+}
+
+pub(crate) fn verify(hash: HashAlgorithm, n: &Odd<U512>, msg: &[u8], sig: &U512) -> bool {
+    if sig >= n.as_ref() {
+        return false;
+    }
+    let params = MontyParams::new(*n);
+    let sig_monty = MontyForm::new(sig, params);
+    let recovered = sig_monty.pow(&U256::from(EXPONENT)).retrieve();
+    recovered == hash_to_qr(hash, n, SIGNATURE_DOMAIN, msg)
+}
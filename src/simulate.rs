@@ -0,0 +1,169 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::accumulator::BraavosAccumulator;
+use crate::corpus::{CorpusOp, CorpusSpec};
+use crate::holder::WitnessHolder;
+use crate::manager::{AllowAll, Manager};
+
+// A churn workload to size a deployment against: per-round add and delete
+// rates and a holder population syncing every `sync_interval` rounds, at
+// staggered offsets. Only `tracked_holders` holders are simulated; the report
+// scales their costs up to `holders`. Tracked holders are never deleted.
+// The elements and deletions are the corpus() workload, the same anywhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scenario {
+    pub prime_bits: u32,
+    pub rounds: u32,
+    pub initial_members: u32,
+    pub adds_per_round: u32,
+    pub deletes_per_round: u32,
+    pub holders: u64,
+    pub tracked_holders: u32,
+    pub sync_interval: u32,
+    pub seed: u64, // Seeds the corpus
+}
+
+// Measured costs of one run. Manager figures cover the whole workload; holder
+// figures are totals over the tracked holders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub scenario: Scenario,
+    pub adds: u64,
+    pub deletes: u64, // Fewer than requested once only tracked holders are left
+    pub add_time: Duration,
+    pub delete_time: Duration,
+    pub log_bytes: u64, // Encoded epoch updates published
+    pub syncs: u64,
+    pub sync_time: Duration,
+    pub sync_bytes: u64, // Coalesced updates downloaded
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            prime_bits: 128,
+            rounds: 10,
+            initial_members: 100,
+            adds_per_round: 20,
+            deletes_per_round: 2,
+            holders: 10_000,
+            tracked_holders: 10,
+            sync_interval: 2,
+            seed: 0,
+        }
+    }
+}
+
+impl Scenario {
+    pub fn run(&self) -> Result<SimulationReport, &'static str> {
+        if self.sync_interval == 0 {
+            return Err("Invalid simulation scenario");
+        }
+        let acc = BraavosAccumulator::builder().prime_bits(self.prime_bits).build()?;
+        let params = acc.params();
+        let mut manager = Manager::new(acc, AllowAll);
+        let corpus = self.corpus().generate()?;
+        let mut report = SimulationReport {
+            scenario: *self,
+            ..Default::default()
+        };
+
+        let mut holders = Vec::new();
+        for (i, element) in corpus.initial.iter().enumerate() {
+            let witness = manager.add("simulator", element)?;
+            if i < corpus.protected().len() {
+                holders.push(WitnessHolder::new(&params, element, witness, 0)?);
+            }
+        }
+
+        for (round, ops) in (0..).zip(&corpus.rounds) {
+            for op in ops {
+                let start = Instant::now();
+                match op {
+                    CorpusOp::Add(element) => {
+                        manager.add("simulator", element)?;
+                        report.add_time += start.elapsed();
+                        report.adds += 1;
+                    }
+                    CorpusOp::Delete(element) => {
+                        let update = manager.delete("simulator", element)?;
+                        report.delete_time += start.elapsed();
+                        report.deletes += 1;
+                        report.log_bytes += update.to_bytes().len() as u64;
+                    }
+                }
+            }
+            for (i, holder) in holders.iter_mut().enumerate() {
+                if !(round + i as u32).is_multiple_of(self.sync_interval) {
+                    continue;
+                }
+                if let Some(update) = manager.update_log().since(holder.epoch())? {
+                    report.sync_bytes += update.to_bytes().len() as u64;
+                }
+                let start = Instant::now();
+                holder.sync(manager.update_log())?;
+                report.sync_time += start.elapsed();
+                report.syncs += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    // The workload run: tracked holders are the protected initial members.
+    pub fn corpus(&self) -> CorpusSpec {
+        CorpusSpec {
+            seed: self.seed,
+            initial_members: self.initial_members,
+            protected: self.tracked_holders,
+            rounds: self.rounds,
+            adds_per_round: self.adds_per_round,
+            deletes_per_round: self.deletes_per_round,
+            ..CorpusSpec::default()
+        }
+    }
+}
+
+impl SimulationReport {
+    pub fn manager_time(&self) -> Duration {
+        self.add_time + self.delete_time
+    }
+
+    // Holder costs scaled from the tracked holders to the whole population.
+    pub fn projected_sync_time(&self) -> Duration {
+        self.sync_time.mul_f64(self.scale())
+    }
+
+    pub fn projected_sync_bytes(&self) -> u64 {
+        (self.sync_bytes as f64 * self.scale()) as u64
+    }
+
+    fn scale(&self) -> f64 {
+        self.scenario.holders as f64 / self.scenario.tracked_holders.max(1) as f64
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let per = |total: Duration, count: u64| total.checked_div(count.max(1) as u32).unwrap_or_default();
+        let s = &self.scenario;
+        writeln!(
+            f,
+            "{} rounds, {}-bit primes, {} holders syncing every {} rounds",
+            s.rounds, s.prime_bits, s.holders, s.sync_interval
+        )?;
+        writeln!(f, "manager: {:?} total", self.manager_time())?;
+        writeln!(f, "  adds:    {:>8} at {:?} each", self.adds, per(self.add_time, self.adds))?;
+        writeln!(f, "  deletes: {:>8} at {:?} each", self.deletes, per(self.delete_time, self.deletes))?;
+        writeln!(f, "update log: {} bytes published", self.log_bytes)?;
+        let sync = per(self.sync_time, self.syncs);
+        writeln!(f, "holders ({} tracked): {} syncs at {:?} each", s.tracked_holders, self.syncs, sync)?;
+        write!(
+            f,
+            "  projected for {}: {:?} CPU, {} bytes downloaded",
+            s.holders,
+            self.projected_sync_time(),
+            self.projected_sync_bytes()
+        )
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::backend::AccumulatorBackend;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::hash::HashAlgorithm;
+
+const KEY_DOMAIN: &[u8] = b"braavos-smt-key";
+const LEAF_DOMAIN: &[u8] = b"braavos-smt-leaf";
+const NODE_DOMAIN: &[u8] = b"braavos-smt-node";
+const PROOF_TAG: &[u8] = b"braavos-smt-proof";
+pub(crate) const DEPTH: usize = 256;
+
+// Sparse Merkle tree over the 2^256 element digests. Members occupy their
+// leaf and every other leaf is empty, so membership and non-membership are
+// both shown by the path to one leaf. Needs no trapdoor or setup, but any
+// change to the set moves the root and invalidates every outstanding proof;
+// holders fetch a fresh one instead of applying updates.
+//
+// Nodes equal to the empty subtree of their height are not stored.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree {
+    hash: HashAlgorithm,
+    nodes: HashMap<(usize, [u8; 32]), [u8; 32]>, // (depth, key prefix) -> hash, non-empty only
+    empty: Vec<[u8; 32]>,                        // Hash of an empty subtree at each depth
+    members: BTreeSet<Vec<u8>>,
+}
+
+// Path from the root to the leaf of one element, listing only the siblings
+// that are not empty subtrees.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmtProof {
+    pub present: [u8; 32],       // Bit d set when the sibling at depth d + 1 is listed
+    pub siblings: Vec<[u8; 32]>, // Listed siblings, root side first
+}
+
+impl SparseMerkleTree {
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Self {
+            hash,
+            nodes: HashMap::new(),
+            empty: empty_hashes(hash),
+            members: BTreeSet::new(),
+        }
+    }
+
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.node(0, &[0; 32])
+    }
+
+    pub fn member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    // Proof that x is a member when it is, and that it is not otherwise.
+    pub fn prove(&self, x: impl AsRef<[u8]>) -> SmtProof {
+        let x = x.as_ref();
+        let key = self.key(x);
+        let mut proof = SmtProof {
+            present: [0; 32],
+            siblings: Vec::new(),
+        };
+        for d in 0..DEPTH {
+            let mut sibling = prefix(&key, d + 1);
+            sibling[d / 8] ^= 0x80 >> (d % 8);
+            if let Some(hash) = self.nodes.get(&(d + 1, sibling)) {
+                proof.present[d / 8] |= 0x80 >> (d % 8);
+                proof.siblings.push(*hash);
+            }
+        }
+        proof
+    }
+
+    fn key(&self, x: &[u8]) -> [u8; 32] {
+        self.hash.hash(KEY_DOMAIN, &[x])
+    }
+
+    fn node(&self, depth: usize, prefix: &[u8; 32]) -> [u8; 32] {
+        self.nodes.get(&(depth, *prefix)).copied().unwrap_or(self.empty[depth])
+    }
+
+    // Sets or clears the leaf of `key` and rehashes its path to the root.
+    fn set_leaf(&mut self, key: &[u8; 32], occupied: bool) {
+        if occupied {
+            self.nodes.insert((DEPTH, *key), leaf_hash(self.hash, key));
+        } else {
+            self.nodes.remove(&(DEPTH, *key));
+        }
+        for d in (0..DEPTH).rev() {
+            let id = prefix(key, d);
+            let mut right = id;
+            right[d / 8] |= 0x80 >> (d % 8);
+            let parent = node_hash(self.hash, &self.node(d + 1, &id), &self.node(d + 1, &right));
+            if parent == self.empty[d] {
+                self.nodes.remove(&(d, id));
+            } else {
+                self.nodes.insert((d, id), parent);
+            }
+        }
+    }
+}
+
+impl SmtProof {
+    // Checks that x is (member = true) or is not (member = false) in the tree
+    // with the given root.
+    pub fn verify(&self, hash: HashAlgorithm, root: &[u8; 32], x: impl AsRef<[u8]>, member: bool) -> bool {
+        let x = x.as_ref();
+        let listed = self.present.iter().map(|b| b.count_ones() as usize).sum::<usize>();
+        if listed != self.siblings.len() {
+            return false;
+        }
+        let empty = empty_hashes(hash);
+        let key = hash.hash(KEY_DOMAIN, &[x]);
+        let mut current = if member { leaf_hash(hash, &key) } else { empty[DEPTH] };
+        let mut siblings = self.siblings.iter().rev();
+        for d in (0..DEPTH).rev() {
+            let sibling = if bit(&self.present, d) {
+                let Some(&sibling) = siblings.next() else {
+                    return false;
+                };
+                sibling
+            } else {
+                empty[d + 1]
+            };
+            current = if bit(&key, d) {
+                node_hash(hash, &sibling, &current)
+            } else {
+                node_hash(hash, &current, &sibling)
+            };
+        }
+        current == *root
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PROOF_TAG);
+        w.put_bytes(&self.present);
+        w.put_u32(self.siblings.len() as u32);
+        for sibling in &self.siblings {
+            w.put_bytes(sibling);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, PROOF_TAG, policy)?;
+        let present = r.get_bytes()?.try_into().map_err(|_| "Invalid proof bitmap")?;
+        let count = r.get_count()?;
+        if count > DEPTH {
+            return Err("Too many siblings in proof");
+        }
+        let mut siblings = Vec::with_capacity(count);
+        for _ in 0..count {
+            siblings.push(r.get_bytes()?.try_into().map_err(|_| "Invalid sibling hash")?);
+        }
+        r.finish()?;
+        Ok(Self { present, siblings })
+    }
+}
+
+impl AccumulatorBackend for SparseMerkleTree {
+    type Value = [u8; 32];
+    type Witness = SmtProof;
+    type NonMembershipWitness = SmtProof;
+    type Update = [u8; 32]; // The new root
+
+    fn value(&self) -> [u8; 32] {
+        self.root()
+    }
+
+    fn contains(&self, x: impl AsRef<[u8]>) -> bool {
+        self.members.contains(x.as_ref())
+    }
+
+    fn add(&mut self, x: impl AsRef<[u8]>) -> Result<SmtProof, &'static str> {
+        let x = x.as_ref();
+        if self.members.insert(x.to_vec()) {
+            let key = self.key(x);
+            self.set_leaf(&key, true);
+        }
+        Ok(self.prove(x))
+    }
+
+    fn delete(&mut self, x: impl AsRef<[u8]>) -> Result<[u8; 32], &'static str> {
+        let x = x.as_ref();
+        if !self.members.remove(x) {
+            return Err("Element is not a member");
+        }
+        let key = self.key(x);
+        self.set_leaf(&key, false);
+        Ok(self.root())
+    }
+
+    fn verify(&mut self, x: impl AsRef<[u8]>, w: &SmtProof) -> bool {
+        w.verify(self.hash, &self.root(), x, true)
+    }
+
+    fn non_membership_witness(&self, x: impl AsRef<[u8]>) -> Result<SmtProof, &'static str> {
+        let x = x.as_ref();
+        if self.members.contains(x) {
+            return Err("Element is a member");
+        }
+        Ok(self.prove(x))
+    }
+
+    fn verify_non_membership(&self, x: impl AsRef<[u8]>, w: &SmtProof) -> bool {
+        w.verify(self.hash, &self.root(), x, false)
+    }
+}
+
+fn empty_hashes(hash: HashAlgorithm) -> Vec<[u8; 32]> {
+    let mut empty = vec![[0; 32]; DEPTH + 1];
+    for d in (0..DEPTH).rev() {
+        empty[d] = node_hash(hash, &empty[d + 1], &empty[d + 1]);
+    }
+    empty
+}
+
+fn leaf_hash(hash: HashAlgorithm, key: &[u8; 32]) -> [u8; 32] {
+    hash.hash(LEAF_DOMAIN, &[key])
+}
+
+fn node_hash(hash: HashAlgorithm, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hash.hash(NODE_DOMAIN, &[left, right])
+}
+
+// Bit d of the key, most significant first; it picks the child at depth d + 1.
+pub(crate) fn bit(key: &[u8; 32], d: usize) -> bool {
+    key[d / 8] & (0x80 >> (d % 8)) != 0
+}
+
+// The first `depth` bits of the key, the rest cleared: a node's identifier.
+pub(crate) fn prefix(key: &[u8; 32], depth: usize) -> [u8; 32] {
+    let mut out = [0; 32];
+    out[..depth / 8].copy_from_slice(&key[..depth / 8]);
+    if !depth.is_multiple_of(8) {
+        out[depth / 8] = key[depth / 8] & (0xff << (8 - depth % 8));
+    }
+    out
+}
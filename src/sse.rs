@@ -0,0 +1,131 @@
+// Epoch updates as server-sent events, for holders behind proxies that pass
+// plain HTTP streaming but not WebSocket upgrades.
+//
+// Each update is one `update` event whose id is the epoch it brings a holder
+// to and whose data is the hex of its tagged encoding. A browser EventSource
+// that reconnects sends the last id back as Last-Event-ID, and the feed
+// resumes from that epoch with everything since coalesced into one event.
+// The HTTP server is the caller's: answer with SSE_CONTENT_TYPE, write
+// prelude(), then each event as it arrives.
+use crate::decoding::DecodingPolicy;
+use crate::report::hex;
+use crate::stream::UpdateStream;
+use crate::update::{EpochUpdate, UpdateLog};
+
+pub const SSE_CONTENT_TYPE: &str = "text/event-stream";
+
+// Reconnection delay suggested to clients, in milliseconds.
+const RETRY_MS: u64 = 3000;
+
+#[derive(Debug)]
+pub struct SseFeed {
+    stream: UpdateStream,
+    catch_up: Option<EpochUpdate>,
+    epoch: u64, // Epoch of the last event handed out, or the resume point
+}
+
+impl SseFeed {
+    // A feed resuming after `last_event_id`, or from `current` without one.
+    // The stream must have been opened before the log was read, so nothing
+    // published in between is missed; updates the catch-up covers are
+    // skipped.
+    pub(crate) fn resume(
+        stream: UpdateStream,
+        log: &UpdateLog,
+        last_event_id: Option<&str>,
+        current: u64,
+    ) -> Result<Self, &'static str> {
+        let epoch = match last_event_id {
+            Some(id) => id.trim().parse().map_err(|_| "Last-Event-ID is not an epoch")?,
+            None => current,
+        };
+        let catch_up = match epoch == current {
+            true => None,
+            false => log.since(epoch)?,
+        };
+        Ok(Self {
+            stream,
+            catch_up,
+            epoch,
+        })
+    }
+
+    // The start of the response body: the retry delay and, for a holder
+    // that was behind, the catch-up event.
+    pub fn prelude(&mut self) -> String {
+        let mut out = format!("retry: {}\n\n", RETRY_MS);
+        if let Some(update) = self.catch_up.take() {
+            self.epoch = update.to_epoch;
+            out.push_str(&event(&update));
+        }
+        out
+    }
+
+    // The next event if an update is queued, without waiting.
+    pub fn try_next_event(&mut self) -> Option<String> {
+        while let Some(update) = self.stream.try_recv() {
+            if let Some(event) = self.accept(update) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    // Waits for the next event; None once the manager is gone.
+    pub async fn next_event(&mut self) -> Option<String> {
+        loop {
+            let update = self.stream.recv().await?;
+            if let Some(event) = self.accept(update) {
+                return Some(event);
+            }
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    fn accept(&mut self, update: EpochUpdate) -> Option<String> {
+        if update.to_epoch <= self.epoch {
+            return None;
+        }
+        self.epoch = update.to_epoch;
+        Some(event(&update))
+    }
+
+    // The update carried by one event as the feed writes it, for clients.
+    pub fn decode_event(event: &str) -> Result<EpochUpdate, &'static str> {
+        Self::decode_event_with(event, &DecodingPolicy::default())
+    }
+
+    pub fn decode_event_with(event: &str, policy: &DecodingPolicy) -> Result<EpochUpdate, &'static str> {
+        let mut id = None;
+        let mut data = None;
+        for line in event.lines() {
+            match line.split_once(": ") {
+                Some(("id", value)) => id = Some(value),
+                Some(("data", value)) => data = Some(value),
+                _ => {}
+            }
+        }
+        let update = EpochUpdate::from_bytes_with(&unhex(data.ok_or("Event has no data")?)?, policy)?;
+        if id != Some(update.to_epoch.to_string().as_str()) {
+            return Err("Event id is not the update's epoch");
+        }
+        Ok(update)
+    }
+}
+
+fn event(update: &EpochUpdate) -> String {
+    format!("id: {}\nevent: update\ndata: {}\n\n", update.to_epoch, hex(&update.to_bytes()))
+}
+
+fn unhex(s: &str) -> Result<Vec<u8>, &'static str> {
+    if !s.len().is_multiple_of(2) {
+        return Err("Event data is not hex");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| s.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()).ok_or("Event data is not hex"))
+        .collect()
+}
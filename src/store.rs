@@ -0,0 +1,208 @@
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::collections::BTreeMap;
+use std::fmt;
+use subtle::ConstantTimeEq;
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::holder::WitnessHolder;
+use crate::mac::{apply_keystream, prf};
+
+const STORE_TAG: &[u8] = b"braavos-witness-store";
+const ID_DOMAIN: &[u8] = b"braavos-store-id";
+const ENTRY_KEY_DOMAIN: &[u8] = b"braavos-store-entry-key";
+const ENC_DOMAIN: &[u8] = b"braavos-store-enc";
+const MAC_DOMAIN: &[u8] = b"braavos-store-mac";
+const NONCE_LEN: usize = 16;
+const OPEN_FAILED: &str = "Wrong access token or tampered entry";
+
+// Holder-side witnesses encrypted at rest, since whoever copies a witness
+// database can present its credentials. Every entry is sealed under a key
+// derived from the device's StoreKey and the element's AccessToken, so
+// reading one takes both: a stolen store is opaque without the device key,
+// and a component given one element's token cannot open the others.
+//
+// Entries are found by a keyed hash of the element, so the store does not
+// list its elements either. Each is encrypted with an HMAC-SHA256 keystream
+// under a fresh nonce and authenticated together with its id (as backups
+// are); replacing an entry with an older one of the same element is not
+// detected, and yields a stale witness rather than a forged one.
+#[derive(Clone)]
+pub struct StoreKey([u8; 32]);
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct AccessToken(pub [u8; 32]);
+
+#[derive(Clone, Debug)]
+pub struct WitnessStore {
+    key: StoreKey,
+    entries: BTreeMap<[u8; 32], Entry>, // By element id
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+    tag: [u8; 32],
+}
+
+impl StoreKey {
+    pub fn generate() -> Self {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    // A key kept by the platform (keychain, TPM-sealed blob, ...).
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl fmt::Debug for StoreKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StoreKey(..)")
+    }
+}
+
+impl AccessToken {
+    pub fn generate() -> Self {
+        let mut token = [0u8; 32];
+        OsRng.fill_bytes(&mut token);
+        Self(token)
+    }
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AccessToken(..)")
+    }
+}
+
+impl WitnessStore {
+    pub fn new(key: StoreKey) -> Self {
+        Self {
+            key,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    // Stores the holder under a fresh token, replacing any entry for its
+    // element, and returns the token.
+    pub fn insert(&mut self, holder: &WitnessHolder) -> AccessToken {
+        let token = AccessToken::generate();
+        let id = self.id(holder.element());
+        let entry = self.seal(&id, &token, &holder.to_bytes());
+        self.entries.insert(id, entry);
+        token
+    }
+
+    // Replaces the entry for the holder's element, such as after a sync,
+    // keeping its token. The token must open the current entry.
+    pub fn put(&mut self, holder: &WitnessHolder, token: &AccessToken) -> Result<(), &'static str> {
+        self.get(holder.element(), token)?;
+        let id = self.id(holder.element());
+        let entry = self.seal(&id, token, &holder.to_bytes());
+        self.entries.insert(id, entry);
+        Ok(())
+    }
+
+    pub fn get(&self, element: impl AsRef<[u8]>, token: &AccessToken) -> Result<WitnessHolder, &'static str> {
+        let element = element.as_ref();
+        let id = self.id(element);
+        let entry = self.entries.get(&id).ok_or("No entry for element")?;
+        let holder = WitnessHolder::from_bytes(&self.open(&id, token, entry)?)?;
+        if holder.element() != element {
+            return Err(OPEN_FAILED);
+        }
+        Ok(holder)
+    }
+
+    pub fn remove(&mut self, element: impl AsRef<[u8]>) -> bool {
+        let id = self.id(element.as_ref());
+        self.entries.remove(&id).is_some()
+    }
+
+    pub fn contains(&self, element: impl AsRef<[u8]>) -> bool {
+        self.entries.contains_key(&self.id(element.as_ref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // The sealed entries, for writing to disk; the key is not included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(STORE_TAG);
+        w.put_u32(self.entries.len() as u32);
+        for (id, entry) in &self.entries {
+            w.put_bytes(id);
+            w.put_bytes(&entry.nonce);
+            w.put_bytes(&entry.ciphertext);
+            w.put_bytes(&entry.tag);
+        }
+        w.finish()
+    }
+
+    // Entries are authenticated as they are opened, not here.
+    pub fn from_bytes(bytes: &[u8], key: StoreKey) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, key, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], key: StoreKey, policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, STORE_TAG, policy)?;
+        let mut entries = BTreeMap::new();
+        for _ in 0..r.get_count()? {
+            let id = r.get_bytes()?.try_into().map_err(|_| "Invalid entry id")?;
+            let entry = Entry {
+                nonce: r.get_bytes()?.try_into().map_err(|_| "Invalid entry nonce")?,
+                ciphertext: r.get_bytes()?.to_vec(),
+                tag: r.get_bytes()?.try_into().map_err(|_| "Invalid entry tag")?,
+            };
+            if entries.insert(id, entry).is_some() {
+                return Err("Duplicate entry in store");
+            }
+        }
+        r.finish()?;
+        Ok(Self { key, entries })
+    }
+
+    fn id(&self, element: &[u8]) -> [u8; 32] {
+        prf(&self.key.0, &[ID_DOMAIN, element])
+    }
+
+    fn keys(&self, id: &[u8; 32], token: &AccessToken, nonce: &[u8]) -> ([u8; 32], [u8; 32]) {
+        let entry_key = prf(&self.key.0, &[ENTRY_KEY_DOMAIN, id, &token.0]);
+        (prf(&entry_key, &[ENC_DOMAIN, nonce]), prf(&entry_key, &[MAC_DOMAIN]))
+    }
+
+    fn seal(&self, id: &[u8; 32], token: &AccessToken, plaintext: &[u8]) -> Entry {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let (enc_key, mac_key) = self.keys(id, token, &nonce);
+        let mut ciphertext = plaintext.to_vec();
+        apply_keystream(&enc_key, &mut ciphertext);
+        let tag = prf(&mac_key, &[id, &nonce, &ciphertext]);
+        Entry { nonce, ciphertext, tag }
+    }
+
+    fn open(&self, id: &[u8; 32], token: &AccessToken, entry: &Entry) -> Result<Vec<u8>, &'static str> {
+        let (enc_key, mac_key) = self.keys(id, token, &entry.nonce);
+        let tag = prf(&mac_key, &[id, &entry.nonce, &entry.ciphertext]);
+        if !bool::from(tag.ct_eq(&entry.tag)) {
+            return Err(OPEN_FAILED);
+        }
+        let mut plaintext = entry.ciphertext.clone();
+        apply_keystream(&enc_key, &mut plaintext);
+        Ok(plaintext)
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+
+use crate::sync::lock;
+use crate::update::EpochUpdate;
+
+// Epoch updates as a manager publishes them, for async holders and relays.
+// Built on std::future alone, with no runtime of its own: any executor
+// (tokio, async-std, smol, a hand-rolled block_on) can await recv, and the
+// wake-up comes from whichever thread publishes. Updates queue until read,
+// so a slow reader sees every one in order.
+#[derive(Debug)]
+pub struct UpdateStream {
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    queue: VecDeque<EpochUpdate>,
+    waker: Option<Waker>,
+    closed: bool, // The manager is gone; nothing more will arrive
+}
+
+// The future returned by UpdateStream::recv.
+#[derive(Debug)]
+pub struct NextUpdate<'a> {
+    stream: &'a mut UpdateStream,
+}
+
+impl UpdateStream {
+    // The next update, or None once the manager has been dropped and the
+    // queue is drained.
+    pub fn recv(&mut self) -> NextUpdate<'_> {
+        NextUpdate { stream: self }
+    }
+
+    // The next update if one is queued, without waiting.
+    pub fn try_recv(&mut self) -> Option<EpochUpdate> {
+        lock(&self.shared).queue.pop_front()
+    }
+}
+
+impl Future for NextUpdate<'_> {
+    type Output = Option<EpochUpdate>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<EpochUpdate>> {
+        let mut shared = lock(&self.stream.shared);
+        if let Some(update) = shared.queue.pop_front() {
+            return Poll::Ready(Some(update));
+        }
+        if shared.closed {
+            return Poll::Ready(None);
+        }
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+// The manager's side: every open stream, dropped ones pruned as it sends.
+#[derive(Debug, Default)]
+pub(crate) struct Publisher {
+    subscribers: Vec<Weak<Mutex<Shared>>>,
+}
+
+impl Publisher {
+    pub(crate) fn subscribe(&mut self) -> UpdateStream {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        self.subscribers.push(Arc::downgrade(&shared));
+        UpdateStream { shared }
+    }
+
+    pub(crate) fn send(&mut self, update: &EpochUpdate) {
+        self.subscribers.retain(|subscriber| {
+            let Some(shared) = subscriber.upgrade() else {
+                return false;
+            };
+            let mut shared = lock(&shared);
+            shared.queue.push_back(update.clone());
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+            true
+        });
+    }
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        for shared in self.subscribers.iter().filter_map(Weak::upgrade) {
+            let mut shared = lock(&shared);
+            shared.closed = true;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+#[cfg(feature = "pool")]
+use std::sync::Condvar;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+// Locks that survive a thread panicking while holding them. Everything kept
+// behind these mutexes (caches, queues, subscriber lists) is valid after any
+// single update, so the data is used as is rather than aborting every later
+// caller.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(feature = "pool")]
+pub(crate) fn wait<'a, T>(condvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    condvar.wait(guard).unwrap_or_else(PoisonError::into_inner)
+}
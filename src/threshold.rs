@@ -0,0 +1,426 @@
+// Trapdoor operations by a quorum of custodians, without the secret key ever
+// being reassembled.
+//
+// Issuing a witness and deleting an element both need a^(1/x) with 1/x taken
+// mod sk, and x changes every time, so sharing a fixed exponent (as in
+// threshold RSA) does not suffice. Instead, following Catalano, Gennaro and
+// Howgrave-Graham's inversion over a shared modulus, the dealer hands every
+// custodian one-time tokens: Shamir shares over the integers of sk R and S for
+// fresh random R and S. For an element prime x the quorum publishes
+//
+//     gamma_i = (sk R)_i + x S_i,    so    Gamma = Delta (sk R + x S)
+//
+// which statistically hides sk. With zeta = Gamma^-1 mod x and
+// u = (Gamma zeta - 1) / x, the exponent d = Delta S zeta - u satisfies
+// x d = 1 mod sk, and each custodian contributes a^(zeta S_i) towards a^d.
+// A token is burned after one use: two elements under the same token reveal
+// sk. Results are checked before use, so a bad partial is detected (though
+// not attributed) and the operation can be retried with another quorum.
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    NonZero, Odd, RandomBits, U2048, U256, U512,
+};
+use rand::rngs::OsRng;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::params::PublicParams;
+use crate::sharing::SecretKey;
+use crate::update::EpochUpdate;
+use crate::witness::Witness;
+
+const CUSTODIAN_TAG: &[u8] = b"braavos-threshold-custodian";
+pub const MAX_CUSTODIANS: u32 = 16;
+const DELTA: i128 = 20_922_789_888_000; // 16!, clears every Lagrange denominator
+const STAT_BITS: u32 = 128;
+const R_BITS: u32 = 256 + STAT_BITS;
+const S_BITS: u32 = 256 + R_BITS + STAT_BITS;
+const COEFFICIENT_BITS: u32 = 256 + S_BITS + 2 * STAT_BITS;
+
+// One custodian's shares of every token, kept until the token is used, and
+// the prime each token was committed to. Commitments for two primes under one
+// token would give away both shares, so the first commitment binds the token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Custodian {
+    index: u32,
+    tokens: BTreeMap<u64, (U2048, U2048)>,       // token -> ((sk R)_i, S_i)
+    committed: BTreeMap<u64, (ElemPrime, U2048)>, // token -> (x, gamma_i)
+}
+
+// What the manager asks the quorum to take a root of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RootRequest {
+    pub token: u64,
+    pub prime: ElemPrime,
+    pub value: AccValue,
+}
+
+// Public state of an accumulator whose trapdoor is held by custodians.
+#[derive(Clone, Debug)]
+pub struct ThresholdAccumulator {
+    params: PublicParams,
+    params_digest: [u8; 32],
+    monty_params: MontyParams<8>,
+    value: AccValue,
+    epoch: u64,
+    members: BTreeMap<Vec<u8>, ElemPrime>,
+    threshold: u32,
+    next_token: u64,
+    tokens: u64,
+}
+
+// Deals `tokens` one-time tokens to `custodians`, any `threshold` of whom can
+// take roots together.
+pub(crate) fn deal(
+    sk: &SecretKey,
+    threshold: u32,
+    custodians: u32,
+    tokens: u64,
+) -> Result<Vec<Custodian>, &'static str> {
+    if threshold == 0 || threshold > custodians || custodians > MAX_CUSTODIANS {
+        return Err("Threshold must be between 1 and the number of custodians, at most 16");
+    }
+    let sk: U2048 = sk.as_uint().resize();
+    let mut dealt: Vec<Custodian> = (1..=custodians)
+        .map(|index| Custodian {
+            index,
+            tokens: BTreeMap::new(),
+            committed: BTreeMap::new(),
+        })
+        .collect();
+    for token in 0..tokens {
+        let r = U2048::random_bits(&mut OsRng, R_BITS);
+        let s = U2048::random_bits(&mut OsRng, S_BITS);
+        let masked = integer_shares(&sk.wrapping_mul(&r), threshold, custodians);
+        let mask = integer_shares(&s, threshold, custodians);
+        for (custodian, (masked, mask)) in dealt.iter_mut().zip(masked.into_iter().zip(mask)) {
+            custodian.tokens.insert(token, (masked, mask));
+        }
+    }
+    Ok(dealt)
+}
+
+impl Custodian {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.tokens.len()
+    }
+
+    // First round: gamma_i for the request, which binds the token to its
+    // prime. Asking again for the same prime gives the same gamma_i.
+    pub fn commit(&mut self, request: &RootRequest) -> Result<(u32, U2048), &'static str> {
+        let (masked, mask) = self.tokens.get(&request.token).ok_or("Token already used or unknown")?;
+        if let Some((prime, gamma)) = self.committed.get(&request.token) {
+            if *prime != request.prime {
+                return Err("Token is committed to another element");
+            }
+            return Ok((self.index, *gamma));
+        }
+        let x: U2048 = request.prime.as_uint().resize();
+        let gamma = masked.wrapping_add(&x.wrapping_mul(mask));
+        self.committed.insert(request.token, (request.prime, gamma));
+        Ok((self.index, gamma))
+    }
+
+    // Second round: a^(zeta S_i), given the whole quorum's commitments, for
+    // the prime this custodian committed to. Burns the token whatever the
+    // outcome.
+    pub fn partial(
+        &mut self,
+        params: &PublicParams,
+        request: &RootRequest,
+        commitments: &[(u32, U2048)],
+    ) -> Result<(u32, U512), &'static str> {
+        let (_, mask) = self.tokens.remove(&request.token).ok_or("Token already used or unknown")?;
+        let (prime, gamma) = self.committed.remove(&request.token).ok_or("Token was not committed")?;
+        if prime != request.prime {
+            return Err("Token is committed to another element");
+        }
+        match commitments.iter().find(|(i, _)| *i == self.index) {
+            None => return Err("Custodian is not part of the quorum"),
+            Some((_, own)) if *own != gamma => return Err("Commitment differs from the one this custodian made"),
+            Some(_) => {}
+        }
+        let (zeta, _) = inversion(commitments, &request.prime)?;
+        let monty_params = monty_params(params)?;
+        let exponent = mask.wrapping_mul(&zeta.resize::<32>());
+        let partial = MontyForm::new(request.value.as_uint(), monty_params).pow(&exponent);
+        Ok((self.index, partial.retrieve()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(CUSTODIAN_TAG);
+        w.put_u32(self.index);
+        w.put_u32(self.tokens.len() as u32);
+        for (token, (masked, mask)) in &self.tokens {
+            w.put_u64(*token);
+            w.put_u2048(masked);
+            w.put_u2048(mask);
+        }
+        w.put_u32(self.committed.len() as u32);
+        for (token, (prime, gamma)) in &self.committed {
+            w.put_u64(*token);
+            w.put_u256(prime.as_uint());
+            w.put_u2048(gamma);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, CUSTODIAN_TAG, policy)?;
+        let index = r.get_u32()?;
+        if index == 0 || index > MAX_CUSTODIANS {
+            return Err("Invalid custodian index");
+        }
+        let mut tokens = BTreeMap::new();
+        for _ in 0..r.get_count()? {
+            let token = r.get_u64()?;
+            tokens.insert(token, (r.get_u2048()?, r.get_u2048()?));
+        }
+        let mut committed = BTreeMap::new();
+        for _ in 0..r.get_count()? {
+            let token = r.get_u64()?;
+            committed.insert(token, (r.get_prime()?, r.get_u2048()?));
+        }
+        r.finish()?;
+        Ok(Self {
+            index,
+            tokens,
+            committed,
+        })
+    }
+}
+
+impl ThresholdAccumulator {
+    pub(crate) fn new(
+        params: PublicParams,
+        value: AccValue,
+        epoch: u64,
+        members: BTreeMap<Vec<u8>, ElemPrime>,
+        threshold: u32,
+        tokens: u64,
+    ) -> Result<Self, &'static str> {
+        Ok(Self {
+            params,
+            params_digest: params.digest(),
+            monty_params: monty_params(&params)?,
+            value,
+            epoch,
+            members,
+            threshold,
+            next_token: 0,
+            tokens,
+        })
+    }
+
+    pub fn params(&self) -> PublicParams {
+        self.params
+    }
+
+    pub fn value(&self) -> AccValue {
+        self.value
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn contains(&self, x: impl AsRef<[u8]>) -> bool {
+        self.members.contains_key(x.as_ref())
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    // Tokens not yet handed out; a new ceremony is needed when they run out.
+    pub fn remaining_tokens(&self) -> u64 {
+        self.tokens - self.next_token
+    }
+
+    // Reserves a token for taking the x-th root of the current value, which
+    // both add (as x's witness) and delete (as the new value) need.
+    pub fn request(&mut self, x: impl AsRef<[u8]>) -> Result<RootRequest, &'static str> {
+        let x = x.as_ref();
+        if self.next_token == self.tokens {
+            return Err("No threshold tokens left");
+        }
+        let token = self.next_token;
+        self.next_token += 1;
+        Ok(RootRequest {
+            token,
+            prime: self.params.hash_to_prime(x),
+            value: self.value,
+        })
+    }
+
+    pub fn add(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        request: &RootRequest,
+        commitments: &[(u32, U2048)],
+        partials: &[(u32, U512)],
+    ) -> Result<Witness, &'static str> {
+        let x = x.as_ref();
+        if self.members.contains_key(x) {
+            return Err("Element is already a member");
+        }
+        let root = self.combine(x, request, commitments, partials)?;
+        self.members.insert(x.to_vec(), request.prime);
+        Ok(Witness {
+            params_digest: self.params_digest,
+            value: root,
+        })
+    }
+
+    pub fn delete(
+        &mut self,
+        x: impl AsRef<[u8]>,
+        request: &RootRequest,
+        commitments: &[(u32, U2048)],
+        partials: &[(u32, U512)],
+    ) -> Result<EpochUpdate, &'static str> {
+        let x = x.as_ref();
+        if !self.members.contains_key(x) {
+            return Err("Element is not a member");
+        }
+        let root = self.combine(x, request, commitments, partials)?;
+        let update = EpochUpdate {
+            from_epoch: self.epoch,
+            to_epoch: self.epoch + 1,
+            n: self.params.n,
+            params_digest: self.params_digest,
+            deleted: vec![request.prime],
+            value: root.into_value_after_deletion(),
+        };
+        self.members.remove(x);
+        self.value = update.value;
+        self.epoch = update.to_epoch;
+        Ok(update)
+    }
+
+    pub fn verify(&self, x: impl AsRef<[u8]>, w: Witness) -> bool {
+        let x = x.as_ref();
+        if w.params_digest != self.params_digest {
+            return false;
+        }
+        let prime = self.members.get(x).copied().unwrap_or_else(|| self.params.hash_to_prime(x));
+        core::opens(&w.value, &prime, &self.value, &self.monty_params)
+    }
+
+    // prod partial_i^lambda_i * a^-u, accepted only if it is an x-th root.
+    fn combine(
+        &self,
+        x: &[u8],
+        request: &RootRequest,
+        commitments: &[(u32, U2048)],
+        partials: &[(u32, U512)],
+    ) -> Result<WitnessValue, &'static str> {
+        if request.value != self.value || request.prime != self.params.hash_to_prime(x) {
+            return Err("Request does not match the accumulator");
+        }
+        if commitments.len() < self.threshold as usize {
+            return Err("Not enough custodians in the quorum");
+        }
+        let indices: Vec<u32> = commitments.iter().map(|(i, _)| *i).collect();
+        let (_, u) = inversion(commitments, &request.prime)?;
+        let value = MontyForm::new(self.value.as_uint(), self.monty_params);
+        let value_inv: Option<MontyForm<8>> = value.inv().into();
+        let mut root = value_inv.ok_or("Accumulator value not invertible")?.pow(&u);
+        for (index, (magnitude, negative)) in indices.iter().zip(lagrange_at_zero(&indices)?) {
+            let (_, partial) = partials
+                .iter()
+                .find(|(i, _)| i == index)
+                .ok_or("Missing partial from a quorum member")?;
+            let mut term = MontyForm::new(partial, self.monty_params);
+            if negative {
+                let inv: Option<MontyForm<8>> = term.inv().into();
+                term = inv.ok_or("Partial not invertible")?;
+            }
+            root = root.mul(&term.pow_bounded_exp(&magnitude, 128));
+        }
+        let root = WitnessValue::new(root.retrieve());
+        if root.pow_elem(&request.prime, &self.monty_params) != self.value {
+            return Err("Partials do not combine to a root");
+        }
+        Ok(root)
+    }
+}
+
+// Gamma from the quorum's commitments, then zeta = Gamma^-1 mod x and
+// u = (Gamma zeta - 1) / x.
+fn inversion(commitments: &[(u32, U2048)], x: &ElemPrime) -> Result<(U256, U2048), &'static str> {
+    let indices: Vec<u32> = commitments.iter().map(|(i, _)| *i).collect();
+    let (mut positive, mut negative) = (U2048::ZERO, U2048::ZERO);
+    for ((_, gamma), (magnitude, is_negative)) in commitments.iter().zip(lagrange_at_zero(&indices)?) {
+        let term = gamma.wrapping_mul(&magnitude);
+        if is_negative {
+            negative = negative.wrapping_add(&term);
+        } else {
+            positive = positive.wrapping_add(&term);
+        }
+    }
+    if positive <= negative {
+        return Err("Commitments do not combine");
+    }
+    let gamma = positive.wrapping_sub(&negative);
+    let x_wide: Option<NonZero<U2048>> = NonZero::new(x.as_uint().resize()).into();
+    let x_wide = x_wide.ok_or("Element prime must be nonzero")?;
+    let zeta: Option<U256> = gamma.rem(&x_wide).resize::<4>().inv_mod(x.as_uint()).into();
+    let zeta = zeta.ok_or("Commitments do not combine")?;
+    let (u, rem) = gamma.wrapping_mul(&zeta.resize::<32>()).wrapping_sub(&U2048::ONE).div_rem(&x_wide);
+    if rem != U2048::ZERO {
+        return Err("Commitments do not combine");
+    }
+    Ok((zeta, u))
+}
+
+// Delta times the Lagrange coefficient at zero of each index, as magnitude and
+// sign. Exact over the integers since Delta = 16! and indices are at most 16.
+fn lagrange_at_zero(indices: &[u32]) -> Result<Vec<(U2048, bool)>, &'static str> {
+    let distinct: BTreeSet<u32> = indices.iter().copied().collect();
+    if distinct.len() != indices.len() || indices.iter().any(|i| *i == 0 || *i > MAX_CUSTODIANS) {
+        return Err("Quorum indices must be distinct and between 1 and 16");
+    }
+    Ok(indices
+        .iter()
+        .map(|&i| {
+            let (mut num, mut den) = (DELTA, 1i128);
+            for &j in indices.iter().filter(|&&j| j != i) {
+                num *= j as i128;
+                den *= j as i128 - i as i128;
+            }
+            let lambda = num / den;
+            (U2048::from_u128(lambda.unsigned_abs()), lambda < 0)
+        })
+        .collect())
+}
+
+// Shamir shares over the integers: evaluations at 1..=count of a polynomial
+// with constant term `secret` and random coefficients wide enough to hide it.
+fn integer_shares(secret: &U2048, threshold: u32, count: u32) -> Vec<U2048> {
+    let coefficients: Vec<U2048> = (1..threshold)
+        .map(|_| U2048::random_bits(&mut OsRng, COEFFICIENT_BITS))
+        .collect();
+    (1..=count)
+        .map(|i| {
+            let i = U2048::from(i);
+            coefficients.iter().rev().fold(U2048::ZERO, |acc, c| acc.wrapping_add(c).wrapping_mul(&i)).wrapping_add(secret)
+        })
+        .collect()
+}
+
+fn monty_params(params: &PublicParams) -> Result<MontyParams<8>, &'static str> {
+    let n: Option<Odd<U512>> = Odd::new(params.n).into();
+    Ok(MontyParams::new(n.ok_or("Modulus must be odd")?))
+}
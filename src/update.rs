@@ -0,0 +1,337 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, U512,
+};
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::params::PublicParams;
+use crate::private_update::PrivateEpochUpdate;
+use crate::witness::Witness;
+
+const UPDATE_TAG: &[u8] = b"braavos-epoch-update";
+
+// Update messages let witness holders follow deletions without the trapdoor.
+// Consecutive messages can be merged so relays forward one message per range.
+pub trait UpdateMessage: Sized {
+    // Compose self (earlier) with other (the directly following epochs).
+    fn merge(&self, other: &Self) -> Result<Self, &'static str>;
+
+    // Bring a witness for the element prime x from from_epoch to to_epoch.
+    fn apply_to_witness(&self, x: &ElemPrime, w: &Witness) -> Result<Witness, &'static str>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpochUpdate {
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    pub n: U512,             // RSA modulus
+    pub params_digest: [u8; 32],
+    pub deleted: Vec<ElemPrime>, // Deleted element primes, in deletion order
+    pub value: AccValue,         // Accumulator value at to_epoch
+}
+
+impl EpochUpdate {
+    // Braavos-BP deletion: a valid witness for x is exactly the accumulator value
+    // with x removed, so anyone holding one can delete x without the trapdoor.
+    pub fn from_witness_deletion(
+        params: &PublicParams,
+        epoch: u64,
+        value: &AccValue,
+        x: &[u8],
+        w: &Witness,
+    ) -> Result<Self, &'static str> {
+        w.check_params(params)?;
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let monty_params = MontyParams::new(n.ok_or("Modulus must be odd")?);
+        let prime = params.hash_to_prime(x);
+        // The holder's witness may be either sign; the new value must be in QR_n
+        let Some(root) = core::canonical_root(&w.value, &prime, value, &monty_params) else {
+            return Err("Witness does not open the current accumulator value");
+        };
+        Ok(Self {
+            from_epoch: epoch,
+            to_epoch: epoch + 1,
+            n: params.n,
+            params_digest: w.params_digest,
+            deleted: vec![prime],
+            value: root.into_value_after_deletion(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(UPDATE_TAG);
+        w.put_u64(self.from_epoch);
+        w.put_u64(self.to_epoch);
+        w.put_u512(&self.n);
+        w.put_bytes(&self.params_digest);
+        w.put_u32(self.deleted.len() as u32);
+        for prime in &self.deleted {
+            w.put_u256(prime.as_uint());
+        }
+        w.put_u512(self.value.as_uint());
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, UPDATE_TAG, policy)?;
+        let from_epoch = r.get_u64()?;
+        let to_epoch = r.get_u64()?;
+        let n = r.get_u512()?;
+        if policy.modulus.is_some_and(|m| m != n) {
+            return Err("Update is for a different modulus");
+        }
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let mut deleted = Vec::new();
+        for _ in 0..r.get_count()? {
+            deleted.push(r.get_prime()?);
+        }
+        let value = AccValue::new(r.get_element(Some(&n))?);
+        r.finish()?;
+        if to_epoch < from_epoch {
+            return Err("Update ends before it starts");
+        }
+        Ok(Self {
+            from_epoch,
+            to_epoch,
+            n,
+            params_digest,
+            deleted,
+            value,
+        })
+    }
+}
+
+impl UpdateMessage for EpochUpdate {
+    fn merge(&self, other: &Self) -> Result<Self, &'static str> {
+        if self.params_digest != other.params_digest {
+            return Err("Update messages are for different parameters");
+        }
+        if self.to_epoch != other.from_epoch {
+            return Err("Update messages are not consecutive");
+        }
+        let mut deleted = self.deleted.clone();
+        deleted.extend_from_slice(&other.deleted);
+        Ok(Self {
+            from_epoch: self.from_epoch,
+            to_epoch: other.to_epoch,
+            n: self.n,
+            params_digest: self.params_digest,
+            deleted,
+            value: other.value,
+        })
+    }
+
+    fn apply_to_witness(&self, x: &ElemPrime, w: &Witness) -> Result<Witness, &'static str> {
+        let params = self.monty_params()?;
+        if let [_] = self.deleted.as_slice() {
+            // Single deletion, the steady state: nothing to walk back, no allocation
+            self.apply_with_values(x, w, std::slice::from_ref(&self.value), &params)
+        } else {
+            self.apply_with_values(x, w, &self.values_after_deletions(&params), &params)
+        }
+    }
+}
+
+impl EpochUpdate {
+    pub(crate) fn monty_params(&self) -> Result<MontyParams<8>, &'static str> {
+        let n: Option<Odd<U512>> = Odd::new(self.n).into();
+        Ok(MontyParams::new(n.ok_or("Modulus must be odd")?))
+    }
+
+    // The value after each deletion, walked back from the final value:
+    // values[i] = values[i + 1]^deleted[i + 1]. The same for every witness,
+    // so a holder of several elements computes it once (see WitnessSet).
+    pub(crate) fn values_after_deletions(&self, params: &MontyParams<8>) -> Vec<AccValue> {
+        let mut values = vec![self.value; self.deleted.len()];
+        for i in (0..self.deleted.len().saturating_sub(1)).rev() {
+            let next = MontyForm::new(values[i + 1].as_uint(), *params);
+            values[i] = AccValue::new(next.pow(self.deleted[i + 1].as_uint()).retrieve());
+        }
+        values
+    }
+
+    // apply_to_witness, given values_after_deletions.
+    pub(crate) fn apply_with_values(
+        &self,
+        x: &ElemPrime,
+        w: &Witness,
+        values: &[AccValue],
+        params: &MontyParams<8>,
+    ) -> Result<Witness, &'static str> {
+        if w.params_digest != self.params_digest {
+            return Err("Witness was issued under different parameters");
+        }
+        let mut value = w.value;
+        for (y, a_next) in self.deleted.iter().zip(values) {
+            value = bezout_step(x, y, &value, a_next, params)?;
+        }
+        Ok(Witness {
+            params_digest: self.params_digest,
+            value,
+        })
+    }
+}
+
+fn bezout_step(
+    x: &ElemPrime,
+    y: &ElemPrime,
+    w: &WitnessValue,
+    a_next: &AccValue,
+    params: &MontyParams<8>,
+) -> Result<WitnessValue, &'static str> {
+    if x == y {
+        return Err("Element was deleted in this update");
+    }
+    core::update_on_deletion(w, x, y, a_next, params).ok_or("Witness not invertible modulo n")
+}
+
+// Consecutive epoch updates, as published by the manager.
+#[derive(Clone, Debug, Default)]
+pub struct UpdateLog {
+    start_epoch: u64,     // Epoch the first update starts from
+    compacted_until: u64, // End of the merged first entry, start_epoch when nothing was compacted
+    updates: Vec<EpochUpdate>,
+}
+
+// Decides how much of an update log compact_with merges.
+pub trait CompactionPolicy {
+    // Entries ending at or before this epoch are merged; None leaves the log alone.
+    fn compact_before(&self, log: &UpdateLog) -> Option<u64>;
+}
+
+impl<F> CompactionPolicy for F
+where
+    F: Fn(&UpdateLog) -> Option<u64>,
+{
+    fn compact_before(&self, log: &UpdateLog) -> Option<u64> {
+        self(log)
+    }
+}
+
+// Keeps the last N epochs as published and merges everything older.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeepLastEpochs(pub u64);
+
+impl CompactionPolicy for KeepLastEpochs {
+    fn compact_before(&self, log: &UpdateLog) -> Option<u64> {
+        Some(log.latest_epoch().saturating_sub(self.0))
+    }
+}
+
+impl UpdateLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn starting_at(epoch: u64) -> Self {
+        Self {
+            start_epoch: epoch,
+            compacted_until: epoch,
+            updates: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, update: EpochUpdate) -> Result<(), &'static str> {
+        if update.from_epoch != self.latest_epoch() {
+            return Err("Update does not continue the log");
+        }
+        if let Some(last) = self.updates.last() {
+            if last.params_digest != update.params_digest {
+                return Err("Update messages are for different parameters");
+            }
+        }
+        self.updates.push(update);
+        Ok(())
+    }
+
+    pub fn start_epoch(&self) -> u64 {
+        self.start_epoch
+    }
+
+    pub fn latest_epoch(&self) -> u64 {
+        self.updates.last().map_or(self.start_epoch, |u| u.to_epoch)
+    }
+
+    pub fn updates(&self) -> &[EpochUpdate] {
+        &self.updates
+    }
+
+    // Epoch the compacted first entry ends at; holders strictly between the
+    // start and this epoch can no longer sync from the log.
+    pub fn compacted_until(&self) -> u64 {
+        self.compacted_until
+    }
+
+    // Whether a holder at epoch can still be brought up to date from the log,
+    // now or once it catches up with a holder ahead of it.
+    pub fn reaches(&self, epoch: u64) -> bool {
+        epoch == self.start_epoch || epoch >= self.compacted_until
+    }
+
+    // Merges every entry ending at or before before_epoch into one, the
+    // checkpoint a holder at the start of the log syncs across in a single
+    // step, and returns how many entries were dropped. Only the deleted
+    // primes of the merged range are kept, not a value per epoch, so holders
+    // in between have to fetch a fresh witness (see reaches).
+    pub fn compact(&mut self, before_epoch: u64) -> Result<usize, &'static str> {
+        let merged = self.updates.partition_point(|u| u.to_epoch <= before_epoch);
+        if merged < 2 {
+            return Ok(0);
+        }
+        let mut rest = self.updates.split_off(merged);
+        let mut entries = self.updates.iter();
+        let first = entries.next().ok_or("Update log is empty")?;
+        let checkpoint = entries.try_fold(first.clone(), |acc, u| acc.merge(u))?;
+        self.compacted_until = checkpoint.to_epoch;
+        rest.insert(0, checkpoint);
+        self.updates = rest;
+        Ok(merged - 1)
+    }
+
+    // compact, up to wherever the policy says.
+    pub fn compact_with<C: CompactionPolicy>(&mut self, policy: &C) -> Result<usize, &'static str> {
+        match policy.compact_before(self) {
+            Some(before_epoch) => self.compact(before_epoch),
+            None => Ok(0),
+        }
+    }
+
+    // The published updates after epoch, borrowed from the log.
+    pub fn pending(&self, epoch: u64) -> Result<&[EpochUpdate], &'static str> {
+        if epoch > self.latest_epoch() {
+            return Err("Epoch is ahead of the log");
+        }
+        if epoch < self.start_epoch {
+            return Err("Epoch predates the log");
+        }
+        if !self.reaches(epoch) {
+            return Err("Epoch was compacted out of the log");
+        }
+        let pending = &self.updates[self.updates.partition_point(|u| u.to_epoch <= epoch)..];
+        if pending.first().is_some_and(|u| u.from_epoch != epoch) {
+            return Err("Epoch is not on an update boundary");
+        }
+        Ok(pending)
+    }
+
+    // since, with the deleted primes multiplied together (see PrivateEpochUpdate).
+    pub fn since_private(&self, epoch: u64) -> Result<Option<PrivateEpochUpdate>, &'static str> {
+        Ok(self.since(epoch)?.map(|update| update.to_private()))
+    }
+
+    // Everything after epoch, coalesced into one message; None when up to date.
+    pub fn since(&self, epoch: u64) -> Result<Option<EpochUpdate>, &'static str> {
+        let mut pending = self.pending(epoch)?.iter();
+        let Some(first) = pending.next() else {
+            return Ok(None);
+        };
+        pending.try_fold(first.clone(), |acc, u| acc.merge(u)).map(Some)
+    }
+}
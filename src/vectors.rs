@@ -0,0 +1,99 @@
+use crypto_bigint::U256;
+use std::fmt::Write;
+
+use crate::deadline::Deadline;
+use crate::element::ElementDigest;
+use crate::group::ElemPrime;
+use crate::hash::{self, HashAlgorithm, ELEMENT_PRIME_BITS, HASH_TO_PRIME_PROFILE};
+use crate::report::hex;
+
+// Vectors for hash-to-prime alone, the function other implementations find
+// hardest to match exactly. Each one fixes the key and input and gives the
+// first candidate, the counter of the first prime and the prime itself, so
+// a port can tell a wrong candidate derivation from a wrong primality test.
+// The JSON is what the hash_to_prime_vectors example prints and
+// tests/fixtures/h2p_vectors_sha256.json holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashToPrimeVector {
+    pub key: U256,
+    pub input: Vec<u8>,
+    pub first_candidate: U256,
+    pub counter: u32,
+    pub prime: ElemPrime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashToPrimeVectors {
+    pub profile: u32,
+    pub hash: HashAlgorithm,
+    pub vectors: Vec<HashToPrimeVector>,
+}
+
+impl HashToPrimeVector {
+    pub fn compute(alg: HashAlgorithm, key: U256, input: &[u8]) -> Result<Self, &'static str> {
+        let (prime, counter, _) = hash::hash_to_prime_certified(alg, &key, input, &Deadline::none())?;
+        Ok(Self {
+            key,
+            input: input.to_vec(),
+            first_candidate: hash::candidate(alg, &key, input, 0),
+            counter,
+            prime: ElemPrime::new(prime),
+        })
+    }
+}
+
+impl HashToPrimeVectors {
+    // The published set for one digest: every key against every input,
+    // digest elements included.
+    pub fn standard(alg: HashAlgorithm) -> Result<Self, &'static str> {
+        let keys = [
+            U256::ZERO,
+            U256::from_be_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+        ];
+        let digest = ElementDigest::new("sha256:email", [0x11; 32])?;
+        let inputs: [&[u8]; 8] = [
+            b"",
+            b"alice",
+            &[0x00],
+            &[0xff; 32],
+            &std::array::from_fn::<u8, 256, _>(|i| i as u8),
+            b"member-1000000",
+            &42u64.to_be_bytes(),
+            digest.as_ref(),
+        ];
+        let mut vectors = Vec::new();
+        for key in keys {
+            for input in inputs {
+                vectors.push(HashToPrimeVector::compute(alg, key, input)?);
+            }
+        }
+        Ok(Self {
+            profile: HASH_TO_PRIME_PROFILE,
+            hash: alg,
+            vectors,
+        })
+    }
+
+    // Whether this build reproduces every vector.
+    pub fn check(&self) -> bool {
+        self.profile == HASH_TO_PRIME_PROFILE
+            && self.vectors.iter().all(|v| HashToPrimeVector::compute(self.hash, v.key, &v.input).as_ref() == Ok(v))
+    }
+
+    // One vector per line; byte strings and integers in big-endian hex.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n");
+        let _ = writeln!(out, "  \"profile\": {},", self.profile);
+        let _ = writeln!(out, "  \"hash\": \"{:?}\",", self.hash);
+        let _ = writeln!(out, "  \"element_prime_bits\": {},", ELEMENT_PRIME_BITS);
+        out.push_str("  \"vectors\": [\n");
+        for (i, v) in self.vectors.iter().enumerate() {
+            let _ = write!(out, "    {{\"key\": \"{}\", \"input\": \"{}\", ", hex(&v.key.to_be_bytes()), hex(&v.input));
+            let _ = write!(out, "\"first_candidate\": \"{}\", ", hex(&v.first_candidate.to_be_bytes()));
+            let _ = write!(out, "\"counter\": {}, \"prime\": \"{}\"}}", v.counter, hex(&v.prime.to_be_bytes()));
+            out.push_str(if i + 1 < self.vectors.len() { ",\n" } else { "\n" });
+        }
+        out.push_str("  ]\n}\n");
+        out
+    }
+}
@@ -0,0 +1,104 @@
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::AccValue;
+use crate::hash;
+use crate::params::PublicParams;
+use crate::registry::RegistryEntry;
+use crate::witness::Witness;
+
+const CERTIFIED_WITNESS_TAG: &[u8] = b"braavos-certified-witness";
+
+// How a verifier comes by the prime of the element it checks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VerificationPolicy {
+    // Search x's candidate sequence itself and ignore the supplied prime:
+    // a primality test per candidate up to the first prime.
+    #[default]
+    Recompute,
+    // Check the supplied certificate (see RegistryEntry): a Fermat test per
+    // rejected candidate and one primality test. A certificate cannot skip a
+    // prime, so nothing is taken on the prover's word.
+    Certified,
+    // Take the supplied prime if it is candidate `counter` of x, with no
+    // primality work at all. Trusts the prover that no earlier candidate is
+    // prime and that this one is: a prover holding a root for a later
+    // candidate, or for a composite one, passes.
+    Trusted,
+}
+
+// A witness together with its element's prime and the certificate for it,
+// so verifiers can skip the hash-to-prime search (see
+// BraavosAccumulator::certify).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertifiedWitness {
+    pub witness: Witness,
+    pub entry: RegistryEntry,
+}
+
+impl CertifiedWitness {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(CERTIFIED_WITNESS_TAG);
+        w.put_bytes(&self.witness.to_bytes());
+        w.put_u256(self.entry.prime.as_uint());
+        w.put_u32(self.entry.counter);
+        w.put_bytes(&self.entry.certificate);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, CERTIFIED_WITNESS_TAG, policy)?;
+        let witness = Witness::from_bytes_with(r.get_bytes()?, policy)?;
+        let prime = r.get_prime()?;
+        let counter = r.get_u32()?;
+        let certificate = r.get_bytes()?.to_vec();
+        r.finish()?;
+        Ok(Self {
+            witness,
+            entry: RegistryEntry {
+                prime,
+                counter,
+                certificate,
+            },
+        })
+    }
+}
+
+impl PublicParams {
+    // Whether w opens the published value for x, with x's prime found as the
+    // policy says. Values outside the subgroup are rejected.
+    pub fn verify_with_policy(
+        &self,
+        x: impl AsRef<[u8]>,
+        w: &CertifiedWitness,
+        value: &AccValue,
+        policy: VerificationPolicy,
+    ) -> bool {
+        let x = x.as_ref();
+        let n: Option<Odd<U512>> = Odd::new(self.n).into();
+        let Some(n) = n else {
+            return false;
+        };
+        if w.witness.check_params(self).is_err() || !core::is_group_element(value.as_uint(), &self.n) {
+            return false;
+        }
+        let entry = &w.entry;
+        let prime = match policy {
+            VerificationPolicy::Recompute => self.hash_to_prime(x),
+            VerificationPolicy::Certified if entry.check(self.hash, &self.prf_key, x) => entry.prime,
+            VerificationPolicy::Trusted
+                if hash::candidate(self.hash, &self.prf_key, x, entry.counter) == *entry.prime.as_uint() =>
+            {
+                entry.prime
+            }
+            _ => return false,
+        };
+        core::opens(&w.witness.value, &prime, value, &MontyParams::new(n))
+    }
+}
@@ -0,0 +1,718 @@
+// Epoch updates over WebSocket, for holders that can only open a browser
+// socket (WASM, no gRPC).
+//
+// Every message is one binary frame holding a tagged encoding. A holder sends
+// Subscribe(epoch) and receives everything published since that epoch
+// coalesced into one update, then each new update as it is published. A
+// Refresh(element, witness, epoch) asks the server to bring the witness
+// current itself, for holders too constrained to apply updates. Failures come
+// back as an error code.
+//
+// Only what the protocol needs of RFC 6455 is implemented: no extensions,
+// no fragmented messages, and plain TCP (terminate TLS in front of the
+// server).
+use crypto_bigint::rand_core::RngCore;
+use rand::rngs::OsRng;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::holder::WitnessHolder;
+use crate::params::PublicParams;
+use crate::sync::lock;
+use crate::update::{EpochUpdate, UpdateLog};
+use crate::witness::Witness;
+
+const SUBSCRIBE_TAG: &[u8] = b"braavos-ws-subscribe";
+const REFRESH_TAG: &[u8] = b"braavos-ws-refresh";
+const REFRESHED_TAG: &[u8] = b"braavos-ws-refreshed";
+const ERROR_TAG: &[u8] = b"braavos-ws-error";
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xa;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_HEADER_LINE: usize = 8192;
+const MAX_HEADER_LINES: usize = 64;
+const DEFAULT_MAX_CONNECTIONS: usize = 1024;
+// Frames a connection may have waiting to be written before it is dropped
+const OUTBOX_CAPACITY: usize = 64;
+const CONNECTION_CLOSED: &str = "Connection closed";
+
+// Errors a server can report, sent as their index. Anything else is reported
+// as the last entry.
+const ERRORS: &[&str] = &[
+    "Epoch predates the log",
+    "Epoch is ahead of the log",
+    "Epoch is not on an update boundary",
+    "Element has been deleted",
+    "Witness was issued under different parameters",
+    "Malformed request",
+    "Request failed",
+];
+
+// Serves an update log to WebSocket holders. Each connection gets a thread
+// reading its requests and one writing its frames. publish only queues an
+// update for every subscriber, so a slow holder delays no one else; one that
+// falls OUTBOX_CAPACITY frames behind, or stalls a write for WRITE_TIMEOUT,
+// is disconnected and resyncs when it reconnects.
+pub struct UpdateServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    params: PublicParams,
+    state: Mutex<ServerState>,
+    stopped: AtomicBool,
+    connections: AtomicUsize,
+    max_connections: AtomicUsize,
+}
+
+struct ServerState {
+    log: UpdateLog,
+    subscribers: Vec<Outbox>,
+}
+
+// The frames waiting to be written to one connection, and the means to
+// close it.
+#[derive(Clone)]
+struct Outbox {
+    frames: SyncSender<(u8, Arc<[u8]>)>,
+    stream: Arc<TcpStream>,
+    closed: Arc<AtomicBool>,
+}
+
+impl UpdateServer {
+    // Listens on `addr` (port 0 picks a free one) and serves `log`, which
+    // must continue from the holders' epochs.
+    pub fn bind(addr: impl ToSocketAddrs, params: PublicParams, log: UpdateLog) -> Result<Self, &'static str> {
+        let listener = TcpListener::bind(addr).map_err(|_| "Failed to bind update server")?;
+        let addr = listener.local_addr().map_err(|_| "Failed to bind update server")?;
+        let shared = Arc::new(Shared {
+            params,
+            state: Mutex::new(ServerState {
+                log,
+                subscribers: Vec::new(),
+            }),
+            stopped: AtomicBool::new(false),
+            connections: AtomicUsize::new(0),
+            max_connections: AtomicUsize::new(DEFAULT_MAX_CONNECTIONS),
+        });
+        let accepting = shared.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accepting.stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let max = accepting.max_connections.load(Ordering::SeqCst);
+                if accepting.connections.fetch_add(1, Ordering::SeqCst) >= max {
+                    accepting.connections.fetch_sub(1, Ordering::SeqCst);
+                    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                    let _ = stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n");
+                    continue;
+                }
+                let shared = accepting.clone();
+                thread::spawn(move || {
+                    // A failed connection only affects its own holder
+                    let _ = serve(&shared, stream);
+                    shared.connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        Ok(Self { addr, shared })
+    }
+
+    // Connections beyond `max` (1024 by default) are refused with a 503
+    // until others close.
+    pub fn with_max_connections(self, max: usize) -> Self {
+        self.shared.max_connections.store(max, Ordering::SeqCst);
+        self
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Appends the update to the log and queues it for every subscriber.
+    // Subscribers whose connection failed or fell behind are dropped; they
+    // resync on reconnect.
+    pub fn publish(&self, update: EpochUpdate) -> Result<(), &'static str> {
+        let mut state = lock(&self.shared.state);
+        let frame: Arc<[u8]> = update.to_bytes().into();
+        state.log.push(update)?;
+        state.subscribers.retain(|s| s.send(OP_BINARY, frame.clone()));
+        Ok(())
+    }
+
+    // Number of connections open, subscribed or not.
+    pub fn connections(&self) -> usize {
+        self.shared.connections.load(Ordering::SeqCst)
+    }
+
+    // Number of connections currently receiving updates.
+    pub fn subscribers(&self) -> usize {
+        lock(&self.shared.state).subscribers.len()
+    }
+
+    // Closes every subscriber connection, as if the server had restarted.
+    pub fn disconnect_all(&self) {
+        let mut state = lock(&self.shared.state);
+        for s in state.subscribers.drain(..) {
+            s.close();
+        }
+    }
+}
+
+impl Drop for UpdateServer {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        self.disconnect_all();
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn serve(shared: &Shared, mut stream: TcpStream) -> Result<(), &'static str> {
+    stream
+        .set_read_timeout(Some(HANDSHAKE_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(WRITE_TIMEOUT)))
+        .map_err(|_| "Failed to configure stream")?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|_| "Failed to clone stream")?);
+    accept_handshake(&mut reader, &mut stream)?;
+    // A subscribed holder sends nothing more until it wants something, so
+    // from here reads wait as long as the connection stays open
+    stream.set_read_timeout(None).map_err(|_| "Failed to configure stream")?;
+    let outbox = Outbox::open(stream)?;
+    let served = serve_requests(shared, &mut reader, &outbox);
+    outbox.close();
+    lock(&shared.state).subscribers.retain(|s| !s.is_closed());
+    served
+}
+
+fn serve_requests(shared: &Shared, reader: &mut BufReader<TcpStream>, outbox: &Outbox) -> Result<(), &'static str> {
+    loop {
+        let (opcode, payload) = read_frame(reader, true)?;
+        match opcode {
+            OP_BINARY => {}
+            OP_PING => {
+                outbox.reply(OP_PONG, payload)?;
+                continue;
+            }
+            OP_PONG => continue,
+            OP_CLOSE => {
+                let _ = outbox.reply(OP_CLOSE, Vec::new());
+                return Ok(());
+            }
+            _ => return Err("Unsupported frame"),
+        }
+
+        let policy = DecodingPolicy::strict().for_params(&shared.params);
+        let mut state = lock(&shared.state);
+        let reply = if let Ok(epoch) = decode_subscribe(&payload, &policy) {
+            // Catch-up and registration happen under the lock, so no update is
+            // missed or sent twice
+            match state.log.since(epoch) {
+                Ok(catch_up) => {
+                    if let Some(update) = catch_up {
+                        outbox.reply(OP_BINARY, update.to_bytes())?;
+                    }
+                    state.subscribers.push(outbox.clone());
+                    continue;
+                }
+                Err(e) => encode_error(e),
+            }
+        } else if let Ok((element, witness, epoch)) = decode_refresh(&payload, &policy) {
+            let refreshed = WitnessHolder::new(&shared.params, &element, witness, epoch).and_then(|mut holder| {
+                holder.sync(&state.log)?;
+                Ok(holder)
+            });
+            match refreshed {
+                Ok(holder) => {
+                    let mut w = Writer::new(REFRESHED_TAG);
+                    w.put_bytes(&holder.witness().to_bytes());
+                    w.put_u64(holder.epoch());
+                    w.finish()
+                }
+                Err(e) => encode_error(e),
+            }
+        } else {
+            encode_error("Malformed request")
+        };
+        drop(state);
+        outbox.reply(OP_BINARY, reply)?;
+    }
+}
+
+impl Outbox {
+    // Starts the thread writing the connection's frames in the order they
+    // were queued. A failed or timed out write closes the connection.
+    fn open(stream: TcpStream) -> Result<Self, &'static str> {
+        let (frames, queued) = mpsc::sync_channel::<(u8, Arc<[u8]>)>(OUTBOX_CAPACITY);
+        let mut writer = stream.try_clone().map_err(|_| "Failed to clone stream")?;
+        thread::spawn(move || {
+            for (opcode, payload) in queued {
+                if write_frame(&mut writer, opcode, &payload, false).is_err() {
+                    let _ = writer.shutdown(Shutdown::Both);
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            frames,
+            stream: Arc::new(stream),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    // Queues a frame without waiting; false, with the connection closed, if
+    // it has failed or has OUTBOX_CAPACITY frames still unwritten.
+    fn send(&self, opcode: u8, payload: Arc<[u8]>) -> bool {
+        if self.is_closed() || self.frames.try_send((opcode, payload)).is_err() {
+            self.close();
+            return false;
+        }
+        true
+    }
+
+    fn reply(&self, opcode: u8, payload: Vec<u8>) -> Result<(), &'static str> {
+        match self.send(opcode, payload.into()) {
+            true => Ok(()),
+            false => Err(CONNECTION_CLOSED),
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+// Holder side of the protocol. Keeps one connection and reopens it when it
+// drops, resubscribing from the epoch the caller has reached, so nothing
+// published in between is lost.
+pub struct UpdateFeed {
+    addr: String,
+    retries: u32,
+    conn: Option<Connection>,
+    subscribed: Option<u64>, // Epoch the live subscription was opened at
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    queued: VecDeque<EpochUpdate>, // Updates that arrived while awaiting a reply
+}
+
+impl UpdateFeed {
+    // `addr` is host:port. A dropped connection is retried up to `retries`
+    // times in a row, backing off from 100ms, before next() gives up.
+    pub fn new(addr: &str, retries: u32) -> Self {
+        Self {
+            addr: addr.to_string(),
+            retries,
+            conn: None,
+            subscribed: None,
+        }
+    }
+
+    // Waits for the first update after `epoch`. Updates the caller has
+    // already passed are skipped, and a gap (missed update) forces a
+    // resubscription.
+    pub fn next(&mut self, epoch: u64) -> Result<EpochUpdate, &'static str> {
+        let mut failures = 0;
+        loop {
+            match self.try_next(epoch) {
+                Ok(Some(update)) => return Ok(update),
+                Ok(None) => {}
+                Err(Failure::Server(e)) => return Err(e),
+                Err(Failure::Invalid(e)) => {
+                    self.conn = None;
+                    self.subscribed = None;
+                    return Err(e);
+                }
+                Err(Failure::Connection) => {
+                    self.conn = None;
+                    self.subscribed = None;
+                    if failures == self.retries {
+                        return Err("Update server unreachable");
+                    }
+                    thread::sleep(Duration::from_millis(100) * 2u32.pow(failures.min(6)));
+                    failures += 1;
+                }
+            }
+        }
+    }
+
+    fn try_next(&mut self, epoch: u64) -> Result<Option<EpochUpdate>, Failure> {
+        if self.subscribed.is_none() {
+            let mut w = Writer::new(SUBSCRIBE_TAG);
+            w.put_u64(epoch);
+            self.connection()?.send(&w.finish())?;
+            self.subscribed = Some(epoch);
+        }
+        let update = self.connection()?.next_update()?;
+        if update.to_epoch <= epoch {
+            return Ok(None);
+        }
+        if update.from_epoch > epoch {
+            // Missed something: start over from where the caller is
+            self.conn = None;
+            self.subscribed = None;
+            return Ok(None);
+        }
+        Ok(Some(update))
+    }
+
+    // Has the server bring the witness current; returns it with its epoch.
+    pub fn refresh(
+        &mut self,
+        element: impl AsRef<[u8]>,
+        witness: &Witness,
+        epoch: u64,
+    ) -> Result<(Witness, u64), &'static str> {
+        let element = element.as_ref();
+        let mut w = Writer::new(REFRESH_TAG);
+        w.put_bytes(element);
+        w.put_bytes(&witness.to_bytes());
+        w.put_u64(epoch);
+        let request = w.finish();
+        let reply = self.connection().and_then(|conn| {
+            conn.send(&request)?;
+            conn.next_reply()
+        });
+        let reply = match reply {
+            Ok(reply) => reply,
+            Err(Failure::Server(e)) => return Err(e),
+            Err(Failure::Invalid(e)) => {
+                self.conn = None;
+                self.subscribed = None;
+                return Err(e);
+            }
+            Err(Failure::Connection) => {
+                self.conn = None;
+                self.subscribed = None;
+                return Err("Update server unreachable");
+            }
+        };
+        let policy = DecodingPolicy::strict();
+        let mut r = Reader::with_policy(&reply, REFRESHED_TAG, &policy)?;
+        let witness = Witness::from_bytes_with(r.get_bytes()?, &policy)?;
+        let epoch = r.get_u64()?;
+        r.finish()?;
+        Ok((witness, epoch))
+    }
+
+    fn connection(&mut self) -> Result<&mut Connection, Failure> {
+        if self.conn.is_none() {
+            self.conn = Some(Connection::open(&self.addr).map_err(|_| Failure::Connection)?);
+        }
+        self.conn.as_mut().ok_or(Failure::Connection)
+    }
+}
+
+enum Failure {
+    Connection,            // Worth reconnecting
+    Server(&'static str),  // The server refused the request
+    Invalid(&'static str), // The server broke the protocol; reconnecting would not help
+}
+
+impl Failure {
+    // From a frame read or write: a lost connection, or a frame this holder
+    // must not accept.
+    fn frame(e: &'static str) -> Self {
+        match e {
+            CONNECTION_CLOSED => Failure::Connection,
+            e => Failure::Invalid(e),
+        }
+    }
+}
+
+impl Connection {
+    fn open(addr: &str) -> Result<Self, &'static str> {
+        let mut writer = TcpStream::connect(addr).map_err(|_| "Failed to connect")?;
+        writer
+            .set_read_timeout(Some(HANDSHAKE_TIMEOUT))
+            .and_then(|_| writer.set_write_timeout(Some(WRITE_TIMEOUT)))
+            .map_err(|_| "Failed to configure stream")?;
+        let mut reader = BufReader::new(writer.try_clone().map_err(|_| "Failed to clone stream")?);
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+        let key = base64(&nonce);
+        let request = format!(
+            "GET / HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            addr, key
+        );
+        writer.write_all(request.as_bytes()).map_err(|_| "Failed to send handshake")?;
+        let headers = read_headers(&mut reader)?;
+        if !headers.first().is_some_and(|status| status.starts_with("HTTP/1.1 101")) {
+            return Err("Server refused the WebSocket upgrade");
+        }
+        if header(&headers, "sec-websocket-accept") != Some(accept_key(&key).as_str()) {
+            return Err("Invalid WebSocket accept key");
+        }
+        // Updates may be far apart; a dead server shows up as a closed connection
+        writer.set_read_timeout(None).map_err(|_| "Failed to configure stream")?;
+        Ok(Self {
+            reader,
+            writer,
+            queued: VecDeque::new(),
+        })
+    }
+
+    fn send(&mut self, payload: &[u8]) -> Result<(), Failure> {
+        write_frame(&mut self.writer, OP_BINARY, payload, true).map_err(Failure::frame)
+    }
+
+    // Next binary message, answering pings on the way.
+    fn receive(&mut self) -> Result<Vec<u8>, Failure> {
+        loop {
+            let (opcode, payload) = read_frame(&mut self.reader, false).map_err(Failure::frame)?;
+            match opcode {
+                OP_BINARY => return Ok(payload),
+                OP_PING => write_frame(&mut self.writer, OP_PONG, &payload, true).map_err(Failure::frame)?,
+                OP_PONG => {}
+                _ => return Err(Failure::Connection),
+            }
+        }
+    }
+
+    fn next_update(&mut self) -> Result<EpochUpdate, Failure> {
+        if let Some(update) = self.queued.pop_front() {
+            return Ok(update);
+        }
+        let message = self.receive()?;
+        if let Ok(code) = decode_error(&message) {
+            return Err(Failure::Server(code));
+        }
+        EpochUpdate::from_bytes_with(&message, &DecodingPolicy::strict()).map_err(Failure::Invalid)
+    }
+
+    // The reply to a request, queueing any updates streamed before it.
+    fn next_reply(&mut self) -> Result<Vec<u8>, Failure> {
+        loop {
+            let message = self.receive()?;
+            if let Ok(code) = decode_error(&message) {
+                return Err(Failure::Server(code));
+            }
+            match EpochUpdate::from_bytes_with(&message, &DecodingPolicy::strict()) {
+                Ok(update) => self.queued.push_back(update),
+                Err(_) => return Ok(message),
+            }
+        }
+    }
+}
+
+fn decode_subscribe(payload: &[u8], policy: &DecodingPolicy) -> Result<u64, &'static str> {
+    let mut r = Reader::with_policy(payload, SUBSCRIBE_TAG, policy)?;
+    let epoch = r.get_u64()?;
+    r.finish()?;
+    Ok(epoch)
+}
+
+fn decode_refresh(payload: &[u8], policy: &DecodingPolicy) -> Result<(Vec<u8>, Witness, u64), &'static str> {
+    let mut r = Reader::with_policy(payload, REFRESH_TAG, policy)?;
+    let element = r.get_bytes()?.to_vec();
+    let witness = Witness::from_bytes_with(r.get_bytes()?, policy)?;
+    let epoch = r.get_u64()?;
+    r.finish()?;
+    Ok((element, witness, epoch))
+}
+
+fn encode_error(e: &str) -> Vec<u8> {
+    let code = ERRORS.iter().position(|known| *known == e).unwrap_or(ERRORS.len() - 1);
+    let mut w = Writer::new(ERROR_TAG);
+    w.put_u32(code as u32);
+    w.finish()
+}
+
+fn decode_error(payload: &[u8]) -> Result<&'static str, &'static str> {
+    let mut r = Reader::new(payload, ERROR_TAG)?;
+    let code = r.get_u32()? as usize;
+    r.finish()?;
+    Ok(ERRORS.get(code).copied().unwrap_or(ERRORS[ERRORS.len() - 1]))
+}
+
+fn accept_handshake(reader: &mut BufReader<TcpStream>, writer: &mut TcpStream) -> Result<(), &'static str> {
+    let headers = read_headers(reader)?;
+    let upgrade = header(&headers, "upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    let key = header(&headers, "sec-websocket-key").filter(|_| upgrade);
+    let Some(key) = key else {
+        let _ = writer.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
+        return Err("Not a WebSocket upgrade");
+    };
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    writer.write_all(response.as_bytes()).map_err(|_| "Failed to send handshake")
+}
+
+// The request or status line followed by the header lines, up to the blank line.
+fn read_headers(reader: &mut BufReader<TcpStream>) -> Result<Vec<String>, &'static str> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let mut limited = reader.by_ref().take(MAX_HEADER_LINE as u64);
+        let read = limited.read_line(&mut line).map_err(|_| "Failed to read handshake")?;
+        if read == 0 {
+            return Err("Truncated handshake");
+        }
+        if !line.ends_with('\n') || lines.len() == MAX_HEADER_LINES {
+            return Err("Handshake headers too long");
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            return Ok(lines);
+        }
+        lines.push(line.to_string());
+    }
+}
+
+fn header<'a>(headers: &'a [String], name: &str) -> Option<&'a str> {
+    headers.iter().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+// Reads one frame. Frames from clients must be masked and frames from
+// servers must not be.
+fn read_frame(reader: &mut impl Read, from_client: bool) -> Result<(u8, Vec<u8>), &'static str> {
+    let mut head = [0u8; 2];
+    reader.read_exact(&mut head).map_err(|_| CONNECTION_CLOSED)?;
+    if head[0] & 0x80 == 0 || head[0] & 0x0f == 0 {
+        return Err("Fragmented messages are not supported");
+    }
+    if (head[1] & 0x80 != 0) != from_client {
+        return Err("Invalid frame masking");
+    }
+    let len = match head[1] & 0x7f {
+        126 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len).map_err(|_| CONNECTION_CLOSED)?;
+            u16::from_be_bytes(len) as usize
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            reader.read_exact(&mut len).map_err(|_| CONNECTION_CLOSED)?;
+            usize::try_from(u64::from_be_bytes(len)).map_err(|_| "Message too large")?
+        }
+        len => len as usize,
+    };
+    if len > DecodingPolicy::strict().max_input {
+        return Err("Message too large");
+    }
+    let mut mask = [0u8; 4];
+    if from_client {
+        reader.read_exact(&mut mask).map_err(|_| CONNECTION_CLOSED)?;
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).map_err(|_| CONNECTION_CLOSED)?;
+    for (i, b) in payload.iter_mut().enumerate() {
+        *b ^= mask[i % 4];
+    }
+    Ok((head[0] & 0x0f, payload))
+}
+
+fn write_frame(writer: &mut impl Write, opcode: u8, payload: &[u8], masked: bool) -> Result<(), &'static str> {
+    let mut frame = vec![0x80 | opcode];
+    let mask_bit = if masked { 0x80 } else { 0 };
+    match payload.len() {
+        len if len < 126 => frame.push(mask_bit | len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(mask_bit | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(mask_bit | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        OsRng.fill_bytes(&mut mask);
+        frame.extend_from_slice(&mask);
+    }
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    writer.write_all(&frame).map_err(|_| CONNECTION_CLOSED)
+}
+
+// SHA-1, needed only for the handshake's accept key.
+fn sha1(message: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+    for block in padded.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, t);
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+    let mut out = [0u8; 20];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
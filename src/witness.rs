@@ -0,0 +1,130 @@
+use crypto_bigint::{modular::MontyParams, Odd, U256, U512};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Sink, Writer};
+use crate::group::{AccValue, ElemPrime, WitnessValue};
+use crate::params::PublicParams;
+
+const WITNESS_TAG: &[u8] = b"braavos-witness";
+
+// Membership witness for one element, tagged with the parameters it was
+// issued under. Compares in constant time, as its value does.
+#[derive(Clone, Copy, Debug)]
+pub struct Witness {
+    pub params_digest: [u8; 32],
+    pub value: WitnessValue, // value^prime = accumulator value mod n
+}
+
+impl Witness {
+    pub fn check_params(&self, params: &PublicParams) -> Result<(), &'static str> {
+        if self.params_digest != params.digest() {
+            return Err("Witness was issued under different parameters");
+        }
+        Ok(())
+    }
+
+    // Size of the to_bytes() encoding.
+    pub const ENCODED_LEN: usize = 4 + WITNESS_TAG.len() + 4 + 32 + 64;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(WITNESS_TAG);
+        self.encode(&mut w);
+        w.finish()
+    }
+
+    // Same encoding as to_bytes() into a caller-provided buffer, for hot paths
+    // that serialize without allocating. Returns the number of bytes written.
+    pub fn write_to(&self, out: &mut [u8]) -> Result<usize, &'static str> {
+        let mut w = Writer::in_slice(out, WITNESS_TAG);
+        self.encode(&mut w);
+        w.finish()
+    }
+
+    fn encode<S: Sink>(&self, w: &mut Writer<S>) {
+        w.put_bytes(&self.params_digest);
+        w.put_u512(self.value.as_uint());
+    }
+
+    // Decodes in place: the reader borrows `bytes`, nothing is copied but the
+    // fixed-size fields themselves.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, WITNESS_TAG, policy)?;
+        let params_digest = r.get_bytes()?.try_into().map_err(|_| "Invalid parameter digest")?;
+        let value = WitnessValue::new(r.get_element(None)?);
+        if let Some(n) = policy.modulus.filter(|_| policy.nontrivial_witnesses) {
+            WitnessValue::check(value.as_uint(), &n)?;
+        }
+        r.finish()?;
+        Ok(Self { params_digest, value })
+    }
+}
+
+impl ConstantTimeEq for Witness {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.params_digest[..].ct_eq(&other.params_digest[..]) & self.value.ct_eq(&other.value)
+    }
+}
+
+impl ConditionallySelectable for Witness {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut params_digest = [0u8; 32];
+        for (d, (a, b)) in params_digest.iter_mut().zip(a.params_digest.iter().zip(&b.params_digest)) {
+            *d = u8::conditional_select(a, b, choice);
+        }
+        Self {
+            params_digest,
+            value: WitnessValue::conditional_select(&a.value, &b.value, choice),
+        }
+    }
+}
+
+impl PartialEq for Witness {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Witness {}
+
+// Evidence that an element was never deleted: d^x * base^beta equals the value
+// at `epoch`, where beta = (product of deleted primes)^(-1) mod x. Only valid
+// for that epoch; a new one is needed after every deletion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonMembershipWitness {
+    pub params_digest: [u8; 32],
+    pub epoch: u64,
+    pub d: U512,
+    pub beta: U256,
+}
+
+impl NonMembershipWitness {
+    pub fn verify(&self, params: &PublicParams, x: &ElemPrime, value: &AccValue) -> bool {
+        if self.params_digest != params.digest() {
+            return false;
+        }
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let Some(n) = n else {
+            return false;
+        };
+        core::opens_non_membership(&self.d, &self.beta, x, &params.base, value, &MontyParams::new(n))
+    }
+}
+
+// Seals a serialized witness to the public key of the holder of `element`.
+// Key lookup and the encryption scheme are up to the deployment.
+pub trait WitnessEncryptor {
+    fn encrypt_for(&self, element: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, &'static str>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedWitness {
+    pub element: Vec<u8>,
+    pub epoch: u64,          // Epoch the witness is valid for
+    pub ciphertext: Vec<u8>, // WitnessEncryptor output over Witness::to_bytes()
+}
@@ -0,0 +1,743 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    Odd, RandomBits, U1024, U128, U2048, U512,
+};
+use rand::rngs::OsRng;
+
+use crate::core;
+use crate::decoding::DecodingPolicy;
+use crate::encoding::{Reader, Writer};
+use crate::group::{AccValue, ElemPrime};
+use crate::hash::ELEMENT_PRIME_BITS;
+use crate::params::PublicParams;
+use crate::signature;
+use crate::witness::{NonMembershipWitness, Witness};
+
+// Zero-knowledge proof that a committed element is not among the deleted ones,
+// so a holder can pass a deny-list check without revealing who they are.
+//
+// The element x is hidden in a Pedersen commitment C = g^x h^r and the
+// non-membership witness d in C_d = d h^r2, with g and h hashed into QR_n. The
+// prover then shows knowledge of (x, r, beta, r2, z = x r2, t = r r2) with
+//
+//     C = g^x h^r,    value = C_d^x h^-z base^beta,    1 = C^r2 g^-z h^-t
+//
+// using a Fiat-Shamir'd sigma protocol over the integers (the group order is
+// unknown, so responses are not reduced and masks carry STAT_BITS of slack).
+// The proof only says something about whoever C belongs to; binding C to an
+// identity is up to the credential it comes from.
+//
+// Nothing shows that x is a well-formed element prime. Anyone can commit to
+// x = 1 and prove with beta = 0 and d = value, which "proves" non-membership
+// against every value, so a verifier must only accept commitments a
+// credential issuer certified as hash-to-prime of an identifier (the same
+// holds for MembershipProof and CompositeProof).
+
+const PROOF_TAG: &[u8] = b"braavos-zk-non-membership";
+const RERANDOMIZATION_TAG: &[u8] = b"braavos-zk-rerandomization";
+const MEMBERSHIP_TAG: &[u8] = b"braavos-zk-membership";
+const COMPOSITE_TAG: &[u8] = b"braavos-zk-composite";
+const GENERATOR_DOMAIN: &[u8] = b"braavos-zk-generator";
+
+const STAT_BITS: u32 = 128; // Statistical hiding of the responses
+const CHALLENGE_BITS: u32 = 128;
+
+// Commitment randomness is drawn below 2^BLINDING_BITS, well past the modulus
+// so g^x h^r hides x.
+pub const BLINDING_BITS: u32 = 512 + STAT_BITS;
+
+// Bit sizes of the secrets (x, r, beta, r2, x r2, r r2).
+const SECRET_BITS: [u32; 6] = [
+    ELEMENT_PRIME_BITS,
+    BLINDING_BITS,
+    ELEMENT_PRIME_BITS,
+    BLINDING_BITS,
+    ELEMENT_PRIME_BITS + BLINDING_BITS,
+    2 * BLINDING_BITS,
+];
+
+// Not a proof that the commitment holds an element prime: one to x = 1 passes
+// against any value (see above).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonMembershipProof {
+    pub epoch: u64,            // Epoch of the value the proof was made against
+    pub commitment: U512,      // g^x h^r
+    pub blinded_witness: U512, // d h^r2
+    pub t: [U512; 3],          // Prover's first message, one per relation
+    pub s: [U2048; 6],         // Responses, in SECRET_BITS order
+}
+
+// Pedersen commitment to an element prime.
+pub fn commit_element(params: &PublicParams, x: &ElemPrime, blinding: &U1024) -> Result<U512, &'static str> {
+    let group = Group::new(params)?;
+    Ok(group.g.pow(x.as_uint()).mul(&group.h.pow(blinding)).retrieve())
+}
+
+impl NonMembershipProof {
+    pub fn prove(
+        params: &PublicParams,
+        value: &AccValue,
+        x: &ElemPrime,
+        blinding: &U1024,
+        witness: &NonMembershipWitness,
+    ) -> Result<Self, &'static str> {
+        if !witness.verify(params, x, value) {
+            return Err("Witness does not open the accumulator value");
+        }
+        if blinding.bits() > BLINDING_BITS {
+            return Err("Blinding factor is too large");
+        }
+        let group = Group::new(params)?;
+
+        let r2 = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+        let commitment = group.g.pow(x.as_uint()).mul(&group.h.pow(blinding));
+        let blinded = group.element(&witness.d).mul(&group.h.pow(&r2));
+
+        let (x, r, beta, r2) = (
+            x.as_uint().resize::<32>(),
+            blinding.resize::<32>(),
+            witness.beta.resize::<32>(),
+            r2.resize::<32>(),
+        );
+        let secrets = [x, r, beta, r2, x.wrapping_mul(&r2), r.wrapping_mul(&r2)];
+        let masks = SECRET_BITS.map(|bits| U2048::random_bits(&mut OsRng, bits + STAT_BITS + CHALLENGE_BITS));
+
+        let t = group.relations(&commitment, &blinded, &masks).map(|t| t.retrieve());
+        let mut proof = Self {
+            epoch: witness.epoch,
+            commitment: commitment.retrieve(),
+            blinded_witness: blinded.retrieve(),
+            t,
+            s: [U2048::ZERO; 6],
+        };
+        let c = proof.challenge(params, value);
+        for i in 0..6 {
+            proof.s[i] = masks[i].wrapping_add(&c.wrapping_mul(&secrets[i]));
+        }
+        Ok(proof)
+    }
+
+    // Checks the proof against the accumulator value at self.epoch.
+    pub fn verify(&self, params: &PublicParams, value: &AccValue) -> bool {
+        let Ok(group) = Group::new(params) else {
+            return false;
+        };
+        let in_range = self
+            .s
+            .iter()
+            .zip(SECRET_BITS)
+            .all(|(s, bits)| s.bits() <= bits + STAT_BITS + CHALLENGE_BITS + 1);
+        if !in_range || self.commitment >= params.n || self.blinded_witness >= params.n {
+            return false;
+        }
+        let commitment = group.element(&self.commitment);
+        let blinded = group.element(&self.blinded_witness);
+        let c = self.challenge(params, value);
+
+        let lhs = group.relations(&commitment, &blinded, &self.s);
+        let t = self.t.map(|t| group.element(&t));
+        lhs[0] == t[0].mul(&commitment.pow(&c))
+            && lhs[1] == t[1].mul(&group.element(value.as_uint()).pow(&c))
+            && lhs[2] == t[2]
+    }
+
+    fn challenge(&self, params: &PublicParams, value: &AccValue) -> U2048 {
+        let digest = params.hash.hash(
+            PROOF_TAG,
+            &[
+                &params.digest(),
+                &self.epoch.to_be_bytes(),
+                &value.as_uint().to_be_bytes(),
+                &self.commitment.to_be_bytes(),
+                &self.blinded_witness.to_be_bytes(),
+                &self.t[0].to_be_bytes(),
+                &self.t[1].to_be_bytes(),
+                &self.t[2].to_be_bytes(),
+            ],
+        );
+        U128::from_be_slice(&digest[..(CHALLENGE_BITS / 8) as usize]).resize()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PROOF_TAG);
+        w.put_u64(self.epoch);
+        w.put_u512(&self.commitment);
+        w.put_u512(&self.blinded_witness);
+        for t in &self.t {
+            w.put_u512(t);
+        }
+        for s in &self.s {
+            w.put_u2048(s);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, PROOF_TAG, policy)?;
+        let epoch = r.get_u64()?;
+        let commitment = r.get_residue(None)?;
+        let blinded_witness = r.get_residue(None)?;
+        let mut t = [U512::ZERO; 3];
+        for t in &mut t {
+            *t = r.get_residue(None)?;
+        }
+        let mut s = [U2048::ZERO; 6];
+        for s in &mut s {
+            *s = r.get_u2048()?;
+        }
+        r.finish()?;
+        Ok(Self { epoch, commitment, blinded_witness, t, s })
+    }
+}
+
+// Bit sizes of the membership secrets (x, r, r2, x r2, r r2).
+const MEMBERSHIP_SECRET_BITS: [u32; 5] = [
+    ELEMENT_PRIME_BITS,
+    BLINDING_BITS,
+    BLINDING_BITS,
+    ELEMENT_PRIME_BITS + BLINDING_BITS,
+    2 * BLINDING_BITS,
+];
+
+// Zero-knowledge membership of the element inside a Pedersen commitment, for
+// composing with credential signatures over a committed attribute. Same
+// construction as the non-membership proof, with the witness blinded as
+// C_w = w h^r2 and the relations
+//
+//     C = g^x h^r,    value = C_w^x h^-z,    1 = C^r2 g^-z h^-t
+//
+// The proof does not show that x is a well-formed element prime (x = 1 opens
+// any value); the credential has to certify the committed attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipProof {
+    pub blinded_witness: U512, // w h^r2
+    pub t: [U512; 3],          // Prover's first message, one per relation
+    pub s: [U2048; 5],         // Responses, in MEMBERSHIP_SECRET_BITS order
+}
+
+impl MembershipProof {
+    // `blinding` is the randomness of the commitment the verifier will see,
+    // commit_element(params, x, blinding).
+    pub fn prove(
+        params: &PublicParams,
+        value: &AccValue,
+        x: &ElemPrime,
+        blinding: &U1024,
+        witness: &Witness,
+    ) -> Result<Self, &'static str> {
+        witness.check_params(params)?;
+        if blinding.bits() > BLINDING_BITS {
+            return Err("Blinding factor is too large");
+        }
+        let group = Group::new(params)?;
+        // The proof shows w^x = value exactly, so use the sign that gives it
+        let Some(root) = core::canonical_root(&witness.value, x, value, &group.monty_params) else {
+            return Err("Witness does not open the accumulator value");
+        };
+
+        let r2 = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+        let commitment = group.g.pow(x.as_uint()).mul(&group.h.pow(blinding));
+        let blinded = group.element(root.as_uint()).mul(&group.h.pow(&r2));
+
+        let (x, r, r2) = (x.as_uint().resize::<32>(), blinding.resize::<32>(), r2.resize::<32>());
+        let secrets = [x, r, r2, x.wrapping_mul(&r2), r.wrapping_mul(&r2)];
+        let masks =
+            MEMBERSHIP_SECRET_BITS.map(|bits| U2048::random_bits(&mut OsRng, bits + STAT_BITS + CHALLENGE_BITS));
+
+        let t = group.membership_relations(&commitment, &blinded, &masks).map(|t| t.retrieve());
+        let blinded_witness = blinded.retrieve();
+        let c = membership_challenge(params, value, &commitment.retrieve(), &blinded_witness, &t);
+        let mut s = [U2048::ZERO; 5];
+        for i in 0..5 {
+            s[i] = masks[i].wrapping_add(&c.wrapping_mul(&secrets[i]));
+        }
+        Ok(Self { blinded_witness, t, s })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(MEMBERSHIP_TAG);
+        w.put_u512(&self.blinded_witness);
+        for t in &self.t {
+            w.put_u512(t);
+        }
+        for s in &self.s {
+            w.put_u2048(s);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, MEMBERSHIP_TAG, policy)?;
+        let blinded_witness = r.get_residue(None)?;
+        let mut t = [U512::ZERO; 3];
+        for t in &mut t {
+            *t = r.get_residue(None)?;
+        }
+        let mut s = [U2048::ZERO; 5];
+        for s in &mut s {
+            *s = r.get_u2048()?;
+        }
+        r.finish()?;
+        Ok(Self { blinded_witness, t, s })
+    }
+}
+
+// Checks that the element committed to in `commitment` is accumulated in
+// `value`, learning nothing else about it or its witness.
+pub fn verify_membership_of_commitment(
+    params: &PublicParams,
+    value: &AccValue,
+    commitment: &U512,
+    proof: &MembershipProof,
+) -> bool {
+    let Ok(group) = Group::new(params) else {
+        return false;
+    };
+    let in_range = proof
+        .s
+        .iter()
+        .zip(MEMBERSHIP_SECRET_BITS)
+        .all(|(s, bits)| s.bits() <= bits + STAT_BITS + CHALLENGE_BITS + 1);
+    if !in_range || *commitment >= params.n || proof.blinded_witness >= params.n {
+        return false;
+    }
+    let c = membership_challenge(params, value, commitment, &proof.blinded_witness, &proof.t);
+    let commitment = group.element(commitment);
+    let blinded = group.element(&proof.blinded_witness);
+
+    let lhs = group.membership_relations(&commitment, &blinded, &proof.s);
+    let t = proof.t.map(|t| group.element(&t));
+    lhs[0] == t[0].mul(&commitment.pow(&c))
+        && lhs[1] == t[1].mul(&group.element(value.as_uint()).pow(&c))
+        && lhs[2] == t[2]
+}
+
+fn membership_challenge(
+    params: &PublicParams,
+    value: &AccValue,
+    commitment: &U512,
+    blinded_witness: &U512,
+    t: &[U512; 3],
+) -> U2048 {
+    let digest = params.hash.hash(
+        MEMBERSHIP_TAG,
+        &[
+            &params.digest(),
+            &value.to_be_bytes(),
+            &commitment.to_be_bytes(),
+            &blinded_witness.to_be_bytes(),
+            &t[0].to_be_bytes(),
+            &t[1].to_be_bytes(),
+            &t[2].to_be_bytes(),
+        ],
+    );
+    U128::from_be_slice(&digest[..(CHALLENGE_BITS / 8) as usize]).resize()
+}
+
+// Bit sizes of the composite secrets: the membership ones, then
+// (beta, r3, x r3, r r3) for the non-membership side.
+const COMPOSITE_SECRET_BITS: [u32; 9] = [
+    ELEMENT_PRIME_BITS,
+    BLINDING_BITS,
+    BLINDING_BITS,
+    ELEMENT_PRIME_BITS + BLINDING_BITS,
+    2 * BLINDING_BITS,
+    ELEMENT_PRIME_BITS,
+    BLINDING_BITS,
+    ELEMENT_PRIME_BITS + BLINDING_BITS,
+    2 * BLINDING_BITS,
+];
+
+// One proof that the committed element is accumulated in an allow list and
+// not deleted from a deny list, which may have their own keys. The element is
+// committed once, in the allow list's group, and both halves answer with the
+// same response for x, so they are about the same element. Besides the
+// membership relations, it shows
+//
+//     value_deny = C_d^x h'^-z base'^beta,    1 = C^r3 g^-z h^-t
+//
+// for C_d = d h'^r3 in the deny list's group (primed), the product z = x r3
+// being bound by the commitment in the allow list's. Against two separate
+// proofs it saves a commitment, a relation, two responses and a challenge.
+//
+// Both lists must derive the same prime for the element, for instance by
+// sharing an ElementRegistry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompositeProof {
+    pub epoch: u64,                // Epoch of the deny list value the proof was made against
+    pub commitment: U512,          // g^x h^r, in the allow list's group
+    pub blinded_witness: U512,     // w h^r2
+    pub blinded_non_witness: U512, // d h'^r3
+    pub t: [U512; 5],              // Prover's first message, one per relation
+    pub s: [U2048; 9],             // Responses, in COMPOSITE_SECRET_BITS order
+}
+
+impl CompositeProof {
+    pub fn prove(
+        allow: &PublicParams,
+        allow_value: &AccValue,
+        deny: &PublicParams,
+        deny_value: &AccValue,
+        x: &ElemPrime,
+        blinding: &U1024,
+        witnesses: (&Witness, &NonMembershipWitness),
+    ) -> Result<Self, &'static str> {
+        let (witness, non_witness) = witnesses;
+        witness.check_params(allow)?;
+        if !non_witness.verify(deny, x, deny_value) {
+            return Err("Witness does not open the deny list value");
+        }
+        if blinding.bits() > BLINDING_BITS {
+            return Err("Blinding factor is too large");
+        }
+        let (a, b) = (Group::new(allow)?, Group::new(deny)?);
+        let Some(root) = core::canonical_root(&witness.value, x, allow_value, &a.monty_params) else {
+            return Err("Witness does not open the accumulator value");
+        };
+
+        let r2 = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+        let r3 = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+        let commitment = a.g.pow(x.as_uint()).mul(&a.h.pow(blinding));
+        let blinded = a.element(root.as_uint()).mul(&a.h.pow(&r2));
+        let blinded_d = b.element(&non_witness.d).mul(&b.h.pow(&r3));
+
+        let (x, r, r2, r3) = (
+            x.as_uint().resize::<32>(),
+            blinding.resize::<32>(),
+            r2.resize::<32>(),
+            r3.resize::<32>(),
+        );
+        let secrets = [
+            x,
+            r,
+            r2,
+            x.wrapping_mul(&r2),
+            r.wrapping_mul(&r2),
+            non_witness.beta.resize::<32>(),
+            r3,
+            x.wrapping_mul(&r3),
+            r.wrapping_mul(&r3),
+        ];
+        let masks =
+            COMPOSITE_SECRET_BITS.map(|bits| U2048::random_bits(&mut OsRng, bits + STAT_BITS + CHALLENGE_BITS));
+
+        let t = composite_relations(&a, &b, &commitment, &blinded, &blinded_d, &masks).map(|t| t.retrieve());
+        let mut proof = Self {
+            epoch: non_witness.epoch,
+            commitment: commitment.retrieve(),
+            blinded_witness: blinded.retrieve(),
+            blinded_non_witness: blinded_d.retrieve(),
+            t,
+            s: [U2048::ZERO; 9],
+        };
+        let c = proof.challenge(allow, allow_value, deny, deny_value);
+        for i in 0..9 {
+            proof.s[i] = masks[i].wrapping_add(&c.wrapping_mul(&secrets[i]));
+        }
+        Ok(proof)
+    }
+
+    // Checks the proof against the allow list's current value and the deny
+    // list's value at self.epoch.
+    pub fn verify(
+        &self,
+        allow: &PublicParams,
+        allow_value: &AccValue,
+        deny: &PublicParams,
+        deny_value: &AccValue,
+    ) -> bool {
+        let (Ok(a), Ok(b)) = (Group::new(allow), Group::new(deny)) else {
+            return false;
+        };
+        let in_range = self
+            .s
+            .iter()
+            .zip(COMPOSITE_SECRET_BITS)
+            .all(|(s, bits)| s.bits() <= bits + STAT_BITS + CHALLENGE_BITS + 1);
+        if !in_range
+            || self.commitment >= allow.n
+            || self.blinded_witness >= allow.n
+            || self.blinded_non_witness >= deny.n
+        {
+            return false;
+        }
+        let commitment = a.element(&self.commitment);
+        let blinded = a.element(&self.blinded_witness);
+        let blinded_d = b.element(&self.blinded_non_witness);
+        let c = self.challenge(allow, allow_value, deny, deny_value);
+
+        let lhs = composite_relations(&a, &b, &commitment, &blinded, &blinded_d, &self.s);
+        let [t0, t1, t2, t3, t4] = self.t;
+        lhs[0] == a.element(&t0).mul(&commitment.pow(&c))
+            && lhs[1] == a.element(&t1).mul(&a.element(allow_value.as_uint()).pow(&c))
+            && lhs[2] == a.element(&t2)
+            && lhs[3] == b.element(&t3).mul(&b.element(deny_value.as_uint()).pow(&c))
+            && lhs[4] == a.element(&t4)
+    }
+
+    fn challenge(
+        &self,
+        allow: &PublicParams,
+        allow_value: &AccValue,
+        deny: &PublicParams,
+        deny_value: &AccValue,
+    ) -> U2048 {
+        let digest = allow.hash.hash(
+            COMPOSITE_TAG,
+            &[
+                &allow.digest(),
+                &allow_value.to_be_bytes(),
+                &deny.digest(),
+                &self.epoch.to_be_bytes(),
+                &deny_value.to_be_bytes(),
+                &self.commitment.to_be_bytes(),
+                &self.blinded_witness.to_be_bytes(),
+                &self.blinded_non_witness.to_be_bytes(),
+                &self.t[0].to_be_bytes(),
+                &self.t[1].to_be_bytes(),
+                &self.t[2].to_be_bytes(),
+                &self.t[3].to_be_bytes(),
+                &self.t[4].to_be_bytes(),
+            ],
+        );
+        U128::from_be_slice(&digest[..(CHALLENGE_BITS / 8) as usize]).resize()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(COMPOSITE_TAG);
+        w.put_u64(self.epoch);
+        w.put_u512(&self.commitment);
+        w.put_u512(&self.blinded_witness);
+        w.put_u512(&self.blinded_non_witness);
+        for t in &self.t {
+            w.put_u512(t);
+        }
+        for s in &self.s {
+            w.put_u2048(s);
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, COMPOSITE_TAG, policy)?;
+        let epoch = r.get_u64()?;
+        let commitment = r.get_residue(None)?;
+        let blinded_witness = r.get_residue(None)?;
+        let blinded_non_witness = r.get_residue(None)?;
+        let mut t = [U512::ZERO; 5];
+        for t in &mut t {
+            *t = r.get_residue(None)?;
+        }
+        let mut s = [U2048::ZERO; 9];
+        for s in &mut s {
+            *s = r.get_u2048()?;
+        }
+        r.finish()?;
+        Ok(Self {
+            epoch,
+            commitment,
+            blinded_witness,
+            blinded_non_witness,
+            t,
+            s,
+        })
+    }
+}
+
+// The five composite relations at exponents e: the three membership ones in
+// the allow list's group `a`, the deny list's opening in `b`, and the product
+// x r3 bound in `a`.
+fn composite_relations(
+    a: &Group,
+    b: &Group,
+    commitment: &MontyForm<8>,
+    blinded: &MontyForm<8>,
+    blinded_d: &MontyForm<8>,
+    e: &[U2048; 9],
+) -> [MontyForm<8>; 5] {
+    let [m0, m1, m2] = a.membership_relations(commitment, blinded, &[e[0], e[1], e[2], e[3], e[4]]);
+    [
+        m0,
+        m1,
+        m2,
+        blinded_d.pow(&e[0]).mul(&b.h_inv.pow(&e[7])).mul(&b.base.pow(&e[5])),
+        commitment.pow(&e[6]).mul(&a.g_inv.pow(&e[7])).mul(&a.h_inv.pow(&e[8])),
+    ]
+}
+
+// Exponent size for rerandomization, so value^r is statistically close to a
+// uniform element of the subgroup the value generates.
+pub(crate) const RERANDOMIZATION_BITS: u32 = BLINDING_BITS;
+
+// Proof that a rerandomization raised both the accumulator value and the base
+// to the same secret r, so the base is still the value raised to the product
+// of deleted primes: the member set did not change, only its representation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RerandomizationProof {
+    pub t: [U512; 2], // value^k, base^k
+    pub s: U2048,     // k + c r
+}
+
+impl RerandomizationProof {
+    pub(crate) fn prove(
+        before: &PublicParams,
+        value_before: &AccValue,
+        after: &PublicParams,
+        value_after: &AccValue,
+        r: &U1024,
+    ) -> Result<Self, &'static str> {
+        let [value, base] = Self::bases(before, value_before)?;
+        let k = U2048::random_bits(&mut OsRng, RERANDOMIZATION_BITS + STAT_BITS + CHALLENGE_BITS);
+        let t = [value.pow(&k).retrieve(), base.pow(&k).retrieve()];
+        let c = Self::challenge(before, value_before, after, value_after, &t);
+        Ok(Self {
+            t,
+            s: k.wrapping_add(&c.wrapping_mul(r)),
+        })
+    }
+
+    pub fn verify(
+        &self,
+        before: &PublicParams,
+        value_before: &AccValue,
+        after: &PublicParams,
+        value_after: &AccValue,
+    ) -> bool {
+        if before.n != after.n || before.hash != after.hash || before.prf_key != after.prf_key {
+            return false;
+        }
+        if self.s.bits() > RERANDOMIZATION_BITS + STAT_BITS + CHALLENGE_BITS + 1 {
+            return false;
+        }
+        let (Ok([value, base]), Ok([value_new, base_new])) =
+            (Self::bases(before, value_before), Self::bases(after, value_after))
+        else {
+            return false;
+        };
+        let c = Self::challenge(before, value_before, after, value_after, &self.t);
+        let [t0, t1] = self.t.map(|t| MontyForm::new(&t, *value.params()));
+        value.pow(&self.s) == t0.mul(&value_new.pow(&c)) && base.pow(&self.s) == t1.mul(&base_new.pow(&c))
+    }
+
+    fn bases(params: &PublicParams, value: &AccValue) -> Result<[MontyForm<8>; 2], &'static str> {
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let monty_params = MontyParams::new(n.ok_or("Modulus must be odd")?);
+        Ok([
+            MontyForm::new(value.as_uint(), monty_params),
+            MontyForm::new(params.base.as_uint(), monty_params),
+        ])
+    }
+
+    fn challenge(
+        before: &PublicParams,
+        value_before: &AccValue,
+        after: &PublicParams,
+        value_after: &AccValue,
+        t: &[U512; 2],
+    ) -> U2048 {
+        let digest = before.hash.hash(
+            RERANDOMIZATION_TAG,
+            &[
+                &before.digest(),
+                &value_before.to_be_bytes(),
+                &after.digest(),
+                &value_after.to_be_bytes(),
+                &t[0].to_be_bytes(),
+                &t[1].to_be_bytes(),
+            ],
+        );
+        U128::from_be_slice(&digest[..(CHALLENGE_BITS / 8) as usize]).resize()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(RERANDOMIZATION_TAG);
+        w.put_u512(&self.t[0]);
+        w.put_u512(&self.t[1]);
+        w.put_u2048(&self.s);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with(bytes, &DecodingPolicy::default())
+    }
+
+    pub fn from_bytes_with(bytes: &[u8], policy: &DecodingPolicy) -> Result<Self, &'static str> {
+        let mut r = Reader::with_policy(bytes, RERANDOMIZATION_TAG, policy)?;
+        let t = [r.get_residue(None)?, r.get_residue(None)?];
+        let s = r.get_u2048()?;
+        r.finish()?;
+        Ok(Self { t, s })
+    }
+}
+
+// QR_n with the generators the proof is expressed in.
+struct Group {
+    monty_params: MontyParams<8>,
+    base: MontyForm<8>,
+    g: MontyForm<8>,
+    h: MontyForm<8>,
+    g_inv: MontyForm<8>,
+    h_inv: MontyForm<8>,
+}
+
+impl Group {
+    fn new(params: &PublicParams) -> Result<Self, &'static str> {
+        let n: Option<Odd<U512>> = Odd::new(params.n).into();
+        let n = n.ok_or("Modulus must be odd")?;
+        let monty_params = MontyParams::new(n);
+        let generator = |name: &[u8]| {
+            let g = MontyForm::new(&signature::hash_to_qr(params.hash, &n, GENERATOR_DOMAIN, name), monty_params);
+            let g_inv: Option<MontyForm<8>> = g.inv().into();
+            g_inv.map(|g_inv| (g, g_inv)).ok_or("Generator not invertible modulo n")
+        };
+        let (g, g_inv) = generator(b"g")?;
+        let (h, h_inv) = generator(b"h")?;
+        Ok(Self {
+            monty_params,
+            base: MontyForm::new(params.base.as_uint(), monty_params),
+            g,
+            h,
+            g_inv,
+            h_inv,
+        })
+    }
+
+    fn element(&self, value: &U512) -> MontyForm<8> {
+        MontyForm::new(value, self.monty_params)
+    }
+
+    // The three membership relations at exponents e, in MEMBERSHIP_SECRET_BITS order.
+    fn membership_relations(
+        &self,
+        commitment: &MontyForm<8>,
+        blinded: &MontyForm<8>,
+        e: &[U2048; 5],
+    ) -> [MontyForm<8>; 3] {
+        [
+            self.g.pow(&e[0]).mul(&self.h.pow(&e[1])),
+            blinded.pow(&e[0]).mul(&self.h_inv.pow(&e[3])),
+            commitment.pow(&e[2]).mul(&self.g_inv.pow(&e[3])).mul(&self.h_inv.pow(&e[4])),
+        ]
+    }
+
+    // The three relations evaluated at exponents e, in SECRET_BITS order.
+    fn relations(&self, commitment: &MontyForm<8>, blinded: &MontyForm<8>, e: &[U2048; 6]) -> [MontyForm<8>; 3] {
+        [
+            self.g.pow(&e[0]).mul(&self.h.pow(&e[1])),
+            blinded.pow(&e[0]).mul(&self.h_inv.pow(&e[4])).mul(&self.base.pow(&e[2])),
+            commitment.pow(&e[3]).mul(&self.g_inv.pow(&e[4])).mul(&self.h_inv.pow(&e[5])),
+        ]
+    }
+}
@@ -0,0 +1,33 @@
+use braavos_accumulator::{core, BraavosAccumulator, ElemPrime, ExpStrategy, WitnessValue};
+use crypto_bigint::modular::MontyParams;
+use crypto_bigint::{Odd, RandomBits, U256, U512};
+use rand::rngs::OsRng;
+
+// pow_elem stops at the exponent's top bit; core::pow with Native is the
+// constant-time reference.
+fn check(w: &U512, e: U256, params: &MontyParams<8>) {
+    let expected = core::pow(ExpStrategy::Native, w, &e.resize(), params);
+    let prime = ElemPrime::from_be_bytes(&e.to_be_bytes());
+    assert_eq!(*WitnessValue::new(*w).pow_elem(&prime, params).as_uint(), expected, "exponent {e}");
+}
+
+#[test]
+fn short_and_long_exponents_match_constant_time_pow() {
+    let acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let n = acc.params().n;
+    let params = MontyParams::new(Odd::new(n).unwrap());
+    let w = U512::random_bits(&mut OsRng, n.bits() - 1);
+    let exponents = [
+        U256::ZERO,
+        U256::ONE,
+        U256::from(3u32),
+        U256::from(65537u32),
+        U256::from(u32::MAX),
+        U256::from(u32::MAX as u64 + 1),
+        U256::from_be_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab"),
+        U256::MAX,
+    ];
+    for e in exponents.into_iter().chain((0..50).map(|_| U256::random_bits(&mut OsRng, 256))) {
+        check(&w, e, &params);
+    }
+}
@@ -0,0 +1,42 @@
+use braavos_accumulator::{BraavosAccumulator, ElemPrime};
+use crypto_bigint::U256;
+use crypto_primes::generate_prime;
+
+fn external_prime(bits: u32) -> ElemPrime {
+    ElemPrime::from_be_bytes(&generate_prime::<U256>(bits).to_be_bytes())
+}
+
+#[test]
+fn supplied_primes_are_members_under_their_label() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).strict(true).build().unwrap();
+    let prime = external_prime(256);
+    let w = acc.add_prime(prime, b"cl-credential-7").unwrap();
+    let w_bob = acc.add("bob").unwrap();
+    assert!(acc.verify("cl-credential-7", w));
+    assert_eq!(acc.element_prime("cl-credential-7"), Some(prime));
+    assert_ne!(acc.params().hash_to_prime("cl-credential-7"), prime);
+    assert_eq!(acc.check_integrity(), Ok(()));
+
+    // Strict restore keeps the supplied prime, and deletion works as usual
+    let mut restored = BraavosAccumulator::builder().strict(true).restore(&acc.to_state_bytes().unwrap()).unwrap();
+    assert!(restored.verify("cl-credential-7", w));
+    restored.delete("cl-credential-7").unwrap();
+    let w_bob = restored.update_witness_on_deletion("bob", w_bob, "cl-credential-7").unwrap();
+    assert!(restored.verify("bob", w_bob) && !restored.verify("cl-credential-7", w));
+}
+
+#[test]
+fn supplied_primes_are_validated() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let prime = external_prime(256);
+    let composite = ElemPrime::from_be_bytes(&prime.as_uint().wrapping_add(&U256::ONE).to_be_bytes());
+    assert!(acc.add_prime(composite, b"even").is_err());
+    assert!(acc.add_prime(external_prime(128), b"short").is_err());
+
+    acc.add_prime(prime, b"first").unwrap();
+    assert_eq!(acc.add_prime(prime, b"second").err(), Some("Prime belongs to another member"));
+    assert_eq!(acc.add_prime(external_prime(256), b"first").err(), Some("Label is a member with a different prime"));
+    acc.add("alice").unwrap();
+    let alice = acc.element_prime("alice").unwrap();
+    assert!(acc.add_prime(alice, b"not-alice").is_err());
+}
@@ -0,0 +1,70 @@
+use braavos_accumulator::{
+    AllowAll, AnchorReceipt, BraavosAccumulator, Checkpoint, CheckpointAnchor, Manager, ReceiptVerifier,
+};
+use std::sync::{Arc, Mutex};
+
+// Stands in for a transparency log: evidence is the entry's position.
+#[derive(Default)]
+struct Log(Mutex<Vec<[u8; 32]>>);
+
+impl CheckpointAnchor for Log {
+    fn anchor(&self, _: &Checkpoint, digest: &[u8; 32]) -> Result<Vec<u8>, &'static str> {
+        let mut entries = self.0.lock().unwrap();
+        entries.push(*digest);
+        Ok(((entries.len() - 1) as u64).to_be_bytes().to_vec())
+    }
+}
+
+impl ReceiptVerifier for Log {
+    fn verify(&self, digest: &[u8; 32], evidence: &[u8]) -> bool {
+        let Ok(position) = <[u8; 8]>::try_from(evidence) else {
+            return false;
+        };
+        self.0.lock().unwrap().get(u64::from_be_bytes(position) as usize) == Some(digest)
+    }
+}
+
+struct Unreachable;
+
+impl CheckpointAnchor for Unreachable {
+    fn anchor(&self, _: &Checkpoint, _: &[u8; 32]) -> Result<Vec<u8>, &'static str> {
+        Err("Anchor unreachable")
+    }
+}
+
+#[test]
+fn every_checkpoint_is_anchored_and_checks_against_its_receipt() {
+    let log = Arc::new(Log::default());
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll).with_anchor(log.clone());
+    for x in ["a", "b"] {
+        manager.add("op", x).unwrap();
+        manager.delete("op", x).unwrap();
+        manager.publish_checkpoint().unwrap();
+    }
+    let params = manager.accumulator().params();
+    let chain = manager.checkpoint_chain();
+    assert_eq!(log.0.lock().unwrap().len(), 2);
+    for index in 0..2 {
+        let receipt = manager.anchor_receipt(index).unwrap();
+        let receipt = AnchorReceipt::from_bytes(&receipt.to_bytes()).unwrap();
+        receipt.check(&params, chain, log.as_ref()).unwrap();
+    }
+
+    // A chain the manager swapped in after anchoring no longer matches
+    let mut forked = chain.to_vec();
+    forked[1].value = forked[0].value;
+    assert!(manager.anchor_receipt(1).unwrap().check(&params, &forked, log.as_ref()).is_err());
+    let mut unlogged = manager.anchor_receipt(0).unwrap().clone();
+    unlogged.evidence = 1u64.to_be_bytes().to_vec();
+    assert!(unlogged.check(&params, chain, log.as_ref()).is_err());
+}
+
+#[test]
+fn a_failed_anchor_leaves_the_chain_unchanged() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll).with_anchor(Arc::new(Unreachable));
+    manager.add("op", "a").unwrap();
+    manager.delete("op", "a").unwrap();
+    assert!(manager.publish_checkpoint().is_err());
+    assert!(manager.checkpoint_chain().is_empty());
+    assert!(manager.anchor_receipt(0).is_none());
+}
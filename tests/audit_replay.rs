@@ -0,0 +1,75 @@
+use braavos_accumulator::{AuditReplayer, BraavosAccumulator, GenesisRecord, LoggedOperation, ReplayVerdict};
+
+fn logged_history() -> (GenesisRecord, BraavosAccumulator, Vec<LoggedOperation>) {
+    let genesis = GenesisRecord::new().with_contribution("auditor", [7; 32]);
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).genesis(genesis.clone()).build().unwrap();
+    let mut log = Vec::new();
+    for x in ["alice", "bob", "carol", "dave"] {
+        acc.add(x).unwrap();
+        log.push(LoggedOperation::Add {
+            element: x.into(),
+            value: acc.value(),
+        });
+    }
+    acc.delete("bob").unwrap();
+    log.push(LoggedOperation::Delete {
+        elements: vec![b"bob".to_vec()],
+        value: acc.value(),
+    });
+    acc.delete_batch(&["alice", "dave"]).unwrap();
+    log.push(LoggedOperation::Delete {
+        elements: vec![b"alice".to_vec(), b"dave".to_vec()],
+        value: acc.value(),
+    });
+    (genesis, acc, log)
+}
+
+#[test]
+fn an_honest_log_replays_to_the_published_state() {
+    let (genesis, acc, log) = logged_history();
+    let verdict = AuditReplayer::replay(&genesis, &acc.params(), &log, acc.epoch(), &acc.value()).unwrap();
+    assert_eq!(
+        verdict,
+        ReplayVerdict::Consistent {
+            operations: 6,
+            epoch: 2,
+            members: 1
+        }
+    );
+    assert_eq!(verdict.to_json(), "{\"verdict\":\"consistent\",\"operations\":6,\"epoch\":2,\"members\":1}");
+
+    // Parameters from another setup are refused outright
+    let other = GenesisRecord::new().with_contribution("auditor", [8; 32]);
+    assert!(AuditReplayer::new(&other, &acc.params()).is_err());
+}
+
+#[test]
+fn the_first_divergent_operation_is_reported() {
+    let (genesis, acc, honest) = logged_history();
+    let params = acc.params();
+    let mut log = honest.clone();
+
+    // A deletion claiming a value that is not a root of the previous one
+    let forged = params.base;
+    if let LoggedOperation::Delete { value, .. } = &mut log[4] {
+        *value = forged;
+    }
+    let ReplayVerdict::Diverged(divergence) = AuditReplayer::replay(&genesis, &params, &log, 2, &acc.value()).unwrap()
+    else {
+        panic!("forged deletion accepted");
+    };
+    assert_eq!((divergence.index, divergence.claimed, divergence.expected), (4, forged, None));
+    assert_eq!(divergence.reason, "Claimed value is not the root of the previous value");
+
+    // A log that stops early does not explain the final state
+    let mut replayer = AuditReplayer::new(&genesis, &params).unwrap();
+    assert!(replayer.step(&honest[0]));
+    let verdict = replayer.finish(acc.epoch(), &acc.value());
+    assert!(verdict.to_json().starts_with("{\"verdict\":\"diverged\",\"index\":1,\"reason\":\"Final epoch"));
+
+    // Replay stops at the first divergence
+    assert!(!replayer.step(&honest[0]));
+    assert!(!replayer.step(&honest[1]));
+    assert_eq!(replayer.divergence().map(|d| (d.index, d.reason)), Some((1, "Element added twice")));
+    assert!(!replayer.finish(acc.epoch(), &acc.value()).is_consistent());
+}
@@ -0,0 +1,42 @@
+use braavos_accumulator::{verify_backup, AllowAll, BraavosAccumulator, Manager};
+
+fn manager() -> Manager {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    for x in [&b"a"[..], b"b", b"c"] {
+        manager.add_with_metadata("ops", x, b"issued").unwrap();
+    }
+    manager.delete("ops", b"b").unwrap();
+    manager.bind("ops", b"a", b"key-1").unwrap();
+    manager
+}
+
+#[test]
+fn restores_state_metadata_bindings_and_audit() {
+    let mut manager = manager();
+    let archive = manager.backup(b"correct horse").unwrap();
+    let summary = verify_backup(&archive, b"correct horse").unwrap();
+    assert_eq!((summary.epoch, summary.members, summary.audit_records), (1, 2, 5));
+    assert_eq!(summary.value, manager.accumulator().value());
+
+    let builder = BraavosAccumulator::builder();
+    let mut restored = Manager::restore_backup(&archive, b"correct horse", &builder, AllowAll).unwrap();
+    assert_eq!(restored.accumulator().to_state_bytes().unwrap(), manager.accumulator().to_state_bytes().unwrap());
+    assert_eq!(restored.get_metadata(b"c"), Some(&b"issued"[..]));
+    assert_eq!(restored.audit_log().records(), manager.audit_log().records());
+    assert_eq!(restored.binding(b"a"), manager.binding(b"a"));
+    let w = restored.add("ops", b"d").unwrap();
+    assert!(manager.add("ops", b"d").is_ok() && restored.verify(b"d", w));
+}
+
+#[test]
+fn rejects_wrong_passphrase_and_damage() {
+    let archive = manager().backup(b"correct horse").unwrap();
+    assert_eq!(
+        verify_backup(&archive, b"battery staple"),
+        Err("Wrong passphrase or tampered backup archive")
+    );
+    let mut corrupted = archive.clone();
+    corrupted[40] ^= 1;
+    assert_eq!(verify_backup(&corrupted, b"correct horse"), Err("Backup archive is corrupted"));
+    assert!(verify_backup(&archive[..archive.len() - 1], b"correct horse").is_err());
+}
@@ -0,0 +1,53 @@
+use braavos_accumulator::{AllowAll, Binding, BraavosAccumulator, LinkRecord, Manager};
+
+fn manager() -> Manager {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll);
+    manager.add("issuer", b"alice").unwrap();
+    manager
+}
+
+#[test]
+fn rebinding_moves_the_membership() {
+    let mut manager = manager();
+    let params = manager.accumulator().params();
+    let phone = manager.bind("issuer", b"alice", b"phone-key").unwrap();
+    assert!(phone.verify(&params));
+    assert!(manager.bind("issuer", b"alice", b"other-key").is_err());
+    assert!(manager.bind("issuer", b"bob", b"bob-key").is_err());
+
+    let moved = manager.rebind_witness("issuer", &phone, b"laptop-key").unwrap();
+    assert!(moved.binding.verify(&params) && moved.link.verify(&params));
+    assert_eq!((moved.binding.holder_key.as_slice(), moved.binding.sequence), (&b"laptop-key"[..], 1));
+    assert!(moved.link.supersedes(&phone) && !moved.link.supersedes(&moved.binding));
+    assert!(manager.verify(b"alice", moved.witness));
+    assert_eq!(manager.binding(b"alice"), Some(&moved.binding));
+    assert_eq!(manager.links().len(), 1);
+    assert_eq!(manager.links()[0], moved.link);
+
+    // The old binding cannot be moved again, and nothing was deleted
+    assert!(manager.rebind_witness("issuer", &phone, b"stolen-key").is_err());
+    assert_eq!(manager.accumulator().epoch(), 0);
+
+    // Deleting the member drops its binding
+    manager.delete("issuer", b"alice").unwrap();
+    assert!(manager.binding(b"alice").is_none());
+    assert!(manager.rebind_witness("issuer", &moved.binding, b"tablet-key").is_err());
+}
+
+#[test]
+fn records_round_trip_and_reject_tampering() {
+    let mut manager = manager();
+    let params = manager.accumulator().params();
+    let binding = manager.bind("issuer", b"alice", b"phone-key").unwrap();
+    let link = manager.rebind_witness("issuer", &binding, b"laptop-key").unwrap().link;
+    assert_eq!(Binding::from_bytes(&binding.to_bytes()), Ok(binding.clone()));
+    assert_eq!(LinkRecord::from_bytes(&link.to_bytes()), Ok(link.clone()));
+
+    let mut forged = binding;
+    forged.holder_key = b"attacker-key".to_vec();
+    assert!(!forged.verify(&params));
+    let mut forged = link;
+    forged.new_key = b"attacker-key".to_vec();
+    assert!(!forged.verify(&params));
+}
@@ -0,0 +1,49 @@
+use braavos_accumulator::{AllowAll, BlindIssuance, BlindedPrime, BraavosAccumulator, Decision, Manager, Operation};
+use crypto_bigint::U512;
+
+#[test]
+fn unblinded_witnesses_verify_and_follow_deletions() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    manager.add("op", "bob").unwrap();
+    let params = manager.accumulator().params();
+
+    let (blinded, unblinder) = BlindedPrime::new(&params, "secret-element");
+    assert_eq!(BlindedPrime::from_bytes(&blinded.to_bytes()), Ok(blinded));
+    let issuance = manager.issue_blind("op", &blinded).unwrap();
+    let issuance = BlindIssuance::from_bytes(&issuance.to_bytes()).unwrap();
+    let value = manager.accumulator().value();
+    let mut holder = unblinder.unblind(&params, &issuance, &value).unwrap();
+    assert!(!manager.accumulator().contains("secret-element"));
+    assert!(manager.verify("secret-element", holder.witness()));
+
+    // Deletions carry the witness like any other
+    manager.delete("op", "bob").unwrap();
+    holder.sync(manager.update_log()).unwrap();
+    assert!(manager.verify("secret-element", holder.witness()));
+}
+
+#[test]
+fn the_policy_sees_only_the_blinded_prime() {
+    let only_alice = |operator: &str, _: Operation, _: &[u8]| match operator {
+        "alice" => Decision::Allow,
+        _ => Decision::Deny("not alice".to_string()),
+    };
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), only_alice);
+    let params = manager.accumulator().params();
+    let (blinded, unblinder) = BlindedPrime::new(&params, "x");
+    assert!(manager.issue_blind("mallory", &blinded).is_err());
+    let issuance = manager.issue_blind("alice", &blinded).unwrap();
+
+    let records = manager.audit_log().records();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|r| r.operation == Operation::IssueBlind && r.element == blinded.to_bytes()));
+
+    // An answer to another request does not unblind, and a request too small
+    // to hide anything is refused
+    let base = params.base;
+    assert!(unblinder.clone().unblind(&params, &issuance, &base).is_ok());
+    let (other, _) = BlindedPrime::new(&params, "y");
+    let wrong = manager.issue_blind("alice", &other).unwrap();
+    assert!(unblinder.unblind(&params, &wrong, &base).is_err());
+    assert!(manager.issue_blind("alice", &BlindedPrime(U512::from(7u32))).is_err());
+}
@@ -0,0 +1,50 @@
+use braavos_accumulator::{BraavosAccumulator, StaticBundle};
+use crypto_bigint::U512;
+
+fn frozen() -> (BraavosAccumulator, StaticBundle) {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    for x in ["alice", "bob", "carol"] {
+        acc.add(x).unwrap();
+    }
+    acc.delete("bob").unwrap();
+    let bundle = acc.freeze().unwrap();
+    (acc, bundle)
+}
+
+#[test]
+fn frozen_members_verify_without_the_accumulator() {
+    let (acc, bundle) = frozen();
+    assert_eq!((bundle.params, bundle.value), (acc.params(), acc.value()));
+    assert_eq!(bundle.members.keys().map(Vec::as_slice).collect::<Vec<_>>(), [&b"alice"[..], b"carol"]);
+    assert_eq!(bundle.witness("alice").unwrap().prime, acc.element_prime("alice").unwrap());
+    drop(acc);
+
+    let bundle = StaticBundle::from_bytes(&bundle.to_bytes()).unwrap();
+    assert!(bundle.verify_signature());
+    assert!(bundle.verify_member("alice") && bundle.verify_member("carol"));
+    assert!(!bundle.verify_member("bob"));
+    assert!(bundle.witness("bob").is_none());
+}
+
+#[test]
+fn tampered_bundles_are_rejected() {
+    let (_, bundle) = frozen();
+
+    let mut swapped = bundle.clone();
+    let carol = swapped.members[&b"carol"[..]].clone();
+    swapped.members.insert(b"alice".to_vec(), carol);
+    assert!(!swapped.verify_signature());
+    assert!(!swapped.verify_member("alice"));
+
+    let mut forged = bundle.clone();
+    forged.members.insert(b"mallory".to_vec(), bundle.members[&b"alice"[..]].clone());
+    assert!(!forged.verify_member("mallory"));
+
+    let mut resigned = bundle.clone();
+    resigned.signature = resigned.signature.wrapping_add(&U512::ONE);
+    assert!(!resigned.verify_member("alice"));
+
+    let bytes = bundle.to_bytes();
+    assert!(StaticBundle::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(StaticBundle::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+}
@@ -0,0 +1,94 @@
+use braavos_accumulator::{AccValue, BraavosAccumulator, ElemPrime, WitnessValue};
+use crypto_bigint::{U256, U512};
+
+#[test]
+fn padded_and_little_endian_forms_round_trip() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("x").unwrap().value;
+    let value = acc.value();
+    let prime = acc.params().hash_to_prime("x");
+
+    let mut le = value.to_be_bytes();
+    le.reverse();
+    assert_eq!(value.to_le_bytes(), le);
+    assert_eq!(AccValue::from_le_bytes(&le), value);
+    assert_eq!(WitnessValue::from_le_bytes(&w.to_le_bytes()), w);
+    assert_eq!(ElemPrime::from_le_bytes(&prime.to_le_bytes()), prime);
+
+    // 64-bit primes give a 128-bit modulus, so 16 bytes hold any value
+    for len in [16, 32, 64, 96] {
+        let be = value.to_be_bytes_padded(len).unwrap();
+        let le = value.to_le_bytes_padded(len).unwrap();
+        assert_eq!(be.len(), len);
+        assert_eq!(be.iter().rev().copied().collect::<Vec<_>>(), le);
+        assert_eq!(AccValue::from_be_bytes_padded(&be, len).unwrap(), value);
+        assert_eq!(AccValue::from_le_bytes_padded(&le, len).unwrap(), value);
+        let be = w.to_be_bytes_padded(len).unwrap();
+        assert_eq!(WitnessValue::from_be_bytes_padded(&be, len).unwrap(), w);
+    }
+    for len in [32, 48] {
+        let le = prime.to_le_bytes_padded(len).unwrap();
+        assert_eq!(ElemPrime::from_le_bytes_padded(&le, len).unwrap(), prime);
+        let be = prime.to_be_bytes_padded(len).unwrap();
+        assert_eq!(ElemPrime::from_be_bytes_padded(&be, len).unwrap(), prime);
+    }
+}
+
+#[test]
+fn lengths_are_checked_strictly() {
+    let value = AccValue::new(U512::from_u64(0x0102_0304));
+    assert_eq!(value.to_be_bytes_padded(4).unwrap(), [1, 2, 3, 4]);
+    assert_eq!(value.to_le_bytes_padded(6).unwrap(), [4, 3, 2, 1, 0, 0]);
+    assert!(value.to_be_bytes_padded(3).is_err());
+    assert!(value.to_le_bytes_padded(0).is_err());
+    assert_eq!(AccValue::new(U512::ZERO).to_be_bytes_padded(0).unwrap(), Vec::<u8>::new());
+
+    // The input must be exactly the stated length
+    assert!(AccValue::from_be_bytes_padded(&[1, 2, 3, 4], 5).is_err());
+    assert!(AccValue::from_le_bytes_padded(&[1, 2, 3, 4], 3).is_err());
+    // Bytes past the type's width must be zero
+    let mut wide = vec![0u8; 65];
+    assert!(AccValue::from_be_bytes_padded(&wide, 65).is_ok());
+    wide[0] = 1;
+    assert!(AccValue::from_be_bytes_padded(&wide, 65).is_err());
+    let mut wide = vec![0u8; 33];
+    wide[32] = 1;
+    assert!(ElemPrime::from_le_bytes_padded(&wide, 33).is_err());
+    assert_eq!(ElemPrime::from_be_bytes_padded(&wide, 33).unwrap().as_uint(), &U256::ONE);
+}
+
+#[test]
+fn checked_decoders_apply_the_decoding_rules() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("x").unwrap().value;
+    let params = acc.params();
+    let value = acc.value();
+    let prime = params.hash_to_prime("x");
+
+    let le = w.to_le_bytes_padded(32).unwrap();
+    assert_eq!(WitnessValue::from_le_bytes_padded_checked(&le, 32, &params).unwrap(), w);
+    let be = value.to_be_bytes_padded(32).unwrap();
+    assert_eq!(AccValue::from_be_bytes_padded_checked(&be, 32, &params).unwrap(), value);
+    let be = prime.to_be_bytes_padded(32).unwrap();
+    assert_eq!(ElemPrime::from_be_bytes_padded_checked(&be, 32).unwrap(), prime);
+
+    // The unchecked decoders take w + n; the checked ones do not
+    let unreduced = WitnessValue::new(w.as_uint().wrapping_add(&acc.modulus()));
+    let be = unreduced.to_be_bytes_padded(32).unwrap();
+    assert_eq!(WitnessValue::from_be_bytes_padded(&be, 32).unwrap(), unreduced);
+    assert_eq!(
+        WitnessValue::from_be_bytes_padded_checked(&be, 32, &params),
+        Err("Value is not reduced modulo n")
+    );
+    let le = AccValue::new(*unreduced.as_uint()).to_le_bytes_padded(32).unwrap();
+    assert!(AccValue::from_le_bytes_padded(&le, 32).is_ok());
+    assert!(AccValue::from_le_bytes_padded_checked(&le, 32, &params).is_err());
+    // Nor the identity, which is in QR_n but of small order
+    let one = AccValue::new(U512::ONE).to_be_bytes_padded(32).unwrap();
+    assert_eq!(AccValue::from_be_bytes_padded_checked(&one, 32, &params), Err("Value is of small order"));
+
+    // Even and short primes are not what hash-to-prime gives
+    let even = ElemPrime::from_be_bytes_padded(&[2], 1).unwrap().to_le_bytes_padded(32).unwrap();
+    assert!(ElemPrime::from_le_bytes_padded(&even, 32).is_ok());
+    assert_eq!(ElemPrime::from_le_bytes_padded_checked(&even, 32), Err("Malformed element prime"));
+}
@@ -0,0 +1,43 @@
+use braavos_accumulator::{BraavosAccumulator, VerificationCache};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn repeats_are_served_until_the_value_changes() {
+    let cache = Arc::new(VerificationCache::new(100, Duration::from_secs(60)));
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).verification_cache(cache.clone()).build().unwrap();
+    let w = acc.add(b"credential").unwrap();
+    let w_other = acc.add(b"revoked").unwrap();
+
+    for _ in 0..5 {
+        assert!(acc.verify(b"credential", w));
+    }
+    assert!(!acc.verify(b"stranger", w));
+    assert_eq!((cache.misses(), cache.hits()), (2, 4));
+
+    // The deletion changes the value, so nothing cached before applies
+    acc.delete(b"revoked").unwrap();
+    assert!(!acc.verify(b"revoked", w_other));
+    assert!(!acc.verify(b"credential", w));
+    assert_eq!(cache.misses(), 4);
+    assert!(acc.verify(b"credential", acc.recover_witness(b"credential").unwrap()));
+}
+
+#[test]
+fn capacity_and_ttl_bound_the_cache() {
+    let cache = Arc::new(VerificationCache::new(2, Duration::from_secs(60)));
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).verification_cache(cache.clone()).build().unwrap();
+    let witnesses: Vec<_> = (0..4u8).map(|i| acc.add([i]).unwrap()).collect();
+    for (i, w) in witnesses.iter().enumerate() {
+        assert!(acc.verify([i as u8], *w));
+    }
+    assert_eq!(cache.len(), 2);
+    assert!(acc.verify([0], witnesses[0]));
+    assert_eq!(cache.hits(), 0);
+
+    let expired = Arc::new(VerificationCache::new(10, Duration::ZERO));
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).verification_cache(expired.clone()).build().unwrap();
+    let w = acc.add(b"x").unwrap();
+    assert!(acc.verify(b"x", w) && acc.verify(b"x", w));
+    assert_eq!((expired.hits(), expired.misses()), (0, 2));
+}
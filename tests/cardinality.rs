@@ -0,0 +1,47 @@
+use braavos_accumulator::{BraavosAccumulator, CardinalityProof, CountInclusionProof};
+
+#[test]
+fn proofs_follow_the_member_count() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).track_cardinality(true).build().unwrap();
+    for i in 0..5u8 {
+        acc.add([i]).unwrap();
+    }
+    acc.add([0]).unwrap();
+    acc.delete([4]).unwrap();
+    let (params, value) = (acc.params(), acc.value());
+
+    let proof = acc.cardinality_proof().unwrap();
+    assert!(proof.proves_exactly(&params, &value, 4));
+    assert!(proof.proves_at_most(&params, &value, 10) && !proof.proves_at_most(&params, &value, 3));
+    assert_eq!(CardinalityProof::from_bytes(&proof.to_bytes()), Ok(proof.clone()));
+
+    let mut inflated = proof.clone();
+    inflated.count = 3;
+    assert!(!inflated.verify(&params, &value));
+
+    for i in 0..4u8 {
+        let inclusion = acc.count_inclusion([i]).unwrap();
+        let inclusion = CountInclusionProof::from_bytes(&inclusion.to_bytes()).unwrap();
+        assert!(inclusion.verify(params.hash, &proof, [i]));
+        assert!(!inclusion.verify(params.hash, &proof, [9]));
+    }
+    assert!(acc.count_inclusion([4]).is_err());
+
+    // A statement about an earlier value no longer verifies
+    acc.delete([3]).unwrap();
+    assert!(!proof.verify(&params, &acc.value()));
+    assert!(acc.cardinality_proof().unwrap().proves_exactly(&params, &acc.value(), 3));
+}
+
+#[test]
+fn tracking_is_optional_and_rebuilt_on_restore() {
+    let mut plain = BraavosAccumulator::new(64).unwrap();
+    plain.add("alice").unwrap();
+    assert_eq!(plain.cardinality_proof().err(), Some("Cardinality tracking is not enabled"));
+
+    let state = plain.to_state_bytes().unwrap();
+    let restored = BraavosAccumulator::builder().track_cardinality(true).restore(&state).unwrap();
+    let proof = restored.cardinality_proof().unwrap();
+    assert!(proof.proves_exactly(&restored.params(), &restored.value(), 1));
+    assert!(restored.count_inclusion("alice").unwrap().verify(restored.params().hash, &proof, "alice"));
+}
@@ -0,0 +1,60 @@
+use braavos_accumulator::{verify_checkpoint_chain, AllowAll, BraavosAccumulator, Checkpoint, Manager};
+use crypto_bigint::U512;
+
+// Three checkpoints over five deletions, with adds in between.
+fn manager_with_chain() -> Manager {
+    let acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let mut manager = Manager::new(acc, AllowAll);
+    for i in 0..6u8 {
+        manager.add("issuer", [i]).expect("Failed to add element");
+    }
+    for batch in [&[0u8][..], &[1, 2], &[3, 4]] {
+        for i in batch {
+            manager.delete("issuer", [*i]).expect("Failed to delete element");
+        }
+        manager.add("issuer", [100 + batch[0]]).expect("Failed to add element");
+        manager.publish_checkpoint().expect("Failed to publish checkpoint");
+    }
+    manager
+}
+
+#[test]
+fn chain_reaches_the_current_value() {
+    let manager = manager_with_chain();
+    let acc = manager.accumulator();
+    let chain = manager.checkpoint_chain();
+    assert_eq!(chain.len(), 3);
+    assert_eq!(verify_checkpoint_chain(&acc.params(), chain), Ok((acc.epoch(), acc.value())));
+    assert_eq!(verify_checkpoint_chain(&acc.params(), &[]), Ok((0, acc.params().base)));
+
+    let decoded: Vec<_> = chain.iter().map(|c| Checkpoint::from_bytes(&c.to_bytes()).unwrap()).collect();
+    assert_eq!(decoded, chain);
+}
+
+#[test]
+fn tampered_chains_are_rejected() {
+    let manager = manager_with_chain();
+    let params = manager.accumulator().params();
+    let chain = manager.checkpoint_chain().to_vec();
+
+    let mut forged = chain.clone();
+    forged[1].proof = forged[1].proof.wrapping_add(&U512::ONE);
+    assert!(verify_checkpoint_chain(&params, &forged).is_err());
+
+    let mut dropped = chain.clone();
+    dropped[1].deleted.pop();
+    dropped[1].to_epoch -= 1;
+    assert!(verify_checkpoint_chain(&params, &dropped).is_err());
+
+    assert!(verify_checkpoint_chain(&params, &chain[1..]).is_err());
+    assert!(verify_checkpoint_chain(&params, &[chain[0].clone(), chain[2].clone()]).is_err());
+
+    let other = BraavosAccumulator::new(64).expect("Failed to create accumulator").params();
+    assert!(verify_checkpoint_chain(&other, &chain).is_err());
+}
+
+#[test]
+fn publishing_needs_new_deletions() {
+    let mut manager = manager_with_chain();
+    assert!(manager.publish_checkpoint().is_err());
+}
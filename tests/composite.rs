@@ -0,0 +1,68 @@
+use braavos_accumulator::{
+    commit_element, BraavosAccumulator, CompositeProof, ElementRegistry, HashAlgorithm, BLINDING_BITS,
+};
+use crypto_bigint::{RandomBits, U1024, U2048};
+use rand::rngs::OsRng;
+use std::sync::Arc;
+
+// An allow list and a deny list with their own keys but shared element primes.
+fn lists() -> (BraavosAccumulator, BraavosAccumulator) {
+    let registry = Arc::new(ElementRegistry::new(HashAlgorithm::Sha256));
+    let builder = BraavosAccumulator::builder().prime_bits(64).element_registry(registry);
+    (builder.clone().build().unwrap(), builder.build().unwrap())
+}
+
+#[test]
+fn one_proof_covers_both_lists() {
+    let (mut allow, mut deny) = lists();
+    let w = allow.add("alice").unwrap();
+    let mallory = allow.add("mallory").unwrap();
+    deny.add("mallory").unwrap();
+    deny.delete("mallory").unwrap();
+
+    let proof = allow.prove_valid_composite("alice", w, &deny).unwrap();
+    let proof = CompositeProof::from_bytes(&proof.to_bytes()).unwrap();
+    assert!(allow.verify_valid_composite(&deny, &proof));
+    assert!(!deny.verify_valid_composite(&allow, &proof));
+    assert!(allow.prove_valid_composite("mallory", mallory, &deny).is_err());
+
+    // The commitment is the one the holder would present from a credential
+    let (allow_params, deny_params) = (allow.params(), deny.params());
+    let prime = allow_params.hash_to_prime("alice");
+    let blinding = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+    let non_witness = deny.non_membership_witness("alice").unwrap();
+    let (allow_value, deny_value) = (allow.value(), deny.value());
+    let witnesses = (&w, &non_witness);
+    let proof =
+        CompositeProof::prove(&allow_params, &allow_value, &deny_params, &deny_value, &prime, &blinding, witnesses)
+            .unwrap();
+    assert_eq!(proof.commitment, commit_element(&allow_params, &prime, &blinding).unwrap());
+    assert!(proof.verify(&allow_params, &allow_value, &deny_params, &deny_value));
+
+    // Revoking alice on the deny list invalidates the proof
+    deny.add("alice").unwrap();
+    deny.delete("alice").unwrap();
+    assert!(!allow.verify_valid_composite(&deny, &proof));
+    assert!(allow.prove_valid_composite("alice", w, &deny).is_err());
+}
+
+#[test]
+fn tampered_or_unlinked_proofs_fail() {
+    let (mut allow, deny) = lists();
+    let w = allow.add("alice").unwrap();
+    let proof = allow.prove_valid_composite("alice", w, &deny).unwrap();
+
+    let mut forged = proof.clone();
+    forged.s[0] = forged.s[0].wrapping_add(&U2048::ONE);
+    assert!(!allow.verify_valid_composite(&deny, &forged));
+    let mut swapped = proof.clone();
+    swapped.commitment = allow.prove_valid_composite("alice", w, &deny).unwrap().commitment;
+    assert!(!allow.verify_valid_composite(&deny, &swapped));
+
+    // Without shared primes there is no common element to prove about
+    let unrelated = BraavosAccumulator::new(64).unwrap();
+    assert_eq!(
+        allow.prove_valid_composite("alice", w, &unrelated).err(),
+        Some("Accumulators derive different primes for the element")
+    );
+}
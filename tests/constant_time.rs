@@ -0,0 +1,43 @@
+use braavos_accumulator::{core, AccValue, BraavosAccumulator, SecretKey, Witness, WitnessValue};
+use crypto_bigint::{modular::MontyParams, Odd, U512};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+#[test]
+fn constant_time_equality_agrees_with_eq() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let a = acc.add("alice").unwrap();
+    let b = acc.add("bob").unwrap();
+    let mut other_params = a;
+    other_params.params_digest[31] ^= 1;
+
+    assert!(bool::from(a.ct_eq(&a)) && a == a);
+    assert!(!bool::from(a.ct_eq(&b)) && a != b);
+    assert!(!bool::from(a.ct_eq(&other_params)) && a != other_params);
+    assert!(bool::from(acc.value().ct_eq(&acc.value())));
+    assert!(!bool::from(a.value.ct_eq(&b.value)));
+
+    let sk = acc.secret_key();
+    assert!(bool::from(sk.ct_eq(&sk)) && sk == sk);
+    let other: SecretKey = BraavosAccumulator::new(64).unwrap().secret_key();
+    assert!(sk != other);
+}
+
+#[test]
+fn selection_and_sign_recovery_do_not_branch_on_the_witness() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let a = acc.add("alice").unwrap();
+    let b = acc.add("bob").unwrap();
+    assert_eq!(Witness::conditional_select(&a, &b, Choice::from(0)), a);
+    assert_eq!(Witness::conditional_select(&a, &b, Choice::from(1)), b);
+    let value = AccValue::conditional_select(&acc.value(), &AccValue::new(U512::ONE), Choice::from(0));
+    assert_eq!(value, acc.value());
+
+    // Either sign of a witness yields the same exact root
+    let params = acc.params();
+    let monty = MontyParams::new(Odd::new(params.n).unwrap());
+    let prime = params.hash_to_prime("alice");
+    let negated = WitnessValue::new(params.n.wrapping_sub(a.value.as_uint()));
+    let root = core::canonical_root(&a.value, &prime, &acc.value(), &monty).unwrap();
+    assert_eq!(core::canonical_root(&negated, &prime, &acc.value(), &monty), Some(root));
+    assert_eq!(core::canonical_root(&b.value, &prime, &acc.value(), &monty), None);
+}
@@ -0,0 +1,60 @@
+use braavos_accumulator::{BraavosAccumulator, CorpusOp, CorpusSpec};
+
+#[test]
+fn corpora_depend_on_the_seed_alone() {
+    let spec = CorpusSpec {
+        initial_members: 50,
+        protected: 5,
+        rounds: 4,
+        adds_per_round: 10,
+        deletes_per_round: 20,
+        ..CorpusSpec::default()
+    };
+    let corpus = spec.generate().unwrap();
+    assert_eq!(corpus, spec.generate().unwrap());
+    assert_ne!(corpus.digest(), CorpusSpec { seed: 1, ..spec }.generate().unwrap().digest());
+    // Pinned, so a change to the generator shows up as a failure here
+    assert_eq!(corpus.digest()[..4], [65, 229, 145, 127]);
+
+    // Deletions only take members that are present and unprotected
+    let mut live: Vec<_> = corpus.initial.clone();
+    for op in corpus.operations() {
+        match op {
+            CorpusOp::Add(element) => live.push(element.clone()),
+            CorpusOp::Delete(element) => {
+                assert!(!corpus.protected().contains(element));
+                live.retain(|x| x != element);
+            }
+        }
+    }
+    let deletes = corpus.operations().filter(|op| matches!(op, CorpusOp::Delete(_))).count();
+    assert_eq!(deletes, 45 + 40 - 5);
+    assert_eq!(live.len(), 50 + 40 - deletes);
+    assert!(corpus.initial.iter().all(|x| x.len() == 32));
+}
+
+#[test]
+fn corpora_replay_onto_an_accumulator() {
+    let spec = CorpusSpec {
+        initial_members: 10,
+        rounds: 2,
+        adds_per_round: 3,
+        deletes_per_round: 2,
+        element_len: 40,
+        ..CorpusSpec::default()
+    };
+    let corpus = spec.generate().unwrap();
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    for element in &corpus.initial {
+        acc.add(element).unwrap();
+    }
+    for op in corpus.operations() {
+        match op {
+            CorpusOp::Add(element) => assert!(acc.add(element).is_ok()),
+            CorpusOp::Delete(element) => assert!(acc.delete(element).is_ok()),
+        }
+    }
+    assert_eq!(acc.epoch(), 4);
+    assert!(CorpusSpec { element_len: 8, ..spec }.generate().is_err());
+    assert!(CorpusSpec { protected: 11, ..spec }.generate().is_err());
+}
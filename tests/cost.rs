@@ -0,0 +1,68 @@
+use braavos_accumulator::{BraavosAccumulator, Checkpoint, CostModel, CostedOperation, UpdateMessage};
+#[cfg(feature = "smt")]
+use braavos_accumulator::{AccumulatorBackend, HashAlgorithm, SparseMerkleTree};
+#[cfg(feature = "zk")]
+use braavos_accumulator::{MembershipProof, BLINDING_BITS};
+#[cfg(feature = "zk")]
+use crypto_bigint::{RandomBits, U1024};
+#[cfg(feature = "zk")]
+use rand::rngs::OsRng;
+
+#[test]
+fn sizes_match_the_encodings() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let w = acc.add("alice").unwrap();
+    let estimate = CostModel::estimate(CostedOperation::Membership, &params);
+    assert_eq!(estimate.proof_bytes, w.to_bytes().len());
+    assert_eq!((estimate.exponentiations, estimate.hashes_to_prime), (1, 1));
+
+    for x in ["a", "b", "c"] {
+        acc.add(x).unwrap();
+    }
+    let first = acc.delete("a").unwrap();
+    let update = first.merge(&acc.delete_batch(&["b", "c"]).unwrap()).unwrap();
+    let estimate = CostModel::estimate(CostedOperation::WitnessUpdate { deletions: 3 }, &params);
+    assert_eq!(estimate.proof_bytes, update.to_bytes().len());
+    assert_eq!(estimate.exponentiations, 8);
+    let checkpoint = Checkpoint::prove(&params, None, &update).unwrap();
+    let estimate = CostModel::estimate(CostedOperation::Checkpoint { deletions: 3 }, &params);
+    assert_eq!(estimate.proof_bytes, checkpoint.to_bytes().len());
+    assert_eq!(CostModel::estimate(CostedOperation::WitnessUpdate { deletions: 0 }, &params).exponentiations, 0);
+}
+
+#[cfg(feature = "smt")]
+#[test]
+fn backends_trade_bytes_for_cpu() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let mut tree = SparseMerkleTree::new(HashAlgorithm::Sha256);
+    for i in 0..1000u32 {
+        tree.add(i.to_be_bytes()).unwrap();
+    }
+    let rsa = CostModel::estimate(CostedOperation::Membership, &params);
+    let smt = CostModel::estimate(CostedOperation::SmtMembership { members: 1000 }, &params);
+    assert!(smt.proof_bytes > rsa.proof_bytes && smt.exponentiations == 0);
+    // The estimate is for a typical path; a real one is within a few siblings
+    let actual = tree.prove(7u32.to_be_bytes()).to_bytes().len();
+    assert!(smt.proof_bytes.abs_diff(actual) <= 4 * 32);
+    let small = CostModel::estimate(CostedOperation::SmtNonMembership { members: 10 }, &params);
+    assert!(small.proof_bytes < smt.proof_bytes);
+}
+
+// Zero-knowledge proofs cost an order of magnitude more than witnesses
+#[cfg(feature = "zk")]
+#[test]
+fn zero_knowledge_costs_more_than_a_witness() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let w = acc.add("alice").unwrap();
+    let blinding = U1024::random_bits(&mut OsRng, BLINDING_BITS);
+    let proof = MembershipProof::prove(&params, &acc.value(), &params.hash_to_prime("alice"), &blinding, &w).unwrap();
+    let plain = CostModel::estimate(CostedOperation::Membership, &params);
+    let zk = CostModel::estimate(CostedOperation::ZkMembership, &params);
+    assert_eq!(zk.proof_bytes, proof.to_bytes().len());
+    assert!(zk.proof_bytes > 10 * plain.proof_bytes && zk.exponent_bits > 10 * plain.exponent_bits);
+    let zk_nm = CostModel::estimate(CostedOperation::ZkNonMembership, &params);
+    assert!(zk_nm.proof_bytes > zk.proof_bytes);
+}
@@ -0,0 +1,55 @@
+use braavos_accumulator::{
+    AllowAll, BraavosAccumulator, CertifiedWitness, CustodyRecord, Manager, Operation, VerificationPolicy,
+};
+
+fn manager() -> Manager {
+    let acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let mut manager = Manager::new(acc, AllowAll);
+    for x in ["alice", "bob", "carol"] {
+        manager.add("issuer", x).unwrap();
+    }
+    manager.delete("issuer", "bob").unwrap();
+    manager.delete("issuer", "carol").unwrap();
+    manager
+}
+
+#[test]
+fn custody_record_shows_validity_at_past_epochs() {
+    let mut manager = manager();
+    let record = manager.custody_record("auditor", "alice").unwrap();
+    let params = manager.accumulator().params();
+    assert_eq!(record.verify(&params), Ok(()));
+    assert_eq!(CustodyRecord::from_bytes(&record.to_bytes()), Ok(record.clone()));
+    assert_eq!(manager.audit_log().records().last().unwrap().operation, Operation::ExportCustody);
+
+    // Epoch 1's value as published in the update that reached it
+    let published = manager.update_log().updates()[0].value;
+    let (witness, value) = record.witness_at(&params, 1).unwrap();
+    assert_eq!(value, published);
+    let certified = CertifiedWitness {
+        witness,
+        entry: record.derivation.clone(),
+    };
+    assert!(params.verify_with_policy("alice", &certified, &value, VerificationPolicy::Certified));
+    assert_eq!(record.witness_at(&params, 0).unwrap().1, params.base);
+    assert!(record.witness_at(&params, 3).is_err());
+    assert!(manager.custody_record("auditor", "bob").is_err());
+}
+
+#[test]
+fn altered_records_fail_verification() {
+    let mut manager = manager();
+    let params = manager.accumulator().params();
+    let record = manager.custody_record("auditor", "alice").unwrap();
+
+    let mut dropped = record.clone();
+    dropped.updates.remove(0);
+    assert!(dropped.verify(&params).is_err());
+
+    let mut moved = record.clone();
+    moved.issuance.epoch = 1;
+    assert!(moved.verify(&params).is_err());
+
+    let other = BraavosAccumulator::new(64).unwrap().params();
+    assert!(record.verify(&other).is_err());
+}
@@ -0,0 +1,36 @@
+use braavos_accumulator::{BraavosAccumulator, CancelHandle, Deadline, CANCELLED, TIMEOUT};
+use std::time::{Duration, Instant};
+
+#[test]
+fn missed_deadlines_change_nothing() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add(b"member").unwrap();
+    let expired = Deadline::at(Instant::now());
+    assert_eq!(expired.remaining(), Some(Duration::ZERO));
+
+    assert_eq!(acc.add_within(b"late", &expired), Err(TIMEOUT));
+    assert!(!acc.contains(b"late"));
+    assert_eq!(acc.delete_within(b"member", &expired).map(|u| u.to_epoch), Err(TIMEOUT));
+    assert!(acc.contains(b"member") && acc.epoch() == 0);
+    // Cached primes skip the search, but the exponentiation is still bounded
+    assert_eq!(acc.verify_within(b"member", w, &expired), Err(TIMEOUT));
+
+    let generous = Deadline::after(Duration::from_secs(60));
+    assert_eq!(acc.verify_within(b"member", w, &generous), Ok(true));
+    let w = acc.add_within(b"on-time", &generous).unwrap();
+    assert!(acc.verify(b"on-time", w));
+}
+
+#[test]
+fn cancellation_reaches_every_copy() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let handle = CancelHandle::new();
+    let deadline = Deadline::none().cancelled_by(&handle);
+    assert!(deadline.check().is_ok() && deadline.remaining().is_none());
+    let copy = deadline.clone();
+    handle.cancel();
+    assert!(handle.is_cancelled());
+    assert_eq!(copy.check(), Err(CANCELLED));
+    assert_eq!(acc.add_within(b"x", &deadline), Err(CANCELLED));
+    assert_eq!(acc.member_count(), 0);
+}
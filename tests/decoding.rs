@@ -0,0 +1,91 @@
+use braavos_accumulator::{BraavosAccumulator, DecodingPolicy, EpochUpdate, StaticBundle, Witness};
+#[cfg(feature = "smt")]
+use braavos_accumulator::{AccumulatorBackend, HashAlgorithm, SmtProof, SparseMerkleTree};
+use crypto_bigint::U512;
+
+// Rewrites the trailing 512-bit field of an encoding.
+fn with_last_u512(bytes: &[u8], value: &U512) -> Vec<u8> {
+    let mut out = bytes.to_vec();
+    let len = out.len();
+    out[len - 64..].copy_from_slice(&value.to_be_bytes());
+    out
+}
+
+#[test]
+fn unreduced_witness_is_rejected_or_normalized() {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let w = acc.add(b"x").expect("Failed to add x");
+    let params = acc.params();
+    let shifted = with_last_u512(&w.to_bytes(), &w.value.as_uint().wrapping_add(&params.n));
+
+    let strict = DecodingPolicy::strict().for_params(&params);
+    assert!(Witness::from_bytes_with(&shifted, &strict).is_err());
+    assert!(Witness::from_bytes_with(&with_last_u512(&w.to_bytes(), &U512::ZERO), &strict).is_err());
+    assert_eq!(Witness::from_bytes_with(&w.to_bytes(), &strict), Ok(w));
+
+    let lenient = DecodingPolicy::default().for_params(&params);
+    assert_eq!(Witness::from_bytes_with(&shifted, &lenient), Ok(w));
+}
+
+#[test]
+fn update_values_are_checked_against_their_modulus() {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    acc.add(b"y").expect("Failed to add y");
+    let update = acc.delete(b"y").expect("Failed to delete y");
+    let shifted = with_last_u512(&update.to_bytes(), &update.value.as_uint().wrapping_add(&update.n));
+
+    assert!(EpochUpdate::from_bytes_with(&shifted, &DecodingPolicy::strict()).is_err());
+    assert_eq!(EpochUpdate::from_bytes(&shifted), Ok(update.clone()));
+
+    let other = BraavosAccumulator::new(64).expect("Failed to create accumulator").params();
+    let policy = DecodingPolicy::strict().for_params(&other);
+    assert_eq!(EpochUpdate::from_bytes_with(&update.to_bytes(), &policy), Err("Update is for a different modulus"));
+}
+
+#[test]
+fn size_limits_are_enforced() {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    for i in 0..8u8 {
+        acc.add([i; 64]).expect("Failed to add element");
+    }
+    let bundle = acc.freeze().expect("Failed to freeze").to_bytes();
+
+    let mut policy = DecodingPolicy::strict();
+    assert!(StaticBundle::from_bytes_with(&bundle, &policy).is_ok());
+    policy.max_items = 4;
+    assert_eq!(StaticBundle::from_bytes_with(&bundle, &policy), Err("List exceeds the size limit"));
+    policy = DecodingPolicy::strict();
+    policy.max_field = 32;
+    assert_eq!(StaticBundle::from_bytes_with(&bundle, &policy), Err("Field exceeds the size limit"));
+    policy = DecodingPolicy::strict();
+    policy.max_input = bundle.len() - 1;
+    assert_eq!(StaticBundle::from_bytes_with(&bundle, &policy), Err("Encoding exceeds the size limit"));
+}
+
+// Truncations and single-byte corruptions must fail cleanly, never panic.
+#[cfg(feature = "smt")]
+#[test]
+fn corrupted_encodings_fail_cleanly() {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let w = acc.add(b"x").expect("Failed to add x");
+    acc.add(b"y").expect("Failed to add y");
+    let update = acc.delete(b"y").expect("Failed to delete y");
+    let mut tree = SparseMerkleTree::new(HashAlgorithm::default());
+    tree.add(b"x").expect("Failed to add x");
+    let encodings = [w.to_bytes(), update.to_bytes(), tree.prove(b"y").to_bytes()];
+    let policy = DecodingPolicy::strict().for_params(&acc.params());
+
+    for bytes in &encodings {
+        for len in 0..bytes.len() {
+            assert!(Witness::from_bytes_with(&bytes[..len], &policy).is_err());
+            assert!(EpochUpdate::from_bytes_with(&bytes[..len], &policy).is_err());
+        }
+        for i in 0..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[i] ^= 0xff;
+            let _ = Witness::from_bytes_with(&corrupted, &policy);
+            let _ = EpochUpdate::from_bytes_with(&corrupted, &policy);
+            let _ = SmtProof::from_bytes_with(&corrupted, &policy);
+        }
+    }
+}
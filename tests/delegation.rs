@@ -0,0 +1,50 @@
+use braavos_accumulator::{BraavosAccumulator, DelegatedPresentation, Delegation, DeviceKey, WitnessHolder};
+
+#[test]
+fn devices_prove_membership_until_the_delegation_ends() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).checkpoints(4).build().unwrap();
+    let params = acc.params();
+    let holder = WitnessHolder::new(&params, "alice", acc.add("alice").unwrap(), 0).unwrap();
+    let device = DeviceKey::generate().unwrap();
+    let delegation = holder.delegate(&params, 1, &device.public()).unwrap();
+    assert_eq!(Delegation::from_bytes(&delegation.to_bytes()), Ok(delegation.clone()));
+
+    let presentation = device.present(&delegation, b"nonce-1").unwrap();
+    let bytes = presentation.to_bytes();
+    assert_eq!(DelegatedPresentation::from_bytes(&bytes), Ok(presentation.clone()));
+    assert_eq!(acc.verify_delegated(&presentation, b"nonce-1"), Ok(()));
+    assert_eq!(acc.verify_delegated(&presentation, b"nonce-2"), Err("Device signature does not verify"));
+
+    // Still accepted at the last epoch, against the kept value
+    acc.add("bob").unwrap();
+    acc.delete("bob").unwrap();
+    assert_eq!(acc.verify_delegated(&presentation, b"nonce-1"), Ok(()));
+    acc.add("carol").unwrap();
+    acc.delete("carol").unwrap();
+    assert_eq!(acc.verify_delegated(&presentation, b"nonce-1"), Err("Delegation has expired"));
+}
+
+#[test]
+fn delegations_are_bound_to_their_device_and_scope() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let holder = WitnessHolder::new(&params, "alice", acc.add("alice").unwrap(), 0).unwrap();
+    let (device, thief) = (DeviceKey::generate().unwrap(), DeviceKey::generate().unwrap());
+    let delegation = holder.delegate(&params, 5, &device.public()).unwrap();
+    assert!(thief.present(&delegation, b"nonce").is_err());
+
+    // Another device's signature, or a widened scope, breaks the presentation
+    let mut stolen = device.present(&delegation, b"nonce").unwrap();
+    stolen.signature = thief.sign(b"anything").unwrap();
+    assert_eq!(acc.verify_delegated(&stolen, b"nonce"), Err("Device signature does not verify"));
+    let mut widened = delegation.clone();
+    widened.until_epoch = 50;
+    let widened = device.present(&widened, b"nonce").unwrap();
+    assert_eq!(acc.verify_delegated(&widened, b"nonce"), Err("Delegation proof does not verify"));
+
+    // Revoking the holder revokes the devices with it
+    let presentation = device.present(&delegation, b"nonce").unwrap();
+    assert_eq!(acc.verify_delegated(&presentation, b"nonce"), Ok(()));
+    acc.delete("alice").unwrap();
+    assert_eq!(acc.verify_delegated(&presentation, b"nonce"), Err("Element is no longer a member"));
+}
@@ -0,0 +1,60 @@
+use braavos_accumulator::{BraavosAccumulator, IntegrityError, UpdateMessage};
+
+// Every way of deleting leaves x unprovable, with the witnesses it had
+// before: the one from its add, and that one brought up to date.
+#[test]
+fn every_deletion_path_leaves_no_witness_that_verifies() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let mut issued = Vec::new();
+    for x in ["a", "b", "c", "d", "e", "f"] {
+        issued.push((x, acc.add(x).unwrap()));
+    }
+    let params = acc.params();
+    let warmup = acc.delete("f").unwrap();
+    let witnesses = |x: &str| {
+        let (_, w) = issued.iter().find(|(y, _)| *y == x).unwrap();
+        let current = warmup.apply_to_witness(&params.hash_to_prime(x), w).unwrap();
+        vec![*w, current]
+    };
+    let (wa, wb, wc, wd) = (witnesses("a"), witnesses("b"), witnesses("c"), witnesses("d"));
+
+    let update = acc.delete("a").unwrap();
+    acc.check_deleted("a", &update, &wa).unwrap();
+    let update = acc.delete_batch(&["b", "c"]).unwrap();
+    acc.check_deleted("b", &update, &wb).unwrap();
+    acc.check_deleted("c", &update, &wc).unwrap();
+    let replacement = acc.replace("d", "g").unwrap();
+    acc.check_deleted("d", &replacement.update, &wd).unwrap();
+    let w_e = acc.recover_witness("e").unwrap();
+    let update = acc.delete_with_witness("e", w_e).unwrap();
+    acc.check_deleted("e", &update, &[w_e]).unwrap();
+}
+
+#[test]
+fn bookkeeping_mistakes_are_reported() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("x").unwrap();
+    acc.add("y").unwrap();
+    let other = acc.delete("y").unwrap();
+    assert_eq!(acc.check_deleted("x", &other, &[w]), Err(IntegrityError::StillMember { element: b"x".to_vec() }));
+
+    let update = acc.delete("x").unwrap();
+    assert_eq!(acc.check_deleted("x", &other, &[w]), Err(IntegrityError::NotDeleted { element: b"x".to_vec() }));
+    assert_eq!(acc.check_deleted("x", &update, &[w]), Ok(()));
+    acc.add("x").unwrap();
+    assert!(acc.check_deleted("x", &update, &[w]).is_err());
+}
+
+#[test]
+fn deleting_a_non_member_fails_and_changes_nothing() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("a").unwrap();
+    acc.add("b").unwrap();
+    acc.delete("a").unwrap();
+    let value = acc.value();
+    assert_eq!(acc.delete("a"), Err("Element is not a member"));
+    assert_eq!(acc.delete("never-added"), Err("Element is not a member"));
+    assert_eq!(acc.delete_batch(&["b", "never-added"]), Err("Element is not a member"));
+    assert_eq!((acc.epoch(), acc.value()), (1, value));
+    assert!(acc.contains("b"));
+}
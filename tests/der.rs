@@ -0,0 +1,44 @@
+use braavos_accumulator::{AccValue, BraavosAccumulator, PublicParams, Witness};
+
+#[test]
+fn artifacts_round_trip_through_der() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).base_seed([7; 32]).build().unwrap();
+    let w = acc.add(b"leaf-cert").unwrap();
+    let params = acc.params();
+
+    let der = params.to_der();
+    assert_eq!(der[0], 0x30);
+    assert_eq!(PublicParams::from_der(&der), Ok(params));
+    let unseeded = PublicParams { seed: None, ..params };
+    assert_eq!(PublicParams::from_der(&unseeded.to_der()), Ok(unseeded));
+
+    let der = acc.value().to_der(&params, acc.epoch());
+    assert_eq!(AccValue::from_der(&der, &params), Ok((acc.value(), acc.epoch())));
+    let other = BraavosAccumulator::new(64).unwrap().params();
+    assert!(AccValue::from_der(&der, &other).is_err());
+
+    let der = w.to_der();
+    assert_eq!(Witness::from_der(&der), Ok(w));
+    assert!(acc.verify(b"leaf-cert", Witness::from_der(&der).unwrap()));
+}
+
+#[test]
+fn rejects_non_der_encodings() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let w = Witness {
+        params_digest: acc.params().digest(),
+        value: braavos_accumulator::WitnessValue::new(crypto_bigint::U512::from_u64(5)),
+    };
+    let der = w.to_der();
+    // SEQUENCE { OCTET STRING (32), INTEGER 5 }
+    assert_eq!(&der[..4], &[0x30, 37, 0x04, 32]);
+    assert_eq!(&der[36..], &[0x02, 1, 5]);
+
+    let padded = [&[0x30, 38][..], &der[2..36], &[0x02, 2, 0, 5]].concat();
+    assert_eq!(Witness::from_der(&padded), Err("Non-minimal DER integer"));
+    let negative = [&der[..38], &[0x85]].concat();
+    assert_eq!(Witness::from_der(&negative), Err("Negative DER integer"));
+    let long_form = [&[0x30, 0x81, 37][..], &der[2..]].concat();
+    assert_eq!(Witness::from_der(&long_form), Err("Non-minimal DER length"));
+    assert_eq!(Witness::from_der(&[&der[..], &[0]].concat()), Err("Trailing bytes after DER encoding"));
+}
@@ -0,0 +1,67 @@
+use braavos_accumulator::{BraavosAccumulator, DeviceVerifier, Witness};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Counts allocations made by the current thread.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let out = f();
+    (out, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn verifies_from_fixed_buffers_without_allocating() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add(b"card-1").unwrap();
+    acc.add(b"card-2").unwrap();
+    acc.delete(b"card-2").unwrap();
+    let w = acc.recover_witness(b"card-1").unwrap();
+
+    let mut verifier = [0u8; DeviceVerifier::ENCODED_LEN];
+    let len = DeviceVerifier::new(&acc.params()).unwrap().write_to(&mut verifier).unwrap();
+    assert_eq!(len, DeviceVerifier::ENCODED_LEN);
+    let mut witness = [0u8; Witness::ENCODED_LEN];
+    w.write_to(&mut witness).unwrap();
+    let value = acc.value().to_be_bytes();
+
+    let (ok, count) = allocations_during(|| {
+        let device = DeviceVerifier::from_bytes(&verifier).unwrap();
+        device.verify_encoded(b"card-1", &witness, &value) && !device.verify_encoded(b"card-2", &witness, &value)
+    });
+    assert!(ok);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn rejects_other_parameters_and_values() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add(b"card-1").unwrap();
+    let device = DeviceVerifier::new(&acc.params()).unwrap();
+    assert!(device.verify(b"card-1", &w, &acc.value()));
+
+    let other = BraavosAccumulator::new(64).unwrap();
+    let stranger = DeviceVerifier::new(&other.params()).unwrap();
+    assert!(!stranger.verify(b"card-1", &w, &acc.value()));
+    assert!(!device.verify(b"card-1", &w, &other.value()));
+    assert!(DeviceVerifier::from_bytes(&[0u8; DeviceVerifier::ENCODED_LEN]).is_err());
+}
@@ -0,0 +1,46 @@
+use braavos_accumulator::{BraavosAccumulator, ElementDigest, ElementRegistry, HashAlgorithm};
+use std::sync::Arc;
+
+#[test]
+fn digests_map_to_primes_without_rehashing() {
+    let registry = Arc::new(ElementRegistry::new(HashAlgorithm::Sha256));
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).element_registry(registry.clone()).build().unwrap();
+    let digest = [0x42; 32];
+    let email = ElementDigest::new("sha256:email", digest).unwrap();
+    let w = acc.add(&email).unwrap();
+    assert!(acc.verify(&email, w));
+    assert_eq!((email.domain(), email.digest()), ("sha256:email", digest));
+    assert_eq!(ElementDigest::from_element(email.as_ref()), Some(email.clone()));
+
+    // Every party derives the same prime, and it is not the prime of the raw bytes
+    let params = acc.params();
+    let prime = params.hash_to_prime(&email);
+    assert_eq!(acc.element_prime(&email), Some(prime));
+    assert_eq!(registry.prime(&email), Ok(prime));
+    assert_ne!(params.hash_to_prime(digest), prime);
+
+    // The domain is part of the element
+    let phone = ElementDigest::new("sha256:phone", digest).unwrap();
+    assert_ne!(params.hash_to_prime(&phone), prime);
+    assert!(!acc.verify(&phone, w));
+    acc.delete(&email).unwrap();
+    assert!(!acc.contains(&email) && !acc.verify(&email, w));
+}
+
+#[test]
+fn domains_and_encodings_are_checked() {
+    let long = "x".repeat(65);
+    for domain in ["", "with space", "naïve", long.as_str()] {
+        assert!(ElementDigest::new(domain, [0; 32]).is_err(), "{domain:?} accepted");
+    }
+    let element = ElementDigest::new("sha256:email", [1; 32]).unwrap();
+    let bytes = element.as_ref();
+    assert_eq!(ElementDigest::from_element(&bytes[..bytes.len() - 1]), None);
+    assert_eq!(ElementDigest::from_element(b"alice"), None);
+
+    // Elements posing as digest elements without being well formed are refused
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    assert_eq!(acc.add(&bytes[..bytes.len() - 1]).err(), Some("Malformed digest element"));
+    assert_eq!(acc.issue_witnesses(&[&bytes[..20]]).err(), Some("Malformed digest element"));
+    assert!(acc.add(bytes).is_ok());
+}
@@ -0,0 +1,67 @@
+use braavos_accumulator::math::jacobi;
+use braavos_accumulator::{AllowAll, BlindedPrime, BraavosAccumulator, Manager, Witness, WitnessValue, FAULT_DETECTED};
+use crypto_bigint::U512;
+
+fn checked() -> BraavosAccumulator {
+    BraavosAccumulator::builder().prime_bits(64).double_check(true).build().unwrap()
+}
+
+// Without a fault every check passes, so results match an unchecked run.
+#[test]
+fn checked_operations_succeed_and_verify() {
+    let mut acc = checked();
+    let w_alice = acc.add("alice").unwrap();
+    let issued = acc.issue_witnesses(&["bob", "carol", "dave"]).unwrap();
+    assert!(issued.into_iter().zip(["bob", "carol", "dave"]).all(|((_, w), x)| acc.verify(x, w)));
+
+    acc.delete("bob").unwrap();
+    let w_alice = acc.update_witness_on_deletion("alice", w_alice, "bob").unwrap();
+    assert!(acc.verify("alice", w_alice));
+    acc.delete_batch(&["carol", "dave"]).unwrap();
+    let w_alice = acc.recover_witness("alice").unwrap();
+    assert!(acc.verify("alice", w_alice));
+}
+
+#[test]
+fn signatures_and_blind_issuance_are_checked_too() {
+    let mut manager = Manager::new(checked(), AllowAll);
+    let w = manager.add("op", "alice").unwrap();
+    let params = manager.accumulator().params();
+    assert!(manager.sign_witness("alice", w).unwrap().verify(&params));
+    assert!(manager.signed_state().unwrap().verify(&params));
+
+    let (blinded, unblinder) = BlindedPrime::new(&params, "secret-element");
+    let issuance = manager.issue_blind("op", &blinded).unwrap();
+    let holder = unblinder.unblind(&params, &issuance, &manager.accumulator().value()).unwrap();
+    assert!(manager.verify("secret-element", holder.witness()));
+}
+
+// w times the smallest value with Jacobi symbol -1: outside QR_n and -QR_n.
+fn tampered(acc: &BraavosAccumulator, w: Witness) -> Witness {
+    let n = acc.modulus();
+    let non_residue = (2u64..).map(U512::from).find(|v| jacobi(v, &n) == -1).unwrap();
+    Witness {
+        value: WitnessValue::new(w.value.as_uint().mul_mod(&non_residue, &n.to_nz().unwrap())),
+        ..w
+    }
+}
+
+// A tampered witness takes the trapdoor exponentiation out of the group it
+// is correct in: for about half the deleted primes the result does not open
+// to the witness, which only the double check notices.
+#[test]
+fn a_tampered_witness_is_caught() {
+    let mut acc = checked();
+    let w = acc.add("alice").unwrap();
+    let bad = tampered(&acc, w);
+    let caught = (0..64u32).find(|i| acc.update_witness_on_deletion("alice", bad, i.to_be_bytes()).is_err());
+    let i = caught.expect("No deleted element exposed the tampered witness");
+    assert_eq!(acc.update_witness_on_deletion("alice", bad, i.to_be_bytes()), Err(FAULT_DETECTED));
+    assert!(acc.update_witness_on_deletion("alice", w, i.to_be_bytes()).is_ok());
+
+    // Without the check the result comes back silently
+    let mut unchecked = BraavosAccumulator::builder().prime_bits(64).build().unwrap();
+    let w = unchecked.add("alice").unwrap();
+    let bad = tampered(&unchecked, w);
+    assert!((0..64u32).all(|i| unchecked.update_witness_on_deletion("alice", bad, i.to_be_bytes()).is_ok()));
+}
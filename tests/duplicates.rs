@@ -0,0 +1,50 @@
+use braavos_accumulator::{migrate, BraavosAccumulator, DuplicatePolicy};
+
+#[test]
+fn ignore_and_reject_policies() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    assert_eq!(acc.add("alice"), Ok(w));
+    assert_eq!(acc.multiplicity("alice"), 1);
+    acc.delete("alice").unwrap();
+    assert!(!acc.contains("alice"));
+
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).duplicates(DuplicatePolicy::Reject).build().unwrap();
+    acc.add("alice").unwrap();
+    assert_eq!(acc.add("alice").err(), Some("Element is already a member"));
+    assert_eq!(acc.issue_witnesses(&["bob", "alice"]).err(), Some("Element is already a member"));
+    assert_eq!(acc.issue_witnesses(&["bob", "bob"]).err(), Some("Element appears twice in the batch"));
+    assert!(!acc.contains("bob"));
+}
+
+#[test]
+fn multiset_members_need_as_many_deletes_as_adds() {
+    let builder = BraavosAccumulator::builder().prime_bits(64).duplicates(DuplicatePolicy::Multiset);
+    let mut acc = builder.clone().build().unwrap();
+    let w1 = acc.add("alice").unwrap();
+    let w2 = acc.add("alice").unwrap();
+    acc.add("bob").unwrap();
+    assert_eq!((acc.multiplicity("alice"), acc.member_count()), (2, 2));
+
+    // Dropping a copy leaves the value, and every holder's witness, alone
+    let update = acc.delete("alice").unwrap();
+    assert!(update.deleted.is_empty());
+    assert_eq!((update.from_epoch, update.to_epoch, acc.epoch()), (0, 0, 0));
+    assert!(acc.verify("alice", w1) && acc.verify("alice", w2));
+    assert_eq!(acc.multiplicity("alice"), 1);
+
+    // Copies survive a restore, which needs multiset mode
+    acc.add("alice").unwrap();
+    let state = acc.to_state_bytes().unwrap();
+    assert_eq!(migrate::upgrade(&state).unwrap().copies, vec![(b"alice".to_vec(), 1)]);
+    assert!(BraavosAccumulator::builder().restore(&state).is_err());
+    let mut acc = builder.restore(&state).unwrap();
+    assert_eq!(acc.multiplicity("alice"), 2);
+    assert!(acc.delete_with_witness("alice", w1).is_err());
+
+    // One batch drops a copy of alice and deletes bob
+    let update = acc.delete_batch(&["alice", "bob"]).unwrap();
+    assert_eq!((update.deleted.len(), acc.epoch()), (1, 1));
+    acc.delete("alice").unwrap();
+    assert_eq!((acc.multiplicity("alice"), acc.epoch()), (0, 2));
+}
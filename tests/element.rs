@@ -0,0 +1,34 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, IntoElement, Manager};
+
+#[test]
+fn common_id_types_are_elements() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let by_str = acc.add("alice").unwrap();
+    assert!(acc.verify(String::from("alice"), by_str));
+    assert!(acc.verify(&b"alice"[..], by_str));
+
+    let digest = [7u8; 32];
+    let by_digest = acc.add(digest).unwrap();
+    assert!(acc.verify(&digest[..], by_digest));
+    assert!(acc.verify(digest.into_element(), by_digest));
+
+    let by_id = acc.add(42u64.into_element()).unwrap();
+    assert!(acc.verify(42u64.to_be_bytes(), by_id));
+    assert!(!acc.verify(42u128.into_element(), by_id));
+
+    acc.delete("alice").unwrap();
+    assert!(!acc.contains("alice") && acc.contains(digest));
+}
+
+#[test]
+fn uuids_are_their_rfc_4122_bytes() {
+    let uuid = 0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8u128;
+    let bytes: [u8; 16] = [
+        0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8,
+    ];
+    assert_eq!(uuid.into_element(), bytes);
+
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let w = manager.add("issuer", uuid.into_element()).unwrap();
+    assert!(manager.verify(bytes, w));
+}
@@ -0,0 +1,79 @@
+use braavos_accumulator::argon2::{argon2id, blake2b};
+use braavos_accumulator::{Argon2Params, BraavosAccumulator, ElementKdf};
+use crypto_bigint::U256;
+
+const CHEAP: Argon2Params = Argon2Params {
+    memory_kib: 64,
+    iterations: 1,
+    lanes: 2,
+};
+
+#[test]
+fn derived_elements_are_members_only_through_the_kdf() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let kdf = ElementKdf::new(&acc.params(), CHEAP).unwrap();
+    let phone = kdf.derive("+15550100").unwrap();
+    assert_eq!(phone.domain(), "argon2id:m=64,t=1,p=2");
+    let w = acc.add(&phone).unwrap();
+    assert!(acc.verify(kdf.derive("+15550100").unwrap(), w));
+    assert!(!acc.contains("+15550100"));
+
+    // Another cost or another accumulator's key derives another element
+    let costlier = ElementKdf::new(&acc.params(), Argon2Params { iterations: 2, ..CHEAP }).unwrap();
+    assert!(!acc.contains(costlier.derive("+15550100").unwrap()));
+    let other = BraavosAccumulator::new(64).unwrap();
+    let elsewhere = ElementKdf::new(&other.params(), CHEAP).unwrap();
+    assert_ne!(elsewhere.derive("+15550100").unwrap(), phone);
+}
+
+#[test]
+fn derivation_is_pinned_and_costs_are_checked() {
+    let mut params = BraavosAccumulator::new(64).unwrap().params();
+    params.prf_key = U256::ZERO;
+    let kdf = ElementKdf::new(&params, CHEAP).unwrap();
+    let digest: String = kdf.derive("+15550100").unwrap().digest().iter().map(|b| format!("{b:02x}")).collect();
+    assert_eq!(digest, "9015b2632b0403a5ae451d94c069947a2002c36a94030d16b77605fcc293461a");
+
+    assert_eq!(Argon2Params::default().check(), Ok(()));
+    for cost in [
+        Argon2Params { lanes: 0, ..CHEAP },
+        Argon2Params { iterations: 0, ..CHEAP },
+        Argon2Params { memory_kib: 15, ..CHEAP },
+    ] {
+        assert!(ElementKdf::new(&params, cost).is_err(), "{cost:?} accepted");
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// RFC 9106 section 5.3.
+#[test]
+fn argon2id_matches_the_rfc_vector() {
+    let cost = Argon2Params {
+        memory_kib: 32,
+        iterations: 3,
+        lanes: 4,
+    };
+    let tag = argon2id(&[1; 32], &[2; 16], &[3; 8], &[4; 12], &cost, 32).unwrap();
+    assert_eq!(hex(&tag), "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659");
+    assert!(argon2id(&[1; 32], &[2; 16], &[], &[], &cost, 3).is_err());
+    assert!(argon2id(&[1; 32], &[2; 16], &[], &[], &Argon2Params { lanes: 0, ..cost }, 32).is_err());
+}
+
+// RFC 7693 appendix A, and the digest of the empty message.
+#[test]
+fn blake2b_matches_the_rfc_vectors() {
+    assert_eq!(
+        hex(&blake2b(b"abc", 64).unwrap()),
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+         7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+    assert_eq!(
+        hex(&blake2b(b"", 64).unwrap()),
+        "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+         d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+    );
+    assert!(blake2b(b"abc", 0).is_err() && blake2b(b"abc", 65).is_err());
+}
@@ -0,0 +1,37 @@
+use braavos_accumulator::{BraavosAccumulator, ExpiryPolicy, VerifyFailure};
+
+#[test]
+fn witnesses_within_the_window_are_accepted() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).checkpoints(4).build().unwrap();
+    let w = acc.add("alice").unwrap();
+    for x in ["bob", "carol"] {
+        acc.add(x).unwrap();
+        acc.delete(x).unwrap();
+    }
+
+    // The default policy only accepts current witnesses
+    let strict = ExpiryPolicy::default();
+    assert_eq!(acc.verify_with_expiry("alice", w, &strict), Err(VerifyFailure::Expired { epoch: 0, current: 2 }));
+    let lenient = strict.max_witness_age_epochs(2);
+    assert_eq!(acc.verify_with_expiry("alice", w, &lenient), Ok(()));
+    assert_eq!(acc.verify_with_expiry("bob", w, &lenient), Err(VerifyFailure::Mismatch));
+
+    // A required epoch overrides the window
+    let revoked = lenient.require_epoch(1);
+    let failure = acc.verify_with_expiry("alice", w, &revoked).unwrap_err();
+    assert_eq!(failure, VerifyFailure::Superseded { epoch: 0, required: 1 });
+    assert_eq!(failure.to_string(), "witness is for epoch 0, epoch 1 or later is required");
+}
+
+#[test]
+fn policies_check_epochs_learned_elsewhere() {
+    let policy = ExpiryPolicy::default().max_witness_age_epochs(3).require_epoch(10);
+    assert_eq!(policy.check(12, 15), Ok(()));
+    assert_eq!(policy.check(11, 15), Err(VerifyFailure::Expired { epoch: 11, current: 15 }));
+    assert_eq!(policy.check(9, 10), Err(VerifyFailure::Superseded { epoch: 9, required: 10 }));
+
+    // A current witness still fails while the verifier's view is too old
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    assert_eq!(acc.verify_with_expiry("alice", w, &policy), Err(VerifyFailure::Superseded { epoch: 0, required: 10 }));
+}
@@ -0,0 +1,69 @@
+use braavos_accumulator::{BraavosAccumulator, Witness};
+
+fn accumulator() -> BraavosAccumulator {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    for x in ["carol", "alice", "dave", "bob"] {
+        acc.add(x).unwrap();
+    }
+    acc.delete("dave").unwrap();
+    acc
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn members_are_exported_in_byte_order_with_current_witnesses() {
+    let mut acc = accumulator();
+    let exported: Vec<_> = acc.export_members().map(|m| m.map(|(x, p, w)| (x.to_vec(), p, w)).unwrap()).collect();
+    let elements: Vec<_> = exported.iter().map(|(x, _, _)| x.as_slice()).collect();
+    assert_eq!(elements, [&b"alice"[..], b"bob", b"carol"]);
+    for (x, prime, witness) in exported {
+        assert_eq!(acc.element_prime(&x), Some(prime));
+        assert!(acc.verify(&x, witness));
+    }
+}
+
+#[test]
+fn csv_and_cbor_exports_are_deterministic() {
+    let acc = accumulator();
+    let mut csv = Vec::new();
+    acc.export_members_csv(&mut csv).unwrap();
+    let mut again = Vec::new();
+    acc.export_members_csv(&mut again).unwrap();
+    assert_eq!(csv, again);
+
+    let csv = String::from_utf8(csv).unwrap();
+    let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+    assert_eq!(rows[0], ["element", "prime", "witness"]);
+    assert_eq!(rows.len(), 4);
+    for (row, x) in rows[1..].iter().zip(["alice", "bob", "carol"]) {
+        assert_eq!(row[0], hex(x.as_bytes()));
+        assert_eq!(row[1], hex(&acc.element_prime(x).unwrap().to_be_bytes()));
+        assert_eq!(row[2].len() % 2, 0);
+    }
+
+    let mut cbor = Vec::new();
+    acc.export_members_cbor(&mut cbor).unwrap();
+    let mut again = Vec::new();
+    acc.export_members_cbor(&mut again).unwrap();
+    assert_eq!(cbor, again);
+    // Array of 3 members, the first an array of 3 byte strings: b"alice",
+    // then its 32-byte prime (one-byte length argument)
+    assert_eq!(cbor[..8], [0x83, 0x83, 0x45, b'a', b'l', b'i', b'c', b'e']);
+    assert_eq!(cbor[8..10], [0x58, 32]);
+    assert_eq!(cbor[10..42], acc.element_prime("alice").unwrap().to_be_bytes());
+}
+
+#[test]
+fn exported_witnesses_decode() {
+    let mut acc = accumulator();
+    let mut csv = Vec::new();
+    acc.export_members_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let witness = csv.lines().nth(1).unwrap().split(',').nth(2).unwrap();
+    let byte = |i: usize| u8::from_str_radix(&witness[i..i + 2], 16).unwrap();
+    let bytes: Vec<u8> = (0..witness.len()).step_by(2).map(byte).collect();
+    assert!(acc.verify("alice", Witness::from_bytes(&bytes).unwrap()));
+}
@@ -0,0 +1,39 @@
+use braavos_accumulator::{BraavosAccumulator, FreshnessProof};
+
+#[test]
+fn proofs_are_bound_to_the_nonce_and_epoch() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    acc.add("bob").unwrap();
+
+    let proof = acc.prove_membership_fresh("alice", w, b"nonce-1").unwrap();
+    assert!(acc.verify_membership_fresh("alice", &proof, b"nonce-1"));
+    assert!(!acc.verify_membership_fresh("alice", &proof, b"nonce-2"));
+    assert!(!acc.verify_membership_fresh("bob", &proof, b"nonce-1"));
+    assert!(acc.prove_membership_fresh("alice", w, b"").is_err());
+
+    // A holder proves against the published value, without the accumulator
+    let (params, value, epoch) = (acc.params(), acc.value(), acc.epoch());
+    let proof = FreshnessProof::prove(&params, &value, epoch, "alice", &w, b"nonce-3").unwrap();
+    assert_eq!(FreshnessProof::from_bytes(&proof.to_bytes()), Ok(proof.clone()));
+    assert!(proof.verify(&params, &value, epoch, "alice", b"nonce-3"));
+
+    acc.delete("bob").unwrap();
+    assert!(!acc.verify_membership_fresh("alice", &proof, b"nonce-3"));
+}
+
+#[test]
+fn proofs_need_a_witness_that_opens_the_value() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    let w_bob = acc.add("bob").unwrap();
+    assert!(acc.prove_membership_fresh("alice", w_bob, b"nonce").is_err());
+
+    acc.delete("bob").unwrap();
+    assert!(acc.prove_membership_fresh("alice", w, b"nonce").is_err());
+    assert!(acc.prove_membership_fresh("bob", w_bob, b"nonce").is_err());
+
+    let mut forged = acc.prove_membership_fresh("alice", acc.recover_witness("alice").unwrap(), b"nonce").unwrap();
+    forged.s = forged.t;
+    assert!(!acc.verify_membership_fresh("alice", &forged, b"nonce"));
+}
@@ -0,0 +1,44 @@
+use braavos_accumulator::{BraavosAccumulator, GenesisRecord, PublicParams};
+
+#[test]
+fn the_record_is_bound_into_the_params_digest() {
+    let record = GenesisRecord::new().with_entropy_source("hsm-trng");
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).genesis(record.clone()).build().unwrap();
+    let params = acc.params();
+    assert_eq!(params.genesis, Some(record.digest(params.hash)));
+    assert!(record.matches(&params));
+    assert_eq!(GenesisRecord::from_bytes(&record.to_bytes()), Ok(record.clone()));
+
+    // Any edit to the record breaks the match, and dropping it changes the digest
+    let backdated = GenesisRecord {
+        timestamp: record.timestamp - 1,
+        ..record.clone()
+    };
+    assert!(!backdated.matches(&params));
+    assert_ne!(PublicParams { genesis: None, ..params }.digest(), params.digest());
+
+    let w = acc.add("alice").unwrap();
+    assert_eq!(w.params_digest, params.digest());
+    let restored = BraavosAccumulator::builder().restore(&acc.to_state_bytes().unwrap()).unwrap();
+    assert_eq!(restored.params(), params);
+    assert_eq!(PublicParams::from_der(&params.to_der()), Ok(params));
+    let unseeded = PublicParams { seed: None, ..params };
+    assert_eq!(PublicParams::from_der(&unseeded.to_der()), Ok(unseeded));
+}
+
+#[test]
+fn contributions_determine_the_base_seed() {
+    let record = GenesisRecord::new().with_contribution("alice", [1; 32]).with_contribution("bob", [2; 32]);
+    let acc = BraavosAccumulator::builder().prime_bits(64).genesis(record.clone()).build().unwrap();
+    let params = acc.params();
+    assert_eq!(params.seed, record.contribution_seed(params.hash));
+    assert!(params.verify_base() && record.matches(&params));
+
+    let without_bob = GenesisRecord {
+        contributions: record.contributions[..1].to_vec(),
+        ..record.clone()
+    };
+    assert!(!without_bob.matches(&params));
+    let conflicting = BraavosAccumulator::builder().prime_bits(64).base_seed([9; 32]).genesis(record).build();
+    assert_eq!(conflicting.err(), Some("Base seed conflicts with the genesis contributions"));
+}
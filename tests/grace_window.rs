@@ -0,0 +1,62 @@
+use braavos_accumulator::BraavosAccumulator;
+
+fn accumulator(checkpoints: usize) -> BraavosAccumulator {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).checkpoints(checkpoints).build().unwrap();
+    for x in ["alice", "bob", "carol", "dave", "erin"] {
+        acc.add(x).unwrap();
+    }
+    acc
+}
+
+#[test]
+fn stale_witnesses_verify_within_the_window() {
+    let mut acc = accumulator(2);
+    let w = acc.recover_witness("alice").unwrap();
+    acc.delete("bob").unwrap();
+    let w1 = acc.recover_witness("alice").unwrap();
+    acc.delete("carol").unwrap();
+
+    assert!(!acc.verify("alice", w));
+    assert!(acc.verify_at_epoch("alice", w, 0));
+    assert!(acc.verify_at_epoch("alice", w1, 1));
+    // The witness has to open the value of the epoch it is claimed for
+    assert!(!acc.verify_at_epoch("alice", w, 1));
+    assert!(!acc.verify_at_epoch("alice", w1, 0));
+    assert!(!acc.verify_at_epoch("alice", w, 3));
+    assert!(!acc.verify_at_epoch("dave", w, 0));
+
+    let current = acc.recover_witness("alice").unwrap();
+    assert!(acc.verify_at_epoch("alice", current, acc.epoch()));
+}
+
+#[test]
+fn epochs_older_than_the_window_are_rejected() {
+    let mut acc = accumulator(2);
+    let w = acc.recover_witness("alice").unwrap();
+    acc.delete("bob").unwrap();
+    let w1 = acc.recover_witness("alice").unwrap();
+    acc.delete("carol").unwrap();
+    acc.delete("dave").unwrap();
+    assert!(!acc.verify_at_epoch("alice", w, 0));
+    assert!(acc.verify_at_epoch("alice", w1, 1));
+}
+
+#[test]
+fn deletions_are_seen_once_the_window_passes() {
+    let mut acc = accumulator(1);
+    let w = acc.recover_witness("erin").unwrap();
+    acc.delete("erin").unwrap();
+    // Within the window a deleted member still verifies, as with a cached
+    // OCSP response
+    assert!(acc.verify_at_epoch("erin", w, 0));
+    acc.delete("bob").unwrap();
+    assert!(!acc.verify_at_epoch("erin", w, 0));
+}
+
+#[test]
+fn the_window_is_off_by_default() {
+    let mut acc = accumulator(0);
+    let w = acc.recover_witness("alice").unwrap();
+    acc.delete("bob").unwrap();
+    assert!(!acc.verify_at_epoch("alice", w, 0));
+}
@@ -0,0 +1,51 @@
+use braavos_accumulator::{BraavosAccumulator, HashAlgorithm, PublicParams, StaticBundle};
+
+const ALGORITHMS: [HashAlgorithm; 3] = [HashAlgorithm::Sha256, HashAlgorithm::Sha3_256, HashAlgorithm::Blake3];
+
+#[test]
+fn algorithms_are_distinct_and_identified() {
+    for (i, a) in ALGORITHMS.iter().enumerate() {
+        assert_eq!(HashAlgorithm::from_id(a.id()), Ok(*a));
+        for b in &ALGORITHMS[i + 1..] {
+            assert_ne!(a.hash(b"domain", &[b"alice"]), b.hash(b"domain", &[b"alice"]));
+        }
+    }
+    assert_eq!(HashAlgorithm::from_id(0), Err("Unsupported hash algorithm"));
+    assert_eq!(HashAlgorithm::from_id(4), Err("Unsupported hash algorithm"));
+}
+
+#[test]
+fn the_choice_travels_with_the_params() {
+    for hash in ALGORITHMS {
+        let mut acc = BraavosAccumulator::builder().prime_bits(64).hash(hash).build().unwrap();
+        let w = acc.add("alice").unwrap();
+        acc.add("bob").unwrap();
+        acc.delete("bob").unwrap();
+        let params = acc.params();
+        assert_eq!(params.hash, hash);
+        assert_eq!(PublicParams::from_bytes(&params.to_bytes()).unwrap(), params);
+        assert!(!acc.verify("alice", w));
+        let w = acc.recover_witness("alice").unwrap();
+        assert!(acc.verify("alice", w));
+
+        // Restoring keeps the recorded algorithm, whatever the builder says
+        let restored = BraavosAccumulator::builder().restore(&acc.to_state_bytes().unwrap()).unwrap();
+        assert_eq!(restored.params(), params);
+        let bundle = StaticBundle::from_bytes(&acc.freeze().unwrap().to_bytes()).unwrap();
+        assert!(bundle.verify_member("alice"));
+    }
+}
+
+#[test]
+fn element_primes_depend_on_the_algorithm() {
+    let mut params = BraavosAccumulator::new(64).unwrap().params();
+    let mut primes = Vec::new();
+    let mut digests = Vec::new();
+    for hash in ALGORITHMS {
+        params.hash = hash;
+        primes.push(params.hash_to_prime("alice"));
+        digests.push(params.digest());
+    }
+    assert!(primes[0] != primes[1] && primes[0] != primes[2] && primes[1] != primes[2]);
+    assert!(digests[0] != digests[1] && digests[0] != digests[2] && digests[1] != digests[2]);
+}
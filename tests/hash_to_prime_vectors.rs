@@ -0,0 +1,40 @@
+use braavos_accumulator::{
+    BraavosAccumulator, HashAlgorithm, HashToPrimeVector, HashToPrimeVectors, StaticBundle, HASH_TO_PRIME_PROFILE,
+};
+use crypto_bigint::U256;
+
+#[test]
+fn published_vectors_are_reproduced() {
+    let vectors = HashToPrimeVectors::standard(HashAlgorithm::Sha256).unwrap();
+    assert!(vectors.check());
+    // A change here is a change to the profile, not to the fixture
+    assert_eq!(vectors.to_json(), include_str!("fixtures/h2p_vectors_sha256.json"));
+
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).hash(HashAlgorithm::Sha256).build().unwrap();
+    let params = acc.params();
+    let v = HashToPrimeVector::compute(HashAlgorithm::Sha256, params.prf_key, b"alice").unwrap();
+    assert_eq!(params.hash_to_prime("alice"), v.prime);
+    acc.add("alice").unwrap();
+    assert_eq!(acc.element_prime("alice"), Some(v.prime));
+
+    let mut wrong = vectors.clone();
+    wrong.vectors[3].counter += 1;
+    assert!(!wrong.check());
+    let v = HashToPrimeVector::compute(HashAlgorithm::Sha256, U256::ONE, b"").unwrap();
+    assert_eq!(v.counter == 0, v.prime.as_uint() == &v.first_candidate);
+}
+
+#[test]
+fn params_name_their_profile() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("alice").unwrap();
+    let mut bundle = acc.freeze().unwrap();
+    assert_eq!(bundle.params.h2p_profile, HASH_TO_PRIME_PROFILE);
+    assert_eq!(StaticBundle::from_bytes(&bundle.to_bytes()), Ok(bundle.clone()));
+
+    // Parameters from a later profile are refused rather than misread
+    let digest = bundle.params.digest();
+    bundle.params.h2p_profile = HASH_TO_PRIME_PROFILE + 1;
+    assert_ne!(bundle.params.digest(), digest);
+    assert_eq!(StaticBundle::from_bytes(&bundle.to_bytes()).err(), Some("Unsupported hash-to-prime profile"));
+}
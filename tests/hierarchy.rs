@@ -0,0 +1,52 @@
+use braavos_accumulator::{BraavosAccumulator, SubregistryProof};
+
+#[test]
+fn root_endorses_issuer_registries() {
+    let mut root = BraavosAccumulator::new(64).unwrap();
+    let mut issuer = BraavosAccumulator::new(64).unwrap();
+    let mut rogue = BraavosAccumulator::new(64).unwrap();
+    let w_top = root.endorse_registry(&issuer.params()).unwrap();
+    assert!(root.contains(issuer.params().registry_element()));
+    let w_sub = issuer.add("alice").unwrap();
+
+    let proof = root.prove_in_subregistry(&issuer.params(), w_sub, w_top).unwrap();
+    let proof = SubregistryProof::from_bytes(&proof.to_bytes()).unwrap();
+    assert!(root.verify_in_subregistry(&proof, "alice", &issuer.value()));
+    assert!(proof.verify(&root.params(), &root.value(), "alice", &issuer.value()));
+    assert!(!root.verify_in_subregistry(&proof, "bob", &issuer.value()));
+
+    // A registry the root never endorsed cannot borrow another's witness
+    let w_rogue = rogue.add("alice").unwrap();
+    assert!(root.prove_in_subregistry(&rogue.params(), w_rogue, w_top).is_err());
+    let mut forged = proof.clone();
+    forged.sub_params = rogue.params();
+    forged.sub_witness = w_rogue;
+    assert!(!root.verify_in_subregistry(&forged, "alice", &rogue.value()));
+}
+
+#[test]
+fn revocation_at_either_level_fails_the_proof() {
+    let mut root = BraavosAccumulator::new(64).unwrap();
+    let mut issuer = BraavosAccumulator::new(64).unwrap();
+    let w_top = root.endorse_registry(&issuer.params()).unwrap();
+    let w_sub = issuer.add("alice").unwrap();
+    issuer.add("bob").unwrap();
+    let proof = root.prove_in_subregistry(&issuer.params(), w_sub, w_top).unwrap();
+
+    // The issuer revokes someone else: alice's witness must be brought current
+    let stale_value = issuer.value();
+    issuer.delete("bob").unwrap();
+    assert!(!root.verify_in_subregistry(&proof, "alice", &issuer.value()));
+    assert!(root.verify_in_subregistry(&proof, "alice", &stale_value));
+    let w_sub = issuer.update_witness_on_deletion("alice", w_sub, "bob").unwrap();
+    let proof = root.prove_in_subregistry(&issuer.params(), w_sub, w_top).unwrap();
+    assert!(root.verify_in_subregistry(&proof, "alice", &issuer.value()));
+
+    // The root withdraws its endorsement of the whole registry
+    root.delete(issuer.params().registry_element()).unwrap();
+    assert!(!root.verify_in_subregistry(&proof, "alice", &issuer.value()));
+    assert_eq!(
+        root.prove_in_subregistry(&issuer.params(), w_sub, w_top).err(),
+        Some("Registry is not endorsed by this accumulator")
+    );
+}
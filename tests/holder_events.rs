@@ -0,0 +1,67 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, HolderEvents, Invalidation, Manager, Witness, WitnessHolder};
+
+// Collects what a holder reports while syncing.
+#[derive(Default)]
+struct EventRecorder {
+    epochs: Vec<u64>,
+    updated: usize,
+    invalidated: Vec<Invalidation>,
+}
+
+impl HolderEvents for EventRecorder {
+    fn on_invalidated(&mut self, _element: &[u8], reason: Invalidation) {
+        self.invalidated.push(reason);
+    }
+
+    fn on_updated(&mut self, _element: &[u8], _witness: &Witness, _epoch: u64) {
+        self.updated += 1;
+    }
+
+    fn on_epoch(&mut self, epoch: u64) {
+        self.epochs.push(epoch);
+    }
+}
+
+fn manager_with_holders() -> (Manager, Vec<WitnessHolder>) {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let params = manager.accumulator().params();
+    let mut holders = Vec::new();
+    for x in ["kept", "revoked"] {
+        let w = manager.add("issuer", x).unwrap();
+        holders.push(WitnessHolder::new(&params, x, w, 0).unwrap());
+    }
+    for x in ["c1", "c2"] {
+        manager.add("issuer", x).unwrap();
+        manager.delete("issuer", x).unwrap();
+    }
+    manager.delete("issuer", "revoked").unwrap();
+    (manager, holders)
+}
+
+#[test]
+fn holders_report_each_epoch_and_their_deletion() {
+    let (mut manager, mut holders) = manager_with_holders();
+    let (mut kept, mut revoked) = (EventRecorder::default(), EventRecorder::default());
+    holders[0].sync_with(manager.update_log(), &mut kept).unwrap();
+    assert!(holders[1].sync_with(manager.update_log(), &mut revoked).is_err());
+
+    assert_eq!((kept.epochs, kept.updated, kept.invalidated), (vec![1, 2, 3], 1, vec![]));
+    assert!(manager.verify("kept", holders[0].witness()));
+    assert_eq!(revoked.epochs, [1, 2]);
+    assert_eq!(revoked.invalidated, [Invalidation::Deleted { epoch: 3 }]);
+}
+
+#[test]
+fn rerandomization_asks_for_a_reissued_witness() {
+    // A holder that was current when the parameters changed; one further
+    // behind is past the restarted log and sees Expired instead
+    let (mut manager, mut holders) = manager_with_holders();
+    holders[0].sync(manager.update_log()).unwrap();
+    manager.rerandomize("issuer").unwrap();
+    manager.add("issuer", "c3").unwrap();
+    manager.delete("issuer", "c3").unwrap();
+
+    let mut kept = EventRecorder::default();
+    assert!(holders[0].sync_with(manager.update_log(), &mut kept).is_err());
+    assert_eq!((kept.updated, kept.invalidated), (0, vec![Invalidation::Reissued]));
+}
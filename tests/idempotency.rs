@@ -0,0 +1,52 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Manager, Quota, Service};
+
+#[test]
+fn retried_requests_are_answered_not_reapplied() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll).with_idempotency_window(2);
+    let w = manager.add_idempotent("op", b"req-1", "alice").unwrap();
+    assert_eq!(manager.add_idempotent("op", b"req-1", "alice"), Ok(w));
+    assert_eq!(manager.audit_log().records().len(), 1);
+
+    // A retried delete returns the first update, even if alice was re-added since
+    let update = manager.delete_idempotent("op", b"req-2", "alice").unwrap();
+    manager.add("op", "alice").unwrap();
+    assert_eq!(manager.delete_idempotent("op", b"req-2", "alice"), Ok(update));
+    assert!(manager.accumulator().contains("alice"));
+    assert_eq!(manager.accumulator().epoch(), 1);
+
+    let reused = Some("Idempotency key was used for a different request");
+    assert_eq!(manager.delete_idempotent("op", b"req-2", "bob").err(), reused);
+    assert_eq!(manager.delete_idempotent("other", b"req-2", "alice").err(), reused);
+    assert_eq!(manager.add_idempotent("op", b"req-2", "alice").err(), reused);
+
+    // The window is bounded: the oldest key is forgotten
+    manager.add_idempotent("op", b"req-3", "carol").unwrap();
+    assert_eq!(manager.idempotency_keys(), 2);
+    assert!(manager.add_idempotent("op", b"req-1", "alice").is_ok());
+    assert_eq!(manager.audit_log().records().len(), 5);
+}
+
+#[test]
+fn keys_survive_a_service_restart() {
+    let root = std::env::temp_dir().join(format!("braavos-idempotency-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let mut service = Service::new(&root).unwrap();
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder.clone()).unwrap();
+    let mut session = service.session("issuer", b"token").unwrap();
+    session.add("op", "alice").unwrap();
+    let update = session.delete_idempotent("op", b"delete-alice", "alice").unwrap();
+    drop(service);
+    // State and window share one record, so no crash can leave one without the other
+    let files: Vec<_> = std::fs::read_dir(root.join("issuer")).unwrap().map(|e| e.unwrap().file_name()).collect();
+    assert_eq!(files, ["tenant"]);
+
+    let mut service = Service::new(&root).unwrap();
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder).unwrap();
+    let mut session = service.session("issuer", b"token").unwrap();
+    session.add("op", "alice").unwrap();
+    assert_eq!(session.delete_idempotent("op", b"delete-alice", "alice"), Ok(update));
+    assert!(session.manager().accumulator().contains("alice"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
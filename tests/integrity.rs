@@ -0,0 +1,62 @@
+use braavos_accumulator::migrate::{self, State};
+use braavos_accumulator::{BraavosAccumulator, IntegrityError};
+
+fn state() -> State {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    for x in ["alice", "bob", "carol"] {
+        acc.add(x).unwrap();
+    }
+    acc.delete("bob").unwrap();
+    migrate::upgrade(&acc.to_state_bytes().unwrap()).unwrap()
+}
+
+// Restores without the strict check, so check_integrity can be called on the
+// result, and confirms a strict restore refuses the same state.
+fn check(state: &State) -> Result<(), IntegrityError> {
+    let bytes = state.to_bytes();
+    let result = BraavosAccumulator::builder().restore(&bytes).unwrap().check_integrity();
+    let strict = BraavosAccumulator::builder().strict(true).restore(&bytes);
+    assert_eq!(strict.is_ok(), result.is_ok());
+    result
+}
+
+#[test]
+fn consistent_state_passes() {
+    assert_eq!(check(&state()), Ok(()));
+}
+
+#[test]
+fn a_cached_prime_that_does_not_match_its_member_is_found() {
+    let mut state = state();
+    let carol = state.members.iter().position(|(x, _)| x == b"carol").unwrap();
+    state.members[carol].1 = state.params.hash_to_prime("mallory");
+    assert_eq!(check(&state), Err(IntegrityError::PrimeMismatch { element: b"carol".to_vec() }));
+}
+
+#[test]
+fn an_epoch_that_disagrees_with_the_deletions_is_found() {
+    let mut ahead = state();
+    ahead.epoch += 1;
+    assert_eq!(check(&ahead), Err(IntegrityError::EpochMismatch { epoch: 2, deletions: 1 }));
+    let mut extra = state();
+    extra.deleted.push(extra.params.hash_to_prime("mallory"));
+    assert_eq!(check(&extra), Err(IntegrityError::EpochMismatch { epoch: 1, deletions: 2 }));
+}
+
+#[test]
+fn a_key_from_another_accumulator_is_found() {
+    let mut state = state();
+    let other = BraavosAccumulator::new(64).unwrap();
+    state.secret_key = migrate::upgrade(&other.to_state_bytes().unwrap()).unwrap().secret_key;
+    assert_eq!(check(&state), Err(IntegrityError::KeyMismatch));
+}
+
+#[test]
+fn strict_restores_name_the_failure() {
+    let mut state = state();
+    state.epoch = 0;
+    let restored = BraavosAccumulator::builder().strict(true).restore(&state.to_bytes());
+    assert_eq!(restored.err(), Some("State failed the integrity check"));
+    let err = IntegrityError::EpochMismatch { epoch: 0, deletions: 1 };
+    assert_eq!(err.to_string(), "epoch 0 does not match 1 recorded deletions");
+}
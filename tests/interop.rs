@@ -0,0 +1,30 @@
+use braavos_accumulator::interop::{from_biguint, to_biguint};
+use braavos_accumulator::{AccValue, BraavosAccumulator, ElemPrime, WitnessValue};
+use crypto_bigint::{U256, U512};
+use num_bigint::BigUint;
+
+#[test]
+fn number_types_round_trip_through_biguint() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    acc.add("bob").unwrap();
+    let prime = acc.element_prime("alice").unwrap();
+
+    assert_eq!(ElemPrime::try_from(&BigUint::from(prime)), Ok(prime));
+    assert_eq!(AccValue::try_from(&BigUint::from(acc.value())).unwrap(), acc.value());
+    assert_eq!(WitnessValue::try_from(&BigUint::from(w.value)).unwrap(), w.value);
+    assert_eq!(BigUint::from(prime), to_biguint(prime.as_uint()));
+    assert_eq!(from_biguint::<{ U512::LIMBS }>(&to_biguint(&acc.modulus())), Ok(acc.modulus()));
+}
+
+#[test]
+fn integers_wider_than_the_type_are_rejected() {
+    let too_wide = BigUint::from(1u8) << 256;
+    assert_eq!(ElemPrime::try_from(&too_wide), Err("Integer too large for target width"));
+    assert_eq!(from_biguint::<{ U256::LIMBS }>(&(too_wide.clone() - 1u8)), Ok(U256::MAX));
+
+    let too_wide = BigUint::from(1u8) << 512;
+    assert_eq!(AccValue::try_from(&too_wide).err(), Some("Integer too large for target width"));
+    assert_eq!(WitnessValue::try_from(&too_wide).err(), Some("Integer too large for target width"));
+    assert_eq!(WitnessValue::try_from(&(too_wide - 1u8)).unwrap(), WitnessValue::new(U512::MAX));
+}
@@ -0,0 +1,38 @@
+use braavos_accumulator::{batch_digest, AllowAll, BraavosAccumulator, Decision, Manager, Operation};
+
+#[test]
+fn batches_match_individual_adds_in_input_order() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).precompute(1 << 20).build().unwrap();
+    acc.add("existing").unwrap();
+    let xs: Vec<Vec<u8>> = (0..20u8).rev().map(|i| vec![i]).collect();
+    let issued = acc.issue_witnesses(&xs).unwrap();
+    assert_eq!(issued.len(), xs.len());
+    for (x, (prime, w)) in xs.iter().zip(&issued) {
+        assert_eq!(*prime, acc.params().hash_to_prime(x));
+        assert_eq!(*w, acc.recover_witness(x).unwrap());
+        assert!(acc.verify(x, *w));
+    }
+    assert_eq!(acc.member_count(), 21);
+    assert!(acc.issue_witnesses::<&[u8]>(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn a_batch_is_one_audit_record() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let xs = ["alice", "bob", "carol"];
+    manager.issue_witnesses("onboarding", &xs).unwrap();
+    let records = manager.audit_log().records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].operation, Operation::IssueWitnesses);
+    assert_eq!(records[0].element, batch_digest(&xs));
+    assert_eq!(records[0].metadata, 3u64.to_be_bytes());
+
+    let no_mallory = |_: &str, _: Operation, x: &[u8]| match x {
+        b"mallory" => Decision::Deny("blocked".into()),
+        _ => Decision::Allow,
+    };
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), no_mallory);
+    assert!(manager.issue_witnesses("onboarding", &["dave", "mallory"]).is_err());
+    assert_eq!(manager.accumulator().member_count(), 0);
+    assert_eq!(manager.audit_log().records()[0].decision, Decision::Deny("blocked".into()));
+}
@@ -0,0 +1,37 @@
+use braavos_accumulator::{BraavosAccumulator, IssuanceProof, IssuanceReceipt};
+
+#[test]
+fn every_holder_proves_its_place_in_batches_of_any_size() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    for size in 1..=9 {
+        let xs: Vec<String> = (0..size).map(|i| format!("batch{size}-member{i}")).collect();
+        let batch = acc.issue_witnesses_with_receipt(&xs).unwrap();
+        let receipt = IssuanceReceipt::from_bytes(&batch.receipt.to_bytes()).unwrap();
+        assert_eq!(receipt.size, size);
+        for (x, (_, w, proof)) in xs.iter().zip(&batch.issued) {
+            let proof = IssuanceProof::from_bytes(&proof.to_bytes()).unwrap();
+            assert!(proof.verify(&params, &receipt, x, w), "size {size}, index {}", proof.index);
+            assert!(acc.verify(x, *w));
+        }
+    }
+}
+
+#[test]
+fn proofs_fail_for_other_elements_witnesses_positions_and_receipts() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let batch = acc.issue_witnesses_with_receipt(&["a", "b", "c"]).unwrap();
+    let (receipt, [(_, wa, pa), (_, wb, _), _]) = (&batch.receipt, batch.issued.as_slice()) else {
+        panic!("Expected three issued witnesses");
+    };
+    assert!(pa.verify(&params, receipt, "a", wa));
+    assert!(!pa.verify(&params, receipt, "b", wa));
+    assert!(!pa.verify(&params, receipt, "a", wb));
+    let moved = IssuanceProof { index: 1, ..pa.clone() };
+    assert!(!moved.verify(&params, receipt, "a", wa));
+    let resized = IssuanceReceipt { size: 4, ..receipt.clone() };
+    assert!(!pa.verify(&params, &resized, "a", wa));
+
+    assert!(acc.issue_witnesses_with_receipt::<&str>(&[]).is_err());
+}
@@ -0,0 +1,209 @@
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use braavos_accumulator::{BraavosAccumulator, DuplicatePolicy, JournaledAccumulator, UpdateMessage};
+
+#[link(name = "sqlite3")]
+extern "C" {
+    fn sqlite3_open(filename: *const c_char, db: *mut *mut c_void) -> c_int;
+    fn sqlite3_exec(
+        db: *mut c_void,
+        sql: *const c_char,
+        callback: *const c_void,
+        arg: *mut c_void,
+        errmsg: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_close(db: *mut c_void) -> c_int;
+}
+
+// Runs `sql` on a closed journal, leaving it as a crashed process would have.
+fn edit(path: &Path, sql: &str) {
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let sql = CString::new(sql).unwrap();
+    let mut db = ptr::null_mut();
+    // SAFETY: both strings are NUL-terminated and outlive the calls, `db` is
+    // a local out-pointer, and the handle is closed once, after its last use.
+    let rc = unsafe {
+        let opened = sqlite3_open(path.as_ptr(), &mut db);
+        let rc = match opened {
+            0 => sqlite3_exec(db, sql.as_ptr(), ptr::null(), ptr::null_mut(), ptr::null_mut()),
+            rc => rc,
+        };
+        sqlite3_close(db);
+        rc
+    };
+    assert_eq!(rc, 0, "Failed to edit journal");
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn journal_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("braavos-journal-{}-{}.db", name, std::process::id()));
+    remove(&path);
+    path
+}
+
+fn remove(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+}
+
+#[test]
+fn unfinished_operations_are_completed_on_open() {
+    let path = journal_path("unfinished");
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    journal.add("alice").unwrap();
+    journal.add("bob").unwrap();
+    drop(journal);
+
+    // The process died after journalling a delete of bob and an add of
+    // carol, before applying either
+    let ops = format!("INSERT INTO ops (op, element) VALUES (1, X'{}'), (0, X'{}')", hex(b"bob"), hex(b"carol"));
+    edit(&path, &ops);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    assert_eq!(journal.recovered(), 2);
+    let acc = journal.accumulator();
+    assert_eq!(acc.epoch(), 1);
+    assert!(acc.contains("carol") && !acc.contains("bob"));
+
+    // Recovery filled in the values, so the next open replays them as finished
+    journal.delete("carol").unwrap();
+    let value = journal.accumulator().value();
+    drop(journal);
+    let journal = JournaledAccumulator::open(&path, builder).unwrap();
+    assert_eq!(journal.recovered(), 0);
+    assert_eq!((journal.accumulator().epoch(), journal.accumulator().value()), (2, value));
+    drop(journal);
+    remove(&path);
+}
+
+#[test]
+fn unfinished_operations_that_fail_are_dropped() {
+    let path = journal_path("failed");
+    let builder = BraavosAccumulator::builder().prime_bits(64).duplicates(DuplicatePolicy::Reject);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    journal.add("alice").unwrap();
+    let value = journal.accumulator().value();
+    drop(journal);
+
+    // The process died after journalling a duplicate add of alice and a
+    // delete of a non-member, before either failed and was aborted
+    let ops = format!(
+        "INSERT INTO ops (op, element) VALUES (0, X'{}'), (1, X'{}'), (0, X'{}')",
+        hex(b"alice"),
+        hex(b"mallory"),
+        hex(b"carol")
+    );
+    edit(&path, &ops);
+    let journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    assert_eq!(journal.recovered(), 1);
+    assert_eq!((journal.accumulator().epoch(), journal.accumulator().value()), (0, value));
+    assert!(journal.accumulator().contains("carol") && !journal.accumulator().contains("mallory"));
+    drop(journal);
+
+    // The failed operations are gone for good
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    assert_eq!(journal.recovered(), 0);
+    journal.add("dave").unwrap();
+    drop(journal);
+
+    // A finished operation that fails on replay is still a mismatch, as is an unknown one
+    edit(&path, &format!("INSERT INTO ops (op, element, value) VALUES (1, X'{}', zeroblob(64))", hex(b"erin")));
+    let opened = JournaledAccumulator::open(&path, builder.clone());
+    assert_eq!(opened.err(), Some("Journal does not match the replayed state"));
+    edit(&path, "DELETE FROM ops WHERE op = 1; INSERT INTO ops (op, element) VALUES (7, X'00')");
+    let opened = JournaledAccumulator::open(&path, builder);
+    assert_eq!(opened.err(), Some("Unknown operation in journal"));
+    remove(&path);
+}
+
+#[test]
+fn a_journal_that_disagrees_with_replay_is_refused() {
+    let path = journal_path("mismatch");
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    journal.add("alice").unwrap();
+    journal.add("bob").unwrap();
+    journal.delete("bob").unwrap();
+    drop(journal);
+
+    edit(&path, "UPDATE ops SET value = zeroblob(64) WHERE op = 1");
+    let opened = JournaledAccumulator::open(&path, builder);
+    assert_eq!(opened.err(), Some("Journal does not match the replayed state"));
+    remove(&path);
+}
+
+#[test]
+fn checkpoints_survive_a_reopen() {
+    let path = journal_path("checkpoint");
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    let w = journal.add("alice").unwrap();
+    journal.add("bob").unwrap();
+    let update = journal.delete("bob").unwrap();
+    journal.checkpoint().unwrap();
+    let (epoch, value) = (journal.accumulator().epoch(), journal.accumulator().value());
+    let w = update.apply_to_witness(&journal.accumulator().params().hash_to_prime("alice"), &w).unwrap();
+    drop(journal);
+
+    // Nothing is left to replay: a tampered operation log would be noticed
+    edit(&path, "UPDATE ops SET value = zeroblob(64)");
+    let mut journal = JournaledAccumulator::open(&path, builder).unwrap();
+    assert_eq!(journal.recovered(), 0);
+    assert_eq!((journal.accumulator().epoch(), journal.accumulator().value()), (epoch, value));
+    assert!(journal.verify("alice", w));
+    assert!(journal.accumulator().contains("alice") && !journal.accumulator().contains("bob"));
+    drop(journal);
+    remove(&path);
+}
+
+#[test]
+fn idempotency_keys_are_kept_in_the_journal() {
+    let path = journal_path("idempotency");
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    let w = journal.add_idempotent(b"req-1", "alice").unwrap();
+    journal.add("bob").unwrap();
+    let update = journal.delete_idempotent(b"req-2", "bob").unwrap();
+    assert_eq!(journal.delete_idempotent(b"req-2", "bob").unwrap(), update);
+    assert_eq!(journal.idempotency_keys(), 2);
+    drop(journal);
+
+    // Replay rebuilds the window: retries are answered, not applied again
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    assert_eq!(journal.idempotency_keys(), 2);
+    assert_eq!(journal.add_idempotent(b"req-1", "alice").unwrap().value, w.value);
+    assert_eq!(journal.delete_idempotent(b"req-2", "bob").unwrap(), update);
+    assert_eq!(journal.accumulator().epoch(), 1);
+    assert_eq!(
+        journal.add_idempotent(b"req-2", "carol").err(),
+        Some("Idempotency key was used for a different request")
+    );
+    assert!(!journal.accumulator().contains("carol"));
+
+    // A checkpoint folds the window into the snapshot
+    journal.checkpoint().unwrap();
+    drop(journal);
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap();
+    assert_eq!(journal.delete_idempotent(b"req-2", "bob").unwrap(), update);
+    assert_eq!(journal.accumulator().epoch(), 1);
+    drop(journal);
+
+    // A keyed operation the crash interrupted is completed and remembered
+    edit(&path, &format!("INSERT INTO ops (seq, op, element) VALUES (100, 0, X'{}')", hex(b"dave")));
+    edit(&path, &format!("INSERT INTO idempotency_keys (seq, key) VALUES (100, X'{}')", hex(b"req-3")));
+    let mut journal = JournaledAccumulator::open(&path, builder.clone()).unwrap().with_idempotency_window(2);
+    assert_eq!(journal.recovered(), 1);
+    assert_eq!(journal.idempotency_keys(), 2);
+    let w = journal.add_idempotent(b"req-3", "dave").unwrap();
+    assert!(journal.verify("dave", w));
+    // The window is bounded: req-1 was the oldest and is forgotten
+    assert!(journal.add_idempotent(b"req-1", "erin").is_ok());
+    drop(journal);
+    remove(&path);
+}
@@ -0,0 +1,44 @@
+use braavos_accumulator::{BraavosAccumulator, KeyStatus, RotationReason};
+use std::time::{Duration, SystemTime};
+
+const STATE_V1: &[u8] = include_bytes!("fixtures/state_v1.bin");
+
+#[test]
+fn trapdoor_operations_are_counted_and_persisted() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).rotate_after_operations(3).build().unwrap();
+    acc.add("alice").unwrap();
+    acc.issue_witnesses(&["bob"]).unwrap();
+    acc.delete("alice").unwrap();
+    assert_eq!(acc.key_usage().operations, 3);
+    assert_eq!(acc.key_status(), KeyStatus::Ok);
+    acc.add("carol").unwrap();
+    let advised = KeyStatus::RotationAdvised(RotationReason::Operations);
+    assert_eq!(acc.key_status(), advised);
+
+    let report = acc.report();
+    assert_eq!((report.key_operations, report.key_status), (4, advised));
+    assert!(report.to_json().contains("\"key_operations\":4,") && report.key_age_secs.is_some());
+
+    // The count and creation time survive a restore; thresholds come from the builder
+    let state = acc.to_state_bytes().unwrap();
+    let restored = BraavosAccumulator::builder().rotate_after_operations(3).restore(&state).unwrap();
+    assert_eq!(restored.key_usage(), acc.key_usage());
+    assert_eq!(restored.key_status(), advised);
+    assert_eq!(BraavosAccumulator::builder().restore(&state).unwrap().key_status(), KeyStatus::Ok);
+}
+
+#[test]
+fn old_keys_are_flagged_by_age() {
+    let day = Duration::from_secs(86_400);
+    let acc = BraavosAccumulator::builder().prime_bits(64).rotate_after(30 * day).build().unwrap();
+    let now = SystemTime::now();
+    assert!(acc.key_usage().age(now).is_some_and(|age| age < day));
+    assert_eq!(acc.key_status_at(now + day), KeyStatus::Ok);
+    assert_eq!(acc.key_status_at(now + 31 * day), KeyStatus::RotationAdvised(RotationReason::Age));
+
+    // State from before usage was tracked has no known age
+    let old = BraavosAccumulator::builder().rotate_after(30 * day).restore(STATE_V1).unwrap();
+    assert_eq!((old.key_usage().operations, old.key_usage().created_at), (0, None));
+    assert_eq!(old.key_status_at(now + 365 * day), KeyStatus::Ok);
+    assert!(old.report().to_json().contains("\"key_age_secs\":null,"));
+}
@@ -0,0 +1,87 @@
+use braavos_accumulator::{BraavosAccumulator, DecodingPolicy, KeyShare, SecretKey, ShareCommitments};
+use crypto_bigint::U2048;
+
+#[test]
+fn any_quorum_reconstructs_the_key() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let sk = acc.secret_key();
+    let (shares, commitments) = sk.split(3, 5).unwrap();
+    assert_eq!((shares.len(), commitments.threshold()), (5, 3));
+    assert!(shares.iter().all(|share| commitments.verify(share)));
+
+    for quorum in [[0, 1, 2], [4, 0, 2], [1, 3, 4]] {
+        let quorum: Vec<KeyShare> = quorum.iter().map(|&i| shares[i].clone()).collect();
+        let combined = SecretKey::combine(&quorum).unwrap();
+        assert_eq!(combined, sk);
+        assert!(combined.matches(&acc.params()) && commitments.verify_secret(&combined));
+    }
+    // More than the threshold works too
+    assert_eq!(SecretKey::combine(&shares).unwrap(), sk);
+    assert!(!sk.matches(&BraavosAccumulator::new(64).unwrap().params()));
+}
+
+#[test]
+fn fewer_than_the_threshold_learn_nothing_useful() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let (shares, commitments) = acc.secret_key().split(3, 5).unwrap();
+    // Two points of a quadratic interpolate to some other constant term,
+    // almost always too wide to be a key at all
+    let guess = SecretKey::combine(&shares[..2]);
+    assert!(guess.map_or(true, |guess| !guess.matches(&acc.params()) && !commitments.verify_secret(&guess)));
+    assert_eq!(SecretKey::combine(&[]), Err("No shares to combine"));
+}
+
+#[test]
+fn bad_shares_are_rejected() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let (shares, commitments) = acc.secret_key().split(2, 3).unwrap();
+
+    let mut forged = shares[1].clone();
+    forged.value = forged.value.wrapping_add(&U2048::ONE);
+    assert!(!commitments.verify(&forged));
+    // Combining it anyway gives no key the commitments recognise
+    let wrong = SecretKey::combine(&[shares[0].clone(), forged]);
+    assert!(wrong.map_or(true, |wrong| !wrong.matches(&acc.params()) && !commitments.verify_secret(&wrong)));
+
+    // A share moved to another index, or from another dealing, fails too
+    let moved = KeyShare {
+        index: 3,
+        ..shares[1].clone()
+    };
+    assert!(!commitments.verify(&moved));
+    let (other, _) = acc.secret_key().split(2, 3).unwrap();
+    assert!(!commitments.verify(&other[0]));
+    assert!(!commitments.verify(&KeyShare {
+        index: 0,
+        ..shares[0].clone()
+    }));
+
+    let duplicate = [shares[0].clone(), shares[0].clone()];
+    assert_eq!(SecretKey::combine(&duplicate), Err("Share indices must be distinct and nonzero"));
+    assert!(acc.secret_key().split(4, 3).is_err());
+    assert!(acc.secret_key().split(0, 3).is_err());
+}
+
+#[test]
+fn shares_and_commitments_round_trip() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let (shares, commitments) = acc.secret_key().split(2, 2).unwrap();
+    let decoded = ShareCommitments::from_bytes(&commitments.to_bytes()).unwrap();
+    assert_eq!(decoded, commitments);
+    for share in &shares {
+        let decoded = KeyShare::from_bytes(&share.to_bytes()).unwrap();
+        assert_eq!(&decoded, share);
+        assert!(commitments.verify(&decoded));
+    }
+
+    // A strict decoder refuses shares at index zero
+    let zero = KeyShare {
+        index: 0,
+        ..shares[0].clone()
+    };
+    assert!(KeyShare::from_bytes(&zero.to_bytes()).is_ok());
+    assert_eq!(
+        KeyShare::from_bytes_with(&zero.to_bytes(), &DecodingPolicy::strict()),
+        Err("Share is not canonical")
+    );
+}
@@ -0,0 +1,106 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Manager, WitnessHolder};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeSet;
+
+const MEMBERS: u32 = 10_000;
+const EPOCHS: u64 = 100;
+const HOLDER_STRIDE: u32 = 100; // Every 100th member keeps its own witness
+
+fn element(i: u32) -> Vec<u8> {
+    format!("member-{:06}", i).into_bytes()
+}
+
+// Bulk import, then interleaved adds and deletes until 100 epochs have been
+// published, with a sample of holders syncing from the update log at
+// different cadences. Invariants are checked after every round.
+#[test]
+fn registry_lifecycle() {
+    let mut rng = StdRng::seed_from_u64(0x42);
+    let acc = BraavosAccumulator::builder()
+        .prime_bits(64)
+        .build()
+        .expect("Failed to create accumulator");
+    let mut manager = Manager::new(acc, AllowAll);
+    let params = manager.accumulator().params();
+
+    // Bulk import
+    let mut expected = BTreeSet::new();
+    let mut holders = Vec::new();
+    for i in 0..MEMBERS {
+        let x = element(i);
+        let w = manager.add("importer", &x).expect("Failed to import member");
+        if i.is_multiple_of(HOLDER_STRIDE) {
+            holders.push(WitnessHolder::new(&params, &x, w, manager.accumulator().epoch()).unwrap());
+        }
+        expected.insert(x);
+    }
+    assert_eq!(manager.accumulator().member_count(), MEMBERS as usize);
+
+    let mut next = MEMBERS;
+    let mut round = 0u64;
+    let mut revoked = Vec::new();
+    while manager.accumulator().epoch() < EPOCHS {
+        round += 1;
+
+        // A few new members join; adds never change the value or the epoch
+        let value_before = manager.accumulator().value();
+        for _ in 0..rng.gen_range(0..4) {
+            let x = element(next);
+            next += 1;
+            let w = manager.add("issuer", &x).unwrap();
+            assert!(manager.verify(&x, w), "Fresh witness must verify");
+            if next.is_multiple_of(7) {
+                holders.push(WitnessHolder::new(&params, &x, w, manager.accumulator().epoch()).unwrap());
+            }
+            expected.insert(x);
+        }
+        assert_eq!(manager.accumulator().value(), value_before);
+
+        // One member is revoked per round, sometimes one that holds its own witness
+        let victim = if round.is_multiple_of(10) && !holders.is_empty() {
+            holders[rng.gen_range(0..holders.len())].element().to_vec()
+        } else {
+            expected.iter().nth(rng.gen_range(0..expected.len())).unwrap().clone()
+        };
+        let update = manager.delete("revoker", &victim).unwrap();
+        assert_eq!(update.to_epoch, manager.accumulator().epoch());
+        assert_eq!(update.value, manager.accumulator().value());
+        expected.remove(&victim);
+        if let Some(pos) = holders.iter().position(|h| h.element() == victim.as_slice()) {
+            revoked.push(holders.remove(pos));
+        }
+
+        // Holders sync at different cadences, then spot-verify
+        for (i, holder) in holders.iter_mut().enumerate() {
+            if round.is_multiple_of(i as u64 % 7 + 1) {
+                holder.sync(manager.update_log()).expect("Holder failed to sync");
+                assert_eq!(holder.epoch(), manager.accumulator().epoch());
+                assert!(manager.verify(holder.element(), holder.witness()));
+            }
+        }
+
+        assert_eq!(manager.update_log().latest_epoch(), manager.accumulator().epoch());
+        assert_eq!(manager.accumulator().member_count(), expected.len());
+        assert!(!manager.accumulator().contains(&victim));
+    }
+
+    // Everyone catches up at the end
+    for holder in &mut holders {
+        holder.sync(manager.update_log()).unwrap();
+        assert!(manager.verify(holder.element(), holder.witness()));
+    }
+
+    // Revoked holders can neither sync nor verify with their last witness
+    assert!(!revoked.is_empty());
+    for holder in &mut revoked {
+        assert!(!manager.verify(holder.element(), holder.witness()));
+        assert!(holder.sync(manager.update_log()).is_err());
+    }
+
+    // A late joiner's coalesced sync from genesis matches everyone else
+    let full = manager.update_log().since(0).unwrap().unwrap();
+    assert_eq!(full.to_epoch, EPOCHS);
+    assert_eq!(full.deleted.len() as u64, EPOCHS);
+    assert_eq!(full.value, manager.accumulator().value());
+}
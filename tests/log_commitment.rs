@@ -0,0 +1,51 @@
+use braavos_accumulator::{BraavosAccumulator, LogCommitment, LogHead, LogInclusionProof};
+
+fn log() -> LogCommitment {
+    let mut log = LogCommitment::new(BraavosAccumulator::new(64).unwrap()).unwrap();
+    for (i, record) in ["genesis", "deploy v1", "deploy v2"].iter().enumerate() {
+        log.append(record.as_bytes(), 1_700_000_000 + i as u64).unwrap();
+    }
+    log
+}
+
+#[test]
+fn entries_are_bound_to_their_position() {
+    let log = log();
+    let params = log.params();
+    let head = LogHead::from_bytes(&log.head().unwrap().to_bytes()).unwrap();
+    assert!(head.verify(&params));
+    assert_eq!((head.size, head.last_timestamp), (3, 1_700_000_002));
+
+    let proof = LogInclusionProof::from_bytes(&log.prove(1).unwrap().to_bytes()).unwrap();
+    assert!(proof.verify(&params, &head, b"deploy v1"));
+    assert!(!proof.verify(&params, &head, b"deploy v2"));
+    // The same witness claimed at another position or time
+    let mut moved = proof.clone();
+    moved.index = 2;
+    assert!(!moved.verify(&params, &head, b"deploy v1"));
+    let mut backdated = proof.clone();
+    backdated.timestamp -= 1;
+    assert!(!backdated.verify(&params, &head, b"deploy v1"));
+    assert!(log.prove(3).is_err());
+}
+
+#[test]
+fn heads_bound_what_proofs_may_claim() {
+    let mut log = log();
+    let params = log.params();
+    let old_head = log.head().unwrap();
+    let proof = log.append(b"deploy v3", 1_700_000_010).unwrap();
+    assert_eq!(proof.index, 3);
+    assert!(proof.verify(&params, &log.head().unwrap(), b"deploy v3"));
+    // Not in the log as an older head describes it
+    assert!(!proof.verify(&params, &old_head, b"deploy v3"));
+    // Heads cannot be altered, and time does not run backwards
+    let mut forged = old_head.clone();
+    forged.size = 10;
+    assert!(!forged.verify(&params));
+    assert_eq!(log.append(b"late", 1_700_000_009).err(), Some("Timestamp is earlier than the last entry"));
+
+    let mut used = BraavosAccumulator::new(64).unwrap();
+    used.add("alice").unwrap();
+    assert!(LogCommitment::new(used).is_err());
+}
@@ -0,0 +1,60 @@
+use braavos_accumulator::{
+    verify_checkpoint_chain, AllowAll, BraavosAccumulator, KeepLastEpochs, Manager, UpdateLog, WitnessHolder,
+};
+
+// Six epochs of single deletions, with holders at epochs 0, 3 and 5.
+fn churn() -> (Manager, Vec<WitnessHolder>) {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll);
+    let params = manager.accumulator().params();
+    let mut holders = Vec::new();
+    for epoch in 0..6u8 {
+        if epoch % 3 == 0 || epoch == 5 {
+            let w = manager.add("op", [b'h', epoch]).unwrap();
+            holders.push(WitnessHolder::new(&params, [b'h', epoch], w, epoch as u64).unwrap());
+        }
+        manager.add("op", [epoch]).unwrap();
+        manager.delete("op", [epoch]).unwrap();
+    }
+    (manager, holders)
+}
+
+#[test]
+fn compaction_merges_old_entries_into_one() {
+    let (mut manager, mut holders) = churn();
+    let mut log: UpdateLog = manager.update_log().clone();
+    assert_eq!(log.updates().len(), 6);
+    assert_eq!(log.compact_with(&KeepLastEpochs(2)).unwrap(), 3);
+    assert_eq!(log.updates().len(), 3);
+    assert_eq!((log.start_epoch(), log.compacted_until(), log.latest_epoch()), (0, 4, 6));
+    assert_eq!(log.updates()[0].deleted.len(), 4);
+    // Compacting again within the merged range changes nothing
+    assert_eq!(log.compact(3).unwrap(), 0);
+
+    // Holders at the start and after the merged range still sync
+    holders[0].sync(&log).unwrap();
+    holders[2].sync(&log).unwrap();
+    assert_eq!(holders[0].epoch(), 6);
+    assert_eq!(holders[2].epoch(), 6);
+    assert!(manager.verify(holders[0].element(), holders[0].witness()));
+    // The one in between has lost its epoch's boundary
+    assert!(!log.reaches(3));
+    assert_eq!(holders[1].sync(&log), Err("Epoch was compacted out of the log"));
+}
+
+#[test]
+fn manager_compaction_keeps_the_checkpoint_chain_provable() {
+    let (mut manager, _) = churn();
+    manager.publish_checkpoint().unwrap();
+    manager.add("op", "late").unwrap();
+    manager.delete("op", "late").unwrap();
+    manager.add("op", "later").unwrap();
+    manager.delete("op", "later").unwrap();
+    // The policy asks for everything; the checkpoint at epoch 6 caps it
+    assert_eq!(manager.compact_update_log(&|log: &UpdateLog| Some(log.latest_epoch())).unwrap(), 5);
+    assert_eq!(manager.update_log().compacted_until(), 6);
+    manager.publish_checkpoint().unwrap();
+    let params = manager.accumulator().params();
+    let (epoch, value) = verify_checkpoint_chain(&params, manager.checkpoint_chain()).unwrap();
+    assert_eq!((epoch, value), (8, manager.accumulator().value()));
+}
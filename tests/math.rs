@@ -0,0 +1,76 @@
+use braavos_accumulator::math::{ext_gcd, jacobi, shamir_trick};
+use braavos_accumulator::{BraavosAccumulator, WitnessValue};
+use crypto_bigint::modular::MontyParams;
+use crypto_bigint::{ConstChoice, Int, Odd, RandomBits, I512, U256, U512};
+use rand::rngs::OsRng;
+
+// s * a + t * b, computed wide enough not to overflow.
+fn combination(a: U256, b: U256, s: I512, t: I512) -> Int<16> {
+    let wide = |v: U256| Int::<16>::new_from_abs_sign(v.resize(), ConstChoice::FALSE).unwrap();
+    s.resize::<16>() * wide(a) + t.resize::<16>() * wide(b)
+}
+
+#[test]
+fn ext_gcd_small_values() {
+    let (g, s, t) = ext_gcd(U256::from(240u32), U256::from(46u32));
+    assert_eq!(g, U256::from(2u32));
+    assert_eq!((s, t), (I512::from(-9i32), I512::from(47i32)));
+
+    let (g, s, t) = ext_gcd(U256::from(17u32), U256::ZERO);
+    assert_eq!((g, s, t), (U256::from(17u32), I512::ONE, I512::ZERO));
+
+    let (g, _, _) = ext_gcd(U256::from(35u32), U256::from(64u32));
+    assert_eq!(g, U256::ONE);
+}
+
+#[test]
+fn ext_gcd_identity_holds_for_full_width_values() {
+    for _ in 0..100 {
+        let a = U256::random_bits(&mut OsRng, 256);
+        let b = U256::random_bits(&mut OsRng, 256);
+        let (g, s, t) = ext_gcd(a, b);
+        let g_wide = Int::<16>::new_from_abs_sign(g.resize(), ConstChoice::FALSE).unwrap();
+        assert_eq!(combination(a, b, s, t), g_wide);
+        assert_eq!(a.rem(&g.to_nz().unwrap()), U256::ZERO);
+        assert_eq!(b.rem(&g.to_nz().unwrap()), U256::ZERO);
+    }
+}
+
+#[test]
+fn shamir_trick_aggregates_witnesses() {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let w1 = acc.add(b"x1").unwrap();
+    let w2 = acc.add(b"x2").unwrap();
+    acc.add(b"x3").unwrap();
+    let (x1, x2) = (acc.element_prime(b"x1").unwrap(), acc.element_prime(b"x2").unwrap());
+    let params = MontyParams::new(Odd::new(acc.modulus()).unwrap());
+
+    let w12 = shamir_trick(&w1.value, &w2.value, &x1, &x2, &params).expect("Witnesses should aggregate");
+    // w12^(x1 x2) = value, so w12^x1 is a witness for x2 and vice versa
+    let w12_x1 = WitnessValue::new(*w12.pow_elem(&x1, &params).as_uint());
+    assert_eq!(w12_x1.pow_elem(&x2, &params), acc.value());
+
+    // Witnesses that open different values do not combine
+    acc.delete(b"x3").unwrap();
+    let w2_new = acc.recover_witness(b"x2").unwrap();
+    assert!(shamir_trick(&w1.value, &w2_new.value, &x1, &x2, &params).is_none());
+    // Nor does a prime with itself
+    assert!(shamir_trick(&w1.value, &w1.value, &x1, &x1, &params).is_none());
+}
+
+#[test]
+fn jacobi_matches_euler_criterion() {
+    // For an odd prime p, (a/p) = a^((p-1)/2) mod p, read as -1 when it is p - 1
+    for p in [3u64, 5, 7, 11, 13, 101, 103, 1009] {
+        for a in 0..2 * p {
+            let euler = (0..(p - 1) / 2).fold(1, |acc, _| acc * (a % p) % p);
+            let expected = if euler == p - 1 { -1 } else { euler as i8 };
+            assert_eq!(jacobi(&U512::from(a), &U512::from(p)), expected, "({a}/{p})");
+        }
+    }
+    // Multiplicative in the modulus: (a/15) = (a/3)(a/5)
+    for a in 0..30u64 {
+        let product = jacobi(&U512::from(a), &U512::from(3u64)) * jacobi(&U512::from(a), &U512::from(5u64));
+        assert_eq!(jacobi(&U512::from(a), &U512::from(15u64)), product);
+    }
+}
@@ -0,0 +1,49 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Decision, Manager, MembershipChange, Operation, Policy};
+
+fn changes<P: Policy>(manager: &Manager<P>, x: &str) -> Vec<(MembershipChange, u64)> {
+    manager.history(x).iter().map(|e| (e.change, e.epoch)).collect()
+}
+
+#[test]
+fn history_records_when_an_element_was_revoked() {
+    let acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let mut manager = Manager::new(acc, AllowAll);
+    manager.add("issuer", "alice").unwrap();
+    manager.add("issuer", "bob").unwrap();
+    manager.delete("issuer", "bob").unwrap();
+    manager.delete("issuer", "alice").unwrap();
+    manager.publish_checkpoint().unwrap();
+    manager.add("issuer", "alice").unwrap();
+    manager.delete("issuer", "alice").unwrap();
+
+    use MembershipChange::*;
+    assert_eq!(changes(&manager, "alice"), [(Added, 0), (Deleted, 2), (ReAdded, 2), (Deleted, 3)]);
+    let history = manager.history("alice");
+    assert_eq!((history[1].checkpoint, history[3].checkpoint), (Some(0), None));
+
+    let params = manager.accumulator().params();
+    let chain = manager.checkpoint_chain();
+    assert_eq!(history[1].check(&params, chain, "alice"), Ok(()));
+    assert!(history[1].check(&params, chain, "bob").is_err());
+    assert!(history[3].check(&params, chain, "alice").is_err());
+    assert!(history[0].check(&params, chain, "alice").is_err());
+}
+
+#[test]
+fn denied_and_queued_deletions_leave_no_events() {
+    let policy = |_: &str, op: Operation, x: &[u8]| match (op, x) {
+        (Operation::Delete, b"carol") => Decision::Deny("Protected".to_string()),
+        _ => Decision::Allow,
+    };
+    let acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let mut manager = Manager::new(acc, policy);
+    manager.add("issuer", "carol").unwrap();
+    manager.add("issuer", "erin").unwrap();
+    assert!(manager.delete("issuer", "carol").is_err());
+    manager.queue_delete("issuer", "erin").unwrap();
+
+    assert_eq!(changes(&manager, "carol"), [(MembershipChange::Added, 0)]);
+    assert_eq!(changes(&manager, "erin"), [(MembershipChange::Added, 0)]);
+    manager.commit_deletions().unwrap();
+    assert_eq!(changes(&manager, "erin"), [(MembershipChange::Added, 0), (MembershipChange::Deleted, 1)]);
+}
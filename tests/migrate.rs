@@ -0,0 +1,43 @@
+use braavos_accumulator::migrate::{self, STATE_VERSION};
+use braavos_accumulator::BraavosAccumulator;
+
+// Written by the unversioned layout: alice, bob and carol added, bob deleted.
+const STATE_V1: &[u8] = include_bytes!("fixtures/state_v1.bin");
+
+#[test]
+fn version_1_state_still_restores() {
+    assert_eq!(migrate::version(STATE_V1), Ok(1));
+    let state = migrate::upgrade(STATE_V1).unwrap();
+    assert_eq!(state.epoch, 1);
+    assert_eq!(state.deleted.len(), 1);
+    let members: Vec<&[u8]> = state.members.iter().map(|(x, _)| &x[..]).collect();
+    assert_eq!(members, [&b"alice"[..], b"carol"]);
+    assert!(state.secret_key.matches(&state.params) && state.params.verify_base());
+
+    let mut acc = BraavosAccumulator::builder().strict(true).restore(STATE_V1).unwrap();
+    assert_eq!(acc.params(), state.params);
+    assert!(acc.check_integrity().is_ok());
+    let w = acc.add("dave").unwrap();
+    assert!(acc.verify("dave", w));
+
+    // Persisting again writes the latest layout, which decodes to the same state
+    let upgraded = acc.to_state_bytes().unwrap();
+    assert_eq!(migrate::version(&upgraded), Ok(STATE_VERSION));
+    let mut restored = BraavosAccumulator::builder().restore(&upgraded).unwrap();
+    assert_eq!(restored.value(), acc.value());
+    assert!(restored.verify("dave", w));
+}
+
+#[test]
+fn latest_layout_round_trips_and_rejects_unknown_versions() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("alice").unwrap();
+    let state = migrate::upgrade(&acc.to_state_bytes().unwrap()).unwrap();
+    assert_eq!(state.to_bytes(), acc.to_state_bytes().unwrap());
+
+    let mut future = state.to_bytes();
+    let at = 4 + b"braavos-state".len();
+    future[at..at + 4].copy_from_slice(&(STATE_VERSION + 1).to_be_bytes());
+    assert_eq!(migrate::upgrade(&future).err(), Some("Unsupported state version"));
+    assert!(migrate::upgrade(b"not state").is_err());
+}
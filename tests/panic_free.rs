@@ -0,0 +1,80 @@
+use braavos_accumulator::{
+    AccValue, BraavosAccumulator, CardinalityProof, CountInclusionProof, EpochUpdate, FreshnessProof, PrimePool,
+    PublicParams, StaticBundle, Witness,
+};
+use crypto_bigint::{Odd, U256, U512};
+use crypto_primes::generate_safe_prime;
+use std::panic;
+use std::sync::Arc;
+
+type Decoder = fn(&[u8]) -> bool;
+
+// Every truncation and every single-byte corruption of `bytes`.
+fn mangled(bytes: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    let truncated = (0..bytes.len()).map(|len| bytes[..len].to_vec());
+    let flipped = (0..bytes.len()).map(|i| {
+        let mut out = bytes.to_vec();
+        out[i] ^= 0x5a;
+        out
+    });
+    truncated.chain(flipped)
+}
+
+#[test]
+fn hostile_inputs_are_errors_not_aborts() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).track_cardinality(true).build().unwrap();
+    acc.add("bob").unwrap();
+    let update = acc.delete("bob").unwrap();
+    let w = acc.add("alice").unwrap();
+    let params = acc.params();
+    let value = acc.value();
+    let fresh = acc.prove_membership_fresh("alice", w, b"nonce").unwrap();
+    let count = acc.cardinality_proof().unwrap();
+    let inclusion = acc.count_inclusion("alice").unwrap();
+    let bundle = acc.freeze().unwrap();
+
+    let decoders: Vec<(Vec<u8>, Decoder)> = vec![
+        (w.to_bytes(), |b| Witness::from_bytes(b).is_ok()),
+        (update.to_bytes(), |b| EpochUpdate::from_bytes(b).is_ok()),
+        (fresh.to_bytes(), |b| FreshnessProof::from_bytes(b).is_ok()),
+        (count.to_bytes(), |b| CardinalityProof::from_bytes(b).is_ok()),
+        (inclusion.to_bytes(), |b| CountInclusionProof::from_bytes(b).is_ok()),
+        (bundle.to_bytes(), |b| StaticBundle::from_bytes(b).is_ok()),
+        (params.to_der(), |b| PublicParams::from_der(b).is_ok()),
+        (w.to_der(), |b| Witness::from_der(b).is_ok()),
+    ];
+    for (bytes, decode) in &decoders {
+        for input in mangled(bytes) {
+            assert!(panic::catch_unwind(|| decode(&input)).is_ok(), "decoder panicked");
+        }
+    }
+    let state = acc.to_state_bytes().unwrap();
+    for len in (0..state.len()).step_by(7) {
+        assert!(BraavosAccumulator::builder().restore(&state[..len]).is_err());
+    }
+
+    // Verifiers handed parameters or values no honest party would produce
+    let mut even = params;
+    even.n = params.n.wrapping_add(&U512::ONE);
+    let zero = AccValue::new(U512::ZERO);
+    assert!(!fresh.verify(&even, &value, acc.epoch(), "alice", b"nonce"));
+    assert!(!fresh.verify(&params, &zero, acc.epoch(), "alice", b"nonce"));
+    assert!(!count.verify(&even, &value));
+    assert!(!acc.verify_at_epoch("alice", w, u64::MAX));
+}
+
+#[test]
+fn repeated_pool_primes_are_redrawn() {
+    // A persisted pool whose file repeats one prime would otherwise give p = q
+    let prime = generate_safe_prime::<U256>(64);
+    let path = std::env::temp_dir().join(format!("braavos-pool-{}", std::process::id()));
+    let bytes: Vec<u8> = prime.as_words().iter().flat_map(|w| w.to_le_bytes()).collect();
+    std::fs::write(&path, bytes.repeat(4)).unwrap();
+
+    let pool = Arc::new(PrimePool::spawn(64, 4, Some(path.clone())).unwrap());
+    let acc = BraavosAccumulator::builder().prime_bits(64).prime_pool(pool).build().unwrap();
+    let square = prime.resize::<8>().wrapping_mul(&prime.resize::<8>());
+    assert_ne!(acc.params().n, square);
+    assert!(bool::from(Odd::new(acc.params().n).is_some()));
+    let _ = std::fs::remove_file(path);
+}
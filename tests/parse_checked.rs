@@ -0,0 +1,34 @@
+use braavos_accumulator::{math, BraavosAccumulator, DecodingPolicy, Witness, WitnessValue};
+use crypto_bigint::U512;
+
+#[test]
+fn only_nontrivial_subgroup_elements_parse() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    let (params, n) = (acc.params(), acc.modulus());
+    assert_eq!(WitnessValue::parse_checked(&w.value.to_be_bytes(), &params), Ok(w.value));
+    assert!(WitnessValue::parse_checked(&w.value.to_be_bytes()[1..], &params).is_err());
+
+    let parse = |v: U512| WitnessValue::parse_checked(&v.to_be_bytes(), &params);
+    assert_eq!(parse(w.value.as_uint().wrapping_add(&n)), Err("Value is not reduced modulo n"));
+    assert_eq!(parse(U512::ZERO), Err("Value is not in the quadratic residue subgroup"));
+    let outside = (2u64..).map(U512::from_u64).find(|v| math::jacobi(v, &n) == -1).unwrap();
+    assert_eq!(parse(outside), Err("Value is not in the quadratic residue subgroup"));
+    assert_eq!(parse(U512::ONE), Err("Value is of small order"));
+    assert_eq!(parse(n.wrapping_sub(&U512::ONE)), Err("Value is of small order"));
+}
+
+#[test]
+fn strict_decoding_rejects_trivial_witnesses() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let mut w = acc.add("alice").unwrap();
+    w.value = WitnessValue::new(U512::ONE);
+    let bytes = w.to_bytes();
+
+    // Lenient decoding leaves the check to verification, as before
+    assert!(Witness::from_bytes(&bytes).is_ok());
+    let policy = DecodingPolicy::strict().for_params(&acc.params());
+    assert_eq!(Witness::from_bytes_with(&bytes, &policy), Err("Value is of small order"));
+    // The check needs the modulus
+    assert!(Witness::from_bytes_with(&bytes, &DecodingPolicy::strict()).is_ok());
+}
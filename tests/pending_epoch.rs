@@ -0,0 +1,41 @@
+use braavos_accumulator::{
+    AllowAll, BraavosAccumulator, Manager, PendingVerification, UpdateMessage, Witness, WitnessValue,
+};
+
+#[test]
+fn witnesses_for_the_staged_epoch_verify_provisionally() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let w_alice = manager.add("op", "alice").unwrap();
+    let w_bob = manager.add("op", "bob").unwrap();
+    assert!(manager.staged_update().unwrap().is_none());
+    manager.queue_delete("op", "bob").unwrap();
+
+    let staged = manager.staged_update().unwrap().unwrap().clone();
+    let prime = manager.accumulator().element_prime("alice").unwrap();
+    let next = staged.apply_to_witness(&prime, &w_alice).unwrap();
+    assert_eq!(manager.verify_with_pending("alice", w_alice), Ok(PendingVerification::Committed));
+    assert_eq!(manager.verify_with_pending("alice", next), Ok(PendingVerification::Provisional { epoch: 1 }));
+    assert!(!manager.verify("alice", next));
+
+    // Committing publishes exactly the staged update
+    assert_eq!(manager.commit_deletions().unwrap(), Some(staged));
+    assert_eq!(manager.verify_with_pending("alice", next), Ok(PendingVerification::Committed));
+    assert_eq!(manager.verify_with_pending("alice", w_alice), Ok(PendingVerification::Rejected));
+    assert_eq!(manager.verify_with_pending("bob", w_bob), Ok(PendingVerification::Rejected));
+}
+
+#[test]
+fn queued_deletions_are_not_provisional_members() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let w_bob = manager.add("op", "bob").unwrap();
+    manager.queue_delete("op", "bob").unwrap();
+    // Still a member until the commit
+    assert_eq!(manager.verify_with_pending("bob", w_bob), Ok(PendingVerification::Committed));
+    // The current value opens the staged one for bob, but is not accepted
+    let current = Witness {
+        params_digest: manager.accumulator().params().digest(),
+        value: WitnessValue::new(*manager.accumulator().value().as_uint()),
+    };
+    assert_eq!(manager.verify_with_pending("bob", current), Ok(PendingVerification::Rejected));
+    assert_eq!(manager.verify_with_pending("carol", w_bob), Ok(PendingVerification::Rejected));
+}
@@ -0,0 +1,82 @@
+use braavos_accumulator::{batch_digest, BraavosAccumulator, Decision, Manager, Operation, Policy, Quota, Service};
+
+// Operators may only touch elements under their own prefix ("hr/alice" for
+// "hr"); only "admin" may delete.
+struct PrefixAcl;
+
+impl Policy for PrefixAcl {
+    fn authorize(&self, operator: &str, operation: Operation, x: &[u8]) -> Decision {
+        if operation == Operation::Delete && operator != "admin" {
+            return Decision::Deny(format!("{operator} may not delete"));
+        }
+        match operator == "admin" || x.starts_with(format!("{operator}/").as_bytes()) {
+            true => Decision::Allow,
+            false => Decision::Deny(format!("{operator} may not touch this element")),
+        }
+    }
+}
+
+fn manager() -> Manager<PrefixAcl> {
+    Manager::new(BraavosAccumulator::new(64).unwrap(), PrefixAcl)
+}
+
+#[test]
+fn the_policy_rules_on_each_operation() {
+    let mut manager = manager();
+    manager.add("hr", "hr/alice").unwrap();
+    assert_eq!(manager.add("hr", "it/bob").err(), Some("Operation denied by policy"));
+    assert_eq!(manager.delete("hr", "hr/alice").err(), Some("Operation denied by policy"));
+    assert!(manager.accumulator().contains("hr/alice") && !manager.accumulator().contains("it/bob"));
+    manager.delete("admin", "hr/alice").unwrap();
+    assert!(!manager.accumulator().contains("hr/alice"));
+}
+
+#[test]
+fn every_decision_is_audited() {
+    let mut manager = manager();
+    manager.add("hr", "hr/alice").unwrap();
+    manager.delete("hr", "hr/alice").unwrap_err();
+    manager.delete("admin", "hr/alice").unwrap();
+
+    let records = manager.audit_log().records();
+    let seen: Vec<_> = records.iter().map(|r| (r.seq, r.epoch, r.operator.as_str(), r.operation)).collect();
+    assert_eq!(
+        seen,
+        [(0, 0, "hr", Operation::Add), (1, 0, "hr", Operation::Delete), (2, 0, "admin", Operation::Delete)]
+    );
+    assert!(records.iter().all(|r| r.element == b"hr/alice"));
+    assert_eq!(records[0].decision, Decision::Allow);
+    assert_eq!(records[1].decision, Decision::Deny("hr may not delete".into()));
+    assert_eq!(records[2].decision, Decision::Allow);
+}
+
+#[test]
+fn a_batch_is_refused_whole_and_audited_once() {
+    let mut manager = manager();
+    let batch = ["hr/alice", "it/bob", "hr/carol"];
+    assert_eq!(manager.issue_witnesses("hr", &batch).err(), Some("Operation denied by policy"));
+    assert_eq!(manager.accumulator().member_count(), 0);
+    manager.issue_witnesses("hr", &["hr/alice", "hr/carol"]).unwrap();
+
+    let records = manager.audit_log().records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].operation, Operation::IssueWitnesses);
+    assert_eq!(records[0].element, batch_digest(&batch));
+    assert_eq!(records[0].metadata, 3u64.to_be_bytes());
+    assert_eq!(records[0].decision, Decision::Deny("hr may not touch this element".into()));
+    assert_eq!(records[1].decision, Decision::Allow);
+}
+
+#[test]
+fn tenants_enforce_their_own_policy() {
+    let root = std::env::temp_dir().join(format!("braavos-policy-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut service = Service::<PrefixAcl>::new(&root).unwrap();
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    service.open_tenant("issuer", b"token", Quota::default(), PrefixAcl, builder).unwrap();
+    let mut session = service.session("issuer", b"token").unwrap();
+    session.add("hr", "hr/alice").unwrap();
+    assert_eq!(session.delete("hr", "hr/alice").err(), Some("Operation denied by policy"));
+    assert_eq!(session.manager().audit_log().len(), 2);
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,43 @@
+use braavos_accumulator::BraavosAccumulator;
+
+// The same accumulator twice, one with a 256 KiB fixed-base table.
+fn pair() -> (BraavosAccumulator, BraavosAccumulator) {
+    let mut plain = BraavosAccumulator::new(64).unwrap();
+    plain.add("alice").unwrap();
+    let state = plain.to_state_bytes().unwrap();
+    let fast = BraavosAccumulator::builder().precompute(256 << 10).restore(&state).unwrap();
+    (plain, fast)
+}
+
+#[test]
+fn the_table_stays_within_its_budget() {
+    let (plain, fast) = pair();
+    assert_eq!(plain.precomputed_bytes(), 0);
+    assert!(fast.precomputed_bytes() > 0 && fast.precomputed_bytes() <= 256 << 10);
+    assert_eq!(fast.report().precomputed_bytes, fast.precomputed_bytes());
+
+    let small = BraavosAccumulator::builder().prime_bits(64).precompute(16 << 10).build().unwrap();
+    assert!(small.precomputed_bytes() > 0 && small.precomputed_bytes() <= 16 << 10);
+    let tiny = BraavosAccumulator::builder().prime_bits(64).precompute((16 << 10) - 1).build().unwrap();
+    assert_eq!(tiny.precomputed_bytes(), 0);
+}
+
+#[test]
+fn precomputed_witnesses_match_across_epochs() {
+    let (mut plain, mut fast) = pair();
+    let batch = ["bob", "carol", "dave", "erin"];
+    assert_eq!(fast.issue_witnesses(&batch).unwrap(), plain.issue_witnesses(&batch).unwrap());
+    for x in ["bob", "dave"] {
+        let bytes = fast.precomputed_bytes();
+        assert_eq!(fast.delete(x).unwrap(), plain.delete(x).unwrap());
+        // Rebuilt for the new value, at the same size
+        assert_eq!(fast.precomputed_bytes(), bytes);
+        for y in ["alice", "carol", "erin"] {
+            let w = fast.recover_witness(y).unwrap();
+            assert_eq!(w, plain.recover_witness(y).unwrap());
+            assert!(fast.verify(y, w));
+        }
+        let new = format!("{x}-replacement");
+        assert_eq!(fast.add(&new).unwrap(), plain.add(&new).unwrap());
+    }
+}
@@ -0,0 +1,24 @@
+use braavos_accumulator::{BraavosAccumulator, HashAlgorithm, Preset};
+
+#[test]
+fn demo_preset_pins_its_parameters() {
+    let acc = BraavosAccumulator::builder().preset(Preset::Demo).build().unwrap();
+    let params = acc.params();
+    assert_eq!(Preset::Demo.check_params(&params), Ok(()));
+    assert_eq!(params.hash, HashAlgorithm::Sha256);
+    assert!(Preset::Rsa2048Sha256P128.check_params(&params).is_err());
+
+    // Restoring under a preset checks the state matches it
+    let state = acc.to_state_bytes().unwrap();
+    assert!(BraavosAccumulator::builder().preset(Preset::Demo).restore(&state).is_ok());
+    assert!(BraavosAccumulator::builder().preset(Preset::Rsa2048Sha256P128).restore(&state).is_err());
+}
+
+#[test]
+fn presets_beyond_this_build_refuse_to_build() {
+    let preset = Preset::Rsa2048Sha256P128;
+    assert_eq!(preset.min_security_bits(), 112);
+    assert_eq!(preset.to_string(), "rsa2048-sha256-p128");
+    assert!(preset.check_supported().is_err());
+    assert!(BraavosAccumulator::builder().preset(preset).build().is_err());
+}
@@ -0,0 +1,20 @@
+use braavos_accumulator::{BraavosAccumulator, PrimePool};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn accumulators_can_take_their_primes_from_a_pool() {
+    let pool = Arc::new(PrimePool::spawn(64, 4, None).unwrap());
+    while pool.available() < 2 {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let mut pooled = BraavosAccumulator::builder().prime_bits(64).prime_pool(Arc::clone(&pool)).build().unwrap();
+    let w = pooled.add("alice").unwrap();
+    pooled.add("bob").unwrap();
+    pooled.delete("bob").unwrap();
+    let w = pooled.update_witness_on_deletion("alice", w, "bob").unwrap();
+    assert!(pooled.verify("alice", w));
+
+    let other = BraavosAccumulator::builder().prime_bits(64).prime_pool(pool).build().unwrap();
+    assert_ne!(other.modulus(), pooled.modulus());
+}
@@ -0,0 +1,40 @@
+use braavos_accumulator::{BraavosAccumulator, PrivateEpochUpdate, UpdateMessage};
+
+#[test]
+fn witnesses_follow_the_product_of_deleted_primes() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    for x in ["bob", "carol", "dave"] {
+        acc.add(x).unwrap();
+    }
+    let first = acc.delete_batch(&["bob", "carol"]).unwrap();
+    let second = acc.delete("dave").unwrap();
+
+    // Merging the private updates multiplies their products
+    let merged = first.to_private().merge(&second.to_private()).unwrap();
+    assert_eq!(merged, first.merge(&second).unwrap().to_private());
+    let relayed = PrivateEpochUpdate::from_bytes(&merged.to_bytes()).unwrap();
+    let alice = acc.element_prime("alice").unwrap();
+    let updated = relayed.apply_to_witness(&alice, &w).unwrap();
+    assert!(acc.verify("alice", updated));
+
+    // The encoding carries no individual deleted prime
+    let bytes = relayed.to_bytes();
+    let bob = acc.element_prime("bob").unwrap().to_be_bytes();
+    assert!(!bytes.windows(bob.len()).any(|window| window == bob));
+}
+
+#[test]
+fn deleted_elements_cannot_update() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w_bob = acc.add("bob").unwrap();
+    acc.add("carol").unwrap();
+    let update = acc.delete("bob").unwrap().to_private();
+    let bob = acc.element_prime("bob").unwrap();
+    assert_eq!(update.apply_to_witness(&bob, &w_bob).err(), Some("Element was deleted in this update"));
+
+    let mut truncated = update.to_bytes();
+    truncated.pop();
+    assert!(PrivateEpochUpdate::from_bytes(&truncated).is_err());
+    assert!(update.merge(&update).is_err());
+}
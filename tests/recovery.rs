@@ -0,0 +1,88 @@
+use braavos_accumulator::{
+    AllowAll, BraavosAccumulator, Decision, Manager, Operation, RateLimit, Witness, WitnessEncryptor,
+};
+use std::time::Duration;
+
+// Stands in for sealing to a holder's public key: XOR with a key derived from
+// the element, which is its own inverse. Refuses holders it has no key for.
+struct XorEncryptor;
+
+impl XorEncryptor {
+    fn apply(element: &[u8], bytes: &[u8]) -> Vec<u8> {
+        bytes.iter().enumerate().map(|(i, b)| b ^ element[i % element.len()]).collect()
+    }
+}
+
+impl WitnessEncryptor for XorEncryptor {
+    fn encrypt_for(&self, element: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, &'static str> {
+        match element.starts_with(b"unknown") {
+            true => Err("No key for holder"),
+            false => Ok(Self::apply(element, plaintext)),
+        }
+    }
+}
+
+fn accumulator() -> BraavosAccumulator {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    for x in ["alice", "bob", "carol"] {
+        acc.add(x).unwrap();
+    }
+    acc.delete("bob").unwrap();
+    acc
+}
+
+#[test]
+fn lost_witnesses_are_recovered_for_members_only() {
+    let mut acc = accumulator();
+    let w = acc.recover_witness("alice").unwrap();
+    assert!(acc.verify("alice", w));
+    assert_eq!(acc.recover_witness("bob").err(), Some("Element is not a member"));
+    assert_eq!(acc.recover_witness("dave").err(), Some("Element is not a member"));
+}
+
+#[test]
+fn recovery_is_rate_limited_per_operator() {
+    let limit = RateLimit {
+        max: 2,
+        window: Duration::from_secs(3600),
+    };
+    let mut manager = Manager::new(accumulator(), AllowAll).with_recovery_limit(limit);
+    manager.recover_witness("support", "alice").unwrap();
+    manager.recover_witness("support", "carol").unwrap();
+    assert_eq!(manager.recover_witness("support", "alice").err(), Some("Recovery rate limit exceeded"));
+    manager.recover_witness("desk", "alice").unwrap();
+
+    let records = manager.audit_log().records();
+    assert!(records.iter().all(|r| r.operation == Operation::RecoverWitness));
+    assert_eq!(records[2].decision, Decision::Deny("Recovery rate limit exceeded".into()));
+    assert_eq!(records[3].decision, Decision::Allow);
+}
+
+#[test]
+fn recovery_goes_through_the_policy() {
+    let no_recovery = |_: &str, operation: Operation, _: &[u8]| match operation {
+        Operation::RecoverWitness => Decision::Deny("Recovery is disabled".into()),
+        _ => Decision::Allow,
+    };
+    let mut manager = Manager::new(accumulator(), no_recovery);
+    assert_eq!(manager.recover_witness("support", "alice").err(), Some("Operation denied by policy"));
+}
+
+#[test]
+fn exported_witnesses_open_for_their_holders() {
+    let mut manager = Manager::new(accumulator(), AllowAll);
+    let exported = manager.export_witnesses_encrypted("support", &XorEncryptor).unwrap();
+    let elements: Vec<_> = exported.iter().map(|e| e.element.as_slice()).collect();
+    assert_eq!(elements, [&b"alice"[..], b"carol"]);
+
+    for entry in exported {
+        assert_eq!(entry.epoch, manager.accumulator().epoch());
+        let witness = Witness::from_bytes(&XorEncryptor::apply(&entry.element, &entry.ciphertext)).unwrap();
+        assert!(manager.verify(&entry.element, witness));
+    }
+    let record = manager.audit_log().records().last().unwrap();
+    assert_eq!((record.operation, &record.decision), (Operation::ExportWitnesses, &Decision::Allow));
+
+    manager.add("support", "unknown-holder").unwrap();
+    assert_eq!(manager.export_witnesses_encrypted("support", &XorEncryptor).err(), Some("No key for holder"));
+}
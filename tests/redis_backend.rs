@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use braavos_accumulator::{BraavosAccumulator, RedisBackend, StateBackend};
+
+// Answers GET, SET, RPUSH, LRANGE and AUTH for one connection, enough of
+// Redis for the backend; the password is "hunter2".
+fn fake_redis() -> std::net::SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut r = BufReader::new(stream.try_clone().unwrap());
+        let mut w = stream;
+        let mut strings: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+        let mut lists: HashMap<Vec<u8>, Vec<Vec<u8>>> = HashMap::new();
+        let bulk = |v: &[u8]| [format!("${}\r\n", v.len()).as_bytes(), v, b"\r\n"].concat();
+        loop {
+            let mut line = String::new();
+            if r.read_line(&mut line).unwrap() == 0 {
+                return;
+            }
+            let n: usize = line.trim()[1..].parse().unwrap();
+            let mut args = Vec::new();
+            for _ in 0..n {
+                line.clear();
+                r.read_line(&mut line).unwrap();
+                let mut arg = vec![0; line.trim()[1..].parse::<usize>().unwrap() + 2];
+                r.read_exact(&mut arg).unwrap();
+                arg.truncate(arg.len() - 2);
+                args.push(arg);
+            }
+            let reply = match args[0].as_slice() {
+                b"AUTH" if args[1] == b"hunter2" => b"+OK\r\n".to_vec(),
+                b"AUTH" => b"-WRONGPASS invalid password\r\n".to_vec(),
+                b"SET" => {
+                    strings.insert(args[1].clone(), args[2].clone());
+                    b"+OK\r\n".to_vec()
+                }
+                b"GET" => strings.get(&args[1]).map_or(b"$-1\r\n".to_vec(), |v| bulk(v)),
+                b"RPUSH" => {
+                    let list = lists.entry(args[1].clone()).or_default();
+                    list.push(args[2].clone());
+                    format!(":{}\r\n", list.len()).into_bytes()
+                }
+                b"LRANGE" => {
+                    let list = lists.get(&args[1]).cloned().unwrap_or_default();
+                    let mut out = format!("*{}\r\n", list.len()).into_bytes();
+                    list.iter().for_each(|v| out.extend(bulk(v)));
+                    out
+                }
+                _ => b"-ERR unknown command\r\n".to_vec(),
+            };
+            w.write_all(&reply).unwrap();
+        }
+    });
+    addr
+}
+
+#[test]
+fn state_and_updates_round_trip_through_redis() {
+    let mut backend = RedisBackend::connect(fake_redis(), "acc").unwrap();
+    backend.auth("hunter2").unwrap();
+    assert_eq!(backend.load_state(), Ok(None));
+
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("alice").unwrap();
+    for x in ["bob", "carol"] {
+        acc.add(x).unwrap();
+        backend.append_update(&acc.delete(x).unwrap()).unwrap();
+    }
+    acc.save_to(&mut backend).unwrap();
+
+    let restored = BraavosAccumulator::builder().load_from(&backend).unwrap();
+    assert_eq!((restored.epoch(), restored.value()), (2, acc.value()));
+    let updates = backend.read_updates(1, u64::MAX).unwrap();
+    assert_eq!(updates.iter().map(|u| (u.from_epoch, u.to_epoch)).collect::<Vec<_>>(), [(1, 2)]);
+}
+
+#[test]
+fn error_replies_are_surfaced() {
+    let backend = RedisBackend::connect(fake_redis(), "acc").unwrap();
+    assert_eq!(backend.auth("guess"), Err("Redis returned an error"));
+    assert_eq!(backend.auth("hunter2"), Ok(()));
+}
+
+#[test]
+fn a_reply_that_fails_to_read_closes_the_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 64];
+        let _ = stream.read(&mut request).unwrap();
+        // Claims a 1 TiB value, then sends a reply the next command would misread
+        stream.write_all(b"$1099511627776\r\n+OK\r\n").unwrap();
+        let _ = stream.read(&mut request);
+    });
+    let backend = RedisBackend::connect(addr, "acc").unwrap();
+    assert_eq!(backend.load_state(), Err("Reply from Redis is too large"));
+    assert_eq!(backend.auth("hunter2"), Err("Failed to write to Redis"));
+}
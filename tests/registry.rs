@@ -0,0 +1,64 @@
+use braavos_accumulator::{core, BraavosAccumulator, ElementRegistry, HashAlgorithm};
+use crypto_bigint::{modular::MontyParams, Odd};
+use std::sync::Arc;
+
+#[test]
+fn accumulators_share_primes_through_the_registry() {
+    let registry = Arc::new(ElementRegistry::new(HashAlgorithm::Sha256));
+    let builder = BraavosAccumulator::builder().prime_bits(64).element_registry(registry.clone());
+    let mut allow = builder.clone().build().unwrap();
+    let mut deny = builder.build().unwrap();
+    assert!(registry.matches(&allow.params()) && registry.matches(&deny.params()));
+
+    let w = allow.add(b"alice").unwrap();
+    deny.add(b"alice").unwrap();
+    deny.add(b"mallory").unwrap();
+    assert_eq!(registry.len(), 2);
+    assert_eq!(allow.element_prime(b"alice"), deny.element_prime(b"alice"));
+
+    // A verifier reuses the same primes
+    let prime = registry.prime(b"alice").expect("Failed to derive prime");
+    let n = MontyParams::new(Odd::new(allow.modulus()).unwrap());
+    assert!(core::opens(&w.value, &prime, &allow.value(), &n));
+    assert_eq!(registry.len(), 2);
+
+    // State restores only with the registry it was built with
+    let state = allow.to_state_bytes().unwrap();
+    let builder = BraavosAccumulator::builder().element_registry(registry.clone());
+    assert!(builder.restore(&state).is_ok());
+    let other = Arc::new(ElementRegistry::new(HashAlgorithm::Sha256));
+    assert!(BraavosAccumulator::builder().element_registry(other).restore(&state).is_err());
+}
+
+#[test]
+fn imported_entries_are_certified() {
+    let source = ElementRegistry::new(HashAlgorithm::Sha256);
+    let acc = BraavosAccumulator::builder().prime_bits(64).build().unwrap();
+    let target = ElementRegistry::for_params(&acc.params());
+    assert!(target.matches(&acc.params()) && target.is_empty());
+
+    let shared = ElementRegistry::for_params(&acc.params());
+    for i in 0..8u8 {
+        shared.prime([i]).expect("Failed to derive prime");
+    }
+    for i in 0..8u8 {
+        let entry = shared.entry([i]).unwrap();
+        assert!(entry.check(HashAlgorithm::Sha256, &acc.params().prf_key, [i]));
+        assert_eq!(entry.prime, acc.params().hash_to_prime([i]));
+        target.import([i], entry).unwrap();
+    }
+    assert_eq!(target.len(), 8);
+
+    // Wrong element, wrong key, skipped candidates and padded certificates all fail
+    let entry = shared.entry([0]).unwrap();
+    assert!(target.import([9], entry.clone()).is_err());
+    assert!(source.import([0], entry.clone()).is_err());
+    let (i, mut skipped) = (0..8u8).map(|i| (i, shared.entry([i]).unwrap())).find(|(_, e)| e.counter > 0).unwrap();
+    skipped.counter -= 1;
+    assert!(!skipped.check(HashAlgorithm::Sha256, &acc.params().prf_key, [i]));
+    let mut padded = entry;
+    padded.certificate.push(2);
+    assert!(target.import([0], padded).is_err());
+    target.clear();
+    assert!(target.is_empty());
+}
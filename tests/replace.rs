@@ -0,0 +1,43 @@
+use braavos_accumulator::{BraavosAccumulator, ReplaceProof, UpdateMessage};
+
+#[test]
+fn holders_follow_a_replacement_with_one_update() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w_alice = acc.add("alice").unwrap();
+    acc.add("old-device").unwrap();
+    let params = acc.params();
+
+    let replacement = acc.replace("old-device", "new-device").unwrap();
+    replacement.verify(&params).unwrap();
+    assert_eq!((replacement.update.from_epoch, replacement.update.to_epoch), (0, 1));
+    assert!(!acc.contains("old-device") && acc.contains("new-device"));
+    assert!(acc.verify("new-device", replacement.witness));
+
+    let w_alice = replacement.update.apply_to_witness(&params.hash_to_prime("alice"), &w_alice).unwrap();
+    assert!(acc.verify("alice", w_alice));
+
+    let proof = ReplaceProof::from_bytes(&replacement.proof.to_bytes()).unwrap();
+    assert_eq!(proof, replacement.proof);
+    assert!(proof.is_for(&params, "old-device", "new-device"));
+    assert!(!proof.is_for(&params, "new-device", "old-device"));
+}
+
+#[test]
+fn failed_replacements_change_nothing_and_proofs_bind_their_update() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("a").unwrap();
+    acc.add("b").unwrap();
+    assert!(acc.replace("missing", "c").is_err());
+    assert!(acc.replace("a", "a").is_err());
+    assert_eq!(acc.epoch(), 0);
+    assert!(acc.contains("a") && !acc.contains("c"));
+
+    let params = acc.params();
+    let replacement = acc.replace("a", "c").unwrap();
+    let other = acc.delete("b").unwrap();
+    assert!(replacement.proof.verify(&params, &replacement.update).is_ok());
+    assert!(replacement.proof.verify(&params, &other).is_err());
+    let mut forged = replacement.proof.clone();
+    forged.added = params.hash_to_prime("d");
+    assert!(forged.verify(&params, &replacement.update).is_err());
+}
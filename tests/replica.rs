@@ -0,0 +1,61 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Manager, ReadReplica, VerificationCache};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn manager() -> Manager {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll);
+    for x in ["alice", "bob", "carol"] {
+        manager.add("op", x).unwrap();
+    }
+    manager.delete("op", "bob").unwrap();
+    manager.publish_checkpoint().unwrap();
+    manager
+}
+
+#[test]
+fn replicas_serve_only_once_warmed() {
+    let manager = manager();
+    let acc = manager.accumulator();
+    let cache = Arc::new(VerificationCache::new(100, Duration::from_secs(60)));
+    let mut replica = ReadReplica::new(acc.params(), manager.checkpoint_chain().to_vec(), cache.clone());
+    let signal = replica.ready_signal();
+    let w = acc.recover_witness("alice").unwrap();
+    assert!(!signal.is_ready() && replica.current().is_none());
+    assert_eq!(replica.verify("alice", &w), Err("Replica is not warmed up"));
+
+    replica.warm_up(&[("alice", w)]).unwrap();
+    assert!(signal.is_ready() && replica.ready());
+    assert_eq!(replica.current(), Some((acc.epoch(), acc.value())));
+    assert_eq!(cache.misses(), 1);
+    assert_eq!(replica.verify("alice", &w), Ok(true));
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(replica.verify("carol", &w), Ok(false));
+}
+
+#[test]
+fn replicas_follow_checkpoints_and_reject_forged_chains() {
+    let mut manager = manager();
+    let cache = Arc::new(VerificationCache::new(100, Duration::from_secs(60)));
+    let params = manager.accumulator().params();
+    let mut forged = manager.checkpoint_chain().to_vec();
+    forged[0].value = params.base;
+    let mut replica = ReadReplica::new(params, forged, cache.clone());
+    assert!(replica.warm_up::<&str>(&[]).is_err());
+    assert!(!replica.ready());
+
+    let mut replica = ReadReplica::new(params, manager.checkpoint_chain().to_vec(), cache);
+    replica.warm_up::<&str>(&[]).unwrap();
+    let w = manager.accumulator().recover_witness("alice").unwrap();
+    manager.delete("op", "carol").unwrap();
+    let checkpoint = manager.publish_checkpoint().unwrap().clone();
+    let mut tampered = checkpoint.clone();
+    tampered.deleted.clear();
+    assert!(replica.advance(tampered).is_err());
+    replica.advance(checkpoint).unwrap();
+    assert!(replica.ready());
+    assert_eq!(replica.current().map(|(epoch, _)| epoch), Some(manager.accumulator().epoch()));
+    assert_eq!(replica.verify("alice", &w), Ok(false));
+    let w = manager.accumulator().recover_witness("alice").unwrap();
+    assert_eq!(replica.verify("alice", &w), Ok(true));
+}
@@ -0,0 +1,51 @@
+use braavos_accumulator::{BraavosAccumulator, VerificationCache, REPORTED_OPERATIONS};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn report_summarizes_without_secrets_or_elements() {
+    let cache = Arc::new(VerificationCache::new(8, Duration::from_secs(60)));
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).verification_cache(cache).build().unwrap();
+    let w = acc.add("alice@example.com").unwrap();
+    for i in 0..20u8 {
+        acc.add([i]).unwrap();
+        acc.delete([i]).unwrap();
+    }
+    assert!(!acc.verify("alice@example.com", w));
+
+    let report = acc.report();
+    assert_eq!(report.modulus_bits, acc.modulus().bits());
+    assert_eq!(report.params_digest, acc.params().digest());
+    assert_eq!((report.epoch, report.member_count, report.deleted_count), (20, 1, 20));
+    assert_eq!(report.verification_cache_entries, Some(1));
+    assert_eq!(report.recent_deletions.len(), REPORTED_OPERATIONS);
+    assert_eq!(report.recent_deletions.last(), acc.element_prime([19u8]).as_ref());
+    assert!(report.recent_operations.is_empty());
+
+    let json = report.to_json();
+    assert!(json.starts_with("{\"modulus_bits\":") && json.ends_with("\"recent_operations\":[]}"));
+    assert!(json.contains("\"member_count\":1,") && json.contains("\"verification_cache_entries\":1,"));
+    assert!(!json.contains("alice"));
+}
+
+#[test]
+#[cfg(feature = "service")]
+fn manager_reports_its_last_operations() {
+    use braavos_accumulator::{Decision, Manager, Operation};
+
+    let no_deletes = |_: &str, operation: Operation, _: &[u8]| match operation {
+        Operation::Delete => Decision::Deny("Deletes are frozen".into()),
+        _ => Decision::Allow,
+    };
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, no_deletes);
+    manager.add("ops \"on call\"", "alice").unwrap();
+    assert!(manager.delete("ops \"on call\"", "alice").is_err());
+
+    let report = manager.report();
+    let operations: Vec<_> = report.recent_operations.iter().map(|op| (op.operation.as_str(), op.allowed)).collect();
+    assert_eq!(operations, [("Add", true), ("Delete", false)]);
+    let json = report.to_json();
+    assert!(json.contains(r#"{"seq":1,"epoch":0,"operator":"ops \"on call\"","operation":"Delete","allowed":false}"#));
+    assert!(!json.contains("alice") && !json.contains("frozen"));
+}
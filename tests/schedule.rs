@@ -0,0 +1,68 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, EpochScheduler, ManualClock, Quota, Service, WitnessHolder};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn service(name: &str, scheduler: EpochScheduler, clock: Arc<ManualClock>) -> (Service, std::path::PathBuf) {
+    let root = std::env::temp_dir().join(format!("braavos-schedule-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut service = Service::new(&root).unwrap().with_scheduler(scheduler).with_clock(clock);
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder).unwrap();
+    (service, root)
+}
+
+#[test]
+fn deletions_commit_on_interval_or_count() {
+    let clock = Arc::new(ManualClock::new());
+    let scheduler = EpochScheduler::every(Duration::from_secs(60)).or_after_operations(3);
+    let (mut service, root) = service("cadence", scheduler, clock.clone());
+    let mut session = service.session("issuer", b"token").unwrap();
+    let w = session.add("op", "keep").unwrap();
+    for i in 0..6u8 {
+        session.add("op", [i]).unwrap();
+    }
+
+    assert_eq!(session.schedule_delete("op", [0]), Ok(None));
+    assert_eq!(session.schedule_delete("op", [1]), Ok(None));
+    let update = session.schedule_delete("op", [2]).unwrap().expect("Count reached");
+    assert_eq!((update.from_epoch, update.to_epoch, update.deleted.len()), (0, 1, 3));
+
+    assert_eq!(session.schedule_delete("op", [3]), Ok(None));
+    drop(session);
+    assert!(service.tick().unwrap().is_empty());
+    clock.advance(Duration::from_secs(60));
+    let published = service.tick().unwrap();
+    assert_eq!(published.len(), 1);
+    assert_eq!((published[0].1.to_epoch, published[0].1.deleted.len()), (2, 1));
+
+    let mut session = service.session("issuer", b"token").unwrap();
+    let params = session.manager().accumulator().params();
+    let mut holder = WitnessHolder::new(&params, "keep", w, 0).unwrap();
+    holder.sync(session.manager().update_log()).unwrap();
+    assert!(session.verify("keep", holder.witness()).unwrap());
+    assert!(!session.manager().accumulator().contains([3]));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn queued_deletions_survive_a_restart() {
+    let clock = Arc::new(ManualClock::new());
+    let (mut service, root) = service("restart", EpochScheduler::after_operations(10), clock.clone());
+    let mut session = service.session("issuer", b"token").unwrap();
+    session.add("op", "alice").unwrap();
+    session.add("op", "bob").unwrap();
+    assert_eq!(session.schedule_delete("op", "bob"), Ok(None));
+    assert!(session.schedule_delete("op", "bob").is_err());
+    drop(service);
+
+    let mut service = Service::new(&root).unwrap().with_clock(clock);
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder).unwrap();
+    let mut session = service.session("issuer", b"token").unwrap();
+    assert_eq!(session.manager().pending_deletions(), [b"bob".to_vec()]);
+    let update = session.commit().unwrap().expect("Deletion was queued");
+    assert_eq!(update.to_epoch, 1);
+    assert!(!session.manager().accumulator().contains("bob") && session.manager().pending_deletions().is_empty());
+    assert_eq!(session.commit(), Ok(None));
+    std::fs::remove_dir_all(&root).unwrap();
+}
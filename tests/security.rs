@@ -0,0 +1,27 @@
+use braavos_accumulator::{BraavosAccumulator, SecurityEstimate};
+
+#[test]
+fn estimates_follow_modulus_size() {
+    let rsa2048 = SecurityEstimate::for_modulus_bits(2048);
+    assert_eq!((rsa2048.factoring_bits, rsa2048.collision_bits, rsa2048.bits), (112, 128, 112));
+    let rsa1024 = SecurityEstimate::for_modulus_bits(1024);
+    assert!((78..=82).contains(&rsa1024.bits));
+    assert_eq!(SecurityEstimate::for_modulus_bits(15360).bits, 128);
+
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let estimate = SecurityEstimate::for_params(&acc.params());
+    assert!(estimate.modulus_bits <= 128 && estimate.bits < 40);
+    assert_eq!(BraavosAccumulator::builder().prime_bits(64).security_estimate().modulus_bits, 128);
+}
+
+#[test]
+fn builder_enforces_the_minimum() {
+    let weak = BraavosAccumulator::builder().prime_bits(64).min_security_bits(80);
+    assert_eq!(weak.clone().build().err(), Some("Configuration is below the minimum security level"));
+
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let state = acc.to_state_bytes().unwrap();
+    assert!(weak.restore(&state).is_err());
+    let floor = SecurityEstimate::for_params(&acc.params()).bits;
+    assert!(BraavosAccumulator::builder().min_security_bits(floor).restore(&state).is_ok());
+}
@@ -0,0 +1,35 @@
+use braavos_accumulator::{AccValue, BraavosAccumulator};
+
+#[test]
+fn base_is_derived_from_the_seed() {
+    let seed = [7u8; 32];
+    let acc = BraavosAccumulator::builder().prime_bits(64).base_seed(seed).build().unwrap();
+    let params = acc.params();
+    assert_eq!(params.seed, Some(seed));
+    assert!(params.verify_base());
+
+    let mut other_seed = params;
+    other_seed.seed = Some([8u8; 32]);
+    assert!(!other_seed.verify_base());
+    let mut chosen_base = params;
+    chosen_base.base = AccValue::new(params.base.as_uint().wrapping_add(&1u32.into()));
+    assert!(!chosen_base.verify_base());
+
+    // The seed survives persistence and is part of the digest
+    let restored = BraavosAccumulator::builder().restore(&acc.to_state_bytes().unwrap()).unwrap();
+    assert_eq!(restored.params(), params);
+    assert_ne!(other_seed.digest(), params.digest());
+}
+
+#[test]
+#[cfg(feature = "zk")]
+fn rerandomization_drops_the_seed() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    assert!(acc.params().verify_base());
+    acc.rerandomize().unwrap();
+    let params = acc.params();
+    assert_eq!(params.seed, None);
+    assert!(!params.verify_base());
+    let restored = BraavosAccumulator::builder().restore(&acc.to_state_bytes().unwrap()).unwrap();
+    assert_eq!(restored.params().digest(), params.digest());
+}
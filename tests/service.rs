@@ -0,0 +1,125 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, ManualClock, Quota, RateLimit, Service};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn service(name: &str, clock: Arc<ManualClock>, quota: Quota) -> (Service, PathBuf) {
+    let root = std::env::temp_dir().join(format!("braavos-service-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut service = Service::new(&root).unwrap().with_clock(clock);
+    for (tenant, token) in [("issuer-a", b"token-a"), ("issuer-b", b"token-b")] {
+        let builder = BraavosAccumulator::builder().prime_bits(64);
+        service.open_tenant(tenant, token, quota, AllowAll, builder).unwrap();
+    }
+    (service, root)
+}
+
+#[test]
+fn tenants_only_reach_their_own_accumulator() {
+    let (mut service, root) = service("isolation", Arc::new(ManualClock::new()), Quota::default());
+    assert_eq!(service.session("issuer-a", b"token-b").err(), Some("Authentication failed"));
+    assert_eq!(service.session("issuer-c", b"token-a").err(), Some("Authentication failed"));
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let reopened = service.open_tenant("issuer-a", b"token", Quota::default(), AllowAll, builder);
+    assert_eq!(reopened, Err("Tenant is already open"));
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    assert!(service.open_tenant("../issuer-a", b"token", Quota::default(), AllowAll, builder).is_err());
+
+    let mut a = service.session("issuer-a", b"token-a").unwrap();
+    let w = a.add("op", "alice").unwrap();
+    let modulus = a.manager().accumulator().modulus();
+    let mut b = service.session("issuer-b", b"token-b").unwrap();
+    // Each tenant has its own keys, so a witness means nothing to another
+    assert_ne!(b.manager().accumulator().modulus(), modulus);
+    assert!(!b.manager().accumulator().contains("alice"));
+    assert!(!b.verify("alice", w).unwrap());
+    assert_eq!(b.delete("op", "alice").err(), Some("Element is not a member"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn quotas_are_enforced_per_tenant() {
+    let clock = Arc::new(ManualClock::new());
+    let quota = Quota {
+        max_members: 2,
+        requests: RateLimit {
+            max: 4,
+            window: Duration::from_secs(60),
+        },
+    };
+    let (mut service, root) = service("quota", clock.clone(), quota);
+    let mut a = service.session("issuer-a", b"token-a").unwrap();
+    a.add("op", "alice").unwrap();
+    a.add("op", "bob").unwrap();
+    assert_eq!(a.add("op", "carol").err(), Some("Tenant member quota exceeded"));
+    // Adding an existing member needs no room
+    a.add("op", "alice").unwrap();
+    assert_eq!(a.add("op", "alice").err(), Some("Tenant request quota exceeded"));
+
+    // The other tenant has its own allowance
+    let mut b = service.session("issuer-b", b"token-b").unwrap();
+    b.add("op", "carol").unwrap();
+
+    clock.advance(Duration::from_secs(60));
+    let mut a = service.session("issuer-a", b"token-a").unwrap();
+    a.delete("op", "bob").unwrap();
+    a.add("op", "carol").unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn every_tenant_is_restored_with_its_own_state() {
+    let clock = Arc::new(ManualClock::new());
+    let (mut service, root) = service("restore", clock.clone(), Quota::default());
+    let mut witnesses = Vec::new();
+    let tenants = [("issuer-a", b"token-a", "alice", "bob"), ("issuer-b", b"token-b", "carol", "dave")];
+    for (tenant, token, keep, drop) in tenants {
+        let mut session = service.session(tenant, token).unwrap();
+        let w = session.add_with_metadata("op", keep, tenant.as_bytes()).unwrap();
+        session.add("op", drop).unwrap();
+        session.delete("op", drop).unwrap();
+        let acc = session.manager().accumulator();
+        witnesses.push((tenant, token, keep, drop, acc.recover_witness(keep).unwrap(), w, acc.value(), acc.modulus()));
+    }
+    drop(service);
+
+    let mut service = Service::new(&root).unwrap().with_clock(clock);
+    for (tenant, token) in [("issuer-a", b"token-a"), ("issuer-b", b"token-b")] {
+        let builder = BraavosAccumulator::builder().prime_bits(64);
+        service.open_tenant(tenant, token, Quota::default(), AllowAll, builder).unwrap();
+    }
+    for (tenant, token, keep, dropped, w, stale, value, modulus) in witnesses {
+        let mut session = service.session(tenant, token).unwrap();
+        let acc = session.manager().accumulator();
+        assert_eq!((acc.epoch(), acc.value(), acc.modulus()), (1, value, modulus));
+        assert!(acc.contains(keep) && !acc.contains(dropped));
+        assert_eq!(session.manager().get_metadata(keep), Some(tenant.as_bytes()));
+        assert!(session.verify(keep, w).unwrap());
+        assert!(!session.verify(keep, stale).unwrap());
+    }
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_strict_restart_refuses_a_tampered_tenant_record() {
+    let (mut service, root) = service("tamper", Arc::new(ManualClock::new()), Quota::default());
+    let mut session = service.session("issuer-a", b"token-a").unwrap();
+    session.add("op", "alice").unwrap();
+    let state = session.manager().accumulator().to_state_bytes().unwrap();
+    drop(service);
+
+    // Flip a bit of the member's cached prime. After it in the state come the
+    // deletion and copy counts, the key usage and the supplied-prime labels:
+    // 32 bytes.
+    let file = root.join("issuer-a").join("tenant");
+    let mut bytes = std::fs::read(&file).unwrap();
+    let start = bytes.windows(state.len()).position(|w| w == state).unwrap();
+    bytes[start + state.len() - 33] ^= 1;
+    std::fs::write(&file, bytes).unwrap();
+
+    let mut service = Service::new(&root).unwrap();
+    let strict = BraavosAccumulator::builder().prime_bits(64).strict(true);
+    let reopened = service.open_tenant("issuer-a", b"token-a", Quota::default(), AllowAll, strict);
+    assert_eq!(reopened, Err("State failed the integrity check"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,50 @@
+use braavos_accumulator::{BraavosAccumulator, ShardedAccumulator, ShardedWitness};
+
+fn sharded(shards: u32) -> ShardedAccumulator {
+    ShardedAccumulator::new(shards, BraavosAccumulator::builder().prime_bits(64)).expect("Failed to create shards")
+}
+
+#[test]
+fn batches_match_single_operations() {
+    let mut acc = sharded(3);
+    let names: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i]).collect();
+    let refs: Vec<&[u8]> = names.iter().map(Vec::as_slice).collect();
+    let witnesses: Vec<_> = acc.add_batch(&refs).into_iter().map(Result::unwrap).collect();
+    assert_eq!(acc.member_count(), 9);
+    for (x, w) in refs.iter().zip(&witnesses) {
+        assert_eq!(w.shard, acc.shard_of(x));
+        assert!(acc.contains(x));
+    }
+    let items: Vec<_> = refs.iter().copied().zip(witnesses.iter().copied()).collect();
+    assert!(acc.verify_all(&items));
+
+    // Re-adding returns the same witness, as for a single accumulator
+    let again = acc.add_batch(&[refs[0], b"new"]);
+    assert_eq!(again[0], Ok(witnesses[0]));
+    assert_eq!(acc.member_count(), 10);
+
+    let deleted = acc.delete_batch(&refs[..3]);
+    let total: u64 = acc.epochs().iter().sum();
+    assert_eq!(total, 3);
+    for (x, result) in refs.iter().zip(&deleted) {
+        let (shard, update) = result.as_ref().unwrap();
+        assert_eq!(*shard, acc.shard_of(x));
+        assert!(update.to_epoch <= acc.shard(*shard).unwrap().epoch());
+        assert!(!acc.contains(x));
+    }
+}
+
+#[test]
+fn offline_verification_checks_the_shard() {
+    let mut acc = sharded(2);
+    let w = acc.add(b"member").unwrap();
+    let (params, values) = (acc.params(), acc.values());
+    assert!(w.verify(&params, &values, b"member"));
+    assert!(!w.verify(&params, &values, b"other"));
+
+    let moved = ShardedWitness { shard: 1 - w.shard, ..w };
+    assert!(!moved.verify(&params, &values, b"member"));
+    assert!(!acc.verify(b"member", &moved));
+    assert!(!w.verify(&params, &values[..1], b"member"));
+    assert!(ShardedAccumulator::new(0, BraavosAccumulator::builder()).is_err());
+}
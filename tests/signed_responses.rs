@@ -0,0 +1,53 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Manager, Quota, Service, SignedState, SignedWitness};
+
+#[test]
+fn service_responses_verify_after_relaying() {
+    let root = std::env::temp_dir().join(format!("braavos-signed-responses-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    let mut service = Service::new(&root).unwrap();
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder).unwrap();
+    let mut session = service.session("issuer", b"token").unwrap();
+    let params = session.manager().accumulator().params();
+
+    // What a cache hands back is decoded and checked against pinned parameters
+    let response = session.add_signed("op", "alice").unwrap();
+    let relayed = SignedWitness::from_bytes(&response.to_bytes()).unwrap();
+    assert!(relayed.verify(&params));
+    assert_eq!((relayed.element.as_slice(), relayed.epoch), (&b"alice"[..], 0));
+    session.add("op", "bob").unwrap();
+    session.delete("op", "bob").unwrap();
+    let recovered = session.recover_witness_signed("op", "alice").unwrap();
+    assert!(recovered.verify(&params));
+    assert_eq!(recovered.epoch, 1);
+
+    let state = SignedState::from_bytes(&session.state().unwrap().to_bytes()).unwrap();
+    assert!(state.verify(&params));
+    assert_eq!((state.epoch, state.value), (1, session.manager().accumulator().value()));
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn tampered_responses_are_rejected() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut other = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll);
+    let params = manager.accumulator().params();
+    let w = manager.add("op", "alice").unwrap();
+    let response = manager.sign_witness("alice", w).unwrap();
+
+    // Swapped for another element's answer
+    let mut swapped = response.clone();
+    swapped.element = b"mallory".to_vec();
+    assert!(!swapped.verify(&params));
+    // A witness from another accumulator
+    let mut forged = response.clone();
+    forged.witness = other.add("alice").unwrap();
+    assert!(!forged.verify(&params));
+    // A rolled-back state
+    let mut state = manager.signed_state().unwrap();
+    state.epoch += 1;
+    assert!(!state.verify(&params));
+    // Signed by a different manager
+    assert!(!response.verify(&other.params()));
+}
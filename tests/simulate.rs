@@ -0,0 +1,27 @@
+use braavos_accumulator::Scenario;
+
+#[test]
+fn simulation_accounts_for_the_workload() {
+    let scenario = Scenario {
+        prime_bits: 64,
+        rounds: 4,
+        initial_members: 6,
+        adds_per_round: 2,
+        deletes_per_round: 3,
+        holders: 400,
+        tracked_holders: 4,
+        sync_interval: 2,
+        seed: 1,
+    };
+    let report = scenario.run().unwrap();
+    assert_eq!(report.adds, 8);
+    // Two deletable initial members: from the third round deletions are capped by the adds
+    assert_eq!(report.deletes, 10);
+    assert_eq!(report.syncs, 8);
+    assert!(report.log_bytes > 0 && report.sync_bytes > 0);
+    assert_eq!(report.projected_sync_bytes(), report.sync_bytes * 100);
+    assert!(report.to_string().contains("projected for 400"));
+
+    assert!(Scenario { sync_interval: 0, ..scenario }.run().is_err());
+    assert!(Scenario { tracked_holders: 7, ..scenario }.run().is_err());
+}
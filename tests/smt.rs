@@ -0,0 +1,100 @@
+use braavos_accumulator::{AccumulatorBackend, BraavosAccumulator, HashAlgorithm, SmtProof, SparseMerkleTree};
+
+fn screening_list() -> SparseMerkleTree {
+    let mut smt = SparseMerkleTree::new(HashAlgorithm::Sha256);
+    for x in ["listed-1", "listed-2", "listed-3"] {
+        smt.add(x).unwrap();
+    }
+    smt
+}
+
+#[test]
+fn non_membership_proofs_verify_and_reject_members() {
+    let smt = screening_list();
+    let root = smt.root();
+    let proof = smt.non_membership_witness("alice").unwrap();
+    assert!(smt.verify_non_membership("alice", &proof));
+    assert!(proof.verify(HashAlgorithm::Sha256, &root, "alice", false));
+    assert!(!proof.verify(HashAlgorithm::Sha256, &root, "alice", true));
+    assert!(!proof.verify(HashAlgorithm::Sha256, &root, "listed-1", false));
+    assert_eq!(smt.non_membership_witness("listed-1").err(), Some("Element is a member"));
+    // A proof made for a member says so, and cannot be passed off as absence
+    let member = smt.prove("listed-1");
+    assert!(!smt.verify_non_membership("listed-1", &member));
+}
+
+#[test]
+fn membership_proofs_verify_against_the_current_root_only() {
+    let mut smt = screening_list();
+    let proof = smt.add("listed-4").unwrap();
+    assert!(smt.verify("listed-4", &proof));
+    assert!(!smt.verify("listed-5", &proof));
+    let old_root = smt.root();
+
+    assert_eq!(smt.delete("listed-4"), Ok(smt.root()));
+    assert_ne!(smt.root(), old_root);
+    assert!(!smt.verify("listed-4", &proof));
+    assert!(proof.verify(HashAlgorithm::Sha256, &old_root, "listed-4", true));
+    let absent = smt.non_membership_witness("listed-4").unwrap();
+    assert!(smt.verify_non_membership("listed-4", &absent));
+    assert_eq!(smt.delete("listed-4"), Err("Element is not a member"));
+}
+
+#[test]
+fn the_root_depends_only_on_the_set() {
+    let mut forward = SparseMerkleTree::new(HashAlgorithm::Sha256);
+    let empty = forward.root();
+    let mut backward = forward.clone();
+    for x in ["a", "b", "c"] {
+        forward.add(x).unwrap();
+    }
+    for x in ["c", "b", "a", "b"] {
+        backward.add(x).unwrap();
+    }
+    assert_eq!((forward.root(), forward.member_count()), (backward.root(), 3));
+    for x in ["a", "b", "c"] {
+        forward.delete(x).unwrap();
+    }
+    assert_eq!(forward.root(), empty);
+}
+
+#[test]
+fn tampered_proofs_are_rejected() {
+    let smt = screening_list();
+    let proof = smt.prove("listed-2");
+    assert_eq!(SmtProof::from_bytes(&proof.to_bytes()), Ok(proof.clone()));
+    assert!(!proof.siblings.is_empty());
+
+    let mut sibling = proof.clone();
+    sibling.siblings[0][0] ^= 1;
+    assert!(!sibling.verify(smt.hash(), &smt.root(), "listed-2", true));
+    assert!(!smt.verify_non_membership("listed-2", &sibling));
+    let mut present = proof.clone();
+    present.present[31] ^= 1;
+    assert!(!present.verify(smt.hash(), &smt.root(), "listed-2", true));
+    let mut extra = proof.clone();
+    extra.siblings.push([0; 32]);
+    assert!(!extra.verify(smt.hash(), &smt.root(), "listed-2", true));
+    let bytes = proof.to_bytes();
+    assert!(SmtProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+// Adds then deletes through the common trait, checking the backend's own
+// membership and non-membership answers for an element never added.
+fn exercise<B: AccumulatorBackend>(backend: &mut B) {
+    let w = backend.add("alice").unwrap();
+    assert!(backend.contains("alice") && backend.verify("alice", &w));
+    backend.add("bob").unwrap();
+    let absent = backend.non_membership_witness("carol").unwrap();
+    assert!(backend.verify_non_membership("carol", &absent));
+    backend.delete("bob").unwrap();
+    assert!(!backend.contains("bob"));
+    let absent = backend.non_membership_witness("carol").unwrap();
+    assert!(backend.verify_non_membership("carol", &absent));
+}
+
+#[test]
+fn both_backends_share_the_trait() {
+    exercise(&mut SparseMerkleTree::new(HashAlgorithm::Sha256));
+    exercise(&mut BraavosAccumulator::new(64).unwrap());
+}
@@ -0,0 +1,61 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Quota, Service, SseFeed, UpdateMessage};
+
+fn service(name: &str) -> (std::path::PathBuf, Service) {
+    let root = std::env::temp_dir().join(format!("braavos-sse-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut service = Service::new(&root).unwrap();
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder).unwrap();
+    (root, service)
+}
+
+#[test]
+fn live_events_carry_each_update() {
+    let (root, mut service) = service("live");
+    let mut session = service.session("issuer", b"token").unwrap();
+    let mut w = session.add("op", "alice").unwrap();
+    let prime = session.manager().accumulator().element_prime("alice").unwrap();
+
+    let mut feed = session.sse_feed(None).unwrap();
+    assert_eq!(feed.prelude(), "retry: 3000\n\n");
+    assert_eq!(feed.try_next_event(), None);
+    for x in ["bob", "carol"] {
+        session.add("op", x).unwrap();
+        session.delete("op", x).unwrap();
+    }
+    for epoch in 1..=2 {
+        let event = feed.try_next_event().unwrap();
+        assert!(event.starts_with(&format!("id: {epoch}\nevent: update\ndata: ")) && event.ends_with("\n\n"));
+        w = SseFeed::decode_event(&event).unwrap().apply_to_witness(&prime, &w).unwrap();
+    }
+    assert_eq!(feed.epoch(), 2);
+    assert!(session.verify("alice", w).unwrap());
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn reconnects_resume_from_last_event_id() {
+    let (root, mut service) = service("resume");
+    let mut session = service.session("issuer", b"token").unwrap();
+    for x in ["alice", "bob", "carol"] {
+        session.add("op", x).unwrap();
+        session.delete("op", x).unwrap();
+    }
+
+    // A client that last saw epoch 1 gets 1..3 as one event, then live ones
+    let mut feed = session.sse_feed(Some("1")).unwrap();
+    let prelude = feed.prelude();
+    let catch_up = SseFeed::decode_event(prelude.strip_prefix("retry: 3000\n\n").unwrap()).unwrap();
+    assert_eq!((catch_up.from_epoch, catch_up.to_epoch, catch_up.deleted.len()), (1, 3, 2));
+    session.add("op", "dave").unwrap();
+    session.delete("op", "dave").unwrap();
+    assert!(feed.try_next_event().unwrap().starts_with("id: 4\n"));
+
+    // An up-to-date client gets no catch-up; bad ids are refused
+    let mut feed = session.sse_feed(Some("4")).unwrap();
+    assert_eq!(feed.prelude(), "retry: 3000\n\n");
+    assert_eq!(session.sse_feed(Some("four")).err(), Some("Last-Event-ID is not an epoch"));
+    assert_eq!(session.sse_feed(Some("9")).err(), Some("Epoch is ahead of the log"));
+    assert!(SseFeed::decode_event("id: 1\nevent: update\ndata: zz\n\n").is_err());
+    let _ = std::fs::remove_dir_all(&root);
+}
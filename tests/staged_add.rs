@@ -0,0 +1,46 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, Manager, PendingVerification};
+
+#[test]
+fn staged_witnesses_activate_with_the_commit() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    manager.add("op", "alice").unwrap();
+    manager.add("op", "bob").unwrap();
+    manager.queue_delete("op", "bob").unwrap();
+
+    let (pending, ticket) = manager.stage_add("op", "carol").unwrap();
+    assert_eq!((pending.element.as_slice(), pending.epoch), (&b"carol"[..], 1));
+    assert!(!manager.accumulator().contains("carol") && !manager.is_active(&ticket));
+    assert!(!manager.verify("carol", pending.witness));
+    let provisional = manager.verify_with_pending("carol", pending.witness);
+    assert_eq!(provisional, Ok(PendingVerification::Provisional { epoch: 1 }));
+
+    let update = manager.commit_deletions().unwrap().unwrap();
+    assert_eq!(update.to_epoch, pending.epoch);
+    assert!(manager.is_active(&ticket));
+    assert!(manager.verify("carol", pending.witness));
+    assert_eq!(manager.reissue_pending(&ticket).err(), Some("Ticket is not staged"));
+}
+
+#[test]
+fn later_deletions_need_a_reissued_witness() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    manager.add("op", "alice").unwrap();
+    let (early, ticket) = manager.stage_add("op", "dave").unwrap();
+    assert_eq!(early.epoch, 0);
+    // Opens the current value, but dave is not a member until the commit
+    let provisional = manager.verify_with_pending("dave", early.witness);
+    assert_eq!(provisional, Ok(PendingVerification::Provisional { epoch: 0 }));
+    assert_eq!(manager.stage_add("op", "dave").err(), Some("Element is already staged"));
+
+    manager.queue_delete("op", "alice").unwrap();
+    assert_eq!(manager.verify_with_pending("dave", early.witness), Ok(PendingVerification::Rejected));
+    let reissued = manager.reissue_pending(&ticket).unwrap();
+    assert_eq!(reissued.epoch, 1);
+    manager.commit_deletions().unwrap();
+    assert!(manager.verify("dave", reissued.witness) && !manager.verify("dave", early.witness));
+
+    let (_, dropped) = manager.stage_add("op", "erin").unwrap();
+    manager.unstage(&dropped).unwrap();
+    manager.commit_deletions().unwrap();
+    assert!(!manager.is_active(&dropped) && !manager.accumulator().contains("erin"));
+}
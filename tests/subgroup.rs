@@ -0,0 +1,82 @@
+use braavos_accumulator::math::jacobi;
+use braavos_accumulator::{core, BraavosAccumulator, Deadline, DecodingPolicy, Witness, WitnessValue};
+use crypto_bigint::U512;
+
+// The smallest value with Jacobi symbol -1: outside QR_n and -QR_n.
+fn non_residue(n: &U512) -> U512 {
+    (2u64..).map(U512::from).find(|v| jacobi(v, n) == -1).unwrap()
+}
+
+#[test]
+fn witnesses_verify_up_to_sign() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add(b"x").unwrap();
+    acc.add(b"y").unwrap();
+    let n = acc.modulus();
+    let negated = Witness {
+        value: WitnessValue::new(n.wrapping_sub(w.value.as_uint())),
+        ..w
+    };
+    assert!(acc.verify(b"x", negated));
+    assert!(core::is_group_element(negated.value.as_uint(), &n));
+    let odd = Witness {
+        value: WitnessValue::new(w.value.as_uint().mul_mod(&non_residue(&n), &n.to_nz().unwrap())),
+        ..w
+    };
+    assert!(!acc.verify(b"x", odd));
+
+    // Deleting with the negated witness still leaves the value in QR_n
+    acc.delete_with_witness(b"x", negated).unwrap();
+    assert_eq!(acc.value().as_uint(), w.value.as_uint());
+    assert!(acc.check_integrity().is_ok());
+    let w_y = acc.recover_witness(b"y").unwrap();
+    assert!(acc.verify(b"y", w_y));
+}
+
+#[test]
+fn decoders_reject_values_outside_the_subgroup() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add(b"x").unwrap();
+    let update = acc.delete(b"x").unwrap();
+    let n = acc.modulus();
+    let policy = DecodingPolicy::default().for_params(&acc.params());
+    let bad = non_residue(&n);
+
+    let mut forged = w;
+    forged.value = WitnessValue::new(bad);
+    assert!(Witness::from_bytes_with(&forged.to_bytes(), &policy).is_err());
+    // Without a modulus the decoder cannot tell
+    assert!(Witness::from_bytes(&forged.to_bytes()).is_ok());
+
+    let mut forged = update;
+    forged.value = braavos_accumulator::AccValue::new(bad);
+    assert_eq!(
+        braavos_accumulator::EpochUpdate::from_bytes(&forged.to_bytes()),
+        Err("Value is not in the quadratic residue subgroup")
+    );
+}
+
+#[test]
+fn unreduced_witnesses_are_rejected_at_any_epoch() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add(b"x").unwrap();
+    let epoch = acc.epoch();
+    acc.add(b"y").unwrap();
+    let n = acc.modulus();
+    // w + n opens the same value once reduced, but is not a group element
+    let unreduced = Witness {
+        value: WitnessValue::new(w.value.as_uint().wrapping_add(&n)),
+        ..w
+    };
+    assert!(acc.verify_at_epoch(b"x", w, epoch));
+    assert!(!acc.verify_at_epoch(b"x", unreduced, epoch));
+
+    let current = acc.recover_witness(b"x").unwrap();
+    let unreduced = Witness {
+        value: WitnessValue::new(current.value.as_uint().wrapping_add(&n)),
+        ..current
+    };
+    assert_eq!(acc.verify_within(b"x", current, &Deadline::none()), Ok(true));
+    assert_eq!(acc.verify_within(b"x", unreduced, &Deadline::none()), Ok(false));
+    assert!(acc.verify_detailed(b"x", unreduced).is_err());
+}
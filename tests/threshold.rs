@@ -0,0 +1,176 @@
+use braavos_accumulator::{BraavosAccumulator, Custodian, PublicParams, RootRequest, UpdateMessage};
+use crypto_bigint::{U2048, U512};
+
+type Round = (Vec<(u32, U2048)>, Vec<(u32, U512)>);
+
+fn round(quorum: &mut [Custodian], params: &PublicParams, request: &RootRequest) -> Round {
+    let commitments: Vec<_> = quorum.iter_mut().map(|c| c.commit(request).unwrap()).collect();
+    let partials = quorum
+        .iter_mut()
+        .map(|c| c.partial(params, request, &commitments).unwrap())
+        .collect();
+    (commitments, partials)
+}
+
+#[test]
+fn a_quorum_adds_and_deletes() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("carol").unwrap();
+    let (mut acc, mut custodians) = acc.into_threshold(2, 3, 4).unwrap();
+    let params = acc.params();
+    assert!(acc.contains("carol"));
+
+    let request = acc.request("alice").unwrap();
+    let (commitments, partials) = round(&mut custodians[..2], &params, &request);
+    let w_alice = acc.add("alice", &request, &commitments, &partials).unwrap();
+    assert!(acc.verify("alice", w_alice));
+
+    // Any two of the three will do
+    let request = acc.request("bob").unwrap();
+    let (commitments, partials) = round(&mut custodians[1..], &params, &request);
+    let w_bob = acc.add("bob", &request, &commitments, &partials).unwrap();
+    assert!(acc.verify("bob", w_bob));
+
+    let request = acc.request("bob").unwrap();
+    let (commitments, partials) = round(&mut custodians[..], &params, &request);
+    let update = acc.delete("bob", &request, &commitments, &partials).unwrap();
+    assert_eq!((update.from_epoch, update.to_epoch, acc.epoch()), (0, 1, 1));
+    assert!(!acc.contains("bob") && !acc.verify("bob", w_bob));
+    let w_alice = update.apply_to_witness(&params.hash_to_prime("alice"), &w_alice).unwrap();
+    assert!(acc.verify("alice", w_alice));
+    assert_eq!(acc.remaining_tokens(), 1);
+}
+
+#[test]
+fn fewer_than_the_threshold_cannot_take_a_root() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let (mut acc, mut custodians) = acc.into_threshold(3, 4, 2).unwrap();
+    let params = acc.params();
+    let value = acc.value();
+
+    // Two commitments interpolate to nothing useful, whatever partials follow
+    let request = acc.request("alice").unwrap();
+    let commitments: Vec<_> = custodians[..2].iter_mut().map(|c| c.commit(&request).unwrap()).collect();
+    let partials: Vec<_> = custodians[..2]
+        .iter_mut()
+        .filter_map(|c| c.partial(&params, &request, &commitments).ok())
+        .collect();
+    assert_eq!(
+        acc.add("alice", &request, &commitments, &partials),
+        Err("Not enough custodians in the quorum")
+    );
+    assert!(!acc.contains("alice"));
+    assert_eq!(acc.value(), value);
+
+    // Nor can a quorum leave out one of its members' partials
+    let request = acc.request("alice").unwrap();
+    let (commitments, partials) = round(&mut custodians[..3], &params, &request);
+    assert!(acc.add("alice", &request, &commitments, &partials[..2]).is_err());
+
+    assert!(BraavosAccumulator::new(64).unwrap().into_threshold(4, 3, 1).is_err());
+    assert!(BraavosAccumulator::new(64).unwrap().into_threshold(0, 3, 1).is_err());
+}
+
+#[test]
+fn a_bad_partial_is_detected() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("alice").unwrap();
+    let (mut acc, mut custodians) = acc.into_threshold(2, 3, 2).unwrap();
+    let params = acc.params();
+    let value = acc.value();
+
+    let request = acc.request("alice").unwrap();
+    let (commitments, mut partials) = round(&mut custodians[..2], &params, &request);
+    partials[1].1 = partials[1].1.wrapping_add(&U512::ONE);
+    assert_eq!(
+        acc.delete("alice", &request, &commitments, &partials),
+        Err("Partials do not combine to a root")
+    );
+    assert!(acc.contains("alice"));
+    assert_eq!((acc.value(), acc.epoch()), (value, 0));
+
+    // The operation is retried with a fresh token
+    let request = acc.request("alice").unwrap();
+    let (commitments, partials) = round(&mut custodians[1..], &params, &request);
+    assert!(acc.delete("alice", &request, &commitments, &partials).is_ok());
+    assert!(!acc.contains("alice"));
+}
+
+#[test]
+fn tokens_are_burned_after_one_use() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let (mut acc, mut custodians) = acc.into_threshold(2, 2, 1).unwrap();
+    let params = acc.params();
+    assert_eq!(custodians[0].remaining(), 1);
+
+    let request = acc.request("alice").unwrap();
+    let (commitments, partials) = round(&mut custodians[..], &params, &request);
+    assert!(custodians.iter().all(|c| c.remaining() == 0));
+    assert_eq!(custodians[0].commit(&request), Err("Token already used or unknown"));
+    assert_eq!(
+        custodians[1].partial(&params, &request, &commitments),
+        Err("Token already used or unknown")
+    );
+    acc.add("alice", &request, &commitments, &partials).unwrap();
+
+    assert_eq!(acc.remaining_tokens(), 0);
+    assert_eq!(acc.request("bob"), Err("No threshold tokens left"));
+
+    // A burned token stays burned across serialization
+    let mut restored = Custodian::from_bytes(&custodians[0].to_bytes()).unwrap();
+    assert_eq!(restored.remaining(), 0);
+    assert!(restored.commit(&request).is_err());
+}
+
+#[test]
+fn a_token_commits_to_one_element() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let (mut acc, mut custodians) = acc.into_threshold(2, 2, 1).unwrap();
+    let params = acc.params();
+
+    let request = acc.request("alice").unwrap();
+    let other = RootRequest {
+        prime: params.hash_to_prime("bob"),
+        ..request
+    };
+    let gamma = custodians[0].commit(&request).unwrap();
+    assert_eq!(custodians[0].commit(&request), Ok(gamma));
+    assert_eq!(custodians[0].commit(&other), Err("Token is committed to another element"));
+    // The binding survives serialization
+    let mut restored = Custodian::from_bytes(&custodians[0].to_bytes()).unwrap();
+    assert_eq!(restored.commit(&other), Err("Token is committed to another element"));
+
+    // A partial is only given for the committed prime and commitment
+    let commitments = [gamma, custodians[1].commit(&other).unwrap()];
+    assert_eq!(
+        restored.partial(&params, &other, &commitments),
+        Err("Token is committed to another element")
+    );
+    let mut custodian = custodians[0].clone();
+    let tampered = [(gamma.0, gamma.1.wrapping_add(&U2048::ONE)), commitments[1]];
+    assert_eq!(
+        custodian.partial(&params, &request, &tampered),
+        Err("Commitment differs from the one this custodian made")
+    );
+}
+
+#[test]
+fn members_are_checked_before_taking_a_root() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("alice").unwrap();
+    let (mut acc, mut custodians) = acc.into_threshold(2, 2, 2).unwrap();
+    let params = acc.params();
+    let value = acc.value();
+
+    let request = acc.request("alice").unwrap();
+    let (commitments, partials) = round(&mut custodians[..], &params, &request);
+    assert_eq!(
+        acc.add("alice", &request, &commitments, &partials),
+        Err("Element is already a member")
+    );
+    let request = acc.request("bob").unwrap();
+    let (commitments, partials) = round(&mut custodians[..], &params, &request);
+    assert_eq!(acc.delete("bob", &request, &commitments, &partials), Err("Element is not a member"));
+    assert_eq!((acc.value(), acc.epoch()), (value, 0));
+    assert!(acc.contains("alice") && !acc.contains("bob"));
+}
@@ -0,0 +1,43 @@
+use braavos_accumulator::{
+    hash_to_prime_uint, hash_to_prime_uint_within, Deadline, HashAlgorithm, PrimalityPolicy, TIMEOUT,
+};
+use crypto_bigint::{U1024, U128, U256};
+use crypto_primes::{is_prime, is_safe_prime};
+use std::time::Duration;
+
+const SHA256: HashAlgorithm = HashAlgorithm::Sha256;
+
+#[test]
+fn primes_have_the_requested_size_and_policy() {
+    let policy = PrimalityPolicy::default();
+    let p: U1024 = hash_to_prime_uint(SHA256, b"vdf-challenge", b"block 1", 600, policy).unwrap();
+    assert_eq!(p.bits(), 600);
+    assert!(is_prime(&p));
+    let again: U1024 = hash_to_prime_uint(SHA256, b"vdf-challenge", b"block 1", 600, policy).unwrap();
+    assert_eq!(p, again);
+    let other: U1024 = hash_to_prime_uint(SHA256, b"cl-signature", b"block 1", 600, policy).unwrap();
+    assert_ne!(p, other);
+
+    // Miller-Rabin accepts the same first prime of the sequence
+    let policy = PrimalityPolicy::MillerRabin { rounds: 8 };
+    let q: U256 = hash_to_prime_uint(SHA256, b"vdf-challenge", b"block 2", 200, policy).unwrap();
+    let r: U256 = hash_to_prime_uint(SHA256, b"vdf-challenge", b"block 2", 200, PrimalityPolicy::BailliePsw).unwrap();
+    assert_eq!(q, r);
+
+    let safe: U128 = hash_to_prime_uint(SHA256, b"cl-signature", b"e", 64, PrimalityPolicy::SafePrime).unwrap();
+    assert_eq!(safe.bits(), 64);
+    assert!(is_safe_prime(&safe));
+}
+
+#[test]
+fn sizes_and_deadlines_are_enforced() {
+    let policy = PrimalityPolicy::default();
+    assert_eq!(hash_to_prime_uint::<2>(SHA256, b"d", b"x", 129, policy), Err("Prime size out of range"));
+    assert_eq!(hash_to_prime_uint::<2>(SHA256, b"d", b"x", 8, policy), Err("Prime size out of range"));
+    let small: U128 = hash_to_prime_uint(SHA256, b"d", b"x", 16, policy).unwrap();
+    assert_eq!(small.bits(), 16);
+
+    let expired = Deadline::after(Duration::ZERO);
+    let result = hash_to_prime_uint_within::<16>(SHA256, b"d", b"x", 1024, PrimalityPolicy::SafePrime, &expired);
+    assert_eq!(result, Err(TIMEOUT));
+}
@@ -0,0 +1,100 @@
+use braavos_accumulator::{BraavosAccumulator, EpochUpdate, UpdateMessage, Witness};
+
+struct Fixture {
+    acc: BraavosAccumulator,
+    updates: Vec<EpochUpdate>,
+}
+
+// Adds x plus three more elements, then deletes the other three one per epoch.
+fn fixture() -> (Fixture, Witness) {
+    let mut acc = BraavosAccumulator::new(64).expect("Failed to create accumulator");
+    let w_x = acc.add(b"x").expect("Failed to add x");
+    for y in [&b"y1"[..], b"y2", b"y3"] {
+        acc.add(y).expect("Failed to add y");
+    }
+    let updates = [&b"y1"[..], b"y2", b"y3"]
+        .iter()
+        .map(|y| acc.delete(y).expect("Failed to delete y"))
+        .collect();
+    (Fixture { acc, updates }, w_x)
+}
+
+#[test]
+fn merge_is_associative() {
+    let (f, _) = fixture();
+    let [m1, m2, m3] = [&f.updates[0], &f.updates[1], &f.updates[2]];
+
+    let left = m1.merge(m2).unwrap().merge(m3).unwrap();
+    let right = m1.merge(&m2.merge(m3).unwrap()).unwrap();
+    assert_eq!(left, right);
+    assert_eq!(left.from_epoch, 0);
+    assert_eq!(left.to_epoch, 3);
+    assert_eq!(left.value, f.acc.value());
+}
+
+#[test]
+fn merged_update_matches_sequential_application() {
+    let (mut f, w_x) = fixture();
+    let x_prime = f.acc.element_prime(b"x").unwrap();
+
+    let mut sequential = w_x;
+    for m in &f.updates {
+        sequential = m.apply_to_witness(&x_prime, &sequential).unwrap();
+    }
+
+    let merged = f.updates[0]
+        .merge(&f.updates[1])
+        .and_then(|m| m.merge(&f.updates[2]))
+        .unwrap();
+    let coalesced = merged.apply_to_witness(&x_prime, &w_x).unwrap();
+
+    assert_eq!(sequential, coalesced);
+    assert!(f.acc.verify(b"x", coalesced));
+}
+
+#[test]
+fn merge_rejects_gaps() {
+    let (f, _) = fixture();
+    assert!(f.updates[0].merge(&f.updates[2]).is_err());
+    assert!(f.updates[1].merge(&f.updates[0]).is_err());
+}
+
+#[test]
+fn deleted_element_cannot_be_updated() {
+    let (mut f, _) = fixture();
+    let w_y1 = f.acc.add(b"y1").unwrap();
+    let y1_prime = f.acc.element_prime(b"y1").unwrap();
+    assert!(f.updates[0].apply_to_witness(&y1_prime, &w_y1).is_err());
+}
+
+#[test]
+fn update_for_other_params_is_rejected() {
+    let (f, w_x) = fixture();
+    let (other, _) = fixture();
+    let x_prime = f.acc.element_prime(b"x").unwrap();
+    assert!(other.updates[0].apply_to_witness(&x_prime, &w_x).is_err());
+    assert!(f.updates[0].merge(&other.updates[1]).is_err());
+}
+
+#[test]
+fn witness_deletion_matches_trapdoor_deletion() {
+    let (mut f, w_x) = fixture();
+    let w_z = f.acc.add(b"z").unwrap();
+    let x_prime = f.acc.element_prime(b"x").unwrap();
+
+    let update = f.acc.delete_with_witness(b"z", w_z).unwrap();
+    assert_eq!(update.value, w_z.value.into_value_after_deletion());
+    assert_eq!(update.from_epoch, 3);
+    assert_eq!(f.acc.epoch(), 4);
+
+    let merged = f.updates[0]
+        .merge(&f.updates[1])
+        .and_then(|m| m.merge(&f.updates[2]))
+        .and_then(|m| m.merge(&update))
+        .unwrap();
+    let w_x = merged.apply_to_witness(&x_prime, &w_x).unwrap();
+    assert!(f.acc.verify(b"x", w_x));
+
+    // A stale witness no longer opens the value, so it cannot delete again
+    assert!(f.acc.delete_with_witness(b"z", w_z).is_err());
+}
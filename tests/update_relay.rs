@@ -0,0 +1,48 @@
+use braavos_accumulator::{AllowAll, BraavosAccumulator, EpochUpdate, Manager, UpdateMessage};
+
+#[test]
+fn holders_at_one_epoch_share_one_merged_range() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let mut w = manager.add("op", "alice").unwrap();
+    let prime = manager.accumulator().element_prime("alice").unwrap();
+    manager.add("op", "bob").unwrap();
+    manager.delete("op", "bob").unwrap();
+
+    // The relay starts from the log so far and follows what comes after
+    let mut relay = manager.relay();
+    for x in ["carol", "dave"] {
+        manager.add("op", x).unwrap();
+        manager.delete("op", x).unwrap();
+    }
+    assert_eq!(relay.latest_epoch(), 1);
+    assert_eq!(relay.poll(), Ok(2));
+    assert_eq!(relay.latest_epoch(), 3);
+
+    let path = relay.path_for(0).unwrap().unwrap();
+    assert_eq!(path, "/updates/0-3");
+    let bytes = relay.get(&path).unwrap();
+    assert_eq!(relay.get(&path), Ok(bytes.clone()));
+    assert_eq!(relay.cached_ranges(), 1);
+    w = EpochUpdate::from_bytes(&bytes).unwrap().apply_to_witness(&prime, &w).unwrap();
+    assert!(manager.verify("alice", w));
+    assert_eq!(relay.path_for(3), Ok(None));
+}
+
+#[test]
+fn range_paths_are_checked_against_the_log() {
+    let mut manager = Manager::new(BraavosAccumulator::new(64).unwrap(), AllowAll);
+    let mut relay = manager.relay();
+    for x in ["alice", "bob"] {
+        manager.add("op", x).unwrap();
+        manager.delete("op", x).unwrap();
+    }
+    relay.poll().unwrap();
+
+    // A range ending mid-log is still immutable, and served as asked
+    let first = EpochUpdate::from_bytes(&relay.get("/updates/0-1").unwrap()).unwrap();
+    assert_eq!((first.from_epoch, first.to_epoch), (0, 1));
+    for path in ["/updates/1-1", "/updates/0-9", "/updates/x-2", "/epochs/0-2"] {
+        assert!(relay.get(path).is_err(), "{path} served");
+    }
+    assert!(relay.path_for(5).is_err());
+}
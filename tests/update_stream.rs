@@ -0,0 +1,90 @@
+use braavos_accumulator::{
+    AllowAll, BraavosAccumulator, EpochScheduler, Manager, ManualClock, Quota, Service, WitnessHolder,
+};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+// The smallest executor there is, standing in for tokio, async-std or smol.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn updates_wake_an_async_holder() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll);
+    let params = manager.accumulator().params();
+    let w = manager.add("op", "alice").unwrap();
+    let mut holder = WitnessHolder::new(&params, "alice", w, 0).unwrap();
+    let mut stream = manager.subscribe();
+    assert!(stream.try_recv().is_none());
+
+    // Published from another thread after the holder starts waiting
+    let issuer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        for x in ["bob", "carol"] {
+            manager.add("op", x).unwrap();
+            manager.delete("op", x).unwrap();
+        }
+        manager
+    });
+    for _ in 0..2 {
+        block_on(holder.sync_from_stream(&mut stream, &mut ())).unwrap();
+    }
+    let mut manager = issuer.join().unwrap();
+    assert_eq!(holder.epoch(), 2);
+    assert!(manager.verify("alice", holder.witness()));
+
+    // Dropping the manager ends the stream
+    drop(manager);
+    assert_eq!(block_on(stream.recv()), None);
+    assert_eq!(block_on(holder.sync_from_stream(&mut stream, &mut ())), Err("Update stream is closed"));
+}
+
+#[test]
+fn a_driver_loop_sleeps_until_the_next_tick() {
+    let root = std::env::temp_dir().join(format!("braavos-update-stream-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let clock = Arc::new(ManualClock::new());
+    let scheduler = EpochScheduler::every(Duration::from_secs(60));
+    let mut service = Service::new(&root).unwrap().with_scheduler(scheduler).with_clock(clock.clone());
+    let builder = BraavosAccumulator::builder().prime_bits(64);
+    service.open_tenant("issuer", b"token", Quota::default(), AllowAll, builder).unwrap();
+    assert_eq!(service.next_tick(), None);
+
+    let mut session = service.session("issuer", b"token").unwrap();
+    let mut stream = session.subscribe();
+    session.add("op", "bob").unwrap();
+    session.schedule_delete("op", "bob").unwrap();
+    drop(session);
+    clock.advance(Duration::from_secs(15));
+    assert_eq!(service.next_tick(), Some(Duration::from_secs(45)));
+
+    // What the runtime's timer would do, then the tick publishes the batch
+    clock.advance(service.next_tick().unwrap());
+    assert_eq!(service.tick().unwrap().len(), 1);
+    assert_eq!(service.next_tick(), None);
+    let update = block_on(stream.recv()).unwrap();
+    assert_eq!((update.to_epoch, update.deleted.len()), (1, 1));
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,44 @@
+use braavos_accumulator::{BraavosAccumulator, CertifiedWitness, ElementRegistry, HashAlgorithm, VerificationPolicy};
+use std::sync::Arc;
+
+const POLICIES: [VerificationPolicy; 3] =
+    [VerificationPolicy::Recompute, VerificationPolicy::Certified, VerificationPolicy::Trusted];
+
+#[test]
+fn every_policy_accepts_an_honest_witness() {
+    let registry = Arc::new(ElementRegistry::new(HashAlgorithm::Sha256));
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).element_registry(registry.clone()).build().unwrap();
+    let w = acc.add("alice").unwrap();
+    acc.add("bob").unwrap();
+    let certified = acc.certify("alice", w).unwrap();
+    assert_eq!(certified.entry, registry.entry("alice").unwrap());
+    let certified = CertifiedWitness::from_bytes(&certified.to_bytes()).unwrap();
+
+    let (params, value) = (acc.params(), acc.value());
+    for policy in POLICIES {
+        assert!(params.verify_with_policy("alice", &certified, &value, policy));
+        assert!(!params.verify_with_policy("bob", &certified, &value, policy));
+    }
+    assert_eq!(VerificationPolicy::default(), VerificationPolicy::Recompute);
+}
+
+#[test]
+fn supplied_primes_are_checked_as_the_policy_says() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    let (params, value) = (acc.params(), acc.value());
+    let honest = acc.certify("alice", w).unwrap();
+
+    // A certificate that skips a candidate fails Certified but not Trusted
+    let mut skipped = honest.clone();
+    skipped.entry.certificate.push(2);
+    assert!(!params.verify_with_policy("alice", &skipped, &value, VerificationPolicy::Certified));
+    assert!(params.verify_with_policy("alice", &skipped, &value, VerificationPolicy::Trusted));
+
+    // Recompute ignores the supplied prime altogether
+    let mut wrong = honest.clone();
+    wrong.entry.counter += 1;
+    assert!(params.verify_with_policy("alice", &wrong, &value, VerificationPolicy::Recompute));
+    assert!(!params.verify_with_policy("alice", &wrong, &value, VerificationPolicy::Certified));
+    assert!(!params.verify_with_policy("alice", &wrong, &value, VerificationPolicy::Trusted));
+}
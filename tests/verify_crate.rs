@@ -0,0 +1,37 @@
+use accumulators_verify::Verifier;
+use braavos_accumulator::{BraavosAccumulator, ElementDigest, GenesisRecord, PublicParams};
+
+#[test]
+fn witnesses_from_the_manager_verify_in_the_sub_crate() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let verifier = Verifier::from_params_bytes(&params.to_bytes()).unwrap();
+    assert_eq!(verifier.params_digest(), params.digest());
+    assert_eq!(PublicParams::from_bytes(&params.to_bytes()), Ok(params));
+
+    let digest = ElementDigest::new("device-id", [9; 32]).unwrap();
+    let w = acc.add("alice").unwrap();
+    let wd = acc.add(&digest).unwrap();
+    acc.add("bob").unwrap();
+    acc.delete("bob").unwrap();
+    let w = acc.update_witness_on_deletion("alice", w, "bob").unwrap();
+    let value = acc.value().to_be_bytes();
+    assert_eq!(verifier.hash_to_prime(b"alice"), *params.hash_to_prime("alice").as_uint());
+    assert_eq!(verifier.hash_to_prime(digest.as_ref()), *params.hash_to_prime(&digest).as_uint());
+    assert!(verifier.verify(b"alice", &w.to_bytes(), &value));
+    assert!(!verifier.verify(b"bob", &w.to_bytes(), &value));
+    assert!(verifier.verify(digest.as_ref(), &wd.to_bytes(), &verifier.base()));
+}
+
+#[test]
+fn every_parameter_layout_loads_with_the_same_digest() {
+    let seeded = BraavosAccumulator::builder().prime_bits(64).base_seed([3; 32]).build().unwrap();
+    let documented = BraavosAccumulator::builder().prime_bits(64).genesis(GenesisRecord::default()).build().unwrap();
+    for params in [BraavosAccumulator::new(64).unwrap().params(), seeded.params(), documented.params()] {
+        let verifier = Verifier::from_params_bytes(&params.to_bytes()).unwrap();
+        assert_eq!(verifier.params_digest(), params.digest());
+    }
+    let mut bytes = BraavosAccumulator::new(64).unwrap().params().to_bytes();
+    bytes.push(0);
+    assert!(Verifier::from_params_bytes(&bytes).is_err());
+}
@@ -0,0 +1,42 @@
+use braavos_accumulator::{BraavosAccumulator, VerifyFailure, WitnessValue};
+use crypto_bigint::U512;
+
+#[test]
+fn failures_say_what_is_wrong() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let mut other = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    assert_eq!(acc.verify_detailed("alice", w), Ok(()));
+    assert_eq!(acc.verify_detailed("alice", other.add("alice").unwrap()), Err(VerifyFailure::WrongParams));
+    assert_eq!(acc.verify_detailed("bob", w), Err(VerifyFailure::Mismatch));
+
+    let mut malformed = w;
+    malformed.value = WitnessValue::new(U512::ZERO);
+    assert_eq!(acc.verify_detailed("alice", malformed), Err(VerifyFailure::BadGroupElement));
+    // The value plus the modulus opens the same way, but is not reduced
+    malformed.value = WitnessValue::new(w.value.as_uint().wrapping_add(&acc.modulus()));
+    assert_eq!(acc.verify_detailed("alice", malformed), Err(VerifyFailure::BadGroupElement));
+    assert!(!acc.verify("alice", malformed));
+}
+
+#[test]
+fn stale_witnesses_are_told_apart_from_deleted_ones() {
+    let mut acc = BraavosAccumulator::builder().prime_bits(64).checkpoints(4).build().unwrap();
+    let w_alice = acc.add("alice").unwrap();
+    let w_bob = acc.add("bob").unwrap();
+    acc.delete("bob").unwrap();
+
+    let stale = acc.verify_detailed("alice", w_alice).unwrap_err();
+    assert_eq!(stale, VerifyFailure::StaleEpoch { epoch: 0, current: 1 });
+    assert_eq!(stale.to_string(), "witness is for epoch 0, the accumulator is at epoch 1");
+    assert_eq!(acc.verify_detailed("bob", w_bob), Err(VerifyFailure::Mismatch));
+    let refreshed = acc.update_witness_on_deletion("alice", w_alice, "bob").unwrap();
+    assert_eq!(acc.verify_detailed("alice", refreshed), Ok(()));
+
+    // Without checkpoints staleness cannot be told from a bad witness
+    let mut bare = BraavosAccumulator::new(64).unwrap();
+    let w = bare.add("alice").unwrap();
+    bare.add("bob").unwrap();
+    bare.delete("bob").unwrap();
+    assert_eq!(bare.verify_detailed("alice", w), Err(VerifyFailure::Mismatch));
+}
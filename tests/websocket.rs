@@ -0,0 +1,108 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use braavos_accumulator::{BraavosAccumulator, UpdateFeed, UpdateLog, UpdateServer, WitnessHolder};
+
+fn raw_request(server: &UpdateServer, request: &[u8]) -> String {
+    let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let _ = stream.write_all(request);
+    let mut response = Vec::new();
+    let _ = stream.read_to_end(&mut response);
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+fn wait_for(condition: impl Fn() -> bool) -> bool {
+    let start = Instant::now();
+    while !condition() {
+        if start.elapsed() > Duration::from_secs(5) {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    true
+}
+
+#[test]
+fn handshake_rejects_plain_and_oversized_requests() {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let server = UpdateServer::bind("127.0.0.1:0", acc.params(), UpdateLog::new()).unwrap();
+    let response = raw_request(&server, b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+
+    let mut oversized = b"GET / HTTP/1.1\r\nX-Padding: ".to_vec();
+    oversized.extend(std::iter::repeat_n(b'a', 16 * 1024));
+    oversized.extend_from_slice(b"\r\n\r\n");
+    assert!(!raw_request(&server, &oversized).starts_with("HTTP/1.1 101"));
+    assert!(wait_for(|| server.connections() == 0));
+}
+
+#[test]
+fn subscribers_catch_up_then_follow_the_stream() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let w = acc.add("alice").unwrap();
+    for x in ["a", "b", "c"] {
+        acc.add(x).unwrap();
+    }
+    let server = UpdateServer::bind("127.0.0.1:0", params, UpdateLog::new()).unwrap();
+    server.publish(acc.delete("a").unwrap()).unwrap();
+    server.publish(acc.delete("b").unwrap()).unwrap();
+
+    // Everything before the subscription arrives as one update
+    let mut feed = UpdateFeed::new(&server.local_addr().to_string(), 0);
+    let mut holder = WitnessHolder::new(&params, "alice", w, 0).unwrap();
+    holder.sync_from(&mut feed, &mut ()).unwrap();
+    assert_eq!(holder.epoch(), 2);
+    assert_eq!(server.subscribers(), 1);
+
+    server.publish(acc.delete("c").unwrap()).unwrap();
+    holder.sync_from(&mut feed, &mut ()).unwrap();
+    assert_eq!(holder.epoch(), 3);
+    assert!(acc.verify("alice", holder.witness()));
+
+    // Server refusals are returned at once, not retried as lost connections
+    let mut ahead = UpdateFeed::new(&server.local_addr().to_string(), 5);
+    assert_eq!(ahead.next(9).err(), Some("Epoch is ahead of the log"));
+}
+
+#[test]
+fn feeds_reconnect_and_resync_after_a_disconnect() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let w = acc.add("alice").unwrap();
+    for x in ["a", "b", "c"] {
+        acc.add(x).unwrap();
+    }
+    let server = UpdateServer::bind("127.0.0.1:0", params, UpdateLog::new()).unwrap();
+    let mut feed = UpdateFeed::new(&server.local_addr().to_string(), 5);
+    let mut holder = WitnessHolder::new(&params, "alice", w, 0).unwrap();
+    server.publish(acc.delete("a").unwrap()).unwrap();
+    holder.sync_from(&mut feed, &mut ()).unwrap();
+
+    server.disconnect_all();
+    assert_eq!(server.subscribers(), 0);
+    server.publish(acc.delete("b").unwrap()).unwrap();
+    server.publish(acc.delete("c").unwrap()).unwrap();
+    holder.sync_from(&mut feed, &mut ()).unwrap();
+    assert_eq!(holder.epoch(), 3);
+    assert!(acc.verify("alice", holder.witness()));
+}
+
+#[test]
+fn connections_beyond_the_limit_are_refused() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("a").unwrap();
+    let server = UpdateServer::bind("127.0.0.1:0", acc.params(), UpdateLog::new()).unwrap().with_max_connections(1);
+    server.publish(acc.delete("a").unwrap()).unwrap();
+    let mut feed = UpdateFeed::new(&server.local_addr().to_string(), 0);
+    feed.next(0).unwrap();
+    assert_eq!(server.connections(), 1);
+    assert!(raw_request(&server, b"GET / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 503"));
+
+    drop(feed);
+    assert!(wait_for(|| server.connections() == 0));
+    assert!(raw_request(&server, b"GET / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 400"));
+}
@@ -0,0 +1,85 @@
+use braavos_accumulator::{
+    AllowAll, BraavosAccumulator, HolderEvents, Invalidation, Manager, Witness, WitnessHolder, WitnessSet,
+};
+
+// Six credentials on one device, alice's issued an epoch later than the rest.
+fn device() -> (Manager, WitnessSet) {
+    let acc = BraavosAccumulator::new(64).unwrap();
+    let mut manager = Manager::new(acc, AllowAll);
+    let params = manager.accumulator().params();
+    let mut set = WitnessSet::new();
+    for i in 0..5u8 {
+        let w = manager.add("op", [i]).unwrap();
+        set.insert(WitnessHolder::new(&params, [i], w, 0).unwrap());
+    }
+    manager.add("op", "other").unwrap();
+    manager.delete("op", "other").unwrap();
+    let w = manager.add("op", "alice").unwrap();
+    set.insert(WitnessHolder::new(&params, "alice", w, 1).unwrap());
+    // One update deleting three, whose intermediate values the set shares
+    let batch = [[10u8], [11], [12]];
+    for x in batch {
+        manager.add("op", x).unwrap();
+    }
+    manager.delete_batch("op", &batch).unwrap();
+    (manager, set)
+}
+
+#[derive(Default)]
+struct Recorder {
+    invalidated: Vec<(Vec<u8>, Invalidation)>,
+    updated: Vec<(Vec<u8>, Witness, u64)>,
+}
+
+impl HolderEvents for Recorder {
+    fn on_invalidated(&mut self, element: &[u8], reason: Invalidation) {
+        self.invalidated.push((element.to_vec(), reason));
+    }
+
+    fn on_updated(&mut self, element: &[u8], witness: &Witness, epoch: u64) {
+        self.updated.push((element.to_vec(), *witness, epoch));
+    }
+}
+
+#[test]
+fn sync_all_matches_syncing_each_holder() {
+    let (mut manager, mut set) = device();
+    let mut separate: Vec<WitnessHolder> = set.holders().to_vec();
+    manager.delete("op", [3u8]).unwrap();
+
+    let mut set_events = Recorder::default();
+    let result = set.sync_all_with(manager.update_log(), &mut set_events);
+    assert_eq!(result, Err("Element has been deleted"));
+    let mut each_events = Recorder::default();
+    for holder in &mut separate {
+        let _ = holder.sync_with(manager.update_log(), &mut each_events);
+    }
+    for (together, alone) in set.holders().iter().zip(&separate) {
+        assert_eq!((together.witness(), together.epoch()), (alone.witness(), alone.epoch()));
+    }
+    set_events.updated.sort_by(|a, b| a.0.cmp(&b.0));
+    each_events.updated.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(set_events.updated, each_events.updated);
+    assert_eq!(set_events.invalidated, [(vec![3], Invalidation::Deleted { epoch: 3 })]);
+
+    let epoch = manager.accumulator().epoch();
+    for holder in set.holders().iter().filter(|h| h.element() != [3]) {
+        assert_eq!(holder.epoch(), epoch);
+        assert!(manager.verify(holder.element(), holder.witness()));
+    }
+}
+
+#[test]
+fn holders_are_keyed_by_element() {
+    let (mut manager, mut set) = device();
+    assert_eq!(set.len(), 6);
+    let stale = set.get("alice").unwrap().clone();
+    set.insert(stale.clone());
+    assert_eq!(set.len(), 6);
+    assert_eq!(set.remove("alice").map(|h| h.witness()), Some(stale.witness()));
+    assert!(set.get("alice").is_none());
+
+    set.sync_all(manager.update_log()).unwrap();
+    assert!(set.holders().iter().all(|h| manager.verify(h.element(), h.witness())));
+    assert_eq!(set.sync_all(manager.update_log()), Ok(()));
+}
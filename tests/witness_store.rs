@@ -0,0 +1,50 @@
+use braavos_accumulator::{AccessToken, BraavosAccumulator, StoreKey, UpdateLog, WitnessHolder, WitnessStore};
+
+#[test]
+fn entries_open_only_with_the_device_key_and_their_token() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let mut store = WitnessStore::new(StoreKey::from_bytes([7; 32]));
+    let mut tokens = Vec::new();
+    for x in ["alice", "bob"] {
+        let holder = WitnessHolder::new(&params, x, acc.add(x).unwrap(), 0).unwrap();
+        tokens.push(store.insert(&holder));
+    }
+    let alice = store.get("alice", &tokens[0]).unwrap();
+    assert!(acc.verify("alice", alice.witness()));
+    assert!(store.get("alice", &tokens[1]).is_err());
+    assert!(store.get("carol", &tokens[0]).is_err());
+
+    // The persisted form names no element, and needs the same key to open
+    let bytes = store.to_bytes();
+    assert!(!bytes.windows(5).any(|w| w == b"alice"));
+    let restored = WitnessStore::from_bytes(&bytes, StoreKey::from_bytes([7; 32])).unwrap();
+    assert_eq!(restored.get("bob", &tokens[1]).unwrap().witness(), store.get("bob", &tokens[1]).unwrap().witness());
+    let stolen = WitnessStore::from_bytes(&bytes, StoreKey::generate()).unwrap();
+    assert!(!stolen.contains("bob"));
+    assert!(stolen.get("bob", &tokens[1]).is_err());
+}
+
+#[test]
+fn put_keeps_the_token_and_tampering_is_detected() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let params = acc.params();
+    let mut holder = WitnessHolder::new(&params, "alice", acc.add("alice").unwrap(), 0).unwrap();
+    acc.add("bob").unwrap();
+    let mut log = UpdateLog::starting_at(0);
+    log.push(acc.delete("bob").unwrap()).unwrap();
+    let key = StoreKey::generate();
+    let mut store = WitnessStore::new(key.clone());
+    let token = store.insert(&holder);
+
+    holder.sync(&log).unwrap();
+    assert!(store.put(&holder, &AccessToken::generate()).is_err());
+    store.put(&holder, &token).unwrap();
+    assert_eq!(store.get("alice", &token).unwrap().epoch(), 1);
+
+    let mut bytes = store.to_bytes();
+    let at = bytes.len() - 40;
+    bytes[at] ^= 1;
+    let tampered = WitnessStore::from_bytes(&bytes, key).unwrap();
+    assert!(tampered.get("alice", &token).is_err());
+}
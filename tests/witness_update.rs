@@ -0,0 +1,44 @@
+use braavos_accumulator::BraavosAccumulator;
+
+#[test]
+fn witnesses_follow_a_run_of_deletions() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let mut w_x = acc.add("x").unwrap();
+    let mut w_z = acc.add("z").unwrap();
+    for y in ["y", "d", "e"] {
+        acc.add(y).unwrap();
+    }
+    for deleted in ["y", "d", "e"] {
+        acc.delete(deleted).unwrap();
+        assert!(!acc.verify("x", w_x));
+        w_x = acc.update_witness_on_deletion("x", w_x, deleted).unwrap();
+        w_z = acc.update_witness_on_deletion("z", w_z, deleted).unwrap();
+        assert!(acc.verify("x", w_x) && acc.verify("z", w_z));
+    }
+}
+
+#[test]
+fn members_added_after_a_deletion_verify_alongside_older_ones() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w_x = acc.add("x").unwrap();
+    acc.add("y").unwrap();
+    acc.delete("y").unwrap();
+    let w_x = acc.update_witness_on_deletion("x", w_x, "y").unwrap();
+    let w_f = acc.add("f").unwrap();
+    let w_g = acc.add("g").unwrap();
+    assert!(acc.verify("x", w_x) && acc.verify("f", w_f) && acc.verify("g", w_g));
+}
+
+#[test]
+fn a_deleted_element_can_be_added_again() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let old = acc.add("f").unwrap();
+    let w_g = acc.add("g").unwrap();
+    acc.delete("f").unwrap();
+    let w_g = acc.update_witness_on_deletion("g", w_g, "f").unwrap();
+    assert!(!acc.contains("f") && !acc.verify("f", old));
+
+    let new = acc.add("f").unwrap();
+    assert!(acc.contains("f") && acc.verify("f", new) && acc.verify("g", w_g));
+    assert!(!acc.verify("f", old));
+}
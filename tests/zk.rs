@@ -0,0 +1,220 @@
+use braavos_accumulator::{
+    commit_element, verify_membership_of_commitment, BraavosAccumulator, ElemPrime, MembershipProof,
+    NonMembershipProof, NonMembershipWitness, RerandomizationProof, BLINDING_BITS,
+};
+use crypto_bigint::{RandomBits, U1024, U2048, U256};
+use rand::rngs::OsRng;
+
+// An accumulator that has deleted "mallory" but never held "alice".
+fn deny_list() -> BraavosAccumulator {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    acc.add("mallory").unwrap();
+    acc.add("bob").unwrap();
+    acc.delete("mallory").unwrap();
+    acc
+}
+
+fn blinding() -> U1024 {
+    U1024::random_bits(&mut OsRng, BLINDING_BITS)
+}
+
+#[test]
+fn non_membership_proofs_verify() {
+    let acc = deny_list();
+    let params = acc.params();
+    let prime = params.hash_to_prime("alice");
+    let blinding = blinding();
+    let witness = acc.non_membership_witness("alice").unwrap();
+
+    let proof = NonMembershipProof::prove(&params, &acc.value(), &prime, &blinding, &witness).unwrap();
+    assert_eq!(proof.commitment, commit_element(&params, &prime, &blinding).unwrap());
+    assert_eq!(proof.epoch, acc.epoch());
+    let proof = NonMembershipProof::from_bytes(&proof.to_bytes()).unwrap();
+    assert!(proof.verify(&params, &acc.value()));
+
+    // Deleted elements get no witness, and a witness for another element proves nothing
+    assert!(acc.non_membership_witness("mallory").is_err());
+    let mallory = params.hash_to_prime("mallory");
+    assert!(NonMembershipProof::prove(&params, &acc.value(), &mallory, &blinding, &witness).is_err());
+}
+
+#[test]
+fn non_membership_proofs_reject_a_wrong_value() {
+    let mut acc = deny_list();
+    let params = acc.params();
+    let prime = params.hash_to_prime("alice");
+    let witness = acc.non_membership_witness("alice").unwrap();
+    let proof = NonMembershipProof::prove(&params, &acc.value(), &prime, &blinding(), &witness).unwrap();
+
+    let stale = acc.value();
+    acc.delete("bob").unwrap();
+    assert!(!proof.verify(&params, &acc.value()));
+    assert!(proof.verify(&params, &stale));
+    // Nor under another accumulator's parameters
+    let other = deny_list();
+    assert!(!proof.verify(&other.params(), &other.value()));
+}
+
+#[test]
+fn non_membership_proofs_reject_a_wrong_commitment() {
+    let acc = deny_list();
+    let params = acc.params();
+    let (alice, carol) = (params.hash_to_prime("alice"), params.hash_to_prime("carol"));
+    let witness = acc.non_membership_witness("alice").unwrap();
+    let proof = NonMembershipProof::prove(&params, &acc.value(), &alice, &blinding(), &witness).unwrap();
+
+    let mut forged = proof.clone();
+    forged.commitment = commit_element(&params, &carol, &blinding()).unwrap();
+    assert!(!forged.verify(&params, &acc.value()));
+    let mut forged = proof;
+    forged.blinded_witness = commit_element(&params, &alice, &blinding()).unwrap();
+    assert!(!forged.verify(&params, &acc.value()));
+}
+
+#[test]
+fn non_membership_proofs_reject_tampered_responses() {
+    let acc = deny_list();
+    let params = acc.params();
+    let prime = params.hash_to_prime("alice");
+    let witness = acc.non_membership_witness("alice").unwrap();
+    let proof = NonMembershipProof::prove(&params, &acc.value(), &prime, &blinding(), &witness).unwrap();
+
+    for i in 0..proof.s.len() {
+        let mut forged = proof.clone();
+        forged.s[i] = forged.s[i].wrapping_add(&U2048::ONE);
+        assert!(!forged.verify(&params, &acc.value()));
+    }
+    // Responses wider than any honest one are rejected before the relations
+    let mut forged = proof.clone();
+    forged.s[0] = U2048::MAX;
+    assert!(!forged.verify(&params, &acc.value()));
+    let mut forged = proof;
+    forged.t[1] = forged.t[2];
+    assert!(!forged.verify(&params, &acc.value()));
+}
+
+// The documented limit: nothing shows the committed x is an element prime, so
+// a commitment to 1 "proves" non-membership against any value.
+#[test]
+fn a_commitment_to_one_is_not_ruled_out() {
+    let acc = deny_list();
+    let params = acc.params();
+    let one = ElemPrime::from_be_bytes(&U256::ONE.to_be_bytes());
+    let value = acc.value();
+    let witness = NonMembershipWitness {
+        params_digest: params.digest(),
+        epoch: acc.epoch(),
+        d: *value.as_uint(),
+        beta: U256::ZERO,
+    };
+    let proof = NonMembershipProof::prove(&params, &value, &one, &blinding(), &witness).unwrap();
+    assert!(proof.verify(&params, &value));
+}
+
+#[test]
+fn membership_proofs_verify_against_the_commitment() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    acc.add("bob").unwrap();
+    let params = acc.params();
+    let prime = params.hash_to_prime("alice");
+    let blinding = blinding();
+    let commitment = commit_element(&params, &prime, &blinding).unwrap();
+
+    let proof = MembershipProof::prove(&params, &acc.value(), &prime, &blinding, &w).unwrap();
+    let proof = MembershipProof::from_bytes(&proof.to_bytes()).unwrap();
+    assert!(verify_membership_of_commitment(&params, &acc.value(), &commitment, &proof));
+
+    // The witness must open the value for the committed prime
+    let bob = params.hash_to_prime("bob");
+    assert!(MembershipProof::prove(&params, &acc.value(), &bob, &blinding, &w).is_err());
+    let other = BraavosAccumulator::new(64).unwrap();
+    assert!(MembershipProof::prove(&other.params(), &acc.value(), &prime, &blinding, &w).is_err());
+}
+
+#[test]
+fn membership_proofs_reject_a_wrong_value_or_commitment() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    acc.add("bob").unwrap();
+    let params = acc.params();
+    let prime = params.hash_to_prime("alice");
+    let r = blinding();
+    let commitment = commit_element(&params, &prime, &r).unwrap();
+    let value = acc.value();
+    let proof = MembershipProof::prove(&params, &value, &prime, &r, &w).unwrap();
+
+    // Same element, fresh randomness: a different commitment
+    let recommitted = commit_element(&params, &prime, &blinding()).unwrap();
+    assert!(!verify_membership_of_commitment(&params, &value, &recommitted, &proof));
+    let bob = commit_element(&params, &params.hash_to_prime("bob"), &r).unwrap();
+    assert!(!verify_membership_of_commitment(&params, &value, &bob, &proof));
+
+    acc.delete("bob").unwrap();
+    assert!(!verify_membership_of_commitment(&params, &acc.value(), &commitment, &proof));
+    assert!(verify_membership_of_commitment(&params, &value, &commitment, &proof));
+}
+
+#[test]
+fn membership_proofs_reject_tampered_responses() {
+    let mut acc = BraavosAccumulator::new(64).unwrap();
+    let w = acc.add("alice").unwrap();
+    let params = acc.params();
+    let prime = params.hash_to_prime("alice");
+    let blinding = blinding();
+    let commitment = commit_element(&params, &prime, &blinding).unwrap();
+    let value = acc.value();
+    let proof = MembershipProof::prove(&params, &value, &prime, &blinding, &w).unwrap();
+
+    for i in 0..proof.s.len() {
+        let mut forged = proof.clone();
+        forged.s[i] = forged.s[i].wrapping_add(&U2048::ONE);
+        assert!(!verify_membership_of_commitment(&params, &value, &commitment, &forged));
+    }
+    let mut forged = proof.clone();
+    forged.s[2] = U2048::MAX;
+    assert!(!verify_membership_of_commitment(&params, &value, &commitment, &forged));
+    let mut forged = proof.clone();
+    forged.blinded_witness = *w.value.as_uint();
+    assert!(!verify_membership_of_commitment(&params, &value, &commitment, &forged));
+    let mut forged = proof;
+    forged.t.swap(0, 1);
+    assert!(!verify_membership_of_commitment(&params, &value, &commitment, &forged));
+}
+
+#[test]
+fn rerandomization_proofs_verify_and_witnesses_are_reissued() {
+    let mut acc = deny_list();
+    let (before, value_before) = (acc.params(), acc.value());
+    let w = acc.recover_witness("bob").unwrap();
+
+    let proof = acc.rerandomize().unwrap();
+    assert!(proof.verify(&before, &value_before, &acc.params(), &acc.value()));
+    assert_ne!(acc.value(), value_before);
+    // The base moved with the value, so old witnesses no longer open it
+    assert!(!acc.verify("bob", w));
+    let w = acc.recover_witness("bob").unwrap();
+    assert!(acc.verify("bob", w));
+}
+
+#[test]
+fn rerandomization_proofs_reject_other_values_and_params() {
+    let mut acc = deny_list();
+    let (before, value_before) = (acc.params(), acc.value());
+    let proof = acc.rerandomize().unwrap();
+    let (after, value_after) = (acc.params(), acc.value());
+
+    assert!(!proof.verify(&before, &value_before, &after, &value_before));
+    assert!(!proof.verify(&before, &value_after, &after, &value_after));
+    assert!(!proof.verify(&before, &value_before, &before, &value_after));
+    let other = BraavosAccumulator::new(64).unwrap();
+    assert!(!proof.verify(&before, &value_before, &other.params(), &value_after));
+
+    acc.rerandomize().unwrap();
+    assert!(!proof.verify(&before, &value_before, &acc.params(), &acc.value()));
+
+    let tampered = RerandomizationProof { s: proof.s.wrapping_add(&U2048::ONE), ..proof.clone() };
+    assert!(!tampered.verify(&before, &value_before, &after, &value_after));
+    let tampered = RerandomizationProof { t: [proof.t[1], proof.t[0]], ..proof };
+    assert!(!tampered.verify(&before, &value_before, &after, &value_after));
+}
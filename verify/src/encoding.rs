@@ -0,0 +1,51 @@
+use crypto_bigint::{U256, U512};
+
+// The subset of the manager crate's length-prefixed big-endian encoding a
+// verifier reads, always canonically.
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8], tag: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Self { buf };
+        if r.get_bytes()? != tag {
+            return Err("Unexpected encoding tag");
+        }
+        Ok(r)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        if self.buf.len() < len {
+            return Err("Truncated encoding");
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn get_u32(&mut self) -> Result<u32, &'static str> {
+        let bytes = self.take(4)?.try_into().map_err(|_| "Truncated encoding")?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    pub(crate) fn get_bytes(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = self.get_u32()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn get_u256(&mut self) -> Result<U256, &'static str> {
+        Ok(U256::from_be_slice(self.take(32)?))
+    }
+
+    pub(crate) fn get_u512(&mut self) -> Result<U512, &'static str> {
+        Ok(U512::from_be_slice(self.take(64)?))
+    }
+
+    pub(crate) fn finish(self) -> Result<(), &'static str> {
+        if !self.buf.is_empty() {
+            return Err("Trailing bytes after encoding");
+        }
+        Ok(())
+    }
+}
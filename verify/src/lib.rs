@@ -0,0 +1,17 @@
+// Membership verification for Braavos accumulators without the manager
+// crate: no RNG, no allocator and no std, and only crypto-bigint, the
+// primality tests of crypto-primes and SHA-256 underneath. It reads the
+// manager crate's encodings (PublicParams::to_bytes, Witness::to_bytes) and
+// derives element primes under the same hash-to-prime profile, checked
+// against the published vectors; parameters under another digest or profile
+// are refused rather than verified differently.
+#![no_std]
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+#![deny(clippy::todo, clippy::unimplemented, clippy::unreachable)]
+
+mod encoding;
+mod prime;
+mod verifier;
+
+pub use prime::{hash_to_prime, HASH_TO_PRIME_PROFILE};
+pub use verifier::Verifier;
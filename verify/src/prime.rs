@@ -0,0 +1,103 @@
+use crypto_bigint::{Limb, NonZero, Odd, U256};
+use crypto_primes::hazmat::{lucas_test, AStarBase, LucasCheck, MillerRabin, Primality};
+use sha2::{Digest, Sha256};
+
+// The hash-to-prime profile implemented here; parameters naming another are
+// refused.
+pub const HASH_TO_PRIME_PROFILE: u32 = 1;
+
+pub(crate) const ELEMENT_PRIME_BITS: u32 = 256;
+
+const DIGEST_MARKER: &[u8] = b"\xffbraavos-digest";
+const MAX_DOMAIN_LEN: usize = 64;
+
+// SHA-256 of the domain and parts, each prefixed with its length.
+pub(crate) fn domain_hash(domain: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update((domain.len() as u32).to_be_bytes());
+    hasher.update(domain);
+    for part in parts {
+        hasher.update((part.len() as u32).to_be_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+// The element prime of x under SHA-256 and the given key: the first
+// candidate of x's sequence that passes the small-prime sieve and
+// Baillie-PSW.
+pub fn hash_to_prime(key: &U256, x: &[u8]) -> U256 {
+    let key_bytes = key.to_be_bytes();
+    let digest_start = parse_digest(x).map(|(domain, digest)| {
+        let mask = domain_hash(b"braavos-digest-to-prime", &[&key_bytes, domain]);
+        (U256::from_be_slice(digest) ^ U256::from_be_slice(&mask)) | U256::ONE | top_bit()
+    });
+    let mut counter: u32 = 0;
+    loop {
+        let candidate = match &digest_start {
+            Some(start) => start.wrapping_add(&U256::from(counter).shl_vartime(1)) | top_bit(),
+            None => {
+                let digest = domain_hash(b"braavos-hash-to-prime", &[&key_bytes, x, &counter.to_be_bytes()]);
+                U256::from_be_slice(&digest) | U256::ONE | top_bit()
+            }
+        };
+        if !has_small_factor(&candidate) && is_prime(&candidate) {
+            return candidate;
+        }
+        counter = counter.wrapping_add(1);
+    }
+}
+
+fn top_bit() -> U256 {
+    U256::ONE.shl_vartime(ELEMENT_PRIME_BITS - 1)
+}
+
+// (domain, digest) of a well-formed digest element (ElementDigest), whose
+// candidates start from the digest instead of a hash of it.
+fn parse_digest(x: &[u8]) -> Option<(&[u8], &[u8; 32])> {
+    let rest = x.strip_prefix(DIGEST_MARKER)?;
+    let (&len, rest) = rest.split_first()?;
+    let len = len as usize;
+    if rest.len() != len + 32 || !valid_domain(&rest[..len]) {
+        return None;
+    }
+    let (domain, digest) = rest.split_at(len);
+    Some((domain, digest.try_into().ok()?))
+}
+
+fn valid_domain(domain: &[u8]) -> bool {
+    (1..=MAX_DOMAIN_LEN).contains(&domain.len()) && domain.iter().all(|b| b.is_ascii_graphic())
+}
+
+// Miller-Rabin to base 2 and a strong Lucas test: the deterministic part of
+// crypto-primes' is_prime, whose extra random-base round only matters for a
+// Baillie-PSW pseudoprime, of which none is known.
+fn is_prime(candidate: &U256) -> bool {
+    let odd: Option<Odd<U256>> = Odd::new(*candidate).into();
+    let Some(odd) = odd else {
+        return false;
+    };
+    MillerRabin::new(odd).test_base_two().is_probably_prime()
+        && lucas_test(odd, AStarBase, LucasCheck::Strong) != Primality::Composite
+}
+
+// Odd primes below 1000, as the manager crate's sieve uses.
+const SMALL_PRIMES: [u32; 167] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+    97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181,
+    191, 193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277,
+    281, 283, 293, 307, 311, 313, 317, 331, 337, 347, 349, 353, 359, 367, 373, 379, 383,
+    389, 397, 401, 409, 419, 421, 431, 433, 439, 443, 449, 457, 461, 463, 467, 479, 487,
+    491, 499, 503, 509, 521, 523, 541, 547, 557, 563, 569, 571, 577, 587, 593, 599, 601,
+    607, 613, 617, 619, 631, 641, 643, 647, 653, 659, 661, 673, 677, 683, 691, 701, 709,
+    719, 727, 733, 739, 743, 751, 757, 761, 769, 773, 787, 797, 809, 811, 821, 823, 827,
+    829, 839, 853, 857, 859, 863, 877, 881, 883, 887, 907, 911, 919, 929, 937, 941, 947,
+    953, 967, 971, 977, 983, 991, 997
+];
+
+fn has_small_factor(candidate: &U256) -> bool {
+    SMALL_PRIMES
+        .iter()
+        .filter_map(|&p| Option::<NonZero<Limb>>::from(NonZero::new(Limb::from(p))))
+        .any(|p| candidate.rem_limb(p) == Limb::ZERO)
+}
@@ -0,0 +1,157 @@
+use crypto_bigint::{
+    modular::{MontyForm, MontyParams},
+    NonZero, Odd, U256, U512,
+};
+
+use crate::encoding::Reader;
+use crate::prime::{domain_hash, hash_to_prime, ELEMENT_PRIME_BITS, HASH_TO_PRIME_PROFILE};
+
+const PARAMS_TAG: &[u8] = b"braavos-public-params";
+const WITNESS_TAG: &[u8] = b"braavos-witness";
+const SHA256_ID: u32 = 1;
+
+// A verifier for one set of public parameters. Loading checks the encoding
+// is the canonical one, so its digest is the manager crate's
+// PublicParams::digest, which witnesses carry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Verifier {
+    params_digest: [u8; 32],
+    prf_key: U256,
+    base: U512,
+    monty_params: MontyParams<8>,
+}
+
+impl Verifier {
+    // From PublicParams::to_bytes. Only SHA-256 parameters under the first
+    // hash-to-prime profile (layouts 1 to 3) are supported.
+    pub fn from_params_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader::new(bytes, PARAMS_TAG)?;
+        let version = r.get_u32()?;
+        if version == 4 {
+            return Err("Unsupported hash-to-prime profile");
+        }
+        if !(1..=3).contains(&version) {
+            return Err("Unsupported parameter version");
+        }
+        let n: Option<Odd<U512>> = Odd::new(r.get_u512()?).into();
+        let n = n.filter(|n| n.get() != U512::ONE).ok_or("Modulus must be odd")?;
+        let base = r.get_u512()?;
+        if !is_group_element(&base, &n.get()) {
+            return Err("Base is not in the quadratic residue subgroup");
+        }
+        if r.get_u32()? != SHA256_ID {
+            return Err("Unsupported hash algorithm");
+        }
+        if r.get_u32()? != ELEMENT_PRIME_BITS {
+            return Err("Unsupported element prime size");
+        }
+        let prf_key = r.get_u256()?;
+        // Layouts carry only what they need: 2 a seed, 3 a genesis digest
+        // and an optional seed
+        match version {
+            2 if r.get_bytes()?.len() != 32 => return Err("Invalid base seed"),
+            3 => {
+                if !matches!(r.get_bytes()?.len(), 0 | 32) {
+                    return Err("Invalid base seed");
+                }
+                if r.get_bytes()?.len() != 32 {
+                    return Err("Invalid genesis digest");
+                }
+            }
+            _ => {}
+        }
+        r.finish()?;
+        Ok(Self {
+            params_digest: domain_hash(PARAMS_TAG, &[bytes]),
+            prf_key,
+            base,
+            monty_params: MontyParams::new(n),
+        })
+    }
+
+    pub fn params_digest(&self) -> [u8; 32] {
+        self.params_digest
+    }
+
+    pub fn profile(&self) -> u32 {
+        HASH_TO_PRIME_PROFILE
+    }
+
+    // Accumulator value at setup, which opens every witness issued before
+    // the first deletion.
+    pub fn base(&self) -> [u8; 64] {
+        self.base.to_be_bytes()
+    }
+
+    pub fn hash_to_prime(&self, x: &[u8]) -> U256 {
+        hash_to_prime(&self.prf_key, x)
+    }
+
+    // Whether x is a member under the published value, from a
+    // Witness::to_bytes encoding and the value's big-endian bytes. The
+    // witness must be reduced, in the subgroup and not of small order.
+    pub fn verify(&self, x: &[u8], witness: &[u8], value: &[u8; 64]) -> bool {
+        self.check(x, witness, value).is_ok()
+    }
+
+    // verify, saying why a witness is refused.
+    pub fn check(&self, x: &[u8], witness: &[u8], value: &[u8; 64]) -> Result<(), &'static str> {
+        let n = self.monty_params.modulus().get();
+        let mut r = Reader::new(witness, WITNESS_TAG)?;
+        if r.get_bytes()? != self.params_digest {
+            return Err("Witness was issued under different parameters");
+        }
+        let w = r.get_u512()?;
+        r.finish()?;
+        if !is_group_element(&w, &n) || w == U512::ONE || w == n.wrapping_sub(&U512::ONE) {
+            return Err("Witness is not a nontrivial subgroup element");
+        }
+        let value = U512::from_be_slice(value);
+        if !is_group_element(&value, &n) {
+            return Err("Value is not in the quadratic residue subgroup");
+        }
+        let opened = MontyForm::new(&w, self.monty_params).pow(&self.hash_to_prime(x)).retrieve();
+        // Up to sign: verification works in QR_n / {1, -1}
+        if opened != value && opened.wrapping_add(&value) != n {
+            return Err("Witness does not open the value");
+        }
+        Ok(())
+    }
+}
+
+// Nonzero, reduced and of Jacobi symbol 1.
+fn is_group_element(x: &U512, n: &U512) -> bool {
+    *x != U512::ZERO && x < n && jacobi(x, n) == 1
+}
+
+// The Jacobi symbol (a/n) for odd n.
+fn jacobi(a: &U512, n: &U512) -> i8 {
+    let Some(modulus) = Option::<NonZero<U512>>::from(NonZero::new(*n)) else {
+        return 0;
+    };
+    let (mut a, mut n) = (a.rem(&modulus), *n);
+    let mut t = 1;
+    while a != U512::ZERO {
+        let zeros = a.trailing_zeros_vartime();
+        a = a.shr_vartime(zeros);
+        // (2/n) = -1 exactly when n = 3 or 5 mod 8
+        let n_mod_8 = n.as_words()[0] & 7;
+        if zeros % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            t = -t;
+        }
+        // Quadratic reciprocity: flips when both are 3 mod 4
+        if a.as_words()[0] & 3 == 3 && n_mod_8 & 3 == 3 {
+            t = -t;
+        }
+        (a, n) = (n, a);
+        let Some(n_nz) = Option::<NonZero<U512>>::from(NonZero::new(n)) else {
+            return 0;
+        };
+        a = a.rem(&n_nz);
+    }
+    if n == U512::ONE {
+        t
+    } else {
+        0
+    }
+}
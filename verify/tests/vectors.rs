@@ -0,0 +1,28 @@
+use accumulators_verify::{hash_to_prime, HASH_TO_PRIME_PROFILE};
+use crypto_bigint::U256;
+
+// Shared with the manager crate, so both derive the same primes
+const VECTORS: &str = include_str!("../../tests/fixtures/h2p_vectors_sha256.json");
+
+fn field<'a>(line: &'a str, name: &str) -> &'a str {
+    let start = line.find(&format!("\"{name}\": \"")).unwrap() + name.len() + 5;
+    let len = line[start..].find('"').unwrap();
+    &line[start..start + len]
+}
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+}
+
+#[test]
+fn hash_to_prime_matches_the_published_vectors() {
+    assert!(VECTORS.contains(&format!("\"profile\": {HASH_TO_PRIME_PROFILE},")));
+    let mut checked = 0;
+    for line in VECTORS.lines().filter(|line| line.contains("\"prime\"")) {
+        let key = U256::from_be_hex(field(line, "key"));
+        let prime = U256::from_be_hex(field(line, "prime"));
+        assert_eq!(hash_to_prime(&key, &unhex(field(line, "input"))), prime);
+        checked += 1;
+    }
+    assert!(checked > 0);
+}